@@ -1,4 +1,16 @@
 fn main() {
+    // gRPC transcript API (src/grpc.rs). Use the bundled protoc so builds
+    // don't need one installed.
+    if std::env::var_os("PROTOC").is_none() {
+        if let Ok(protoc) = protoc_bin_vendored::protoc_bin_path() {
+            std::env::set_var("PROTOC", protoc);
+        }
+    }
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/mangochat.proto"], &["proto"])
+        .expect("Failed to compile proto/mangochat.proto");
+
     #[cfg(target_os = "windows")]
     {
         let mut res = winres::WindowsResource::new();
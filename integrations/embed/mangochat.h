@@ -0,0 +1,38 @@
+/* Mango Chat dictation for embedding: mangochat_embed.dll.
+ *
+ * Calls return 0 on success and -1 on failure, including a panic inside
+ * the library; mangochat_last_error() explains the calling thread's last
+ * failure. Provider keys, microphone
+ * and language come from Mango Chat's own settings.
+ */
+#ifndef MANGOCHAT_H
+#define MANGOCHAT_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Called from a background thread. `text` is UTF-8 and only valid during
+ * the call. `is_final` is 0 for interim text. */
+typedef void (*mangochat_transcript_cb)(const char *text, int is_final, void *user_data);
+
+int mangochat_init(void);
+/* "deepgram", "openai", "elevenlabs" or "assemblyai". */
+int mangochat_set_provider(const char *provider_id);
+/* Pass NULL to clear. */
+int mangochat_set_transcript_callback(mangochat_transcript_cb callback, void *user_data);
+/* Non-zero (default): finals are typed into the focused window.
+ * Zero: finals only go to the callback. */
+int mangochat_set_typing(int enabled);
+int mangochat_start(void);
+int mangochat_stop(void);
+void mangochat_shutdown(void);
+/* NULL if nothing failed on this thread. The string belongs to the library
+ * and stays valid until the next failing call on the same thread. */
+const char *mangochat_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,135 @@
+use rusqlite::params;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+/// One archived final transcript.
+pub struct ArchivedTranscript {
+    pub session_id: u64,
+    pub provider: String,
+    pub created_ms: u64,
+    pub text: String,
+}
+
+/// Local calendar day of a transcript, as stored in SQLite ("YYYY-MM-DD").
+const DAY_EXPR: &str = "date(created_ms / 1000, 'unixepoch', 'localtime')";
+
+/// Store a final transcript. Only called when the archive is turned on.
+pub fn record(session_id: u64, provider: &str, text: &str) -> Result<(), String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    crate::usage::with_db(|conn| {
+        conn.execute(
+            "INSERT INTO transcripts (session_id, provider, created_ms, text)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![session_id as i64, provider, now as i64, text],
+        )
+        .map(|_| ())
+    })
+}
+
+/// Days that have archived transcripts, newest first.
+pub fn days() -> Vec<String> {
+    let result = crate::usage::with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT {} AS day FROM transcripts ORDER BY day DESC",
+            DAY_EXPR
+        ))?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    });
+    result.unwrap_or_else(|e| {
+        app_err!("[archive] {}", e);
+        vec![]
+    })
+}
+
+pub fn transcripts_for_day(day: &str) -> Result<Vec<ArchivedTranscript>, String> {
+    crate::usage::with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT session_id, provider, created_ms, text FROM transcripts
+             WHERE {} = ?1 ORDER BY created_ms",
+            DAY_EXPR
+        ))?;
+        let rows = stmt.query_map(params![day], |r| {
+            Ok(ArchivedTranscript {
+                session_id: r.get::<_, i64>(0)? as u64,
+                provider: r.get(1)?,
+                created_ms: r.get::<_, i64>(2)? as u64,
+                text: r.get(3)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })
+}
+
+pub fn export_dir() -> Result<PathBuf, String> {
+    crate::usage::data_dir()
+        .map(|d| d.join("transcripts"))
+        .ok_or_else(|| "Failed to resolve data directory for transcript export".into())
+}
+
+fn time_of_day(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Render one day's transcripts, grouped by session. `format` is "md" or "txt".
+pub fn render_day(day: &str, format: &str, items: &[ArchivedTranscript]) -> String {
+    let markdown = format == "md";
+    let mut out = String::new();
+    if markdown {
+        let _ = writeln!(out, "# Transcripts {}", day);
+    } else {
+        let _ = writeln!(out, "Transcripts {}", day);
+    }
+    let mut session = None;
+    for t in items {
+        if session != Some(t.session_id) {
+            session = Some(t.session_id);
+            out.push('\n');
+            if markdown {
+                let _ = writeln!(
+                    out,
+                    "## {} ({}, session {})\n",
+                    time_of_day(t.created_ms),
+                    crate::provider::display_name(&t.provider),
+                    t.session_id
+                );
+            } else {
+                let _ = writeln!(
+                    out,
+                    "--- {} ({}, session {}) ---",
+                    time_of_day(t.created_ms),
+                    crate::provider::display_name(&t.provider),
+                    t.session_id
+                );
+            }
+        }
+        if markdown {
+            let _ = writeln!(out, "- **{}** {}", time_of_day(t.created_ms), t.text.trim());
+        } else {
+            let _ = writeln!(out, "[{}] {}", time_of_day(t.created_ms), t.text.trim());
+        }
+    }
+    out
+}
+
+/// Write `day` to transcripts/<day>.<format> in the data directory.
+pub fn export_day(day: &str, format: &str) -> Result<PathBuf, String> {
+    let items = transcripts_for_day(day)?;
+    if items.is_empty() {
+        return Err(format!("No transcripts archived for {}", day));
+    }
+    let dir = export_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create transcripts dir: {}", e))?;
+    let ext = if format == "md" { "md" } else { "txt" };
+    let path = dir.join(format!("{}.{}", day, ext));
+    fs::write(&path, render_day(day, format, &items))
+        .map_err(|e| format!("Failed to write transcript export: {}", e))?;
+    app_log!("[archive] exported {} transcripts to {}", items.len(), path.display());
+    Ok(path)
+}
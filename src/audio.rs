@@ -24,7 +24,8 @@ const VAD_SAMPLE_RATE: u32 = 16000;
 const VAD_FRAME_MS: usize = 20;
 const VAD_START_TRIGGER_FRAMES: usize = 2;
 const FFT_SIZE: usize = 256;
-const BAR_COUNT: usize = 50;
+/// Audio held back while the channel is full before the oldest is dropped.
+const MAX_BACKLOG_MS: usize = 2000;
 
 pub struct AudioCapture {
     _stream: cpal::Stream,
@@ -39,17 +40,7 @@ impl AudioCapture {
         state: Arc<AppState>,
         target_rate: u32,
     ) -> Result<Self, String> {
-        let host = cpal::default_host();
-
-        let device = if let Some(name) = device_name {
-            host.input_devices()
-                .map_err(|e| format!("Failed to list devices: {}", e))?
-                .find(|d| d.name().map(|n| n == name).unwrap_or(false))
-                .ok_or_else(|| format!("Device '{}' not found", name))?
-        } else {
-            host.default_input_device()
-                .ok_or("No default input device")?
-        };
+        let device = input_device(device_name)?;
 
         let device_name = device.name().unwrap_or_else(|_| "unknown".into());
         app_log!("[audio] using device: {}", device_name);
@@ -92,6 +83,47 @@ impl AudioCapture {
             },
         };
 
+        Self::run(&device, config, decimate, audio_tx, Some(ui_event_tx), state, target_rate)
+    }
+
+    /// System audio (whatever plays on the default output) via WASAPI
+    /// loopback, VAD-gated like the mic. Meeting mode uses it for the other
+    /// side of a call.
+    pub fn start_loopback(
+        audio_tx: mpsc::Sender<Vec<u8>>,
+        state: Arc<AppState>,
+        target_rate: u32,
+    ) -> Result<Self, String> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or("No default output device")?;
+        let default = device
+            .default_output_config()
+            .map_err(|e| format!("No output config: {}", e))?;
+        app_log!(
+            "[audio] loopback device: {}",
+            device.name().unwrap_or_else(|_| "unknown".into())
+        );
+        let config = StreamConfig {
+            channels: default.channels(),
+            sample_rate: default.sample_rate(),
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let decimate = (config.sample_rate.0 / target_rate.max(1)).max(1);
+        Self::run(&device, config, decimate, audio_tx, None, state, target_rate)
+    }
+
+    /// `ui_event_tx` is None for secondary captures (loopback), which neither
+    /// drive the visualizer nor report a lost device.
+    fn run(
+        device: &cpal::Device,
+        config: StreamConfig,
+        decimate: u32,
+        audio_tx: mpsc::Sender<Vec<u8>>,
+        ui_event_tx: Option<std::sync::mpsc::Sender<AppEvent>>,
+        state: Arc<AppState>,
+        target_rate: u32,
+    ) -> Result<Self, String> {
         let effective_rate = config.sample_rate.0 / decimate;
         app_log!(
             "[audio] stream config: {}Hz, {}ch, decimate={}, effective={}Hz",
@@ -102,7 +134,7 @@ impl AudioCapture {
         let (raw_tx, raw_rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(128);
 
         let channels = config.channels as usize;
-        let err_event_tx = ui_event_tx.clone();
+        let visualize = ui_event_tx.is_some();
         let stream = device
             .build_input_stream(
                 &config,
@@ -125,9 +157,11 @@ impl AudioCapture {
                 },
                 move |err| {
                     app_err!("[audio] stream error: {}", err);
-                    let _ = err_event_tx.send(AppEvent::AudioInputLost {
-                        message: err.to_string(),
-                    });
+                    if let Some(tx) = &ui_event_tx {
+                        let _ = tx.send(AppEvent::AudioInputLost {
+                            message: err.to_string(),
+                        });
+                    }
                 },
                 None,
             )
@@ -143,7 +177,7 @@ impl AudioCapture {
             } else {
                 target_rate
             };
-            process_audio(raw_rx, audio_tx, state, effective_rate, target);
+            process_audio(raw_rx, audio_tx, state, effective_rate, target, visualize);
         });
 
         Ok(Self {
@@ -153,6 +187,134 @@ impl AudioCapture {
     }
 }
 
+/// Feed mono samples at `input_rate` from outside cpal (the phone remote mic)
+/// through the same VAD, resampling and visualizer as the mic. Dropping the
+/// returned sender ends the stream, like dropping an `AudioCapture`.
+pub fn start_external(
+    input_rate: u32,
+    audio_tx: mpsc::Sender<Vec<u8>>,
+    state: Arc<AppState>,
+    target_rate: u32,
+) -> std::sync::mpsc::SyncSender<Vec<f32>> {
+    let (raw_tx, raw_rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(128);
+    std::thread::spawn(move || {
+        let target = if target_rate == 0 {
+            DEFAULT_SAMPLE_RATE
+        } else {
+            target_rate
+        };
+        process_audio(raw_rx, audio_tx, state, input_rate, target, true);
+    });
+    raw_tx
+}
+
+/// Plain mono 16-bit capture at a fixed rate (no VAD/FFT), used for the
+/// mic track of screen recordings. Samples are appended to `sink`.
+pub struct RawCapture {
+    _stream: cpal::Stream,
+}
+
+impl RawCapture {
+    pub fn start(
+        device_name: Option<&str>,
+        rate: u32,
+        sink: Arc<std::sync::Mutex<Vec<i16>>>,
+    ) -> Result<Self, String> {
+        let device = input_device(device_name)?;
+        let config = try_config(&device, rate)
+            .ok_or_else(|| format!("Device does not support {}Hz capture", rate))?;
+
+        let channels = config.channels as usize;
+        let stream = device
+            .build_input_stream(
+                &config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    if let Ok(mut buf) = sink.lock() {
+                        buf.extend(data.chunks(channels).map(|frame| {
+                            let v = frame.iter().sum::<f32>() / channels as f32;
+                            (v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+                        }));
+                    }
+                },
+                move |err| {
+                    app_err!("[audio] raw capture stream error: {}", err);
+                },
+                None,
+            )
+            .map_err(|e| format!("Failed to build stream: {}", e))?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start stream: {}", e))?;
+        Ok(Self { _stream: stream })
+    }
+}
+
+/// Mono 16-bit capture resampled to `rate` from the device's native config,
+/// delivered in callback-sized blocks. Used by the wake-word listener, which
+/// needs 16kHz from devices that usually only offer 44.1/48kHz.
+pub struct ResampledCapture {
+    _stream: cpal::Stream,
+}
+
+impl ResampledCapture {
+    pub fn start(
+        device_name: Option<&str>,
+        rate: u32,
+        sink: std::sync::mpsc::SyncSender<Vec<i16>>,
+    ) -> Result<Self, String> {
+        let device = input_device(device_name)?;
+        let default = device
+            .default_input_config()
+            .map_err(|e| format!("No input config: {}", e))?;
+        let config = StreamConfig {
+            channels: default.channels(),
+            sample_rate: default.sample_rate(),
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let channels = config.channels as usize;
+        let input_rate = config.sample_rate.0;
+        let mut resampler = ResamplerState::default();
+        let stream = device
+            .build_input_stream(
+                &config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    let mono: Vec<f32> = data
+                        .chunks(channels)
+                        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                        .collect();
+                    let pcm = resample_linear(&mono, input_rate, rate, &mut resampler)
+                        .iter()
+                        .map(|&s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
+                        .collect();
+                    let _ = sink.try_send(pcm);
+                },
+                move |err| {
+                    app_err!("[audio] resampled capture stream error: {}", err);
+                },
+                None,
+            )
+            .map_err(|e| format!("Failed to build stream: {}", e))?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start stream: {}", e))?;
+        Ok(Self { _stream: stream })
+    }
+}
+
+/// The named input device, or the system default when `device_name` is None.
+fn input_device(device_name: Option<&str>) -> Result<cpal::Device, String> {
+    let host = cpal::default_host();
+    if let Some(name) = device_name {
+        host.input_devices()
+            .map_err(|e| format!("Failed to list devices: {}", e))?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .ok_or_else(|| format!("Device '{}' not found", name))
+    } else {
+        host.default_input_device()
+            .ok_or_else(|| "No default input device".to_string())
+    }
+}
+
 fn try_config(device: &cpal::Device, rate: u32) -> Option<StreamConfig> {
     let config = StreamConfig {
         channels: 1,
@@ -191,6 +353,7 @@ fn process_audio(
     state: Arc<AppState>,
     input_rate: u32,
     target_rate: u32,
+    visualize: bool,
 ) {
     let mut last_voice_ts = Instant::now() - std::time::Duration::from_secs(10);
     let mut is_sending = false;
@@ -212,9 +375,12 @@ fn process_audio(
     let fft = planner.plan_fft_forward(FFT_SIZE);
     let mut fft_ring = Vec::with_capacity(FFT_SIZE * 2);
     let mut fft_buffer = vec![Complex::new(0.0, 0.0); FFT_SIZE];
-    let mut fft_smoothed = [0.0f32; BAR_COUNT];
+    let mut fft_smoothed: Vec<f32> = Vec::new();
+    let mut last_fft = Instant::now() - std::time::Duration::from_secs(1);
+    let mut outlet = AudioOutlet::new(audio_tx, state.clone(), target_rate);
 
     while let Ok(samples) = raw_rx.recv() {
+        outlet.flush();
         // Resample to target rate if needed, then convert to 16-bit PCM.
         let send_samples = if input_rate == target_rate {
             samples.clone()
@@ -322,16 +488,40 @@ fn process_audio(
             silence_ms += chunk_ms;
         }
 
-        // Accumulate samples for FFT
-        fft_ring.extend_from_slice(&samples);
-        // Keep only the latest window (avoid unbounded growth)
-        if fft_ring.len() > FFT_SIZE * 2 {
-            let drain = fft_ring.len() - FFT_SIZE * 2;
-            fft_ring.drain(..drain);
+        // Accumulate samples for FFT, only while someone can see the bars
+        let fft_wanted = visualize
+            && state
+                .visualizer_active
+                .load(std::sync::atomic::Ordering::Relaxed);
+        if fft_wanted {
+            fft_ring.extend_from_slice(&samples);
+            // Keep only the latest window (avoid unbounded growth)
+            if fft_ring.len() > FFT_SIZE * 2 {
+                let drain = fft_ring.len() - FFT_SIZE * 2;
+                fft_ring.drain(..drain);
+            }
+        } else if !fft_ring.is_empty() {
+            // Hidden or turned off: drop stale bars so they don't reappear.
+            fft_ring.clear();
+            fft_smoothed.clear();
+            state.fft_data.store(Default::default());
         }
 
-        // Compute FFT when we have enough samples
-        if fft_ring.len() >= FFT_SIZE {
+        // Compute FFT when we have enough samples, at the configured rate
+        let fft_interval = state
+            .visualizer_interval_ms
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if fft_wanted
+            && fft_ring.len() >= FFT_SIZE
+            && last_fft.elapsed().as_millis() as u64 >= fft_interval
+        {
+            last_fft = Instant::now();
+            let bands = state
+                .visualizer_bands
+                .load(std::sync::atomic::Ordering::Relaxed) as usize;
+            if fft_smoothed.len() != bands {
+                fft_smoothed = vec![0.0; bands];
+            }
             let start = fft_ring.len() - FFT_SIZE;
             for i in 0..FFT_SIZE {
                 let window = 0.5
@@ -343,19 +533,17 @@ fn process_audio(
             }
             fft.process(&mut fft_buffer);
 
-            // Extract BAR_COUNT bars from lower frequency bins (skip DC at 0)
+            // Extract one bar per band from the frequency bins (skip DC at 0)
             let max_bin = FFT_SIZE / 2;
-            for i in 0..BAR_COUNT {
-                let idx = 1 + ((i as f32 / BAR_COUNT as f32) * (max_bin as f32 - 1.0)) as usize;
+            for i in 0..bands {
+                let idx = 1 + ((i as f32 / bands as f32) * (max_bin as f32 - 1.0)) as usize;
                 let idx = idx.min(max_bin - 1);
                 let mag = fft_buffer[idx].norm();
                 // Scale: typical speech FFT magnitudes are small; normalize gently.
                 let normalized = (mag * 0.4).min(1.0);
                 fft_smoothed[i] = fft_smoothed[i] * 0.6 + normalized * 0.4;
             }
-            if let Ok(mut data) = state.fft_data.lock() {
-                *data = fft_smoothed;
-            }
+            state.fft_data.store(Arc::new(fft_smoothed.clone()));
         }
 
         // Preroll buffer
@@ -372,14 +560,14 @@ fn process_audio(
         }
 
         if pending_stop {
-            let _ = audio_tx.try_send(pcm.clone());
+            outlet.send(pcm.clone());
             post_roll_remaining_ms -= chunk_ms;
             if post_roll_remaining_ms <= 0.0 {
                 app_log!(
                     "[audio] VAD commit: post_roll_ms={:.1} mode={}",
                     post_roll_ms, vad_label
                 );
-                send_commit_signal(&audio_tx, "[audio] commit post-roll");
+                outlet.commit();
                 pending_stop = false;
                 is_sending = false;
                 voiced_ms = 0.0;
@@ -397,19 +585,14 @@ fn process_audio(
                     usage.ms_suppressed = usage.ms_suppressed.saturating_add(suppressed_ms);
                     usage.last_update_ms = now_ms();
                 }
-                let mut provider_key: Option<String> = None;
-                if let Ok(mut session) = state.session_usage.lock() {
-                    if session.started_ms != 0 {
-                        session.ms_suppressed = session.ms_suppressed.saturating_add(suppressed_ms);
-                        session.updated_ms = now_ms();
-                        if !session.provider.is_empty() {
-                            provider_key = Some(session.provider.clone());
-                        }
-                    }
-                }
-                if let Some(provider) = provider_key {
+                state.add_session_usage(|session| {
+                    session.ms_suppressed = session.ms_suppressed.saturating_add(suppressed_ms);
+                    session.updated_ms = now_ms();
+                });
+                let session = state.session_usage.load();
+                if session.started_ms != 0 && !session.provider.is_empty() {
                     if let Ok(mut pt) = state.provider_totals.lock() {
-                        let entry = pt.entry(provider).or_default();
+                        let entry = pt.entry(session.provider.clone()).or_default();
                         entry.ms_suppressed = entry.ms_suppressed.saturating_add(suppressed_ms);
                     }
                 }
@@ -423,7 +606,7 @@ fn process_audio(
                     pending_stop = post_roll_ms > 0.0;
                     post_roll_remaining_ms = post_roll_ms;
                     if !pending_stop {
-                        send_commit_signal(&audio_tx, "[audio] commit immediate");
+                        outlet.commit();
                         is_sending = false;
                         voiced_ms = 0.0;
                         silence_ms = 0.0;
@@ -447,46 +630,129 @@ fn process_audio(
                 peak, vad_label, preroll_ms
             );
             is_sending = true;
-            for buf in &preroll {
-                let _ = audio_tx.try_send(buf.clone());
+            for buf in preroll.drain(..) {
+                outlet.send(buf);
             }
-            preroll.clear();
             preroll_ms = 0.0;
         }
 
         if is_sending {
-            let _ = audio_tx.try_send(pcm);
+            outlet.send(pcm);
         }
     }
 
     // Clear FFT when stream stops
-    if let Ok(mut data) = state.fft_data.lock() {
-        *data = [0.0; BAR_COUNT];
+    if visualize {
+        state.fft_data.store(Default::default());
     }
     app_log!("[audio] processing thread stopped");
 }
 
-fn send_commit_signal(audio_tx: &mpsc::Sender<Vec<u8>>, context: &str) {
-    for attempt in 1..=25 {
-        match audio_tx.try_send(Vec::new()) {
-            Ok(()) => return,
-            Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
-                std::thread::sleep(std::time::Duration::from_millis(4));
-            }
-            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
-                app_err!("[audio] {} failed: channel closed", context);
-                return;
+/// Sending end of the provider audio channel with a drop-oldest policy. When
+/// the session can't keep up and the channel is full, chunks wait here;
+/// past `MAX_BACKLOG_MS` the oldest audio is dropped and counted in usage.
+/// Commit signals (empty chunks) are never dropped and stay in order.
+struct AudioOutlet {
+    tx: mpsc::Sender<Vec<u8>>,
+    state: Arc<AppState>,
+    backlog: VecDeque<Vec<u8>>,
+    backlog_bytes: usize,
+    max_backlog_bytes: usize,
+    /// Chunks dropped since the backlog last drained, for the log.
+    dropping: u64,
+}
+
+impl AudioOutlet {
+    fn new(tx: mpsc::Sender<Vec<u8>>, state: Arc<AppState>, rate: u32) -> Self {
+        Self {
+            tx,
+            state,
+            backlog: VecDeque::new(),
+            backlog_bytes: 0,
+            max_backlog_bytes: rate as usize * 2 * MAX_BACKLOG_MS / 1000,
+            dropping: 0,
+        }
+    }
+
+    fn send(&mut self, chunk: Vec<u8>) {
+        self.flush();
+        if !self.backlog.is_empty() {
+            self.push(chunk);
+            return;
+        }
+        if let Err(mpsc::error::TrySendError::Full(chunk)) = self.tx.try_send(chunk) {
+            self.push(chunk);
+        }
+    }
+
+    /// Tell the session the turn ended (VAD commit).
+    fn commit(&mut self) {
+        self.send(Vec::new());
+    }
+
+    /// Move backlogged chunks into the channel while it has room.
+    fn flush(&mut self) {
+        while let Some(chunk) = self.backlog.pop_front() {
+            let len = chunk.len();
+            match self.tx.try_send(chunk) {
+                Ok(()) => self.backlog_bytes -= len,
+                Err(mpsc::error::TrySendError::Full(chunk)) => {
+                    self.backlog.push_front(chunk);
+                    return;
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    self.backlog.clear();
+                    self.backlog_bytes = 0;
+                }
             }
         }
-        if attempt == 25 {
-            app_err!(
-                "[audio] {} dropped after retries: audio channel remained full",
-                context
+        if self.dropping > 0 {
+            app_log!(
+                "[audio] provider caught up after {} dropped chunks",
+                self.dropping
             );
+            self.dropping = 0;
+        }
+    }
+
+    fn push(&mut self, chunk: Vec<u8>) {
+        self.backlog_bytes += chunk.len();
+        self.backlog.push_back(chunk);
+        let mut dropped = 0u64;
+        while self.backlog_bytes > self.max_backlog_bytes {
+            let Some(pos) = self.backlog.iter().position(|c| !c.is_empty()) else {
+                break;
+            };
+            if let Some(old) = self.backlog.remove(pos) {
+                self.backlog_bytes -= old.len();
+                dropped += 1;
+            }
         }
+        if dropped == 0 {
+            return;
+        }
+        if self.dropping == 0 {
+            app_warn!("[audio] provider can't keep up; dropping oldest audio");
+        }
+        self.dropping += dropped;
+        record_dropped_chunks(&self.state, dropped);
     }
 }
 
+fn record_dropped_chunks(state: &AppState, dropped: u64) {
+    state
+        .audio_chunks_dropped
+        .fetch_add(dropped, std::sync::atomic::Ordering::Relaxed);
+    if let Ok(mut usage) = state.usage.lock() {
+        usage.chunks_dropped = usage.chunks_dropped.saturating_add(dropped);
+        usage.last_update_ms = now_ms();
+    }
+    state.add_session_usage(|session| {
+        session.chunks_dropped = session.chunks_dropped.saturating_add(dropped);
+        session.updated_ms = now_ms();
+    });
+}
+
 #[derive(Default)]
 struct ResamplerState {
     t: f64,
@@ -548,6 +814,13 @@ fn now_ms() -> u64 {
         .as_millis() as u64
 }
 
+/// Name of the system default input device, if there is one.
+pub fn default_input_device_name() -> Option<String> {
+    cpal::default_host()
+        .default_input_device()
+        .and_then(|d| d.name().ok())
+}
+
 /// List available input devices (name strings).
 pub fn list_input_devices() -> Vec<String> {
     let host = cpal::default_host();
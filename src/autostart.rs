@@ -0,0 +1,97 @@
+/// Per-user Run key; entries here start at sign-in without admin rights.
+#[cfg(windows)]
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(windows)]
+const RUN_VALUE: &str = "MangoChat";
+
+/// Each profile registers its own entry ("MangoChat.work").
+#[cfg(windows)]
+fn run_value() -> String {
+    crate::profile::scoped(RUN_VALUE)
+}
+
+/// Command registered under the Run key: this exe, started hidden to tray
+/// in the current profile.
+#[cfg(windows)]
+fn launch_command() -> Result<String, String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to resolve executable path: {}", e))?;
+    let mut command = format!("\"{}\" --minimized", exe.to_string_lossy());
+    if let Some(name) = crate::profile::name() {
+        command.push_str(&format!(" --profile {}", name));
+    }
+    Ok(command)
+}
+
+/// True when a MangoChat entry exists under the Run key.
+#[cfg(windows)]
+pub fn is_enabled() -> bool {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let mut size = 0u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            &HSTRING::from(run_value()),
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            Some(&mut size),
+        )
+    };
+    status.is_ok()
+}
+
+#[cfg(windows)]
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegDeleteValueW, RegOpenKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER,
+        KEY_SET_VALUE, REG_SZ,
+    };
+
+    let command = if enabled { Some(launch_command()?) } else { None };
+    let mut key = HKEY::default();
+    unsafe {
+        RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            0,
+            KEY_SET_VALUE,
+            &mut key,
+        )
+        .ok()
+        .map_err(|e| format!("Failed to open Run key: {}", e))?;
+
+        let result = if let Some(command) = command {
+            let wide: Vec<u16> = command.encode_utf16().chain(std::iter::once(0)).collect();
+            let bytes = std::slice::from_raw_parts(wide.as_ptr() as *const u8, wide.len() * 2);
+            RegSetValueExW(key, &HSTRING::from(run_value()), 0, REG_SZ, Some(bytes))
+                .ok()
+                .map_err(|e| format!("Failed to register startup entry: {}", e))
+        } else {
+            let status = RegDeleteValueW(key, &HSTRING::from(run_value()));
+            if status.is_ok() || !is_enabled() {
+                Ok(())
+            } else {
+                Err(format!("Failed to remove startup entry: {:?}", status))
+            }
+        };
+        let _ = RegCloseKey(key);
+        result?;
+    }
+    app_log!("[autostart] start with Windows {}", if enabled { "on" } else { "off" });
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn is_enabled() -> bool {
+    false
+}
+
+#[cfg(not(windows))]
+pub fn set_enabled(_enabled: bool) -> Result<(), String> {
+    Err("Start with Windows is only supported on Windows".into())
+}
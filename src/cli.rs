@@ -0,0 +1,114 @@
+use crate::settings::RunOverrides;
+use crate::state::AppEvent;
+
+/// Startup flags. Settings overrides last for this run only and are never
+/// written back to settings.json.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub provider: Option<String>,
+    pub language: Option<String>,
+    /// Monitor number ("2" -> \\.\DISPLAY2) or a full device id.
+    pub monitor: Option<String>,
+    pub start_recording: bool,
+    pub stop_recording: bool,
+    pub toggle_recording: bool,
+    /// Open the snip overlay.
+    pub snip: bool,
+    /// Start hidden; the tray icon brings the window back.
+    pub minimized: bool,
+    /// Use the mock provider and synthetic audio instead of the network and mic.
+    pub mock: bool,
+}
+
+const USAGE: &str = "mangochat [--provider <id>] [--language <code>] [--monitor <n|id>] [--start-recording] [--stop-recording] [--toggle-recording] [--snip] [--minimized] [--mock] [--profile <name>]";
+
+/// Parse `args` (without the program name). Bad flags are logged and skipped
+/// so a typo in a shortcut never stops the app from launching.
+pub fn parse(args: &[String]) -> CliArgs {
+    let mut cli = CliArgs::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((f, v)) => (f, Some(v.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| {
+            let v = inline.clone().or_else(|| iter.next().cloned());
+            let v = v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+            if v.is_none() {
+                app_err!("[cli] {} needs a value ({})", name, USAGE);
+            }
+            v
+        };
+        match flag {
+            "--provider" => {
+                if let Some(p) = value("--provider") {
+                    let p = p.to_ascii_lowercase();
+                    if matches!(p.as_str(), "openai" | "deepgram" | "elevenlabs" | "assemblyai") {
+                        cli.provider = Some(p);
+                    } else {
+                        app_err!("[cli] unknown provider \"{}\"", p);
+                    }
+                }
+            }
+            "--language" => cli.language = value("--language"),
+            "--monitor" => cli.monitor = value("--monitor"),
+            "--start-recording" => cli.start_recording = true,
+            "--stop-recording" => cli.stop_recording = true,
+            "--toggle-recording" => cli.toggle_recording = true,
+            "--snip" => cli.snip = true,
+            "--minimized" => cli.minimized = true,
+            "--mock" => cli.mock = true,
+            // Already applied by `profile::from_args`; it picks the instance.
+            "--profile" => {
+                let _ = value("--profile");
+            }
+            _ => app_err!("[cli] unknown argument \"{}\" ({})", arg, USAGE),
+        }
+    }
+    cli
+}
+
+impl CliArgs {
+    /// What a second invocation asks the running instance to do. A bare
+    /// relaunch just brings the window back.
+    pub fn forwarded_events(&self) -> Vec<AppEvent> {
+        if self.language.is_some() || self.monitor.is_some() || self.minimized || self.mock {
+            app_log!("[cli] --language, --monitor, --minimized and --mock only apply at launch");
+        }
+        let mut events = vec![];
+        if let Some(provider) = &self.provider {
+            events.push(AppEvent::TraySelectProvider(provider.clone()));
+        }
+        if self.start_recording {
+            events.push(AppEvent::SetRecording(true));
+        }
+        if self.stop_recording {
+            events.push(AppEvent::SetRecording(false));
+        }
+        if self.toggle_recording {
+            events.push(AppEvent::TrayToggleRecording);
+        }
+        if self.snip {
+            events.push(AppEvent::SnipTrigger);
+        }
+        if events.is_empty() {
+            events.push(AppEvent::ShowWindow);
+        }
+        events
+    }
+
+    pub fn run_overrides(&self) -> RunOverrides {
+        RunOverrides {
+            provider: self.provider.clone(),
+            language: self.language.clone(),
+            monitor_id: self.monitor.as_ref().map(|m| {
+                if m.chars().all(|c| c.is_ascii_digit()) {
+                    format!("\\\\.\\DISPLAY{}", m)
+                } else {
+                    m.clone()
+                }
+            }),
+        }
+    }
+}
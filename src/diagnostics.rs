@@ -3,12 +3,65 @@ use std::backtrace::Backtrace;
 use std::fs::{self, File};
 use std::io::{Seek, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Mutex, OnceLock};
 use zip::write::FileOptions;
 
+#[macro_export]
+macro_rules! app_log {
+    ($($arg:tt)*) => {{
+        if $crate::diagnostics::log_enabled(::log::Level::Info) {
+            $crate::diagnostics::emit("INFO", &format!($($arg)*));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! app_err {
+    ($($arg:tt)*) => {{
+        if $crate::diagnostics::log_enabled(::log::Level::Error) {
+            $crate::diagnostics::emit("ERROR", &format!($($arg)*));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! app_warn {
+    ($($arg:tt)*) => {{
+        if $crate::diagnostics::log_enabled(::log::Level::Warn) {
+            $crate::diagnostics::emit("WARN", &format!($($arg)*));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! app_debug {
+    ($($arg:tt)*) => {{
+        if $crate::diagnostics::log_enabled(::log::Level::Debug) {
+            $crate::diagnostics::emit("DEBUG", &format!($($arg)*));
+        }
+    }};
+}
+
 static LOG_FILE: OnceLock<Mutex<File>> = OnceLock::new();
+/// Where native crash minidumps go; resolved up front so the crash handler
+/// doesn't have to look anything up.
+#[cfg(windows)]
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Most verbose level written, as a `log::LevelFilter` discriminant.
+static LOG_LEVEL: AtomicUsize = AtomicUsize::new(log::LevelFilter::Info as usize);
+
+/// Write JSON lines instead of the plain `[ts] [LEVEL] msg` format.
+static LOG_JSON: AtomicBool = AtomicBool::new(false);
+/// Send all console output to stderr (stdout carries the MCP stdio stream).
+static CONSOLE_STDERR_ONLY: AtomicBool = AtomicBool::new(false);
+/// Recording session tagged onto JSON lines; 0 outside a session.
+static LOG_SESSION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Levels offered in settings, least to most verbose.
+pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
 
-const LOG_ROTATE_KEEP: usize = 5;
 const CRASH_LOG_KEEP: usize = 5;
 const SUPPORT_EMAIL: &str = "mangochathelp@gmail.com";
 
@@ -17,24 +70,24 @@ pub fn support_email() -> &'static str {
 }
 
 pub fn data_dir() -> Result<PathBuf, String> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Ok(dir.join("MangoChat"));
-    }
-    if let Some(home) = dirs::home_dir() {
-        return Ok(home.join(".mangochat"));
-    }
-    Err("Failed to resolve MangoChat data directory".into())
+    crate::profile::data_dir().ok_or_else(|| "Failed to resolve MangoChat data directory".into())
 }
 
 pub fn logs_dir() -> Result<PathBuf, String> {
     Ok(data_dir()?.join("logs"))
 }
 
-pub fn init_session_logging() -> Result<PathBuf, String> {
+/// Session logs kept in the logs folder: the window's and the background
+/// service's, each rotated on its own.
+const LOG_NAMES: [&str; 2] = ["app", "service"];
+
+/// Open `logs/<name>.log` for this process, rotating the previous one.
+/// `name` is "app", or "service" for the background service.
+pub fn init_session_logging(name: &str) -> Result<PathBuf, String> {
     let dir = logs_dir()?;
     fs::create_dir_all(&dir).map_err(|e| format!("Failed to create logs dir: {}", e))?;
-    rotate_logs(&dir)?;
-    let active = dir.join("app.log");
+    rotate_logs(&dir, name)?;
+    let active = dir.join(format!("{}.log", name));
     let file = File::options()
         .create(true)
         .append(true)
@@ -52,18 +105,38 @@ pub fn init_session_logging() -> Result<PathBuf, String> {
     Ok(active)
 }
 
-fn rotate_logs(dir: &Path) -> Result<(), String> {
-    for i in (1..LOG_ROTATE_KEEP).rev() {
-        let from = dir.join(format!("app.{}.log", i));
-        let to = dir.join(format!("app.{}.log", i + 1));
-        if from.exists() {
-            let _ = fs::remove_file(&to);
-            fs::rename(&from, &to).map_err(|e| format!("Failed to rotate log {}: {}", i, e))?;
-        }
+/// Index `n` of a rotated `<log>.<n>.log`.
+fn rotated_index(path: &Path, log: &str) -> Option<u32> {
+    let name = path.file_name()?.to_str()?;
+    name.strip_prefix(log)?
+        .strip_prefix('.')?
+        .strip_suffix(".log")?
+        .parse()
+        .ok()
+}
+
+/// Rotated session logs as (index, path), newest (`<log>.1.log`) first.
+fn rotated_logs(dir: &Path, log: &str) -> Vec<(u32, PathBuf)> {
+    let mut logs: Vec<(u32, PathBuf)> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| rotated_index(&e.path(), log).map(|i| (i, e.path())))
+                .collect()
+        })
+        .unwrap_or_default();
+    logs.sort_by_key(|(i, _)| *i);
+    logs
+}
+
+fn rotate_logs(dir: &Path, log: &str) -> Result<(), String> {
+    for (i, from) in rotated_logs(dir, log).into_iter().rev() {
+        let to = dir.join(format!("{}.{}.log", log, i + 1));
+        fs::rename(&from, &to).map_err(|e| format!("Failed to rotate log {}: {}", i, e))?;
     }
-    let active = dir.join("app.log");
+    let active = dir.join(format!("{}.log", log));
     if active.exists() {
-        let to = dir.join("app.1.log");
+        let to = dir.join(format!("{}.1.log", log));
         let _ = fs::remove_file(&to);
         fs::rename(&active, &to).map_err(|e| format!("Failed to rotate active log: {}", e))?;
     }
@@ -71,6 +144,11 @@ fn rotate_logs(dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Crash reports: `crash-*.log` from panics and `crash-*.dmp` minidumps.
+fn is_crash_file(name: &str) -> bool {
+    name.starts_with("crash-") && (name.ends_with(".log") || name.ends_with(".dmp"))
+}
+
 fn prune_crash_logs(dir: &Path, keep: usize) -> Result<(), String> {
     let mut crash_files: Vec<(std::time::SystemTime, PathBuf)> = vec![];
     for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read logs dir: {}", e))? {
@@ -82,7 +160,7 @@ fn prune_crash_logs(dir: &Path, keep: usize) -> Result<(), String> {
         let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
             continue;
         };
-        if !(name.starts_with("crash-") && name.ends_with(".log")) {
+        if !is_crash_file(name) {
             continue;
         }
         let modified = entry
@@ -99,9 +177,196 @@ fn prune_crash_logs(dir: &Path, keep: usize) -> Result<(), String> {
     Ok(())
 }
 
+/// Limits applied to rotated session logs. A zero disables that limit.
+pub struct LogRetention {
+    pub max_files: u32,
+    pub max_total_mb: u32,
+    pub max_age_days: u32,
+}
+
+/// Delete rotated logs beyond the file count, total size or age limits,
+/// applied to the window's and the service's logs separately. The active
+/// logs always stay. Returns the number of files removed.
+pub fn prune_logs(limits: &LogRetention) -> Result<usize, String> {
+    let dir = logs_dir()?;
+    let now = std::time::SystemTime::now();
+    Ok(LOG_NAMES
+        .iter()
+        .map(|log| prune_rotated_logs(&dir, log, limits, now))
+        .sum())
+}
+
+fn prune_rotated_logs(
+    dir: &Path,
+    log: &str,
+    limits: &LogRetention,
+    now: std::time::SystemTime,
+) -> usize {
+    let max_age = std::time::Duration::from_secs(limits.max_age_days as u64 * 24 * 60 * 60);
+    let mut total = fs::metadata(dir.join(format!("{}.log", log)))
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let max_total = limits.max_total_mb as u64 * 1024 * 1024;
+    let mut removed = 0;
+    for (kept, (_, path)) in rotated_logs(dir, log).into_iter().enumerate() {
+        let meta = fs::metadata(&path).ok();
+        let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+        let age = meta
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| now.duration_since(t).ok())
+            .unwrap_or_default();
+        let too_many = limits.max_files > 0 && kept as u32 >= limits.max_files;
+        let too_old = limits.max_age_days > 0 && age > max_age;
+        let too_big = limits.max_total_mb > 0 && total + size > max_total;
+        if too_many || too_old || too_big {
+            if fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        } else {
+            total += size;
+        }
+    }
+    removed
+}
+
+/// Bytes used by everything in the logs folder.
+pub fn logs_size_bytes() -> u64 {
+    let Ok(dir) = logs_dir() else {
+        return 0;
+    };
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Path of the active session log (`logs/app.log`).
+pub fn session_log_path() -> Result<PathBuf, String> {
+    Ok(logs_dir()?.join("app.log"))
+}
+
+/// Last `max_lines` lines of the active session log, oldest first.
+pub fn read_session_log_tail(max_lines: usize) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(session_log_path()?)
+        .map_err(|e| format!("Failed to read app log: {}", e))?;
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    Ok(lines[start..].iter().map(|l| l.to_string()).collect())
+}
+
+/// Change the log level while running. Applies to the `app_*!` macros and to
+/// dependencies that log through the `log` crate.
+pub fn set_log_level(level: &str) {
+    let filter = level
+        .parse::<log::LevelFilter>()
+        .unwrap_or(log::LevelFilter::Info);
+    let previous = LOG_LEVEL.swap(filter as usize, Ordering::Relaxed);
+    log::set_max_level(filter);
+    if previous != filter as usize {
+        append_line("INFO", &format!("[diagnostics] log level set to {}", level));
+    }
+}
+
+pub fn log_enabled(level: log::Level) -> bool {
+    level as usize <= LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Sends `log` crate records into the session log.
+struct SessionLogger;
+
+impl log::Log for SessionLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        log_enabled(metadata.level())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        emit(
+            record.level().as_str(),
+            &format!("[{}] {}", record.target(), record.args()),
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route the `log` crate into the session log at the default level.
+pub fn init_logger() {
+    static LOGGER: SessionLogger = SessionLogger;
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+pub fn set_console_stderr_only(enabled: bool) {
+    CONSOLE_STDERR_ONLY.store(enabled, Ordering::Relaxed);
+}
+
+/// Print to the console and append to the session log. Errors and warnings
+/// go to stderr, everything else to stdout.
+pub fn emit(level: &str, msg: &str) {
+    if CONSOLE_STDERR_ONLY.load(Ordering::Relaxed) || matches!(level, "ERROR" | "WARN") {
+        eprintln!("{}", msg);
+    } else {
+        println!("{}", msg);
+    }
+    append_line(level, msg);
+}
+
+pub fn set_json_logging(enabled: bool) {
+    LOG_JSON.store(enabled, Ordering::Relaxed);
+}
+
+/// Tag following log lines with a recording session id (0 clears it).
+pub fn set_log_session(session_id: u64) {
+    LOG_SESSION_ID.store(session_id, Ordering::Relaxed);
+}
+
+/// One JSON object per line: timestamp, level, module (the leading `[tag]`),
+/// session_id, message and any `key=value` pairs in the message as fields.
+fn json_line(level: &str, msg: &str) -> String {
+    let (module, text) = match msg.strip_prefix('[').and_then(|m| m.split_once("] ")) {
+        Some((module, rest)) if !module.contains(' ') => (module, rest),
+        _ => ("app", msg),
+    };
+    let fields: serde_json::Map<String, serde_json::Value> = text
+        .split_whitespace()
+        .filter_map(|token| token.split_once('='))
+        .filter(|(k, _)| {
+            !k.is_empty() && k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+        .map(|(k, v)| (k.to_string(), serde_json::Value::String(v.to_string())))
+        .collect();
+    let session_id = match LOG_SESSION_ID.load(Ordering::Relaxed) {
+        0 => serde_json::Value::Null,
+        id => id.into(),
+    };
+    let line = serde_json::json!({
+        "timestamp": Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        "level": level,
+        "module": module,
+        "session_id": session_id,
+        "message": text,
+        "fields": fields,
+    });
+    format!("{}\n", line)
+}
+
 pub fn append_line(level: &str, msg: &str) {
-    let ts = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-    let line = format!("[{}] [{}] {}\n", ts, level, msg);
+    let line = if LOG_JSON.load(Ordering::Relaxed) {
+        json_line(level, msg)
+    } else {
+        let ts = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+        format!("[{}] [{}] {}\n", ts, level, msg)
+    };
     if let Some(lock) = LOG_FILE.get() {
         if let Ok(mut f) = lock.lock() {
             let _ = f.write_all(line.as_bytes());
@@ -120,6 +385,106 @@ pub fn install_panic_hook() {
         let _ = write_crash_file(&panic_msg, &bt.to_string());
         previous(info);
     }));
+    install_native_crash_handler();
+}
+
+/// Write a minidump to the logs folder when the process dies from an
+/// unhandled native exception (access violation, stack overflow, ...).
+#[cfg(windows)]
+fn install_native_crash_handler() {
+    use windows::Win32::System::Diagnostics::Debug::SetUnhandledExceptionFilter;
+
+    let Ok(dir) = logs_dir() else {
+        return;
+    };
+    let _ = CRASH_DIR.set(dir);
+    unsafe {
+        SetUnhandledExceptionFilter(Some(write_minidump));
+    }
+}
+
+#[cfg(not(windows))]
+fn install_native_crash_handler() {}
+
+#[cfg(windows)]
+unsafe extern "system" fn write_minidump(
+    info: *const windows::Win32::System::Diagnostics::Debug::EXCEPTION_POINTERS,
+) -> i32 {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::{BOOL, HANDLE};
+    use windows::Win32::System::Diagnostics::Debug::{
+        MiniDumpWithThreadInfo, MiniDumpWriteDump, MINIDUMP_EXCEPTION_INFORMATION,
+    };
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+    };
+    // EXCEPTION_CONTINUE_SEARCH: let Windows finish tearing the process down.
+    const CONTINUE_SEARCH: i32 = 0;
+
+    let Some(dir) = CRASH_DIR.get() else {
+        return CONTINUE_SEARCH;
+    };
+    let path = dir.join(format!("crash-{}.dmp", Local::now().format("%Y%m%d-%H%M%S")));
+    let Ok(file) = File::create(&path) else {
+        return CONTINUE_SEARCH;
+    };
+    let exception = MINIDUMP_EXCEPTION_INFORMATION {
+        ThreadId: GetCurrentThreadId(),
+        ExceptionPointers: info as *mut _,
+        ClientPointers: BOOL(0),
+    };
+    let written = MiniDumpWriteDump(
+        GetCurrentProcess(),
+        GetCurrentProcessId(),
+        HANDLE(file.as_raw_handle()),
+        MiniDumpWithThreadInfo,
+        Some(&exception),
+        None,
+        None,
+    );
+    drop(file);
+    match written {
+        Ok(()) => append_line("CRASH", &format!("minidump written to {}", path.display())),
+        Err(e) => append_line("CRASH", &format!("minidump failed: {}", e)),
+    }
+    CONTINUE_SEARCH
+}
+
+/// Marker that exists while the app is running. Finding it at startup means
+/// the previous run crashed or was killed.
+fn running_marker_path() -> Result<PathBuf, String> {
+    Ok(logs_dir()?.join("running.marker"))
+}
+
+/// Create the running marker. Returns true if the last run didn't exit cleanly.
+pub fn begin_run_marker() -> bool {
+    let Ok(path) = running_marker_path() else {
+        return false;
+    };
+    let crashed = path.exists();
+    if let Err(e) = fs::write(&path, std::process::id().to_string()) {
+        app_err!("[diagnostics] failed to write run marker: {}", e);
+    }
+    crashed
+}
+
+/// Remove the running marker on a clean exit.
+pub fn end_run_marker() {
+    if let Ok(path) = running_marker_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Newest crash report (log or minidump) in the logs folder, if any.
+pub fn latest_crash_file() -> Option<PathBuf> {
+    let dir = logs_dir().ok()?;
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| e.file_name().to_str().is_some_and(is_crash_file))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .max_by_key(|(t, _)| *t)
+        .map(|(_, p)| p)
 }
 
 fn write_crash_file(message: &str, backtrace: &str) -> Result<PathBuf, String> {
@@ -158,7 +523,123 @@ pub fn default_export_zip_path() -> Result<PathBuf, String> {
     Ok(exports_dir.join(zip_name))
 }
 
-pub fn export_diagnostics_zip_to(zip_path: &Path) -> Result<PathBuf, String> {
+/// Settings keys whose values never leave the machine. `api_keys` keeps its
+/// provider names so the bundle still shows which providers were configured.
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("api_key")
+        || key.contains("token")
+        || key.contains("secret")
+        || key.contains("password")
+        || key == "update_feed_url_override"
+}
+
+fn redact_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_sensitive_key(key) {
+                    mask_value(v);
+                } else {
+                    redact_value(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+fn mask_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(s) if !s.trim().is_empty() => {
+            *s = "<redacted>".into();
+        }
+        serde_json::Value::Object(map) => map.values_mut().for_each(mask_value),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(mask_value),
+        _ => {}
+    }
+}
+
+/// Settings as JSON with API keys and other secrets masked.
+pub fn redacted_settings_json(settings: &crate::settings::Settings) -> Result<String, String> {
+    let mut value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    redact_value(&mut value);
+    serde_json::to_string_pretty(&value).map_err(|e| format!("Failed to serialize settings: {}", e))
+}
+
+/// Replace any literal API key that slipped into a log with a marker.
+fn scrub_secrets(text: &str, secrets: &[&str]) -> String {
+    let mut out = text.to_string();
+    for secret in secrets {
+        if secret.len() >= 8 {
+            out = out.replace(secret, "<redacted>");
+        }
+    }
+    out
+}
+
+fn os_version() -> String {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        if let Ok(out) = std::process::Command::new("cmd")
+            .args(["/C", "ver"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+        {
+            let ver = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            if !ver.is_empty() {
+                return ver;
+            }
+        }
+    }
+    std::env::consts::OS.to_string()
+}
+
+/// OS, audio devices and monitors, as plain text for bug reports. The UI
+/// describes the monitors, since it owns the monitor layout.
+pub fn system_info(settings: &crate::settings::Settings, monitors: &[String]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "app_version={}\nos={}\narch={}\n",
+        env!("CARGO_PKG_VERSION"),
+        os_version(),
+        std::env::consts::ARCH
+    ));
+
+    out.push_str("\n[audio]\n");
+    out.push_str(&format!(
+        "selected_mic={}\n",
+        if settings.mic_device.trim().is_empty() {
+            "(system default)"
+        } else {
+            settings.mic_device.as_str()
+        }
+    ));
+    out.push_str(&format!(
+        "default_input={}\n",
+        crate::audio::default_input_device_name().unwrap_or_else(|| "(none)".into())
+    ));
+    for name in crate::audio::list_input_devices() {
+        out.push_str(&format!("input={}\n", name));
+    }
+
+    out.push_str("\n[monitors]\n");
+    for line in monitors {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+pub fn export_diagnostics_zip_to(
+    zip_path: &Path,
+    settings: &crate::settings::Settings,
+    monitors: &[String],
+) -> Result<PathBuf, String> {
     if let Some(parent) = zip_path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create export dir: {}", e))?;
@@ -177,7 +658,7 @@ pub fn export_diagnostics_zip_to(zip_path: &Path) -> Result<PathBuf, String> {
         &mut zip,
         "manifest.txt",
         &format!(
-            "Mango Chat diagnostics\nversion={}\ncreated={}\nsupport_email={}\n",
+            "Mango Chat diagnostics\nversion={}\ncreated={}\nsupport_email={}\nredacted=api keys and tokens\n",
             env!("CARGO_PKG_VERSION"),
             Local::now().to_rfc3339(),
             SUPPORT_EMAIL
@@ -185,19 +666,22 @@ pub fn export_diagnostics_zip_to(zip_path: &Path) -> Result<PathBuf, String> {
         opts,
     )?;
 
-    if let Ok(path) = crate::settings::settings_path() {
-        add_file(&mut zip, &path, "settings.json", opts)?;
-    }
-    if let Ok(path) = crate::usage::usage_path() {
-        add_file(&mut zip, &path, "usage.jsonl", opts)?;
-    }
-    if let Ok(path) = crate::usage::session_usage_path() {
-        add_file(&mut zip, &path, "usage-session.jsonl", opts)?;
-    }
-    if let Ok(path) = crate::usage::provider_totals_path() {
-        add_file(&mut zip, &path, "usage-provider.json", opts)?;
-    }
+    add_text(&mut zip, "settings.json", &redacted_settings_json(settings)?, opts)?;
+    add_text(&mut zip, "system.txt", &system_info(settings, monitors), opts)?;
 
+    let usage = serde_json::json!({
+        "totals": crate::usage::load_usage(),
+        "providers": crate::usage::load_provider_totals(),
+        "recent_sessions": crate::usage::load_recent_sessions(50),
+    });
+    add_text(
+        &mut zip,
+        "usage.json",
+        &serde_json::to_string_pretty(&usage).unwrap_or_default(),
+        opts,
+    )?;
+
+    let secrets: Vec<&str> = settings.api_keys.values().map(|k| k.trim()).collect();
     if let Ok(logs) = collect_recent_logs(5) {
         for path in logs {
             let filename = path
@@ -205,7 +689,11 @@ pub fn export_diagnostics_zip_to(zip_path: &Path) -> Result<PathBuf, String> {
                 .and_then(|s| s.to_str())
                 .unwrap_or("unknown.log")
                 .to_string();
-            add_file(&mut zip, &path, &format!("logs/{}", filename), opts)?;
+            let Ok(bytes) = fs::read(&path) else {
+                continue;
+            };
+            let text = scrub_secrets(&String::from_utf8_lossy(&bytes), &secrets);
+            add_text(&mut zip, &format!("logs/{}", filename), &text, opts)?;
         }
     }
 
@@ -229,9 +717,9 @@ fn collect_recent_logs(limit: usize) -> Result<Vec<PathBuf>, String> {
         let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
             continue;
         };
-        if !(name.starts_with("app") && name.ends_with(".log")
-            || name.starts_with("crash-") && name.ends_with(".log"))
-        {
+        // Minidumps hold raw process memory, so they stay out of the bundle.
+        let session_log = LOG_NAMES.iter().any(|log| name.starts_with(log));
+        if !(name.ends_with(".log") && (session_log || name.starts_with("crash-"))) {
             continue;
         }
         let modified = entry
@@ -257,34 +745,115 @@ fn add_text<W: Write + Seek>(
         .map_err(|e| format!("Failed to write {}: {}", name, e))
 }
 
-fn add_file<W: Write + Seek>(
-    zip: &mut zip::ZipWriter<W>,
-    path: &Path,
-    name: &str,
-    opts: FileOptions,
-) -> Result<(), String> {
-    if !path.exists() {
-        return Ok(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+    fn log_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mangochat-logs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for file in [
+            "app.log",
+            "app.1.log",
+            "app.2.log",
+            "app.3.log",
+            "service.1.log",
+        ] {
+            fs::write(dir.join(file), "line\n").unwrap();
+        }
+        dir
     }
-    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
-    zip.start_file(name, opts)
-        .map_err(|e| format!("Failed to add {}: {}", name, e))?;
-    zip.write_all(&bytes)
-        .map_err(|e| format!("Failed to write {}: {}", name, e))
-}
 
-#[macro_export]
-macro_rules! app_log {
-    ($($arg:tt)*) => {{
-        ::std::println!($($arg)*);
-        $crate::diagnostics::append_line("INFO", &format!($($arg)*));
-    }};
-}
+    fn remaining(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
 
-#[macro_export]
-macro_rules! app_err {
-    ($($arg:tt)*) => {{
-        ::std::eprintln!($($arg)*);
-        $crate::diagnostics::append_line("ERROR", &format!($($arg)*));
-    }};
+    #[test]
+    fn prune_keeps_newest_rotated_logs_up_to_count() {
+        let dir = log_dir("count");
+        let limits = LogRetention {
+            max_files: 1,
+            max_total_mb: 0,
+            max_age_days: 0,
+        };
+        let now = std::time::SystemTime::now();
+        assert_eq!(prune_rotated_logs(&dir, "app", &limits, now), 2);
+        assert_eq!(remaining(&dir), ["app.1.log", "app.log", "service.1.log"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_removes_rotated_logs_past_max_age() {
+        let dir = log_dir("age");
+        let limits = LogRetention {
+            max_files: 0,
+            max_total_mb: 0,
+            max_age_days: 7,
+        };
+        let now = std::time::SystemTime::now();
+        assert_eq!(prune_rotated_logs(&dir, "app", &limits, now + DAY), 0);
+        assert_eq!(prune_rotated_logs(&dir, "app", &limits, now + 8 * DAY), 3);
+        assert_eq!(remaining(&dir), ["app.log", "service.1.log"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sensitive_keys_match_in_any_case() {
+        let sensitive = [
+            "API_KEY_deepgram",
+            "AuthToken",
+            "Client_Secret",
+            "OBS_PASSWORD",
+            "Update_Feed_Url_Override",
+        ];
+        for key in sensitive {
+            assert!(is_sensitive_key(key), "{} should be sensitive", key);
+        }
+        for key in ["provider", "obs_port", "key_bindings", "my_api_key"] {
+            assert!(!is_sensitive_key(key), "{} should not be sensitive", key);
+        }
+    }
+
+    #[test]
+    fn redaction_masks_nested_mixed_case_keys() {
+        let mut value = serde_json::json!({
+            "provider": "openai",
+            "Integrations": [{"Webhook_Token": "abc123", "url": "https://example.com"}],
+            "Obs_Password": "",
+        });
+        redact_value(&mut value);
+        assert_eq!(value["provider"], "openai");
+        assert_eq!(value["Integrations"][0]["Webhook_Token"], "<redacted>");
+        assert_eq!(value["Integrations"][0]["url"], "https://example.com");
+        assert_eq!(value["Obs_Password"], "");
+    }
+
+    #[test]
+    fn scrub_secrets_skips_secrets_shorter_than_eight_chars() {
+        let log = "connect key=sk-12345678 pass=hunter2 seven=1234567";
+        let scrubbed = scrub_secrets(log, &["sk-12345678", "hunter2", "1234567", ""]);
+        assert_eq!(
+            scrubbed,
+            "connect key=<redacted> pass=hunter2 seven=1234567"
+        );
+    }
+
+    #[test]
+    fn scrub_secrets_replaces_every_occurrence() {
+        let log = "a=12345678\nb=12345678\n";
+        assert_eq!(
+            scrub_secrets(log, &["12345678"]),
+            "a=<redacted>\nb=<redacted>\n"
+        );
+    }
 }
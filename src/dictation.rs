@@ -0,0 +1,425 @@
+// Dictation sessions, shared by the window and the background service: the
+// checks a new session must pass (with the foreground app's per-app
+// override), the audio input and provider session of each leg, the
+// max-length timer, budget alerts and the usage row. It reports what
+// happened through return values; each frontend shows that its own way and
+// keeps its own recording flag.
+
+use crate::audio::{self, AudioCapture};
+use crate::fake_audio::FakeSource;
+use crate::provider::session::{run_session, TranscriptSink};
+use crate::provider::{create_provider, ProviderSettings};
+use crate::remote_mic::RemoteMic;
+use crate::settings::Settings;
+use crate::state::{AppEvent, AppState, SessionUsage};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::Sender as EventSender;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Why a session didn't start.
+#[derive(Debug)]
+pub enum Refusal {
+    /// The session's provider has no key; `any_key` when another one does.
+    NoKey {
+        any_key: bool,
+    },
+    MicUnavailable,
+    /// `provider`'s monthly budget is spent and the settings block new
+    /// sessions. `alert` is set when this check crossed the 100% mark.
+    BudgetReached {
+        provider: String,
+        alert: Option<String>,
+    },
+    /// Mic or voice notes folder error, shown as is.
+    Failed(String),
+}
+
+impl Refusal {
+    /// Status line text and state for the refusal.
+    pub fn status(&self) -> (String, &'static str) {
+        match self {
+            Refusal::NoKey { any_key: true } => (
+                "Select a default provider with an API key in Settings".into(),
+                "idle",
+            ),
+            Refusal::NoKey { any_key: false } => {
+                ("Set up provider keys in Settings".into(), "idle")
+            }
+            Refusal::MicUnavailable => ("Device unavailable. Change in Settings.".into(), "error"),
+            Refusal::BudgetReached { provider, .. } => (
+                format!(
+                    "{} monthly budget reached. New sessions resume next month.",
+                    crate::provider::display_name(provider)
+                ),
+                "error",
+            ),
+            Refusal::Failed(message) => (message.clone(), "error"),
+        }
+    }
+}
+
+/// A session that passed its checks, ready for `Dictation::start`.
+pub struct Plan {
+    pub provider_id: String,
+    language: String,
+    vad_mode: String,
+    notes_dir: Option<PathBuf>,
+    /// Budget threshold the checks crossed, to announce.
+    pub budget_alert: Option<String>,
+}
+
+/// Audio input, timers and usage bookkeeping for the dictation sessions of
+/// one frontend.
+pub struct Dictation {
+    state: Arc<AppState>,
+    event_tx: EventSender<AppEvent>,
+    /// Log prefix of the frontend, e.g. "ui" or "service".
+    log_tag: &'static str,
+    audio_capture: Option<AudioCapture>,
+    /// Synthetic mic standing in for `audio_capture` under --mock.
+    fake_audio: Option<FakeSource>,
+    /// Language the current session started with, kept for provider switches.
+    language: String,
+    /// Matches the max-length timer of the session in progress.
+    limit_token: u64,
+    /// Highest budget threshold (80 or 100) already announced, per provider,
+    /// with the month it applies to.
+    budget_alerts: HashMap<String, (String, u8)>,
+}
+
+/// True when a microphone is configured but not connected.
+pub fn mic_unavailable(settings: &Settings) -> bool {
+    if settings.mic_device.trim().is_empty() {
+        return false;
+    }
+    !audio::list_input_devices()
+        .iter()
+        .any(|d| d == &settings.mic_device)
+}
+
+/// Key a session with `provider_id` connects with; "mock" stands in for a
+/// missing one under --mock.
+fn session_key(settings: &Settings, provider_id: &str) -> String {
+    let key = settings.api_key_for(provider_id).trim();
+    if key.is_empty() && crate::provider::mock_enabled() {
+        return "mock".into();
+    }
+    key.to_string()
+}
+
+impl Dictation {
+    pub fn new(
+        state: Arc<AppState>,
+        event_tx: EventSender<AppEvent>,
+        log_tag: &'static str,
+    ) -> Self {
+        Self {
+            state,
+            event_tx,
+            log_tag,
+            audio_capture: None,
+            fake_audio: None,
+            language: String::new(),
+            limit_token: 0,
+            budget_alerts: HashMap::new(),
+        }
+    }
+
+    /// Pick the provider, language and VAD mode for a new session, with the
+    /// foreground app's override, and run the checks it must pass.
+    /// `local_mic` is false when the phone feeds the session.
+    pub fn plan(&mut self, settings: &Settings, local_mic: bool) -> Result<Plan, Refusal> {
+        let app_override = crate::typing::foreground_process_name()
+            .and_then(|process| {
+                let o = settings.override_for(&process).cloned()?;
+                app_log!("[{}] per-app override for {}", self.log_tag, process);
+                Some(o)
+            })
+            .unwrap_or_default();
+        let (provider_id, language, vad_mode) = settings.session_options(&app_override);
+        crate::typing::set_paste_output(app_override.output == "paste");
+
+        if provider_id.trim().is_empty() || session_key(settings, &provider_id).is_empty() {
+            return Err(Refusal::NoKey {
+                any_key: settings.has_any_api_key(),
+            });
+        }
+        if local_mic && !crate::provider::mock_enabled() && mic_unavailable(settings) {
+            return Err(Refusal::MicUnavailable);
+        }
+        let budget_alert = self.check_budget(settings, &provider_id, true)?;
+        let notes_dir = if settings.voice_notes_mode {
+            let dir = crate::notes::notes_dir(settings)
+                .map_err(|e| Refusal::Failed(format!("Voice notes: {}", e)))?;
+            Some(dir)
+        } else {
+            None
+        };
+        Ok(Plan {
+            provider_id,
+            language,
+            vad_mode,
+            notes_dir,
+            budget_alert,
+        })
+    }
+
+    /// Start a planned session: start cue, VAD mode, max-length timer and the
+    /// first provider leg. Returns the id of the leg's usage row.
+    pub fn start(
+        &mut self,
+        settings: &Settings,
+        runtime: &tokio::runtime::Runtime,
+        plan: Plan,
+        remote: Option<&RemoteMic>,
+    ) -> Result<u64, Refusal> {
+        if let Err(e) = crate::start_cue::play_start_cue(&settings.start_cue) {
+            app_err!("[{}] start cue error: {}", self.log_tag, e);
+        }
+        let mode = match plan.vad_mode.as_str() {
+            "lenient" => 1,
+            _ => 0,
+        };
+        self.state.vad_mode.store(mode, Ordering::SeqCst);
+
+        self.limit_token = self.limit_token.saturating_add(1);
+        let token = self.limit_token;
+        let minutes = settings.max_session_length_minutes.clamp(1, 120);
+        let limit_tx = self.event_tx.clone();
+        runtime.spawn(async move {
+            tokio::time::sleep(Duration::from_secs(minutes.saturating_mul(60))).await;
+            let _ = limit_tx.send(AppEvent::SessionMaxDurationReached { token, minutes });
+        });
+
+        self.language = plan.language;
+        self.start_leg(settings, runtime, &plan.provider_id, plan.notes_dir, remote)
+    }
+
+    /// True for the max-length timer of the session in progress.
+    pub fn is_current_limit(&self, token: u64) -> bool {
+        token == self.limit_token
+    }
+
+    /// Start audio input and a provider session for `provider_id`, fed by
+    /// the phone when `remote` is set, else the local mic (synthetic audio
+    /// under --mock). Returns the id of the leg's usage row.
+    pub fn start_leg(
+        &mut self,
+        settings: &Settings,
+        runtime: &tokio::runtime::Runtime,
+        provider_id: &str,
+        notes_dir: Option<PathBuf>,
+        remote: Option<&RemoteMic>,
+    ) -> Result<u64, Refusal> {
+        let (audio_tx, audio_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
+        if let Ok(mut tx) = self.state.audio_tx.lock() {
+            *tx = Some(audio_tx.clone());
+        }
+        self.state.session_active.store(true, Ordering::SeqCst);
+
+        let provider = create_provider(provider_id);
+        let provider_settings = ProviderSettings {
+            api_key: session_key(settings, provider_id),
+            model: settings.model.clone(),
+            transcription_model: settings.transcription_model.clone(),
+            language: self.language.clone(),
+            pre_commit_silence_ms: None,
+            commit_flush_timeout_ms: None,
+        }
+        .with_commit_timing(settings, provider_id);
+        let sample_rate = provider.sample_rate_hint();
+
+        if crate::provider::mock_enabled() {
+            app_log!("[{}] using synthetic audio", self.log_tag);
+            self.fake_audio = Some(FakeSource::start(
+                crate::fake_audio::DEFAULT_SCRIPT,
+                audio::start_external(
+                    crate::fake_audio::SAMPLE_RATE,
+                    audio_tx,
+                    self.state.clone(),
+                    sample_rate,
+                ),
+            ));
+        } else if let Some(remote) = remote {
+            app_log!("[{}] using the phone remote microphone", self.log_tag);
+            remote.attach(audio::start_external(
+                crate::remote_mic::SAMPLE_RATE,
+                audio_tx,
+                self.state.clone(),
+                sample_rate,
+            ));
+        } else {
+            let mic = Some(settings.mic_device.as_str()).filter(|m| !m.is_empty());
+            match AudioCapture::start(
+                mic,
+                audio_tx,
+                self.event_tx.clone(),
+                self.state.clone(),
+                sample_rate,
+            ) {
+                Ok(capture) => {
+                    app_log!("[{}] audio capture started", self.log_tag);
+                    self.audio_capture = Some(capture);
+                }
+                Err(e) => {
+                    app_err!("[{}] audio capture error: {}", self.log_tag, e);
+                    return Err(Refusal::Failed(format!("Mic error: {}", e)));
+                }
+            }
+        }
+        crate::metrics::record_session(provider_id);
+
+        let gen = self.state.session_gen.fetch_add(1, Ordering::SeqCst) + 1;
+        self.state
+            .ws_send_latency_peak_us
+            .store(0, Ordering::Relaxed);
+        let session_id = self.begin_usage(settings, provider_id);
+
+        let (audio_rx, sink) = match notes_dir {
+            Some(dir) => {
+                let (audio_rx, notes) = crate::notes::start_recorder(
+                    runtime,
+                    audio_rx,
+                    sample_rate,
+                    dir,
+                    provider_id.to_string(),
+                    self.event_tx.clone(),
+                );
+                (audio_rx, TranscriptSink::VoiceNote(notes))
+            }
+            None => (audio_rx, TranscriptSink::Dictation),
+        };
+        let state = self.state.clone();
+        let drained_tx = self.event_tx.clone();
+        let session = run_session(
+            provider,
+            self.event_tx.clone(),
+            state.clone(),
+            provider_settings,
+            audio_rx,
+            settings.provider_inactivity_timeout_secs,
+            sink,
+        );
+        runtime.spawn(async move {
+            session.await;
+            if state.session_gen.load(Ordering::SeqCst) == gen {
+                state.session_active.store(false, Ordering::SeqCst);
+                if let Ok(mut tx) = state.audio_tx.lock() {
+                    *tx = None;
+                }
+                state.hotkey_recording.store(false, Ordering::SeqCst);
+            }
+            let _ = drained_tx.send(AppEvent::ProviderLegDrained { gen });
+        });
+        Ok(session_id)
+    }
+
+    /// Close the audio input. Its session gets a closed audio channel, sends
+    /// its trailing commit and finishes on its own.
+    pub fn stop_input(&mut self, remote: Option<&RemoteMic>) {
+        self.audio_capture = None;
+        self.fake_audio = None;
+        if let Some(remote) = remote {
+            remote.detach();
+        }
+    }
+
+    /// Start the usage row (and the log session tag) for `provider_id`.
+    /// Returns the row's session id.
+    pub fn begin_usage(&self, settings: &Settings, provider_id: &str) -> u64 {
+        let now = now_ms();
+        if let Ok(mut totals) = self.state.usage.lock() {
+            totals.provider = provider_id.to_string();
+            totals.model = settings.model.clone();
+            totals.last_update_ms = now;
+        }
+        crate::diagnostics::set_log_session(now);
+        self.state.session_usage.store(Arc::new(SessionUsage {
+            session_id: now,
+            provider: provider_id.to_string(),
+            model: settings.model.clone(),
+            started_ms: now,
+            updated_ms: now,
+            ..Default::default()
+        }));
+        now
+    }
+
+    /// Take the running session's usage row, leaving none.
+    pub fn end_usage(&self) -> Arc<SessionUsage> {
+        crate::diagnostics::set_log_session(0);
+        self.state.session_usage.swap(Default::default())
+    }
+
+    /// Record a finished usage row in the usage log. Returns the budget
+    /// threshold it crossed, to announce.
+    pub fn close_usage_row(
+        &mut self,
+        settings: &Settings,
+        session: &SessionUsage,
+    ) -> Option<String> {
+        if session.started_ms == 0 || session.bytes_sent == 0 {
+            return None;
+        }
+        crate::persist::append_session(session);
+        self.check_budget(settings, &session.provider, false)
+            .ok()
+            .flatten()
+    }
+
+    /// Check `provider` against its monthly budget. Returns the alert to
+    /// announce the first time a month crosses 80% or 100%. With `enforce`,
+    /// a spent budget refuses new sessions when the settings say so.
+    pub fn check_budget(
+        &mut self,
+        settings: &Settings,
+        provider: &str,
+        enforce: bool,
+    ) -> Result<Option<String>, Refusal> {
+        let Some(limit) = settings.monthly_budgets.get(provider).copied() else {
+            return Ok(None);
+        };
+        let now = now_ms();
+        let Some((level, spent)) = crate::usage::budget_mark(provider, limit, now) else {
+            return Ok(None);
+        };
+        let month = crate::usage::month_key(now);
+        let already = self
+            .budget_alerts
+            .get(provider)
+            .filter(|(m, _)| *m == month)
+            .map(|(_, l)| *l)
+            .unwrap_or(0);
+        let mut alert = None;
+        if level > already {
+            self.budget_alerts
+                .insert(provider.to_string(), (month, level));
+            let text = format!(
+                "{} has used {}% of its ${:.2} monthly budget (${:.2})",
+                crate::provider::display_name(provider),
+                level,
+                limit,
+                spent
+            );
+            app_log!("[usage] {}", text);
+            alert = Some(text);
+        }
+        if enforce && level == 100 && settings.budget_block_at_limit {
+            return Err(Refusal::BudgetReached {
+                provider: provider.to_string(),
+                alert,
+            });
+        }
+        Ok(alert)
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,170 @@
+// Synthetic microphone for the --mock flag: voiced bursts and pauses, fed in
+// real time through `audio::start_external` so they pass the same VAD,
+// resampling and visualizer as a real mic.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::SyncSender;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub const SAMPLE_RATE: u32 = 16_000;
+const FRAME_MS: u32 = 20;
+/// Voice fundamental and the formant peaks its harmonics are shaped by.
+const PITCH_HZ: f32 = 120.0;
+const FORMANTS_HZ: [(f32, f32); 3] = [(500.0, 120.0), (1500.0, 200.0), (2500.0, 300.0)];
+/// Syllable rate of the loudness envelope.
+const SYLLABLE_HZ: f32 = 4.0;
+const SPEECH_PEAK: f32 = 0.3;
+const NOISE_FLOOR: f32 = 0.002;
+
+/// One stretch of a script, with its length in milliseconds.
+#[derive(Debug, Clone, Copy)]
+pub enum Segment {
+    Speech(u32),
+    Silence(u32),
+}
+
+/// Three phrases of different lengths, each followed by a pause long enough
+/// for VAD to commit.
+pub const DEFAULT_SCRIPT: &[Segment] = &[
+    Segment::Silence(500),
+    Segment::Speech(1200),
+    Segment::Silence(1000),
+    Segment::Speech(600),
+    Segment::Silence(1000),
+    Segment::Speech(2000),
+    Segment::Silence(1500),
+];
+
+/// Render `script` as mono samples at `rate`. Speech is a harmonic tone with
+/// formant-shaped overtones under a syllable envelope, which WebRTC VAD takes
+/// for voice; silence is a faint noise floor.
+pub fn render(script: &[Segment], rate: u32) -> Vec<f32> {
+    let rate = rate.max(8000);
+    let harmonics = ((rate as f32 / 2.0 - 200.0) / PITCH_HZ) as usize;
+    let weights: Vec<f32> = (1..=harmonics)
+        .map(|k| {
+            let f = k as f32 * PITCH_HZ;
+            FORMANTS_HZ
+                .iter()
+                .map(|(center, width)| 1.0 / (1.0 + ((f - center) / width).powi(2)))
+                .sum()
+        })
+        .collect();
+    let norm: f32 = weights.iter().sum::<f32>().max(1.0);
+    let mut noise = 0x2545_f491_u32;
+    let mut out = vec![];
+    for segment in script {
+        let (speech, ms) = match *segment {
+            Segment::Speech(ms) => (true, ms),
+            Segment::Silence(ms) => (false, ms),
+        };
+        let count = (rate as u64 * ms as u64 / 1000) as usize;
+        for i in 0..count {
+            // xorshift32: enough for a noise floor, no rand dependency.
+            noise ^= noise << 13;
+            noise ^= noise >> 17;
+            noise ^= noise << 5;
+            let mut sample = (noise as f32 / u32::MAX as f32 * 2.0 - 1.0) * NOISE_FLOOR;
+            if speech {
+                let t = i as f32 / rate as f32;
+                let envelope = 0.35 + 0.65 * (std::f32::consts::PI * SYLLABLE_HZ * t).sin().powi(2);
+                // Fade in and out over 10 ms so segment edges don't click.
+                let edge = (i.min(count - 1 - i) as f32 / (rate as f32 * 0.01)).min(1.0);
+                let phase = std::f32::consts::TAU * PITCH_HZ * t;
+                let voiced: f32 = weights
+                    .iter()
+                    .enumerate()
+                    .map(|(k, w)| w * (phase * (k + 1) as f32).sin())
+                    .sum();
+                sample += voiced / norm * SPEECH_PEAK * envelope * edge;
+            }
+            out.push(sample);
+        }
+    }
+    out
+}
+
+/// Plays a script into a sample feed, looping until dropped.
+pub struct FakeSource {
+    stop: Arc<AtomicBool>,
+}
+
+impl FakeSource {
+    pub fn start(script: &[Segment], feed: SyncSender<Vec<f32>>) -> Self {
+        let samples = render(script, SAMPLE_RATE);
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_thread = stop.clone();
+        std::thread::spawn(move || {
+            let frame = (SAMPLE_RATE * FRAME_MS / 1000) as usize;
+            let started = Instant::now();
+            let mut sent: u64 = 0;
+            for chunk in samples.chunks(frame).cycle() {
+                if stop_thread.load(Ordering::SeqCst) || feed.send(chunk.to_vec()).is_err() {
+                    break;
+                }
+                sent += chunk.len() as u64;
+                // Pace against the start time so sleep overshoot doesn't add up.
+                let due = Duration::from_micros(sent * 1_000_000 / SAMPLE_RATE as u64);
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
+            app_log!(
+                "[fake-audio] stopped after {} ms",
+                sent * 1000 / SAMPLE_RATE as u64
+            );
+        });
+        app_log!("[fake-audio] playing a {} segment script", script.len());
+        Self { stop }
+    }
+}
+
+impl Drop for FakeSource {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use webrtc_vad::{SampleRate as VADSampleRate, Vad, VadMode as VADMode};
+
+    /// Share of 20 ms frames the capture path's VAD calls speech.
+    fn voiced_fraction(samples: &[f32]) -> f32 {
+        let mut vad = Vad::new_with_rate_and_mode(VADSampleRate::Rate16kHz, VADMode::Aggressive);
+        let pcm: Vec<i16> = samples
+            .iter()
+            .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .collect();
+        let frame = (SAMPLE_RATE * FRAME_MS / 1000) as usize;
+        let frames: Vec<&[i16]> = pcm.chunks_exact(frame).collect();
+        let voiced = frames
+            .iter()
+            .filter(|f| vad.is_voice_segment(f).unwrap_or(false))
+            .count();
+        voiced as f32 / frames.len() as f32
+    }
+
+    #[test]
+    fn vad_hears_speech_but_not_silence() {
+        let speech = render(&[Segment::Speech(2000)], SAMPLE_RATE);
+        let silence = render(&[Segment::Silence(2000)], SAMPLE_RATE);
+        assert!(voiced_fraction(&speech) > 0.5);
+        assert!(voiced_fraction(&silence) < 0.15);
+    }
+
+    #[test]
+    fn render_length_follows_script() {
+        let samples = render(DEFAULT_SCRIPT, SAMPLE_RATE);
+        let total_ms: u32 = DEFAULT_SCRIPT
+            .iter()
+            .map(|s| match *s {
+                Segment::Speech(ms) | Segment::Silence(ms) => ms,
+            })
+            .sum();
+        assert_eq!(samples.len(), (SAMPLE_RATE * total_ms / 1000) as usize);
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+    }
+}
@@ -0,0 +1,339 @@
+// C ABI for embedding dictation in another application. Declared in
+// integrations/embed/mangochat.h. Every call returns 0 on success and -1 on
+// failure, with the reason available from mangochat_last_error(). A panic
+// inside a call is caught and reported the same way instead of unwinding into
+// the host. The embedding uses the same settings and API keys as the Mango
+// Chat app.
+
+use crate::audio::AudioCapture;
+use crate::provider::session::{run_session, TranscriptSink};
+use crate::provider::{create_provider, ProviderSettings};
+use crate::settings::Settings;
+use crate::state::{AppEvent, AppState};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::Sender as EventSender;
+use std::sync::{Arc, Mutex};
+
+/// Called with each transcript (UTF-8, valid only for the call) from a
+/// background thread. `is_final` is 0 for interim text.
+pub type TranscriptCallback =
+    extern "C" fn(text: *const c_char, is_final: c_int, user_data: *mut c_void);
+
+/// The registered callback and its user data (as an address so it can cross
+/// threads; the host owns what it points to).
+type CallbackSlot = Arc<Mutex<Option<(TranscriptCallback, usize)>>>;
+
+struct Embedded {
+    runtime: tokio::runtime::Runtime,
+    state: Arc<AppState>,
+    event_tx: EventSender<AppEvent>,
+    settings: Settings,
+    callback: CallbackSlot,
+    type_finals: bool,
+    /// Stops the capture thread when dropped.
+    capture: Option<EventSender<()>>,
+}
+
+static EMBEDDED: Mutex<Option<Embedded>> = Mutex::new(None);
+
+/// Open the microphone on a thread of its own, since the cpal stream can't
+/// live in `EMBEDDED` (it isn't Send). The capture closes when the returned
+/// sender is dropped.
+fn spawn_capture(
+    mic: Option<String>,
+    audio_tx: tokio::sync::mpsc::Sender<Vec<u8>>,
+    event_tx: EventSender<AppEvent>,
+    state: Arc<AppState>,
+    sample_rate: u32,
+) -> Result<EventSender<()>, String> {
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        match AudioCapture::start(mic.as_deref(), audio_tx, event_tx, state, sample_rate) {
+            Ok(capture) => {
+                let _ = ready_tx.send(Ok(()));
+                let _ = stop_rx.recv();
+                drop(capture);
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+            }
+        }
+    });
+    ready_rx
+        .recv()
+        .map_err(|_| "Capture thread exited".to_string())??;
+    Ok(stop_tx)
+}
+
+thread_local! {
+    /// Per calling thread, like errno, so one thread's failure can't free or
+    /// replace the string another thread is reading.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn fail(message: String) -> c_int {
+    app_err!("[ffi] {}", message);
+    let message = CString::new(message.replace('\0', " ")).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    -1
+}
+
+/// Run an entry point's body, turning a panic into a failed call. Unwinding
+/// across `extern "C"` would abort the host.
+fn catch_panic(f: impl FnOnce() -> c_int) -> c_int {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".into());
+        fail(format!("Panicked: {}", message))
+    })
+}
+
+/// Run `f` on the initialized instance, catching any panic.
+fn with_embedded(f: impl FnOnce(&mut Embedded) -> Result<(), String>) -> c_int {
+    catch_panic(|| {
+        let mut guard = match EMBEDDED.lock() {
+            Ok(g) => g,
+            Err(_) => return fail("Embedded state is poisoned".into()),
+        };
+        let Some(embedded) = guard.as_mut() else {
+            return fail("mangochat_init has not been called".into());
+        };
+        match f(embedded) {
+            Ok(()) => 0,
+            Err(e) => fail(e),
+        }
+    })
+}
+
+/// Forward transcript events to the host callback; log the rest.
+fn spawn_event_pump(event_rx: std::sync::mpsc::Receiver<AppEvent>, callback: CallbackSlot) {
+    std::thread::spawn(move || {
+        for event in event_rx {
+            let (text, is_final) = match event {
+                AppEvent::TranscriptDelta(text) => (text, 0),
+                AppEvent::TranscriptFinal(text) => (text, 1),
+                AppEvent::StatusUpdate { status, message } => {
+                    app_log!("[ffi] status {}: {}", status, message);
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some((cb, user_data)) = callback.lock().ok().and_then(|c| *c) else {
+                continue;
+            };
+            if let Ok(text) = CString::new(text.replace('\0', " ")) {
+                cb(text.as_ptr(), is_final, user_data as *mut c_void);
+            }
+        }
+    });
+}
+
+/// Load settings and start the background runtime. Safe to call again; later
+/// calls do nothing.
+#[no_mangle]
+pub extern "C" fn mangochat_init() -> c_int {
+    catch_panic(|| {
+        let mut guard = match EMBEDDED.lock() {
+            Ok(g) => g,
+            Err(_) => return fail("Embedded state is poisoned".into()),
+        };
+        if guard.is_some() {
+            return 0;
+        }
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(r) => r,
+            Err(e) => return fail(format!("Failed to create tokio runtime: {}", e)),
+        };
+        let settings = crate::settings::load();
+        let state = Arc::new(AppState::new());
+        state.load_commands(&settings);
+        crate::plugins::reload(&settings.enabled_plugins);
+        crate::hooks::reload(settings.hooks_enabled);
+        let (event_tx, event_rx) = std::sync::mpsc::channel::<AppEvent>();
+        let callback: CallbackSlot = Arc::new(Mutex::new(None));
+        spawn_event_pump(event_rx, callback.clone());
+        app_log!("[ffi] initialized, provider {}", settings.provider);
+        *guard = Some(Embedded {
+            runtime,
+            state,
+            event_tx,
+            settings,
+            callback,
+            type_finals: true,
+            capture: None,
+        });
+        0
+    })
+}
+
+/// Use `provider_id` ("deepgram", "openai", "elevenlabs", "assemblyai") for
+/// the next mangochat_start. It needs an API key in Mango Chat's settings.
+///
+/// # Safety
+/// `provider_id` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn mangochat_set_provider(provider_id: *const c_char) -> c_int {
+    catch_panic(|| {
+        if provider_id.is_null() {
+            return fail("provider_id is null".into());
+        }
+        let id = CStr::from_ptr(provider_id).to_string_lossy().trim().to_ascii_lowercase();
+        with_embedded(|embedded| {
+            if !crate::provider::PROVIDER_ROWS.iter().any(|(row_id, _)| *row_id == id) {
+                return Err(format!("Unknown provider \"{}\"", id));
+            }
+            if embedded.settings.api_key_for(&id).trim().is_empty() {
+                return Err(format!("No API key for {}", crate::provider::display_name(&id)));
+            }
+            embedded.settings.provider = id;
+            Ok(())
+        })
+    })
+}
+
+/// Register the transcript callback, or clear it with NULL.
+#[no_mangle]
+pub extern "C" fn mangochat_set_transcript_callback(
+    callback: Option<TranscriptCallback>,
+    user_data: *mut c_void,
+) -> c_int {
+    with_embedded(|embedded| {
+        if let Ok(mut slot) = embedded.callback.lock() {
+            *slot = callback.map(|cb| (cb, user_data as usize));
+        }
+        Ok(())
+    })
+}
+
+/// Non-zero (the default) types finals into the focused window like the app
+/// does; zero only reports them to the callback. Applies from the next start.
+#[no_mangle]
+pub extern "C" fn mangochat_set_typing(enabled: c_int) -> c_int {
+    with_embedded(|embedded| {
+        embedded.type_finals = enabled != 0;
+        Ok(())
+    })
+}
+
+/// Open the microphone and stream to the selected provider until
+/// mangochat_stop.
+#[no_mangle]
+pub extern "C" fn mangochat_start() -> c_int {
+    with_embedded(|embedded| {
+        if embedded.capture.is_some() {
+            return Ok(());
+        }
+        let settings = &embedded.settings;
+        let provider_id = settings.provider.clone();
+        let api_key = settings.api_key_for(&provider_id).trim().to_string();
+        if api_key.is_empty() {
+            return Err("Select a provider with an API key first".into());
+        }
+        let provider = create_provider(&provider_id);
+        let provider_settings = ProviderSettings {
+            api_key,
+            model: settings.model.clone(),
+            transcription_model: settings.transcription_model.clone(),
+            language: settings.language.clone(),
+            pre_commit_silence_ms: None,
+            commit_flush_timeout_ms: None,
+        }
+        .with_commit_timing(settings, &provider_id);
+        let sample_rate = provider.sample_rate_hint();
+
+        let (audio_tx, audio_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
+        let mic = (!settings.mic_device.is_empty()).then(|| settings.mic_device.clone());
+        let capture = spawn_capture(
+            mic,
+            audio_tx.clone(),
+            embedded.event_tx.clone(),
+            embedded.state.clone(),
+            sample_rate,
+        )?;
+        if let Ok(mut tx) = embedded.state.audio_tx.lock() {
+            *tx = Some(audio_tx);
+        }
+        embedded.state.session_active.store(true, Ordering::SeqCst);
+        let sink = if embedded.type_finals {
+            TranscriptSink::Dictation
+        } else {
+            TranscriptSink::Embedded
+        };
+        let gen = embedded.state.session_gen.fetch_add(1, Ordering::SeqCst) + 1;
+        let state = embedded.state.clone();
+        let session = run_session(
+            provider,
+            embedded.event_tx.clone(),
+            state.clone(),
+            provider_settings,
+            audio_rx,
+            settings.provider_inactivity_timeout_secs,
+            sink,
+        );
+        embedded.runtime.spawn(async move {
+            session.await;
+            if state.session_gen.load(Ordering::SeqCst) == gen {
+                state.session_active.store(false, Ordering::SeqCst);
+                if let Ok(mut tx) = state.audio_tx.lock() {
+                    *tx = None;
+                }
+            }
+        });
+        embedded.capture = Some(capture);
+        app_log!("[ffi] started with {}", provider_id);
+        Ok(())
+    })
+}
+
+/// Close the microphone. The provider flushes and reports its last final.
+#[no_mangle]
+pub extern "C" fn mangochat_stop() -> c_int {
+    with_embedded(|embedded| {
+        if embedded.capture.take().is_none() {
+            return Ok(());
+        }
+        if let Ok(mut tx) = embedded.state.audio_tx.lock() {
+            *tx = None;
+        }
+        embedded.state.session_active.store(false, Ordering::SeqCst);
+        app_log!("[ffi] stopped");
+        Ok(())
+    })
+}
+
+/// Stop any session and release the runtime. mangochat_init may be called
+/// again afterwards.
+#[no_mangle]
+pub extern "C" fn mangochat_shutdown() {
+    catch_panic(|| {
+        let embedded = EMBEDDED.lock().ok().and_then(|mut g| g.take());
+        if let Some(embedded) = embedded {
+            drop(embedded.capture);
+            embedded
+                .runtime
+                .shutdown_timeout(std::time::Duration::from_secs(2));
+            app_log!("[ffi] shut down");
+        }
+        0
+    });
+}
+
+/// Reason for this thread's last failed call, or NULL. Valid until the next
+/// call on this thread fails.
+#[no_mangle]
+pub extern "C" fn mangochat_last_error() -> *const c_char {
+    std::panic::catch_unwind(|| {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(std::ptr::null(), |s| s.as_ptr())
+        })
+    })
+    .unwrap_or(std::ptr::null())
+}
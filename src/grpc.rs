@@ -0,0 +1,113 @@
+// Optional gRPC server for proto/mangochat.proto: streams transcripts and
+// session start/end to local integrators who want a typed contract. The UI
+// publishes into a broadcast channel and each Subscribe call gets its own
+// receiver.
+
+pub mod pb {
+    tonic::include_proto!("mangochat.v1");
+}
+
+use pb::event::Kind;
+use pb::transcripts_server::{Transcripts, TranscriptsServer};
+use pb::{
+    Event, SessionEnded, SessionStarted, SubscribeRequest, TranscriptDelta, TranscriptFinal,
+};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+/// Events buffered per subscriber before a slow one starts skipping.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How the UI thread feeds subscribers. Sends are dropped while nobody is
+/// subscribed.
+#[derive(Clone)]
+pub struct Publisher {
+    tx: broadcast::Sender<Event>,
+}
+
+impl Publisher {
+    fn send(&self, kind: Kind) {
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let _ = self.tx.send(Event {
+            timestamp_ms,
+            kind: Some(kind),
+        });
+    }
+
+    pub fn session_started(&self, session_id: u64, provider: &str, model: &str) {
+        self.send(Kind::SessionStarted(SessionStarted {
+            session_id,
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }));
+    }
+
+    pub fn session_ended(&self, session_id: u64) {
+        self.send(Kind::SessionEnded(SessionEnded { session_id }));
+    }
+
+    pub fn transcript(&self, session_id: u64, text: &str, is_final: bool) {
+        let text = text.to_string();
+        self.send(if is_final {
+            Kind::TranscriptFinal(TranscriptFinal { session_id, text })
+        } else {
+            Kind::TranscriptDelta(TranscriptDelta { session_id, text })
+        });
+    }
+}
+
+struct Service {
+    tx: broadcast::Sender<Event>,
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Transcripts for Service {
+    type SubscribeStream = EventStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let finals_only = request.into_inner().finals_only;
+        app_log!("[grpc] subscriber connected (finals_only={})", finals_only);
+        let stream = BroadcastStream::new(self.tx.subscribe()).filter_map(move |item| match item {
+            Ok(event) if finals_only && matches!(event.kind, Some(Kind::TranscriptDelta(_))) => {
+                None
+            }
+            Ok(event) => Some(Ok(event)),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                app_warn!("[grpc] slow subscriber skipped {} events", skipped);
+                None
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Serve the Transcripts service on 127.0.0.1:`port`.
+pub fn start_server(runtime: &tokio::runtime::Runtime, port: u16) -> Publisher {
+    let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+    let service = Service { tx: tx.clone() };
+    runtime.spawn(async move {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        app_log!("[grpc] listening on {}", addr);
+        let result = tonic::transport::Server::builder()
+            .add_service(TranscriptsServer::new(service))
+            .serve(addr)
+            .await;
+        if let Err(e) = result {
+            app_err!("[grpc] server on {} stopped: {}", addr, e);
+        }
+    });
+    Publisher { tx }
+}
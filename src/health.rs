@@ -0,0 +1,185 @@
+use crate::settings::Settings;
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of one on-demand health check.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+    /// What to try when the check fails.
+    pub hint: &'static str,
+}
+
+impl HealthCheck {
+    fn from_result(name: &'static str, result: Result<String, String>, hint: &'static str) -> Self {
+        let passed = result.is_ok();
+        Self {
+            name,
+            passed,
+            detail: result.unwrap_or_else(|e| e),
+            hint,
+        }
+    }
+}
+
+/// Run every check; the API key check talks to the provider, so this is async.
+/// Blocking checks run on the blocking pool.
+pub async fn run_all(settings: Settings) -> Vec<HealthCheck> {
+    let blocking_settings = settings.clone();
+    let mut checks = tokio::task::spawn_blocking(move || run_blocking(&blocking_settings))
+        .await
+        .unwrap_or_default();
+    let at = checks.len().min(3);
+    checks.insert(at, check_api_key(&settings).await);
+    checks
+}
+
+fn run_blocking(settings: &Settings) -> Vec<HealthCheck> {
+    let url = crate::provider::create_provider(&settings.provider)
+        .connection_config(&provider_settings(settings))
+        .url;
+    vec![
+        HealthCheck::from_result(
+            "Selected microphone present",
+            check_selected_device(&settings.mic_device),
+            "Plug the microphone back in or pick another one under Session.",
+        ),
+        HealthCheck::from_result(
+            "Microphone reachable",
+            check_mic(&settings.mic_device),
+            "Allow microphone access in Windows Settings > Privacy & security > Microphone.",
+        ),
+        HealthCheck::from_result(
+            "Provider endpoint reachable",
+            check_endpoint(&url),
+            "Check your internet connection, proxy and firewall rules for this host.",
+        ),
+        HealthCheck::from_result(
+            "Clipboard access",
+            check_clipboard(),
+            "Close apps that hold the clipboard open (clipboard managers, remote desktop).",
+        ),
+        HealthCheck::from_result(
+            "Screen capture",
+            check_screen_capture(),
+            "Allow screen capture for desktop apps, or turn off screenshots if unused.",
+        ),
+    ]
+}
+
+fn provider_settings(settings: &Settings) -> crate::provider::ProviderSettings {
+    crate::provider::ProviderSettings {
+        api_key: settings.api_key_for(&settings.provider).to_string(),
+        model: settings.model.clone(),
+        transcription_model: settings.transcription_model.clone(),
+        language: settings.language.clone(),
+        pre_commit_silence_ms: None,
+        commit_flush_timeout_ms: None,
+    }
+    .with_commit_timing(settings, &settings.provider)
+}
+
+fn check_selected_device(mic_device: &str) -> Result<String, String> {
+    let name = mic_device.trim();
+    if name.is_empty() {
+        return Ok("Using the system default input".into());
+    }
+    if crate::audio::list_input_devices().iter().any(|d| d == name) {
+        Ok(format!("\"{}\" is connected", name))
+    } else {
+        Err(format!("\"{}\" is not connected", name))
+    }
+}
+
+fn check_mic(mic_device: &str) -> Result<String, String> {
+    let host = cpal::default_host();
+    let name = mic_device.trim();
+    let device = if name.is_empty() {
+        host.default_input_device()
+            .ok_or_else(|| "No default input device".to_string())?
+    } else {
+        host.input_devices()
+            .map_err(|e| format!("Failed to list devices: {}", e))?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .or_else(|| host.default_input_device())
+            .ok_or_else(|| "No input device".to_string())?
+    };
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to open input: {}", e))?;
+    Ok(format!(
+        "{} ({} Hz, {} ch)",
+        device.name().unwrap_or_else(|_| "unknown".into()),
+        config.sample_rate().0,
+        config.channels()
+    ))
+}
+
+/// TCP connect to the provider host, then a TLS handshake via an HTTPS request.
+/// Any HTTP status counts: only reaching the server matters here.
+fn check_endpoint(ws_url: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(ws_url).map_err(|e| format!("Bad provider URL: {}", e))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| "Provider URL has no host".to_string())?
+        .to_string();
+    let port = url.port().unwrap_or(443);
+    let addr = (host.as_str(), port)
+        .to_socket_addrs()
+        .map_err(|e| format!("DNS lookup for {} failed: {}", host, e))?
+        .next()
+        .ok_or_else(|| format!("DNS lookup for {} returned nothing", host))?;
+    TcpStream::connect_timeout(&addr, NETWORK_TIMEOUT)
+        .map_err(|e| format!("TCP connect to {}:{} failed: {}", host, port, e))?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(NETWORK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    client
+        .get(format!("https://{}:{}/", host, port))
+        .send()
+        .map_err(|e| format!("TLS connection to {} failed: {}", host, e))?;
+    Ok(format!("{}:{} (TCP + TLS)", host, port))
+}
+
+async fn check_api_key(settings: &Settings) -> HealthCheck {
+    let hint = "Paste a fresh key under Provider, or check the account's balance.";
+    let name = "API key valid";
+    let provider_id = settings.provider.clone();
+    if settings.api_key_for(&provider_id).trim().is_empty() {
+        return HealthCheck::from_result(name, Err("No API key set".into()), hint);
+    }
+    let provider = crate::provider::create_provider(&provider_id);
+    let result = crate::provider::session::validate_key(provider, provider_settings(settings))
+        .await
+        .map(|()| {
+            format!(
+                "{} accepted the key",
+                crate::provider::display_name(&provider_id)
+            )
+        })
+        .map_err(|e| e.to_string());
+    HealthCheck::from_result(name, result, hint)
+}
+
+fn check_clipboard() -> Result<String, String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    match clipboard.get_text() {
+        Ok(_) | Err(arboard::Error::ContentNotAvailable) => Ok("Readable".into()),
+        Err(e) => Err(format!("Failed to read clipboard: {}", e)),
+    }
+}
+
+fn check_screen_capture() -> Result<String, String> {
+    let (image, _) = crate::snip::capture_screen(None)?;
+    if image.pixels().all(|p| p.0[..3] == [0, 0, 0]) {
+        return Err("Capture came back black".into());
+    }
+    Ok(format!("Captured {}x{}", image.width(), image.height()))
+}
@@ -0,0 +1,222 @@
+// Rhai hooks script: `hooks.rhai` in the scripts folder, edited under
+// Commands > Hooks.
+//
+// - `fn on_final(text)` runs on each final transcript after the WASM plugins.
+//   Return a string to replace the text ("" types nothing), or nothing to
+//   keep it.
+// - `fn on_command(trigger, args)` runs for phrases that start with a wake
+//   word ("mango deploy staging" -> "deploy", "staging"), before the
+//   built-in commands. Return true to mark the phrase handled.
+//
+// Both can call `open_url(url)`, `press_enter()`, `copy(text)` and
+// `type_text(text)`; the actions run after the hook returns. `print` goes to
+// the log. Scripts have no file or process access and an operation budget,
+// so a runaway loop errors out instead of hanging dictation.
+
+use crate::plugins::PluginAction;
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+
+/// Shown in the editor until a script is saved.
+pub const TEMPLATE: &str = r#"// Runs on every final transcript. Return new text, or nothing to keep it.
+fn on_final(text) {
+    text
+}
+
+// Runs for "mango <trigger> <args>". Return true when handled.
+fn on_command(trigger, args) {
+    if trigger == "search" {
+        open_url("https://www.google.com/search?q=" + args);
+        return true;
+    }
+    false
+}
+"#;
+
+struct Host {
+    engine: Engine,
+    ast: AST,
+    actions: Arc<Mutex<Vec<PluginAction>>>,
+}
+
+static HOST: Mutex<Option<Host>> = Mutex::new(None);
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn scripts_dir() -> Result<PathBuf, String> {
+    Ok(crate::diagnostics::data_dir()?.join("scripts"))
+}
+
+pub fn script_path() -> Result<PathBuf, String> {
+    Ok(scripts_dir()?.join("hooks.rhai"))
+}
+
+/// The script's text, or the template when there is no script yet.
+pub fn read_script() -> String {
+    script_path()
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .unwrap_or_else(|| TEMPLATE.to_string())
+}
+
+pub fn write_script(source: &str) -> Result<(), String> {
+    let path = script_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create scripts folder: {}", e))?;
+    }
+    fs::write(&path, source).map_err(|e| format!("Failed to save hooks script: {}", e))
+}
+
+/// Compile or last-run error, for the editor.
+pub fn last_error() -> Option<String> {
+    LAST_ERROR.lock().ok().and_then(|e| e.clone())
+}
+
+fn set_error(error: Option<String>) {
+    if let Some(e) = &error {
+        app_err!("[hooks] {}", e);
+    }
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = error;
+    }
+}
+
+fn make_engine(actions: Arc<Mutex<Vec<PluginAction>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_call_levels(32);
+    engine.on_print(|s| app_log!("[hooks] {}", s));
+    engine.on_debug(|s, _, _| app_log!("[hooks] {}", s));
+
+    let push = move |action: PluginAction| {
+        if let Ok(mut list) = actions.lock() {
+            list.push(action);
+        }
+    };
+    let p = push.clone();
+    engine.register_fn("open_url", move |url: &str| {
+        p(PluginAction::OpenUrl { url: url.to_string() })
+    });
+    let p = push.clone();
+    engine.register_fn("press_enter", move || p(PluginAction::PressEnter));
+    let p = push.clone();
+    engine.register_fn("copy", move |text: &str| {
+        p(PluginAction::CopyToClipboard {
+            text: text.to_string(),
+        })
+    });
+    engine.register_fn("type_text", move |text: &str| {
+        push(PluginAction::TypeText {
+            text: text.to_string(),
+        })
+    });
+    engine
+}
+
+/// Compile the hooks script when `enabled`, or unload it. Called at startup,
+/// on Save and after the script is edited.
+pub fn reload(enabled: bool) {
+    let mut host = None;
+    let mut error = None;
+    if enabled {
+        let actions = Arc::new(Mutex::new(Vec::new()));
+        let engine = make_engine(actions.clone());
+        let compiled = script_path().and_then(|path| {
+            let source = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            engine
+                .compile(&source)
+                .map_err(|e| format!("Script error: {}", e))
+        });
+        match compiled {
+            Ok(ast) => {
+                app_log!("[hooks] script loaded");
+                host = Some(Host {
+                    engine,
+                    ast,
+                    actions,
+                });
+            }
+            Err(e) => error = Some(e),
+        }
+    }
+    set_error(error);
+    if let Ok(mut guard) = HOST.lock() {
+        *guard = host;
+    }
+}
+
+/// Call `name` if the script defines it with that many parameters. None when
+/// hooks are off, the function is missing or it fails.
+fn call(name: &str, args: Vec<Dynamic>) -> Option<(Dynamic, Vec<PluginAction>)> {
+    let guard = HOST.lock().ok()?;
+    let host = guard.as_ref()?;
+    if !host
+        .ast
+        .iter_functions()
+        .any(|f| f.name == name && f.params.len() == args.len())
+    {
+        return None;
+    }
+    if let Ok(mut list) = host.actions.lock() {
+        list.clear();
+    }
+    // Skip the script's top-level statements; only the hook runs.
+    let options = CallFnOptions::new().eval_ast(false);
+    let result = host.engine.call_fn_with_options::<Dynamic>(
+        options,
+        &mut Scope::new(),
+        &host.ast,
+        name,
+        args,
+    );
+    let actions = host
+        .actions
+        .lock()
+        .map(|mut list| std::mem::take(&mut *list))
+        .unwrap_or_default();
+    match result {
+        Ok(value) => Some((value, actions)),
+        Err(e) => {
+            set_error(Some(format!("{}: {}", name, e)));
+            None
+        }
+    }
+}
+
+/// Run `on_final`. Returns the text to use and any actions it asked for.
+pub fn on_final(text: &str) -> (String, Vec<PluginAction>) {
+    match call("on_final", vec![Dynamic::from(text.to_string())]) {
+        Some((value, actions)) => {
+            let text = if value.is_string() {
+                value.into_string().unwrap_or_default()
+            } else {
+                text.to_string()
+            };
+            (text, actions)
+        }
+        None => (text.to_string(), vec![]),
+    }
+}
+
+/// Run `on_command`. Some(actions) when the script handled the phrase.
+pub fn on_command(trigger: &str, args: &str) -> Option<Vec<PluginAction>> {
+    let (value, actions) = call(
+        "on_command",
+        vec![
+            Dynamic::from(trigger.to_string()),
+            Dynamic::from(args.to_string()),
+        ],
+    )?;
+    if value.as_bool().unwrap_or(false) {
+        app_log!("[hooks] on_command handled \"{}\"", trigger);
+        Some(actions)
+    } else {
+        None
+    }
+}
@@ -1,14 +1,138 @@
 use crate::state::{AppEvent, AppState};
 use rdev::{listen, Event, EventType, Key};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::Sender as EventSender;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender as EventSender};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 const SNIP_TIMEOUT_MS: u64 = 30_000;
+/// A dictation key must be held this long to count; shorter presses are
+/// brushes or switch bounce.
+const MIN_HOLD_MS: u64 = 50;
+/// Two toggles this close together cancel out, so a double tap doesn't
+/// open a provider session only to close it again.
+const COALESCE_MS: u64 = 150;
+
+/// Dictation toggle key. Most Mac keyboards have no Right Ctrl, so macOS
+/// uses Right Command instead.
+#[cfg(target_os = "macos")]
+const DICTATE_KEY: Key = Key::MetaRight;
+#[cfg(target_os = "macos")]
+const DICTATE_KEY_NAME: &str = "Right Command";
+#[cfg(not(target_os = "macos"))]
+const DICTATE_KEY: Key = Key::ControlRight;
+#[cfg(not(target_os = "macos"))]
+const DICTATE_KEY_NAME: &str = "Right Ctrl";
 
 static LISTENER_ACTIVE: AtomicBool = AtomicBool::new(false);
 
+/// Number-row digit of a key, for the snip region preset hotkeys.
+fn digit_key(key: Key) -> Option<u8> {
+    match key {
+        Key::Num1 => Some(1),
+        Key::Num2 => Some(2),
+        Key::Num3 => Some(3),
+        Key::Num4 => Some(4),
+        Key::Num5 => Some(5),
+        Key::Num6 => Some(6),
+        Key::Num7 => Some(7),
+        Key::Num8 => Some(8),
+        Key::Num9 => Some(9),
+        _ => None,
+    }
+}
+
+/// Press or release of a dictation toggle key, named for the log.
+enum KeyEdge {
+    Down(&'static str),
+    Up,
+}
+
+fn toggle_recording(state: &AppState, event_tx: &EventSender<AppEvent>, key: &str) {
+    let was_recording = state.hotkey_recording.load(Ordering::SeqCst);
+    if was_recording {
+        state.hotkey_recording.store(false, Ordering::SeqCst);
+        app_log!("[hotkey] {} -> stop recording", key);
+        let _ = event_tx.send(AppEvent::HotkeyRelease);
+    } else {
+        state.hotkey_recording.store(true, Ordering::SeqCst);
+        app_log!("[hotkey] {} -> start recording", key);
+        let _ = event_tx.send(AppEvent::HotkeyPush);
+    }
+}
+
+/// Turn dictation key edges into toggles: a press counts once held for
+/// `MIN_HOLD_MS`, and a toggle fires `COALESCE_MS` later unless another one
+/// arrives first, in which case both are dropped. Runs on its own thread so
+/// the input hook never waits.
+fn debounce_toggles(
+    edges: mpsc::Receiver<(KeyEdge, Instant)>,
+    state: Arc<AppState>,
+    event_tx: EventSender<AppEvent>,
+) {
+    let min_hold = Duration::from_millis(MIN_HOLD_MS);
+    let coalesce = Duration::from_millis(COALESCE_MS);
+    // Key down but not yet held long enough to count.
+    let mut held: Option<(Instant, &'static str)> = None;
+    // Counted toggle waiting out the coalesce window.
+    let mut pending: Option<(Instant, &'static str)> = None;
+    loop {
+        let deadline = [held.map(|(at, _)| at + min_hold), pending.map(|(at, _)| at)]
+            .into_iter()
+            .flatten()
+            .min();
+        let edge = match deadline {
+            Some(deadline) => {
+                edges.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => edges.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let mut counted = None;
+        match edge {
+            Ok((KeyEdge::Down(key), at)) => {
+                if held.is_none() {
+                    held = Some((at, key));
+                }
+            }
+            Ok((KeyEdge::Up, at)) => {
+                if let Some((down_at, key)) = held.take() {
+                    if at.duration_since(down_at) >= min_hold {
+                        counted = Some(key);
+                    } else {
+                        app_log!("[hotkey] {} tap too short, ignored", key);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        let now = Instant::now();
+        if let Some((down_at, key)) = held {
+            if now.duration_since(down_at) >= min_hold {
+                held = None;
+                counted = Some(key);
+            }
+        }
+        if let Some(key) = counted {
+            if pending.take().is_some() {
+                app_log!(
+                    "[hotkey] {} toggled twice within {}ms, ignored",
+                    key,
+                    COALESCE_MS
+                );
+            } else {
+                pending = Some((now + coalesce, key));
+            }
+        }
+        if let Some((at, key)) = pending {
+            if now >= at {
+                pending = None;
+                toggle_recording(&state, &event_tx, key);
+            }
+        }
+    }
+}
+
 pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
     if LISTENER_ACTIVE.load(Ordering::SeqCst) {
         return;
@@ -16,6 +140,13 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
 
     LISTENER_ACTIVE.store(true, Ordering::SeqCst);
 
+    let (edge_tx, edge_rx) = mpsc::channel();
+    {
+        let state = state.clone();
+        let event_tx = event_tx.clone();
+        std::thread::spawn(move || debounce_toggles(edge_rx, state, event_tx));
+    }
+
     std::thread::spawn(move || {
         let key_held = Arc::new(AtomicBool::new(false));
         let key_held_clone = key_held.clone();
@@ -23,9 +154,23 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
         let snip_key_held_clone = snip_key_held.clone();
         let ctrl_any_held = Arc::new(AtomicBool::new(false));
         let ctrl_any_held_clone = ctrl_any_held.clone();
+        let shift_held = Arc::new(AtomicBool::new(false));
+        let shift_held_clone = shift_held.clone();
+        let print_key_held = Arc::new(AtomicBool::new(false));
+        let print_key_held_clone = print_key_held.clone();
+        let pause_key_held = Arc::new(AtomicBool::new(false));
+        let pause_key_held_clone = pause_key_held.clone();
+        let meta_held = Arc::new(AtomicBool::new(false));
+        let meta_held_clone = meta_held.clone();
+        let win_h_held = Arc::new(AtomicBool::new(false));
+        let win_h_held_clone = win_h_held.clone();
+        let region_key_held = Arc::new(AtomicBool::new(false));
+        let region_key_held_clone = region_key_held.clone();
 
         let callback = move |event: Event| {
-            let trigger_snip = |state: &Arc<AppState>, event_tx: &EventSender<AppEvent>| {
+            let trigger_snip = |state: &Arc<AppState>,
+                                event_tx: &EventSender<AppEvent>,
+                                recrop: bool| {
                 if !state.screenshot_enabled.load(Ordering::SeqCst) {
                     return;
                 }
@@ -51,12 +196,17 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
                     }
                 }
                 state.snip_started_ms.store(now_ms, Ordering::SeqCst);
-                app_log!("[hotkey] Right Alt -> snip");
-                let _ = event_tx.send(AppEvent::SnipTrigger);
+                if recrop {
+                    app_log!("[hotkey] Shift + Right Alt -> re-crop last capture");
+                    let _ = event_tx.send(AppEvent::SnipRecrop);
+                } else {
+                    app_log!("[hotkey] Right Alt -> snip");
+                    let _ = event_tx.send(AppEvent::SnipTrigger);
+                }
             };
 
             match event.event_type {
-                EventType::KeyPress(Key::ControlRight) => {
+                EventType::KeyPress(key) if key == DICTATE_KEY => {
                     if !state.session_hotkey_enabled.load(Ordering::SeqCst) {
                         return;
                     }
@@ -65,21 +215,34 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
                         return;
                     }
                     key_held_clone.store(true, Ordering::SeqCst);
-
-                    let was_recording = state.hotkey_recording.load(Ordering::SeqCst);
-                    if was_recording {
-                        state.hotkey_recording.store(false, Ordering::SeqCst);
-                        app_log!("[hotkey] Right Ctrl -> stop recording");
-                        let _ = event_tx.send(AppEvent::HotkeyRelease);
-                    } else {
-                        state.hotkey_recording.store(true, Ordering::SeqCst);
-                        app_log!("[hotkey] Right Ctrl -> start recording");
-                        let _ = event_tx.send(AppEvent::HotkeyPush);
-                    }
+                    let _ = edge_tx.send((KeyEdge::Down(DICTATE_KEY_NAME), Instant::now()));
                 }
-                EventType::KeyRelease(Key::ControlRight) => {
+                EventType::KeyRelease(key) if key == DICTATE_KEY => {
                     ctrl_any_held_clone.store(false, Ordering::SeqCst);
-                    key_held_clone.store(false, Ordering::SeqCst);
+                    if key_held_clone.swap(false, Ordering::SeqCst) {
+                        let _ = edge_tx.send((KeyEdge::Up, Instant::now()));
+                    }
+                }
+                EventType::KeyPress(Key::MetaLeft) | EventType::KeyPress(Key::MetaRight) => {
+                    meta_held_clone.store(true, Ordering::SeqCst);
+                }
+                EventType::KeyRelease(Key::MetaLeft) | EventType::KeyRelease(Key::MetaRight) => {
+                    meta_held_clone.store(false, Ordering::SeqCst);
+                }
+                // Win+H, when taken over from Windows voice typing (see win_h.rs).
+                EventType::KeyPress(Key::KeyH) => {
+                    if !meta_held_clone.load(Ordering::SeqCst)
+                        || !state.win_h_enabled.load(Ordering::SeqCst)
+                        || win_h_held_clone.swap(true, Ordering::SeqCst)
+                    {
+                        return;
+                    }
+                    let _ = edge_tx.send((KeyEdge::Down("Win+H"), Instant::now()));
+                }
+                EventType::KeyRelease(Key::KeyH) => {
+                    if win_h_held_clone.swap(false, Ordering::SeqCst) {
+                        let _ = edge_tx.send((KeyEdge::Up, Instant::now()));
+                    }
                 }
                 EventType::KeyPress(Key::ControlLeft) => {
                     ctrl_any_held_clone.store(true, Ordering::SeqCst);
@@ -92,7 +255,7 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
                         return;
                     }
                     snip_key_held_clone.store(true, Ordering::SeqCst);
-                    trigger_snip(&state, &event_tx);
+                    trigger_snip(&state, &event_tx, shift_held_clone.load(Ordering::SeqCst));
                 }
                 // Some layouts/apps report Right Alt as Alt + Ctrl instead of AltGr.
                 EventType::KeyPress(Key::Alt) => {
@@ -103,7 +266,7 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
                         return;
                     }
                     snip_key_held_clone.store(true, Ordering::SeqCst);
-                    trigger_snip(&state, &event_tx);
+                    trigger_snip(&state, &event_tx, shift_held_clone.load(Ordering::SeqCst));
                 }
                 EventType::KeyRelease(Key::AltGr) => {
                     snip_key_held_clone.store(false, Ordering::SeqCst);
@@ -111,10 +274,101 @@ pub fn start_listener(state: Arc<AppState>, event_tx: EventSender<AppEvent>) {
                 EventType::KeyRelease(Key::Alt) => {
                     snip_key_held_clone.store(false, Ordering::SeqCst);
                 }
-                EventType::MouseMove { x, y } => {
-                    if let Ok(mut pos) = state.cursor_pos.lock() {
-                        *pos = Some((x as i32, y as i32));
+                EventType::KeyPress(Key::ShiftLeft) | EventType::KeyPress(Key::ShiftRight) => {
+                    shift_held_clone.store(true, Ordering::SeqCst);
+                }
+                EventType::KeyRelease(Key::ShiftLeft) | EventType::KeyRelease(Key::ShiftRight) => {
+                    shift_held_clone.store(false, Ordering::SeqCst);
+                }
+                // Print Screen: instant capture of the current monitor;
+                // Shift + Print Screen: all monitors stitched together;
+                // Ctrl + Print Screen: the last snip's rectangle again.
+                EventType::KeyPress(Key::PrintScreen) => {
+                    if print_key_held_clone.swap(true, Ordering::SeqCst) {
+                        return;
+                    }
+                    if !state.screenshot_enabled.load(Ordering::SeqCst)
+                        || !state.screenshot_hotkey_enabled.load(Ordering::SeqCst)
+                    {
+                        return;
+                    }
+                    if state.snip_active.load(Ordering::SeqCst) {
+                        app_log!("[hotkey] Print Screen pressed but snip active, ignoring");
+                        return;
+                    }
+                    if ctrl_any_held_clone.load(Ordering::SeqCst) {
+                        app_log!("[hotkey] Ctrl + Print Screen -> repeat last region");
+                        let _ = event_tx.send(AppEvent::SnipRepeat);
+                        return;
+                    }
+                    let all_monitors = shift_held_clone.load(Ordering::SeqCst);
+                    app_log!(
+                        "[hotkey] Print Screen -> instant capture ({})",
+                        if all_monitors { "all monitors" } else { "current monitor" }
+                    );
+                    let _ = event_tx.send(AppEvent::SnipInstant { all_monitors });
+                }
+                EventType::KeyRelease(Key::PrintScreen) => {
+                    print_key_held_clone.store(false, Ordering::SeqCst);
+                }
+                // Pause: the last transcript was accurate; Shift + Pause: it wasn't.
+                EventType::KeyPress(Key::Pause) => {
+                    if pause_key_held_clone.swap(true, Ordering::SeqCst) {
+                        return;
+                    }
+                    let accurate = !shift_held_clone.load(Ordering::SeqCst);
+                    app_log!(
+                        "[hotkey] {} -> rate last transcript",
+                        if accurate { "Pause" } else { "Shift + Pause" }
+                    );
+                    let _ = event_tx.send(AppEvent::RateLastTranscript { accurate });
+                }
+                EventType::KeyRelease(Key::Pause) => {
+                    pause_key_held_clone.store(false, Ordering::SeqCst);
+                }
+                // Left Ctrl + Shift + F12: performance overlay.
+                EventType::KeyPress(Key::F12) => {
+                    if ctrl_any_held_clone.load(Ordering::SeqCst)
+                        && shift_held_clone.load(Ordering::SeqCst)
+                    {
+                        let _ = event_tx.send(AppEvent::TogglePerfOverlay);
+                    }
+                }
+                // Scroll Lock: make a click-through compact window interactive again (or not).
+                EventType::KeyPress(Key::ScrollLock) => {
+                    let _ = event_tx.send(AppEvent::ToggleClickThrough);
+                }
+                // Ctrl + Shift + 1-9: the snip region preset bound to that digit.
+                EventType::KeyPress(key) if digit_key(key).is_some() => {
+                    if !ctrl_any_held_clone.load(Ordering::SeqCst)
+                        || !shift_held_clone.load(Ordering::SeqCst)
+                        || region_key_held_clone.swap(true, Ordering::SeqCst)
+                    {
+                        return;
+                    }
+                    if !state.screenshot_enabled.load(Ordering::SeqCst)
+                        || !state.screenshot_hotkey_enabled.load(Ordering::SeqCst)
+                        || state.snip_active.load(Ordering::SeqCst)
+                    {
+                        return;
                     }
+                    let digit = digit_key(key).unwrap_or(0);
+                    let commands = state.commands.load();
+                    let preset = commands.snip_regions.iter().find(|(_, d)| *d == digit);
+                    if let Some((name, _)) = preset {
+                        app_log!(
+                            "[hotkey] Ctrl + Shift + {} -> snip region \"{}\"",
+                            digit,
+                            name
+                        );
+                        let _ = event_tx.send(AppEvent::SnipPreset(name.clone()));
+                    }
+                }
+                EventType::KeyRelease(key) if digit_key(key).is_some() => {
+                    region_key_held_clone.store(false, Ordering::SeqCst);
+                }
+                EventType::MouseMove { x, y } => {
+                    state.set_cursor_pos(x as i32, y as i32);
                 }
                 _ => {}
             }
@@ -0,0 +1,25 @@
+// mangochat_embed: the dictation core behind the C API in ffi.rs, for other
+// applications to embed. These are the modules the C API needs; the app
+// links them from here and declares the rest (UI and integrations) in
+// main.rs.
+
+#[macro_use]
+pub mod diagnostics;
+pub mod audio;
+pub mod health;
+pub mod hooks;
+pub mod meeting;
+pub mod metrics;
+pub mod plugins;
+pub mod profile;
+pub mod provider;
+pub mod secrets;
+pub mod settings;
+pub mod snip;
+pub mod state;
+pub mod typing;
+pub mod updater;
+pub mod usage;
+pub mod vision;
+
+pub mod ffi;
@@ -1,141 +1,248 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 #[macro_use]
-mod diagnostics;
-mod audio;
+extern crate mangochat_embed;
+
+use mangochat_embed::{
+    audio, diagnostics, health, hooks, meeting, metrics, plugins, profile, provider, settings,
+    snip, state, typing, updater, usage, vision,
+};
+
+mod archive;
+mod autostart;
+mod cli;
+mod dictation;
+mod fake_audio;
+mod grpc;
 mod hotkey;
 mod headset;
-mod provider;
-mod settings;
-mod secrets;
+mod mcp;
+mod network;
+mod notes;
+mod notify;
+mod obs;
+mod persist;
+#[cfg(feature = "wake-word")]
+mod porcupine;
+mod recorder;
+mod remote_mic;
+mod service;
 mod single_instance;
-mod snip;
 mod start_cue;
-mod state;
-mod typing;
+mod streamdeck;
 mod ui;
-mod updater;
-mod usage;
+mod wake;
+mod win_h;
 
 use eframe::egui;
 use egui::{vec2, ViewportBuilder};
 use state::{AppEvent, AppState};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
-use usage::{load_usage, save_usage, usage_path, USAGE_SAVE_INTERVAL_SECS, load_provider_totals, save_provider_totals};
+use std::time::{Duration, Instant};
+use usage::{load_usage, USAGE_SAVE_INTERVAL_SECS, load_provider_totals};
+
+const APP_ID: &str = "MangoChat.App.Singleton";
 
 fn main() {
-    let _ = diagnostics::init_session_logging();
+    let args: Vec<String> = std::env::args().collect();
+    profile::set(profile::from_args(&args[1..]));
+    // MCP clients talk JSON-RPC over our stdout, so run the bridge before any
+    // session logging and keep console output on stderr.
+    if args.get(1).is_some_and(|a| a == "--mcp-stdio") {
+        diagnostics::set_console_stderr_only(true);
+        let port = match args.get(2).map(String::as_str) {
+            Some("--port") => args.get(3).and_then(|p| p.parse().ok()),
+            _ => None,
+        }
+        .unwrap_or_else(|| settings::load().mcp_port);
+        if let Err(e) = mcp::run_stdio_bridge(port) {
+            app_err!("[mcp] stdio bridge failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // The background service keeps its own log so it doesn't rotate away the
+    // window's app.log.
+    let log_name = if args.get(1).is_some_and(|a| a == "--service") {
+        "service"
+    } else {
+        "app"
+    };
+    let _ = diagnostics::init_session_logging(log_name);
     diagnostics::install_panic_hook();
-    env_logger::init();
+    diagnostics::init_logger();
+    if let Some(name) = profile::name() {
+        app_log!("[profile] using profile \"{}\"", name);
+    }
 
-    let args: Vec<String> = std::env::args().collect();
     if args.get(1).is_some_and(|a| a == "--apply-update") {
         if let Err(e) = updater::run_update_helper_from_args(&args[2..]) {
             app_err!("[updater] helper failed: {}", e);
         }
         return;
     }
+    if args.get(1).is_some_and(|a| a == "--make-update-patch") {
+        if let Err(e) = updater::make_patch_from_args(&args[2..]) {
+            app_err!("[updater] patch build failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|a| a == "--service") {
+        service::run(&args[2..]);
+        return;
+    }
 
-    let _single_instance_guard = match single_instance::acquire("MangoChat.App.Singleton") {
+    // Each profile is its own instance; a relaunch reaches the one it names.
+    let instance_id = profile::scoped(APP_ID);
+    let _single_instance_guard = match single_instance::acquire(&instance_id) {
         Some(g) => g,
         None => {
-            app_err!("[mangochat] another instance is already running; exiting");
+            match single_instance::forward(&instance_id, &args[1..]) {
+                Ok(()) => app_log!("[mangochat] forwarded args to the running instance"),
+                Err(e) => app_err!("[mangochat] another instance is already running: {}", e),
+            }
             return;
         }
     };
+    let crashed_last_run = diagnostics::begin_run_marker();
+    let cli = cli::parse(&args[1..]);
+    settings::set_run_overrides(cli.run_overrides());
+    if cli.mock {
+        provider::enable_mock();
+    }
+
     let app_state = Arc::new(AppState::new());
     let settings = settings::load();
+    diagnostics::set_log_level(&settings.log_level);
+    diagnostics::set_json_logging(settings.log_json);
+    match diagnostics::prune_logs(&diagnostics::LogRetention {
+        max_files: settings.log_max_files,
+        max_total_mb: settings.log_max_total_mb,
+        max_age_days: settings.log_max_age_days,
+    }) {
+        Ok(0) => {}
+        Ok(n) => app_log!("[diagnostics] pruned {} old log files", n),
+        Err(e) => app_err!("[diagnostics] {}", e),
+    }
     let (event_tx, event_rx) = std::sync::mpsc::channel::<AppEvent>();
+    {
+        let forward_tx = event_tx.clone();
+        let forward_state = app_state.clone();
+        single_instance::listen(&instance_id, move |args| {
+            app_log!("[mangochat] forwarded args: {:?}", args);
+            for event in cli::parse(&args).forwarded_events() {
+                if matches!(event, AppEvent::SnipTrigger) && !forward_state.try_begin_snip() {
+                    continue;
+                }
+                let _ = forward_tx.send(event);
+            }
+        });
+    }
+    if cli.snip && app_state.try_begin_snip() {
+        let _ = event_tx.send(AppEvent::SnipTrigger);
+    }
     let runtime = Arc::new(
         tokio::runtime::Runtime::new().expect("Failed to create tokio runtime"),
     );
 
-    // Load usage totals from disk
-    if let Ok(path) = usage_path() {
-        let usage = load_usage(&path);
-        if let Ok(mut guard) = app_state.usage.lock() {
-            *guard = usage;
-        }
-    }
-    // Load per-provider totals from disk
+    // Load usage totals and prune history in the background so the window
+    // doesn't wait on the database. Anything counted before they arrive is
+    // kept and added on top.
     {
-        let pt = load_provider_totals();
-        if let Ok(mut guard) = app_state.provider_totals.lock() {
-            *guard = pt;
-        }
+        let usage_state = app_state.clone();
+        let usage_tx = event_tx.clone();
+        let retention_days = settings.session_retention_days;
+        let retention_max = settings.session_retention_max;
+        let provider = settings.provider.clone();
+        let model = settings.model.clone();
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            let stored = load_usage();
+            if let Ok(mut usage) = usage_state.usage.lock() {
+                usage.absorb(stored);
+                if usage.provider.is_empty() {
+                    usage.provider = provider;
+                }
+                if usage.model.is_empty() {
+                    usage.model = model;
+                }
+            }
+            match usage::prune_sessions(retention_days, retention_max) {
+                Ok(0) => {}
+                Ok(n) => app_log!("[usage] pruned {} old sessions from history", n),
+                Err(e) => app_err!("[usage] {}", e),
+            }
+            let stored = load_provider_totals();
+            if let Ok(mut totals) = usage_state.provider_totals.lock() {
+                for (provider, usage) in stored {
+                    totals.entry(provider).or_default().absorb(usage);
+                }
+            }
+            usage_state.usage_loaded.store(true, Ordering::SeqCst);
+            app_log!("[usage] loaded in {} ms", started.elapsed().as_millis());
+            let _ = usage_tx.send(AppEvent::UsageLoaded);
+        });
     }
 
     // Populate dynamic config from settings
-    if let Ok(mut p) = app_state.chrome_path.lock() {
-        *p = settings.resolved_browser_path();
-    }
-    if let Ok(mut p) = app_state.paint_path.lock() {
-        *p = settings.paint_path.clone();
-    }
-    if let Ok(mut v) = app_state.url_commands.lock() {
-        *v = settings
-            .url_commands
-            .iter()
-            .map(|c| (c.trigger.clone(), c.url.clone()))
-            .collect();
-    }
-    if let Ok(mut v) = app_state.alias_commands.lock() {
-        *v = settings
-            .alias_commands
-            .iter()
-            .map(|c| (c.trigger.clone(), c.replacement.clone()))
-            .collect();
-    }
-    if let Ok(mut v) = app_state.app_shortcuts.lock() {
-        *v = settings
-            .app_shortcuts
-            .iter()
-            .map(|c| (c.trigger.clone(), c.path.clone()))
-            .collect();
-    }
+    app_state.load_commands(&settings);
+    plugins::reload(&settings.enabled_plugins);
+    hooks::reload(settings.hooks_enabled);
 
-    // Populate feature gates from settings
-    app_state
-        .session_hotkey_enabled
-        .store(settings.session_hotkey_enabled, Ordering::SeqCst);
+    // Populate feature gates from settings. The background service owns the
+    // dictation hotkey while it's on.
+    app_state.session_hotkey_enabled.store(
+        settings.session_hotkey_enabled && !settings.background_service,
+        Ordering::SeqCst,
+    );
     app_state
         .screenshot_enabled
         .store(settings.screenshot_enabled, Ordering::SeqCst);
     app_state
         .screenshot_hotkey_enabled
         .store(settings.screenshot_hotkey_enabled, Ordering::SeqCst);
-    if let Ok(mut usage) = app_state.usage.lock() {
-        if usage.provider.is_empty() {
-            usage.provider = settings.provider.clone();
-        }
-        if usage.model.is_empty() {
-            usage.model = settings.model.clone();
-        }
+
+    persist::start(event_tx.clone());
+    if !settings.background_service {
+        service::stop_running();
     }
 
     // Start hotkey listener
     hotkey::start_listener(app_state.clone(), event_tx.clone());
     // Windows-only test hook for headset mic stem mute/unmute.
     headset::start_mute_watcher(event_tx.clone());
+    ui::theme::start_system_theme_watcher(event_tx.clone());
+    settings::start_file_watcher(event_tx.clone());
+    network::start_monitor(app_state.clone(), event_tx.clone());
+    if settings.metrics_enabled {
+        metrics::start_server(app_state.clone(), settings.metrics_port);
+    }
+    if settings.mcp_enabled {
+        mcp::start_server(app_state.clone(), event_tx.clone(), settings.mcp_port);
+    }
     app_log!("[mangochat] hotkeys active, hold Right Ctrl to dictate");
 
-    // Periodic usage logging thread
-    {
+    // Periodic usage logging thread. The background service saves the
+    // totals itself while it runs the sessions.
+    if !settings.background_service {
         let usage_state = app_state.clone();
         std::thread::spawn(move || loop {
             std::thread::sleep(Duration::from_secs(USAGE_SAVE_INTERVAL_SECS));
+            if !usage_state.usage_loaded.load(Ordering::SeqCst) {
+                continue;
+            }
             let snapshot = match usage_state.usage.lock() {
                 Ok(v) => v.clone(),
                 Err(_) => continue,
             };
-            if let Ok(path) = usage_path() {
-                let _ = save_usage(&path, &snapshot);
-            }
-            if let Ok(pt) = usage_state.provider_totals.lock() {
-                let _ = save_provider_totals(&pt);
-            }
+            let providers = match usage_state.provider_totals.lock() {
+                Ok(pt) => pt.clone(),
+                Err(_) => continue,
+            };
+            persist::save_usage(snapshot.clone(), providers);
             let hours_sent = snapshot.ms_sent as f64 / 3_600_000.0;
             let hours_suppressed = snapshot.ms_suppressed as f64 / 3_600_000.0;
             let mb_sent = snapshot.bytes_sent as f64 / (1024.0 * 1024.0);
@@ -163,7 +270,7 @@ fn main() {
     };
 
     let mut vp = ViewportBuilder::default()
-        .with_title("Mango Chat")
+        .with_title(profile::window_title())
         .with_inner_size(vec2(
             if settings.screenshot_enabled { 360.0 } else { 210.0 },
             if settings.compact_background_enabled { 92.0 } else { 80.0 },
@@ -186,26 +293,35 @@ fn main() {
     app_log!("[mangochat] starting eframe...");
 
     eframe::run_native(
-        "Mango Chat",
+        &profile::window_title(),
         native_options,
         Box::new(move |cc| {
-            if settings.theme == "light" {
-                cc.egui_ctx.set_visuals(egui::Visuals::light());
-            } else {
-                cc.egui_ctx.set_visuals(egui::Visuals::dark());
-            }
+            let dark = ui::theme::theme_is_dark(&settings.theme);
+            ui::theme::set_dark_theme(dark);
+            cc.egui_ctx.set_visuals(ui::theme::theme_visuals(dark));
             app_log!("[mangochat] eframe app created");
-            Ok(Box::new(ui::MangoChatApp::new(
+            let mut app = ui::MangoChatApp::new(
                 app_state,
                 event_tx,
                 event_rx,
                 runtime,
                 settings,
                 cc.egui_ctx.clone(),
-            )))
+            );
+            app.start_recording_on_launch = cli.start_recording || cli.toggle_recording;
+            app.hidden_to_tray = cli.minimized;
+            if cli.minimized {
+                app.refresh_tray_menu();
+            }
+            if crashed_last_run {
+                app.report_previous_crash();
+            }
+            Ok(Box::new(app))
         }),
     )
     .expect("Failed to start eframe");
+    persist::flush();
+    diagnostics::end_run_marker();
 }
 
 
@@ -0,0 +1,443 @@
+use crate::state::{AppEvent, AppState};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender as EventSender;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Newest MCP revision we speak; older clients get their own version echoed.
+const PROTOCOL_VERSION: &str = "2025-03-26";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Request line plus headers; larger heads are refused with 431.
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+const MAX_HEADERS: usize = 64;
+/// Time a client gets to send its whole request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connections served at once; extra ones get 503.
+const MAX_CONNECTIONS: usize = 8;
+/// Longest side of images returned by `capture_region`.
+const CAPTURE_MAX_DIMENSION: u32 = 1600;
+
+fn tool_list() -> Value {
+    json!([
+        {
+            "name": "get_last_transcript",
+            "description": "Return the most recent final transcript from Mango Chat.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "get_status",
+            "description": "Report whether Mango Chat is currently dictating.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "start_dictation",
+            "description": "Start a dictation session with the configured provider.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "stop_dictation",
+            "description": "Stop the current dictation session.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "capture_region",
+            "description": "Capture the desktop, or a region of it, as a PNG image. \
+                Coordinates are physical pixels from the top-left of the combined desktop.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "x": { "type": "integer", "minimum": 0 },
+                    "y": { "type": "integer", "minimum": 0 },
+                    "width": { "type": "integer", "minimum": 1 },
+                    "height": { "type": "integer", "minimum": 1 }
+                }
+            }
+        }
+    ])
+}
+
+fn text_result(text: impl Into<String>) -> Value {
+    json!({ "content": [{ "type": "text", "text": text.into() }], "isError": false })
+}
+
+fn error_result(text: impl Into<String>) -> Value {
+    json!({ "content": [{ "type": "text", "text": text.into() }], "isError": true })
+}
+
+fn capture_region(args: &Value) -> Result<Value, String> {
+    let mut capture = crate::snip::capture_all_monitors()?;
+    let field = |name: &str| args.get(name).and_then(Value::as_u64).map(|v| v as u32);
+    if let (Some(w), Some(h)) = (field("width"), field("height")) {
+        let x = field("x").unwrap_or(0);
+        let y = field("y").unwrap_or(0);
+        capture = crate::snip::crop_region(&capture, x, y, w, h)?;
+    }
+    let capture = crate::snip::limit_dimension(capture, CAPTURE_MAX_DIMENSION);
+    let mut png = std::io::Cursor::new(Vec::new());
+    capture
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode capture: {}", e))?;
+    Ok(json!({
+        "content": [{
+            "type": "image",
+            "data": BASE64.encode(png.into_inner()),
+            "mimeType": "image/png"
+        }],
+        "isError": false
+    }))
+}
+
+fn call_tool(name: &str, args: &Value, state: &AppState, event_tx: &EventSender<AppEvent>) -> Value {
+    let recording = state.session_active.load(Ordering::SeqCst);
+    match name {
+        "get_last_transcript" => match state.last_transcript() {
+            Some(last) => text_result(last),
+            None => text_result("No transcript yet."),
+        },
+        "get_status" => text_result(if recording { "dictating" } else { "idle" }),
+        "start_dictation" | "stop_dictation" => {
+            let start = name == "start_dictation";
+            if start == recording {
+                return text_result(if start {
+                    "Already dictating."
+                } else {
+                    "Not dictating."
+                });
+            }
+            match event_tx.send(AppEvent::SetRecording(start)) {
+                Ok(()) => text_result(if start { "Dictation started." } else { "Dictation stopped." }),
+                Err(_) => error_result("Mango Chat is shutting down."),
+            }
+        }
+        "capture_region" => capture_region(args).unwrap_or_else(error_result),
+        _ => error_result(format!("Unknown tool: {}", name)),
+    }
+}
+
+/// Handle one JSON-RPC message. Notifications (no `id`) get no response.
+fn handle_rpc(msg: &Value, state: &AppState, event_tx: &EventSender<AppEvent>) -> Option<Value> {
+    let id = msg.get("id")?.clone();
+    let method = msg.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = msg.get("params").cloned().unwrap_or(Value::Null);
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "mangochat", "version": env!("CARGO_PKG_VERSION") }
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_list() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+            let args = params.get("arguments").cloned().unwrap_or(Value::Null);
+            app_log!("[mcp] tool call {}", name);
+            Ok(call_tool(name, &args, state, event_tx))
+        }
+        _ => Err(json!({ "code": -32601, "message": format!("Method not found: {}", method) })),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    })
+}
+
+/// Browsers send an Origin header; only accept local pages so a website
+/// can't drive the desktop through this port.
+pub fn origin_allowed(origin: Option<&str>) -> bool {
+    match origin {
+        None => true,
+        Some(o) => {
+            let host = o
+                .split("://")
+                .nth(1)
+                .unwrap_or(o)
+                .split(':')
+                .next()
+                .unwrap_or("");
+            host == "localhost" || host == "127.0.0.1"
+        }
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
+
+/// Socket reader that gives up at `deadline`, so a client trickling bytes
+/// can't hold its connection open past `REQUEST_TIMEOUT`.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    content_length: usize,
+    origin: Option<String>,
+}
+
+/// Read the request line and headers. `Err(Some(status))` is answered with
+/// that status; `Err(None)` means the client hung up or timed out.
+fn read_head(reader: &mut impl BufRead) -> Result<RequestHead, Option<&'static str>> {
+    const TOO_LARGE: &str = "431 Request Header Fields Too Large";
+    let mut limited = reader.take(MAX_HEADER_BYTES);
+    let mut next_line = |line: &mut String| match limited.read_line(line) {
+        Ok(_) if line.ends_with('\n') => Ok(()),
+        Ok(_) if limited.limit() == 0 => Err(Some(TOO_LARGE)),
+        _ => Err(None),
+    };
+
+    let mut request_line = String::new();
+    next_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let mut head = RequestHead {
+        method: parts.next().unwrap_or("").to_string(),
+        path: parts.next().unwrap_or("").to_string(),
+        content_length: 0,
+        origin: None,
+    };
+
+    let mut count = 0;
+    loop {
+        let mut line = String::new();
+        next_line(&mut line)?;
+        if line.trim().is_empty() {
+            return Ok(head);
+        }
+        count += 1;
+        if count > MAX_HEADERS {
+            return Err(Some(TOO_LARGE));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim().to_string();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => head.content_length = value.parse().unwrap_or(0),
+                "origin" => head.origin = Some(value),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream, state: &AppState, event_tx: &EventSender<AppEvent>) {
+    let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+    let Ok(read_half) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(DeadlineReader {
+        stream: read_half,
+        deadline: Instant::now() + REQUEST_TIMEOUT,
+    });
+    let RequestHead {
+        method,
+        path,
+        content_length,
+        origin,
+    } = match read_head(&mut reader) {
+        Ok(head) => head,
+        Err(Some(status)) => {
+            respond(&mut stream, status, "{}");
+            return;
+        }
+        Err(None) => return,
+    };
+
+    if path != "/mcp" {
+        respond(&mut stream, "404 Not Found", "{}");
+        return;
+    }
+    if !origin_allowed(origin.as_deref()) {
+        respond(&mut stream, "403 Forbidden", "{}");
+        return;
+    }
+    if method != "POST" {
+        // No server-initiated stream is offered.
+        respond(&mut stream, "405 Method Not Allowed", "{}");
+        return;
+    }
+    if content_length > MAX_BODY_BYTES {
+        respond(&mut stream, "413 Payload Too Large", "{}");
+        return;
+    }
+    let mut body = vec![0u8; content_length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+    let msg: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => {
+            let error = json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": format!("Parse error: {}", e) }
+            });
+            respond(&mut stream, "400 Bad Request", &error.to_string());
+            return;
+        }
+    };
+    let response = match &msg {
+        Value::Array(batch) => {
+            let replies: Vec<Value> = batch
+                .iter()
+                .filter_map(|m| handle_rpc(m, state, event_tx))
+                .collect();
+            (!replies.is_empty()).then(|| Value::Array(replies))
+        }
+        single => handle_rpc(single, state, event_tx),
+    };
+    match response {
+        Some(reply) => respond(&mut stream, "200 OK", &reply.to_string()),
+        None => respond(&mut stream, "202 Accepted", ""),
+    }
+}
+
+/// Serve MCP (Streamable HTTP, JSON responses) on 127.0.0.1:`port`/mcp.
+pub fn start_server(state: Arc<AppState>, event_tx: EventSender<AppEvent>, port: u16) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(l) => l,
+        Err(e) => {
+            app_err!("[mcp] failed to bind 127.0.0.1:{}: {}", port, e);
+            return;
+        }
+    };
+    app_log!("[mcp] serving http://127.0.0.1:{}/mcp", port);
+    std::thread::spawn(move || {
+        let active = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    app_err!("[mcp] accept error: {}", e);
+                    continue;
+                }
+            };
+            if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                active.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+                respond(&mut stream, "503 Service Unavailable", "{}");
+                continue;
+            }
+            let (state, event_tx, active) = (state.clone(), event_tx.clone(), active.clone());
+            std::thread::spawn(move || {
+                handle(stream, &state, &event_tx);
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+}
+
+/// `mangochat --mcp-stdio`: relay newline-delimited JSON-RPC between stdin/stdout
+/// and the running instance, for MCP clients that only launch commands.
+pub fn run_stdio_bridge(port: u16) -> Result<(), String> {
+    let url = format!("http://127.0.0.1:{}/mcp", port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .body(line.clone())
+            .send()
+            .and_then(|r| r.text());
+        let reply = match reply {
+            Ok(text) => text,
+            Err(e) => {
+                // Answer requests ourselves so the client doesn't hang.
+                let id = serde_json::from_str::<Value>(&line)
+                    .ok()
+                    .and_then(|m| m.get("id").cloned());
+                let Some(id) = id else {
+                    continue;
+                };
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32000,
+                        "message": format!("Mango Chat is not running or MCP is off: {}", e)
+                    }
+                })
+                .to_string()
+            }
+        };
+        if reply.trim().is_empty() {
+            continue;
+        }
+        writeln!(stdout, "{}", reply.trim()).map_err(|e| format!("Failed to write stdout: {}", e))?;
+        stdout
+            .flush()
+            .map_err(|e| format!("Failed to write stdout: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_head_parses_headers() {
+        let request =
+            "POST /mcp HTTP/1.1\r\nContent-Length: 12\r\nOrigin: http://localhost\r\n\r\n{}";
+        let head = read_head(&mut request.as_bytes()).ok().unwrap();
+        assert_eq!((head.method.as_str(), head.path.as_str()), ("POST", "/mcp"));
+        assert_eq!(head.content_length, 12);
+        assert_eq!(head.origin.as_deref(), Some("http://localhost"));
+    }
+
+    #[test]
+    fn read_head_refuses_oversized_heads() {
+        let long = format!(
+            "POST /mcp HTTP/1.1\r\nX-Pad: {}\r\n\r\n",
+            "a".repeat(20_000)
+        );
+        assert_eq!(
+            read_head(&mut long.as_bytes()).err(),
+            Some(Some("431 Request Header Fields Too Large"))
+        );
+        let many = format!(
+            "POST /mcp HTTP/1.1\r\n{}\r\n",
+            "X-A: 1\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert!(matches!(read_head(&mut many.as_bytes()), Err(Some(_))));
+        assert!(matches!(
+            read_head(&mut "POST /mcp HTTP/1.1\r\nHost".as_bytes()),
+            Err(None)
+        ));
+    }
+}
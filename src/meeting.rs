@@ -0,0 +1,207 @@
+// Meeting mode: a long-running session per audio source (the mic as "Me"
+// and, optionally, system audio as "Others"), each on its own provider
+// connection. Finals are appended to a timestamped Markdown document instead
+// of being typed, so the file is complete up to the last line even if the
+// app exits mid-meeting.
+
+use crate::audio::AudioCapture;
+use crate::provider::session::{run_session, TranscriptSink};
+use crate::provider::{create_provider, ProviderSettings};
+use crate::settings::Settings;
+use crate::state::{AppEvent, AppState};
+use chrono::{DateTime, Local};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender as EventSender;
+use std::sync::Arc;
+
+pub const MIC_SPEAKER: &str = "Me";
+pub const SYSTEM_SPEAKER: &str = "Others";
+
+/// One final transcript, labeled with the source it came from.
+#[derive(Debug, Clone)]
+pub struct MeetingLine {
+    pub speaker: String,
+    pub text: String,
+    pub at: DateTime<Local>,
+}
+
+impl MeetingLine {
+    pub fn new(speaker: &str, text: &str) -> Self {
+        Self {
+            speaker: speaker.to_string(),
+            text: text.trim().to_string(),
+            at: Local::now(),
+        }
+    }
+}
+
+pub fn default_dir() -> Result<PathBuf, String> {
+    if let Some(documents) = dirs::document_dir() {
+        return Ok(documents.join("MangoChat").join("Meetings"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        return Ok(home.join("Documents").join("MangoChat").join("Meetings"));
+    }
+    Err("Failed to resolve Documents directory".into())
+}
+
+pub fn meeting_dir(settings: &Settings) -> Result<PathBuf, String> {
+    if settings.meeting_folder.is_empty() {
+        default_dir()
+    } else {
+        Ok(PathBuf::from(&settings.meeting_folder))
+    }
+}
+
+/// A running meeting. Dropping it closes the audio sources; the provider
+/// sessions then flush their last finals and the writer adds the footer.
+pub struct Meeting {
+    path: PathBuf,
+    started: DateTime<Local>,
+    lines: Arc<AtomicUsize>,
+    _captures: Vec<AudioCapture>,
+}
+
+impl Meeting {
+    pub fn start(
+        runtime: &tokio::runtime::Runtime,
+        state: Arc<AppState>,
+        event_tx: EventSender<AppEvent>,
+        settings: &Settings,
+    ) -> Result<Self, String> {
+        let provider_id = settings.provider.clone();
+        let api_key = settings.api_key_for(&provider_id).trim().to_string();
+        if api_key.is_empty() {
+            return Err("Select a default provider with an API key in Settings".into());
+        }
+        let provider_settings = ProviderSettings {
+            api_key,
+            model: settings.model.clone(),
+            transcription_model: settings.transcription_model.clone(),
+            language: settings.language.clone(),
+            pre_commit_silence_ms: None,
+            commit_flush_timeout_ms: None,
+        }
+        .with_commit_timing(settings, &provider_id);
+        let sample_rate = create_provider(&provider_id).sample_rate_hint();
+
+        let mut captures = Vec::new();
+        let mut sources = Vec::new();
+        let mic = (!settings.mic_device.is_empty()).then_some(settings.mic_device.as_str());
+        let (audio_tx, audio_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
+        captures.push(AudioCapture::start(
+            mic,
+            audio_tx,
+            event_tx.clone(),
+            state.clone(),
+            sample_rate,
+        )?);
+        sources.push((MIC_SPEAKER, "microphone", audio_rx));
+        if settings.meeting_system_audio {
+            let (audio_tx, audio_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
+            match AudioCapture::start_loopback(audio_tx, state.clone(), sample_rate) {
+                Ok(capture) => {
+                    captures.push(capture);
+                    sources.push((SYSTEM_SPEAKER, "system audio", audio_rx));
+                }
+                Err(e) => app_err!("[meeting] system audio unavailable, mic only: {}", e),
+            }
+        }
+
+        let dir = meeting_dir(settings)?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create meeting folder: {}", e))?;
+        let started = Local::now();
+        let path = dir.join(format!("meeting-{}.md", started.format("%Y-%m-%d-%H%M%S")));
+        let mut file = File::create(&path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let speakers = sources
+            .iter()
+            .map(|(speaker, source, _)| format!("{} ({})", speaker, source))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(
+            file,
+            "# Meeting {}\n\nProvider: {} · Speakers: {}\n",
+            started.format("%Y-%m-%d %H:%M"),
+            crate::provider::display_name(&provider_id),
+            speakers
+        )
+        .map_err(|e| format!("Failed to write meeting header: {}", e))?;
+
+        let (line_tx, line_rx) = std::sync::mpsc::channel::<MeetingLine>();
+        for (speaker, _, audio_rx) in sources {
+            let sink = TranscriptSink::Meeting {
+                speaker: speaker.to_string(),
+                tx: line_tx.clone(),
+            };
+            runtime.spawn(run_session(
+                create_provider(&provider_id),
+                event_tx.clone(),
+                state.clone(),
+                provider_settings.clone(),
+                audio_rx,
+                settings.provider_inactivity_timeout_secs,
+                sink,
+            ));
+        }
+        drop(line_tx);
+
+        let lines = Arc::new(AtomicUsize::new(0));
+        let written = lines.clone();
+        std::thread::spawn(move || {
+            for line in line_rx {
+                if line.text.is_empty() {
+                    continue;
+                }
+                let result = writeln!(
+                    file,
+                    "**[{}] {}:** {}\n",
+                    line.at.format("%H:%M:%S"),
+                    line.speaker,
+                    line.text
+                );
+                if let Err(e) = result {
+                    app_err!("[meeting] failed to write line: {}", e);
+                    continue;
+                }
+                written.fetch_add(1, Ordering::Relaxed);
+            }
+            let ended = Local::now();
+            let minutes = (ended - started).num_minutes();
+            if let Err(e) = writeln!(
+                file,
+                "---\n\nEnded {} ({} min, {} lines)",
+                ended.format("%H:%M:%S"),
+                minutes,
+                written.load(Ordering::Relaxed)
+            ) {
+                app_err!("[meeting] failed to write footer: {}", e);
+            }
+            app_log!("[meeting] transcript closed");
+        });
+
+        app_log!("[meeting] started: {}", path.display());
+        Ok(Self {
+            path,
+            started,
+            lines,
+            _captures: captures,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn started(&self) -> DateTime<Local> {
+        self.started
+    }
+
+    /// Lines written so far.
+    pub fn line_count(&self) -> usize {
+        self.lines.load(Ordering::Relaxed)
+    }
+}
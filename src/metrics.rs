@@ -0,0 +1,201 @@
+use crate::state::AppState;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Upper bounds (ms) of the commit-to-final latency histogram buckets.
+const LATENCY_BUCKETS_MS: [u64; 8] = [100, 250, 500, 1000, 2000, 3000, 5000, 10000];
+
+#[derive(Default)]
+struct ProviderCounters {
+    sessions: u64,
+    connections: u64,
+    errors: u64,
+    /// Cumulative counts per bucket, plus +Inf as the last entry.
+    latency_buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    latency_sum_ms: u64,
+    latency_count: u64,
+}
+
+/// Counters that aren't already tracked in AppState, keyed by provider id.
+static COUNTERS: Mutex<BTreeMap<String, ProviderCounters>> = Mutex::new(BTreeMap::new());
+
+fn with_counters(provider: &str, f: impl FnOnce(&mut ProviderCounters)) {
+    if let Ok(mut map) = COUNTERS.lock() {
+        f(map.entry(provider.to_string()).or_default());
+    }
+}
+
+/// A recording session was started.
+pub fn record_session(provider: &str) {
+    with_counters(provider, |c| c.sessions += 1);
+}
+
+/// A provider websocket connected (reconnects count again).
+pub fn record_connection(provider: &str) {
+    with_counters(provider, |c| c.connections += 1);
+}
+
+pub fn record_error(provider: &str) {
+    with_counters(provider, |c| c.errors += 1);
+}
+
+/// Time from an audio commit to the first final transcript.
+pub fn observe_final_latency(provider: &str, ms: u64) {
+    with_counters(provider, |c| {
+        for (i, le) in LATENCY_BUCKETS_MS.iter().enumerate() {
+            if ms <= *le {
+                c.latency_buckets[i] += 1;
+            }
+        }
+        c.latency_buckets[LATENCY_BUCKETS_MS.len()] += 1;
+        c.latency_sum_ms += ms;
+        c.latency_count += 1;
+    });
+}
+
+/// Render all metrics in the Prometheus text exposition format.
+pub fn render(state: &AppState) -> String {
+    let Ok(counters) = COUNTERS.lock() else {
+        return String::new();
+    };
+    let mut out = String::new();
+
+    let mut counter = |name: &str, help: &str, rows: &[(String, u64)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        for (provider, v) in rows {
+            let _ = writeln!(out, "{}{{provider=\"{}\"}} {}", name, provider, v);
+        }
+    };
+
+    let usage: Vec<(String, crate::state::ProviderUsage)> = state
+        .provider_totals
+        .lock()
+        .map(|pt| {
+            let mut rows: Vec<_> = pt.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            rows.sort_by(|a, b| a.0.cmp(&b.0));
+            rows
+        })
+        .unwrap_or_default();
+    let rows = |f: fn(&crate::state::ProviderUsage) -> u64| -> Vec<(String, u64)> {
+        usage.iter().map(|(p, u)| (p.clone(), f(u))).collect()
+    };
+    counter(
+        "mangochat_audio_sent_ms_total",
+        "Milliseconds of audio sent to the provider.",
+        &rows(|u| u.ms_sent),
+    );
+    counter(
+        "mangochat_audio_suppressed_ms_total",
+        "Milliseconds of audio held back as silence.",
+        &rows(|u| u.ms_suppressed),
+    );
+    counter(
+        "mangochat_bytes_sent_total",
+        "Audio bytes sent to the provider.",
+        &rows(|u| u.bytes_sent),
+    );
+    counter(
+        "mangochat_transcripts_total",
+        "Final transcripts received.",
+        &rows(|u| u.finals),
+    );
+
+    let rows = |f: fn(&ProviderCounters) -> u64| -> Vec<(String, u64)> {
+        counters.iter().map(|(p, c)| (p.clone(), f(c))).collect()
+    };
+    counter(
+        "mangochat_sessions_total",
+        "Recording sessions started since launch.",
+        &rows(|c| c.sessions),
+    );
+    counter(
+        "mangochat_connections_total",
+        "Provider websocket connections since launch.",
+        &rows(|c| c.connections),
+    );
+    counter(
+        "mangochat_errors_total",
+        "Provider errors since launch.",
+        &rows(|c| c.errors),
+    );
+
+    let dropped = state.audio_chunks_dropped.load(std::sync::atomic::Ordering::Relaxed);
+    let _ = writeln!(
+        out,
+        "# HELP mangochat_audio_chunks_dropped_total Audio chunks dropped because the \
+         provider couldn't keep up, since launch."
+    );
+    let _ = writeln!(out, "# TYPE mangochat_audio_chunks_dropped_total counter");
+    let _ = writeln!(out, "mangochat_audio_chunks_dropped_total {}", dropped);
+
+    let name = "mangochat_final_latency_ms";
+    let _ = writeln!(out, "# HELP {} Commit to first final transcript latency.", name);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    for (provider, c) in counters.iter() {
+        for (i, le) in LATENCY_BUCKETS_MS.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{provider=\"{}\",le=\"{}\"}} {}",
+                name, provider, le, c.latency_buckets[i]
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{provider=\"{}\",le=\"+Inf\"}} {}",
+            name,
+            provider,
+            c.latency_buckets[LATENCY_BUCKETS_MS.len()]
+        );
+        let _ = writeln!(out, "{}_sum{{provider=\"{}\"}} {}", name, provider, c.latency_sum_ms);
+        let _ = writeln!(out, "{}_count{{provider=\"{}\"}} {}", name, provider, c.latency_count);
+    }
+    out
+}
+
+fn handle(mut stream: TcpStream, state: &AppState) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+    let mut request_line = String::new();
+    if BufReader::new(&stream).read_line(&mut request_line).is_err() {
+        return;
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (status, content_type, body) = if path == "/metrics" {
+        ("200 OK", "text/plain; version=0.0.4; charset=utf-8", render(state))
+    } else {
+        ("404 Not Found", "text/plain", "Not found\n".to_string())
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+}
+
+/// Serve `/metrics` on 127.0.0.1:`port` from a background thread.
+/// Bound to localhost only; nothing is reachable from other machines.
+pub fn start_server(state: Arc<AppState>, port: u16) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(l) => l,
+        Err(e) => {
+            app_err!("[metrics] failed to bind 127.0.0.1:{}: {}", port, e);
+            return;
+        }
+    };
+    app_log!("[metrics] serving http://127.0.0.1:{}/metrics", port);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => handle(stream, &state),
+                Err(e) => app_err!("[metrics] accept error: {}", e),
+            }
+        }
+    });
+}
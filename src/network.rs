@@ -0,0 +1,55 @@
+use crate::state::{AppEvent, AppState};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Provider API hosts; reaching any one of them counts as online. Probing
+/// these rather than a public DNS server keeps proxies and corporate
+/// firewalls that only allow the providers from reading as offline.
+const PROBE_HOSTS: [&str; 4] = [
+    "api.deepgram.com",
+    "streaming.assemblyai.com",
+    "api.openai.com",
+    "api.elevenlabs.io",
+];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const ONLINE_INTERVAL: Duration = Duration::from_secs(15);
+/// Probe faster while offline so the banner clears soon after the network returns.
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(4);
+/// Consecutive failed probes before declaring offline, so one dropped
+/// packet doesn't flash the banner.
+const OFFLINE_AFTER_FAILURES: u32 = 2;
+
+fn probe() -> bool {
+    PROBE_HOSTS.iter().any(|host| {
+        let Ok(mut addrs) = (*host, 443).to_socket_addrs() else {
+            return false;
+        };
+        addrs.any(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok())
+    })
+}
+
+/// Poll connectivity in the background. Updates `AppState::online` and sends
+/// `AppEvent::ConnectivityChanged` on every transition.
+pub fn start_monitor(state: Arc<AppState>, event_tx: std::sync::mpsc::Sender<AppEvent>) {
+    std::thread::spawn(move || {
+        let mut failures = 0;
+        loop {
+            let reachable = probe();
+            failures = if reachable { 0 } else { failures + 1 };
+            let online = reachable || failures < OFFLINE_AFTER_FAILURES;
+            if state.online.swap(online, Ordering::SeqCst) != online {
+                if online {
+                    app_log!("[network] back online");
+                } else {
+                    app_err!("[network] offline: no provider host reachable");
+                }
+                if event_tx.send(AppEvent::ConnectivityChanged { online }).is_err() {
+                    return;
+                }
+            }
+            std::thread::sleep(if online { ONLINE_INTERVAL } else { OFFLINE_INTERVAL });
+        }
+    });
+}
@@ -0,0 +1,193 @@
+// Voice notes: in this mode a dictation session types nothing. Each utterance
+// the VAD commits is saved as a WAV file next to a JSON sidecar holding its
+// transcript, and the Notes tab browses them.
+
+use crate::settings::Settings;
+use crate::state::AppEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender as EventSender;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Contents of a note's `.json` sidecar.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteMeta {
+    pub created_ms: u64,
+    pub provider: String,
+    pub text: String,
+    pub duration_ms: u64,
+    /// File name of the recording, next to the sidecar.
+    pub audio: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct VoiceNote {
+    pub meta: NoteMeta,
+    pub json_path: PathBuf,
+    pub audio_path: PathBuf,
+}
+
+pub fn default_dir() -> Result<PathBuf, String> {
+    if let Some(documents) = dirs::document_dir() {
+        return Ok(documents.join("MangoChat").join("Notes"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        return Ok(home.join("Documents").join("MangoChat").join("Notes"));
+    }
+    Err("Failed to resolve Documents directory".into())
+}
+
+pub fn notes_dir(settings: &Settings) -> Result<PathBuf, String> {
+    if settings.voice_notes_folder.is_empty() {
+        default_dir()
+    } else {
+        Ok(PathBuf::from(&settings.voice_notes_folder))
+    }
+}
+
+/// Notes in `dir`, newest first. Sidecars that don't parse are skipped.
+pub fn list(dir: &Path) -> Vec<VoiceNote> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut notes: Vec<VoiceNote> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|x| x.to_str()) == Some("json"))
+        .filter_map(|json_path| {
+            let meta: NoteMeta = fs::read_to_string(&json_path)
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())?;
+            let audio_path = json_path.with_file_name(&meta.audio);
+            Some(VoiceNote {
+                meta,
+                json_path,
+                audio_path,
+            })
+        })
+        .collect();
+    notes.sort_by(|a, b| b.meta.created_ms.cmp(&a.meta.created_ms));
+    notes
+}
+
+pub fn delete(note: &VoiceNote) -> Result<(), String> {
+    if note.audio_path.exists() {
+        fs::remove_file(&note.audio_path)
+            .map_err(|e| format!("Failed to delete {}: {}", note.audio_path.display(), e))?;
+    }
+    fs::remove_file(&note.json_path)
+        .map_err(|e| format!("Failed to delete {}: {}", note.json_path.display(), e))
+}
+
+/// Audio of the utterance being spoken, and committed ones waiting for
+/// their final transcript.
+#[derive(Default)]
+struct Pending {
+    current: Vec<u8>,
+    committed: VecDeque<Vec<u8>>,
+}
+
+/// Sit between the mic and the provider session: returns the receiver the
+/// session should read from and the sender its finals should go to. Each
+/// final is paired with the oldest committed utterance (or the audio so far,
+/// for providers that finalize before the local VAD commits).
+pub fn start_recorder(
+    runtime: &tokio::runtime::Runtime,
+    mut audio_rx: mpsc::Receiver<Vec<u8>>,
+    sample_rate: u32,
+    dir: PathBuf,
+    provider: String,
+    event_tx: EventSender<AppEvent>,
+) -> (mpsc::Receiver<Vec<u8>>, std::sync::mpsc::Sender<String>) {
+    let pending = Arc::new(Mutex::new(Pending::default()));
+
+    let (session_tx, session_rx) = mpsc::channel::<Vec<u8>>(256);
+    let tee = pending.clone();
+    runtime.spawn(async move {
+        while let Some(chunk) = audio_rx.recv().await {
+            if let Ok(mut p) = tee.lock() {
+                if chunk.is_empty() {
+                    let utterance = std::mem::take(&mut p.current);
+                    if !utterance.is_empty() {
+                        p.committed.push_back(utterance);
+                    }
+                } else {
+                    p.current.extend_from_slice(&chunk);
+                }
+            }
+            if session_tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let (final_tx, final_rx) = std::sync::mpsc::channel::<String>();
+    std::thread::spawn(move || {
+        for text in final_rx {
+            let text = text.trim().to_string();
+            if text.is_empty() {
+                continue;
+            }
+            let pcm = pending
+                .lock()
+                .ok()
+                .map(|mut p| {
+                    p.committed
+                        .pop_front()
+                        .unwrap_or_else(|| std::mem::take(&mut p.current))
+                })
+                .unwrap_or_default();
+            match save(&dir, &provider, &text, &pcm, sample_rate) {
+                Ok(()) => {
+                    let _ = event_tx.send(AppEvent::VoiceNoteSaved);
+                }
+                Err(e) => app_err!("[notes] {}", e),
+            }
+        }
+    });
+
+    (session_rx, final_tx)
+}
+
+fn save(dir: &Path, provider: &str, text: &str, pcm: &[u8], sample_rate: u32) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create notes folder: {}", e))?;
+    let now = chrono::Local::now();
+    let stem = format!("note-{}", now.format("%Y-%m-%d-%H%M%S-%3f"));
+    let audio = format!("{}.wav", stem);
+    fs::write(dir.join(&audio), wav_bytes(pcm, sample_rate))
+        .map_err(|e| format!("Failed to write {}: {}", audio, e))?;
+    let meta = NoteMeta {
+        created_ms: now.timestamp_millis() as u64,
+        provider: provider.to_string(),
+        text: text.to_string(),
+        duration_ms: pcm.len() as u64 / 2 * 1000 / sample_rate.max(1) as u64,
+        audio,
+    };
+    let json = serde_json::to_string_pretty(&meta)
+        .map_err(|e| format!("Failed to serialize note: {}", e))?;
+    fs::write(dir.join(format!("{}.json", stem)), json)
+        .map_err(|e| format!("Failed to write note sidecar: {}", e))?;
+    app_log!("[notes] saved {} ({} ms)", stem, meta.duration_ms);
+    Ok(())
+}
+
+/// 16-bit mono PCM in a RIFF/WAVE container.
+fn wav_bytes(pcm: &[u8], sample_rate: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(44 + pcm.len());
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + pcm.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(pcm.len() as u32).to_le_bytes());
+    out.extend_from_slice(pcm);
+    out
+}
@@ -0,0 +1,60 @@
+/// AppUserModelID used for toasts. Unpackaged apps need a registered id, so
+/// borrow PowerShell's, which is present on every Windows install.
+#[cfg(windows)]
+const TOAST_APP_ID: &str =
+    "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+/// Show a native toast notification on a background thread. Failures are logged.
+pub fn show_toast(title: &str, body: &str) {
+    let title = title.to_string();
+    let body = body.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = show_toast_blocking(&title, &body) {
+            app_err!("[notify] {}", e);
+        }
+    });
+}
+
+#[cfg(windows)]
+fn show_toast_blocking(title: &str, body: &str) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    }
+
+    let xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\">\
+         <text>Mango Chat — {}</text><text>{}</text>\
+         </binding></visual></toast>",
+        escape_xml(title),
+        escape_xml(body)
+    );
+    let doc = XmlDocument::new().map_err(|e| format!("Failed to create toast xml: {}", e))?;
+    doc.LoadXml(&HSTRING::from(xml))
+        .map_err(|e| format!("Failed to load toast xml: {}", e))?;
+    let toast = ToastNotification::CreateToastNotification(&doc)
+        .map_err(|e| format!("Failed to create toast: {}", e))?;
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(TOAST_APP_ID))
+        .map_err(|e| format!("Failed to create toast notifier: {}", e))?
+        .Show(&toast)
+        .map_err(|e| format!("Failed to show toast: {}", e))?;
+    app_log!("[notify] toast shown: {}", title);
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn show_toast_blocking(_title: &str, _body: &str) -> Result<(), String> {
+    Err("Toast notifications are only supported on Windows".into())
+}
+
+#[cfg(windows)]
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
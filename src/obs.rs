@@ -0,0 +1,320 @@
+// obs-websocket (v5) client that mirrors live transcripts into OBS: partial and
+// final text go to a text source, finals also go out as stream captions.
+
+use crate::settings::Settings;
+use crate::state::AppState;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+const RPC_VERSION: u64 = 1;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_MIN: Duration = Duration::from_secs(2);
+const RETRY_MAX: Duration = Duration::from_secs(30);
+/// Blank the text source this long after the last final, like broadcast captions.
+const CLEAR_AFTER: Duration = Duration::from_secs(6);
+/// Keep only the tail of long partials so the text source doesn't overflow the scene.
+const MAX_CAPTION_CHARS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub password: String,
+    /// Text (GDI+/FreeType) input to update; empty = don't touch any source.
+    pub text_source: String,
+    /// Also send finals with SendStreamCaption (CEA-608, only while streaming).
+    pub stream_captions: bool,
+}
+
+impl ObsConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            enabled: settings.obs_enabled,
+            host: settings.obs_host.trim().to_string(),
+            port: settings.obs_port,
+            password: settings
+                .api_key_for(crate::settings::OBS_PASSWORD_KEY)
+                .to_string(),
+            text_source: settings.obs_text_source.trim().to_string(),
+            stream_captions: settings.obs_stream_captions,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Caption {
+    /// `incremental` deltas extend the current line (OpenAI); the rest replace it.
+    Delta { text: String, incremental: bool },
+    Final(String),
+}
+
+/// Handle to the background client; dropping it closes the connection.
+pub struct ObsClient {
+    config: ObsConfig,
+    tx: UnboundedSender<Caption>,
+}
+
+impl ObsClient {
+    pub fn start(runtime: &tokio::runtime::Runtime, state: Arc<AppState>, config: ObsConfig) -> Self {
+        let (tx, rx) = unbounded_channel();
+        runtime.spawn(run(state, config.clone(), rx));
+        Self { config, tx }
+    }
+
+    pub fn config(&self) -> &ObsConfig {
+        &self.config
+    }
+
+    pub fn send(&self, caption: Caption) {
+        let _ = self.tx.send(caption);
+    }
+}
+
+fn set_status(state: &AppState, status: &str) {
+    if let Ok(mut s) = state.obs_status.lock() {
+        *s = status.to_string();
+    }
+}
+
+/// `base64(sha256(base64(sha256(password + salt)) + challenge))`
+fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+fn tail(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(count - max_chars)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    text[start..].trim_start()
+}
+
+type Ws = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn next_json(ws: &mut Ws) -> Result<Value, String> {
+    loop {
+        match ws.next().await {
+            Some(Ok(Message::Text(text))) => {
+                return serde_json::from_str(&text)
+                    .map_err(|e| format!("Failed to parse OBS message: {}", e))
+            }
+            Some(Ok(Message::Close(frame))) => {
+                return Err(match frame {
+                    Some(f) if u16::from(f.code) == 4009 => "Authentication failed".into(),
+                    Some(f) if !f.reason.is_empty() => format!("OBS closed the connection: {}", f.reason),
+                    _ => "OBS closed the connection".into(),
+                })
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(format!("OBS connection error: {}", e)),
+            None => return Err("OBS closed the connection".into()),
+        }
+    }
+}
+
+/// Connect and complete the Hello / Identify handshake.
+async fn connect(config: &ObsConfig) -> Result<(Ws, String), String> {
+    let url = format!("ws://{}:{}", config.host, config.port);
+    let (mut ws, _) = tokio::time::timeout(CONNECT_TIMEOUT, connect_async(url.as_str()))
+        .await
+        .map_err(|_| format!("Timed out connecting to {}", url))?
+        .map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+    let hello = next_json(&mut ws).await?;
+    let d = &hello["d"];
+    let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+    if let Some(auth) = d.get("authentication") {
+        if config.password.is_empty() {
+            return Err("OBS requires a password".into());
+        }
+        identify["authentication"] = json!(auth_response(
+            &config.password,
+            auth["salt"].as_str().unwrap_or(""),
+            auth["challenge"].as_str().unwrap_or(""),
+        ));
+    }
+    ws.send(Message::Text(json!({ "op": 1, "d": identify }).to_string()))
+        .await
+        .map_err(|e| format!("Failed to identify with OBS: {}", e))?;
+    let identified = next_json(&mut ws).await?;
+    if identified["op"] != 2 {
+        return Err("Unexpected reply to Identify".into());
+    }
+    let version = d["obsWebSocketVersion"].as_str().unwrap_or("?").to_string();
+    Ok((ws, version))
+}
+
+struct Session {
+    ws: Ws,
+    next_id: u64,
+}
+
+impl Session {
+    async fn request(&mut self, request_type: &str, data: Value) -> Result<(), String> {
+        self.next_id += 1;
+        let msg = json!({
+            "op": 6,
+            "d": {
+                "requestType": request_type,
+                "requestId": self.next_id.to_string(),
+                "requestData": data
+            }
+        });
+        self.ws
+            .send(Message::Text(msg.to_string()))
+            .await
+            .map_err(|e| format!("Failed to send {}: {}", request_type, e))
+    }
+
+    async fn set_text(&mut self, source: &str, text: &str) -> Result<(), String> {
+        if source.is_empty() {
+            return Ok(());
+        }
+        self.request(
+            "SetInputSettings",
+            json!({ "inputName": source, "inputSettings": { "text": text } }),
+        )
+        .await
+    }
+}
+
+/// Log failed requests. SendStreamCaption fails with 501 whenever OBS isn't
+/// streaming, which is normal, so that one is ignored.
+fn check_response(msg: &Value) {
+    if msg["op"] != 7 {
+        return;
+    }
+    let d = &msg["d"];
+    let status = &d["requestStatus"];
+    if status["result"].as_bool().unwrap_or(true) {
+        return;
+    }
+    if d["requestType"] == "SendStreamCaption" && status["code"] == 501 {
+        return;
+    }
+    app_err!(
+        "[obs] {} failed ({}): {}",
+        d["requestType"].as_str().unwrap_or("request"),
+        status["code"],
+        status["comment"].as_str().unwrap_or("")
+    );
+}
+
+async fn run(state: Arc<AppState>, config: ObsConfig, mut rx: UnboundedReceiver<Caption>) {
+    let mut retry = RETRY_MIN;
+    loop {
+        set_status(&state, "Connecting...");
+        let (ws, version) = match connect(&config).await {
+            Ok(c) => c,
+            Err(e) => {
+                app_err!("[obs] {}", e);
+                set_status(&state, &e);
+                // Captions that arrive while disconnected are stale by the next attempt.
+                let deadline = tokio::time::sleep(retry);
+                tokio::pin!(deadline);
+                loop {
+                    tokio::select! {
+                        _ = &mut deadline => break,
+                        caption = rx.recv() => if caption.is_none() { return },
+                    }
+                }
+                retry = (retry * 2).min(RETRY_MAX);
+                continue;
+            }
+        };
+        app_log!("[obs] connected to {}:{} (obs-websocket {})", config.host, config.port, version);
+        set_status(&state, &format!("Connected (obs-websocket {})", version));
+        retry = RETRY_MIN;
+
+        let mut session = Session { ws, next_id: 0 };
+        let mut line = String::new();
+        let mut line_done = false;
+        let clear = tokio::time::sleep(Duration::MAX);
+        tokio::pin!(clear);
+        let mut clear_armed = false;
+        let result: Result<(), String> = loop {
+            tokio::select! {
+                caption = rx.recv() => {
+                    let Some(mut caption) = caption else {
+                        let _ = session.set_text(&config.text_source, "").await;
+                        let _ = session.ws.close(None).await;
+                        set_status(&state, "Disconnected");
+                        return;
+                    };
+                    // Deltas can arrive faster than OBS redraws; only the latest state matters.
+                    let mut finals = vec![];
+                    loop {
+                        match caption {
+                            Caption::Delta { text, incremental } => {
+                                if line_done || !incremental {
+                                    line.clear();
+                                }
+                                line_done = false;
+                                line.push_str(&text);
+                            }
+                            Caption::Final(text) => {
+                                line = text.clone();
+                                line_done = true;
+                                finals.push(text);
+                            }
+                        }
+                        match rx.try_recv() {
+                            Ok(next) => caption = next,
+                            Err(_) => break,
+                        }
+                    }
+                    let shown = tail(line.trim(), MAX_CAPTION_CHARS).to_string();
+                    if let Err(e) = session.set_text(&config.text_source, &shown).await {
+                        break Err(e);
+                    }
+                    if config.stream_captions {
+                        for text in finals {
+                            // A failed send also breaks the read side; handled there.
+                            if session
+                                .request("SendStreamCaption", json!({ "captionText": text }))
+                                .await
+                                .is_err()
+                            {
+                                break;
+                            }
+                        }
+                    }
+                    if line_done {
+                        clear.as_mut().reset(tokio::time::Instant::now() + CLEAR_AFTER);
+                        clear_armed = true;
+                    } else {
+                        clear_armed = false;
+                    }
+                }
+                _ = &mut clear, if clear_armed => {
+                    clear_armed = false;
+                    line.clear();
+                    if let Err(e) = session.set_text(&config.text_source, "").await {
+                        break Err(e);
+                    }
+                }
+                msg = next_json(&mut session.ws) => match msg {
+                    Ok(msg) => check_response(&msg),
+                    Err(e) => break Err(e),
+                },
+            }
+        };
+        if let Err(e) = result {
+            app_err!("[obs] {}", e);
+            set_status(&state, &e);
+        }
+    }
+}
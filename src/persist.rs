@@ -0,0 +1,153 @@
+// Persistence worker: settings, session rows and usage totals are written on
+// a background thread so a slow disk never stalls the UI. Writes that arrive
+// close together are coalesced (only the newest settings and totals are
+// written); finished sessions are all kept. Failed settings writes surface
+// as an error status, like the synchronous save did.
+
+use crate::settings::Settings;
+use crate::state::{AppEvent, ProviderUsage, SessionUsage, UsageTotals};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// How long to wait for more writes before touching the disk.
+const COALESCE_MS: u64 = 200;
+/// Longest `flush` waits at exit.
+const FLUSH_TIMEOUT_MS: u64 = 3000;
+
+enum Job {
+    Settings(Box<Settings>),
+    Session(SessionUsage),
+    Usage(UsageTotals, HashMap<String, ProviderUsage>),
+    Flush(SyncSender<()>),
+}
+
+static WORKER: OnceLock<Sender<Job>> = OnceLock::new();
+/// Settings saves queued but not yet on disk.
+static SETTINGS_PENDING: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Default)]
+struct Batch {
+    settings: Option<Box<Settings>>,
+    settings_saves: usize,
+    sessions: Vec<SessionUsage>,
+    usage: Option<(UsageTotals, HashMap<String, ProviderUsage>)>,
+    waiters: Vec<SyncSender<()>>,
+}
+
+impl Batch {
+    fn add(&mut self, job: Job) {
+        match job {
+            Job::Settings(settings) => {
+                self.settings = Some(settings);
+                self.settings_saves += 1;
+            }
+            Job::Session(session) => self.sessions.push(session),
+            Job::Usage(totals, providers) => self.usage = Some((totals, providers)),
+            Job::Flush(done) => self.waiters.push(done),
+        }
+    }
+
+    fn write(self, event_tx: &Sender<AppEvent>) {
+        if let Some(settings) = self.settings {
+            if let Err(e) = crate::settings::save(&settings) {
+                app_err!("[persist] {}", e);
+                let _ = event_tx.send(AppEvent::StatusUpdate {
+                    status: "error".into(),
+                    message: format!("Save failed: {}", e),
+                });
+            }
+            SETTINGS_PENDING.fetch_sub(self.settings_saves, Ordering::SeqCst);
+        }
+        for session in &self.sessions {
+            if let Err(e) = crate::usage::append_session(session) {
+                app_err!("[usage] {}", e);
+            }
+        }
+        if let Some((totals, providers)) = self.usage {
+            if let Err(e) = crate::usage::save_usage(&totals) {
+                app_err!("[usage] {}", e);
+            }
+            if let Err(e) = crate::usage::save_provider_totals(&providers) {
+                app_err!("[usage] {}", e);
+            }
+        }
+        for done in self.waiters {
+            let _ = done.send(());
+        }
+    }
+}
+
+fn run(rx: Receiver<Job>, event_tx: Sender<AppEvent>) {
+    while let Ok(first) = rx.recv() {
+        let mut batch = Batch::default();
+        batch.add(first);
+        let deadline = Instant::now() + Duration::from_millis(COALESCE_MS);
+        while batch.waiters.is_empty() {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match rx.recv_timeout(left) {
+                Ok(job) => batch.add(job),
+                Err(_) => break,
+            }
+        }
+        batch.write(&event_tx);
+    }
+}
+
+/// Start the worker thread. Until it runs, writes happen on the caller's
+/// thread (the embedding DLL never starts it).
+pub fn start(event_tx: Sender<AppEvent>) {
+    let (tx, rx) = mpsc::channel();
+    if WORKER.set(tx).is_ok() {
+        std::thread::spawn(move || run(rx, event_tx));
+    }
+}
+
+fn submit(job: Job) {
+    let job = match WORKER.get() {
+        Some(tx) => match tx.send(job) {
+            Ok(()) => return,
+            Err(mpsc::SendError(job)) => job,
+        },
+        None => job,
+    };
+    let (event_tx, _) = mpsc::channel();
+    let mut batch = Batch::default();
+    batch.add(job);
+    batch.write(&event_tx);
+}
+
+/// Queue settings.json and the API key store for writing.
+pub fn save_settings(settings: &Settings) {
+    SETTINGS_PENDING.fetch_add(1, Ordering::SeqCst);
+    submit(Job::Settings(Box::new(settings.clone())));
+}
+
+/// Queue a finished session for the usage database.
+pub fn append_session(session: &SessionUsage) {
+    submit(Job::Session(session.clone()));
+}
+
+/// Queue the all-time and per-provider totals.
+pub fn save_usage(totals: UsageTotals, providers: HashMap<String, ProviderUsage>) {
+    submit(Job::Usage(totals, providers));
+}
+
+/// True while a queued settings save hasn't reached the disk, so the file
+/// watcher doesn't reload an older file over newer in-memory settings.
+pub fn settings_pending() -> bool {
+    SETTINGS_PENDING.load(Ordering::SeqCst) > 0
+}
+
+/// Write everything queued so far; called before the process exits.
+pub fn flush() {
+    let Some(tx) = WORKER.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = mpsc::sync_channel(1);
+    if tx.send(Job::Flush(done_tx)).is_ok() {
+        let _ = done_rx.recv_timeout(Duration::from_millis(FLUSH_TIMEOUT_MS));
+    }
+}
@@ -0,0 +1,229 @@
+// WASM post-processing plugins.
+//
+// A plugin is a `.wasm` file in the plugins folder exporting:
+// - `memory`
+// - `alloc(len: i32) -> i32`: reserve `len` bytes for the input
+// - `transform(ptr: i32, len: i32) -> i64`: read the UTF-8 final transcript at
+//   `ptr..ptr+len` and return `(out_ptr << 32) | out_len` pointing at the output
+//
+// The output is either plain text (typed instead of the transcript) or JSON:
+// `{"text": "...", "actions": [{"type": "open_url", "url": "..."}]}`.
+// Plugins get no imports, so they can't touch files, the network or the
+// clipboard; each call runs in a fresh instance with fuel and memory limits.
+
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Instruction budget per call; a runaway loop traps instead of hanging dictation.
+const FUEL_PER_CALL: u64 = 50_000_000;
+const MAX_MEMORY_BYTES: usize = 32 * 1024 * 1024;
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Side effects a plugin can ask for after its text is typed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginAction {
+    OpenUrl { url: String },
+    PressEnter,
+    CopyToClipboard { text: String },
+    TypeText { text: String },
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PluginOutput {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    actions: Vec<PluginAction>,
+}
+
+/// A `.wasm` file in the plugins folder, as shown in Commands > Plugins.
+#[derive(Debug, Clone)]
+pub struct PluginInfo {
+    pub file_name: String,
+    pub enabled: bool,
+    /// Compile or last-run error, if any.
+    pub error: Option<String>,
+}
+
+struct LoadedPlugin {
+    file_name: String,
+    module: Module,
+}
+
+struct Host {
+    engine: Engine,
+    plugins: Vec<LoadedPlugin>,
+    errors: Vec<(String, String)>,
+}
+
+static HOST: Mutex<Option<Host>> = Mutex::new(None);
+
+pub fn plugins_dir() -> Result<PathBuf, String> {
+    Ok(crate::diagnostics::data_dir()?.join("plugins"))
+}
+
+fn wasm_files() -> Vec<String> {
+    let Ok(dir) = plugins_dir() else {
+        return vec![];
+    };
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .filter(|n| n.to_ascii_lowercase().ends_with(".wasm"))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Compile the enabled plugins, in file-name order. Called at startup and
+/// whenever settings are saved.
+pub fn reload(enabled: &[String]) {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let mut plugins = vec![];
+    let mut errors = vec![];
+    let dir = plugins_dir().ok();
+    for name in wasm_files().into_iter().filter(|n| enabled.contains(n)) {
+        let Some(path) = dir.as_ref().map(|d| d.join(&name)) else {
+            continue;
+        };
+        let compiled = fs::read(&path)
+            .map_err(|e| format!("Failed to read plugin: {}", e))
+            .and_then(|bytes| {
+                Module::new(&engine, &bytes[..]).map_err(|e| format!("Invalid plugin: {}", e))
+            });
+        match compiled {
+            Ok(module) => {
+                app_log!("[plugins] loaded {}", name);
+                plugins.push(LoadedPlugin {
+                    file_name: name,
+                    module,
+                });
+            }
+            Err(e) => {
+                app_err!("[plugins] {}: {}", name, e);
+                errors.push((name, e));
+            }
+        }
+    }
+    if let Ok(mut host) = HOST.lock() {
+        *host = Some(Host {
+            engine,
+            plugins,
+            errors,
+        });
+    }
+}
+
+/// Every `.wasm` in the plugins folder with its enabled state and last error.
+pub fn list(enabled: &[String]) -> Vec<PluginInfo> {
+    let errors: Vec<(String, String)> = HOST
+        .lock()
+        .ok()
+        .and_then(|h| h.as_ref().map(|h| h.errors.clone()))
+        .unwrap_or_default();
+    wasm_files()
+        .into_iter()
+        .map(|file_name| PluginInfo {
+            enabled: enabled.contains(&file_name),
+            error: errors
+                .iter()
+                .find(|(n, _)| *n == file_name)
+                .map(|(_, e)| e.clone()),
+            file_name,
+        })
+        .collect()
+}
+
+fn call_transform(engine: &Engine, module: &Module, input: &str) -> Result<String, String> {
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_BYTES)
+        .build();
+    let mut store: Store<StoreLimits> = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store
+        .set_fuel(FUEL_PER_CALL)
+        .map_err(|e| format!("Failed to set fuel: {}", e))?;
+    // No host functions: a plugin that imports anything fails to instantiate.
+    let linker = Linker::<StoreLimits>::new(engine);
+    let instance = linker
+        .instantiate(&mut store, module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| format!("Failed to instantiate: {}", e))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or("Plugin does not export memory")?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|e| format!("Missing alloc export: {}", e))?;
+    let transform = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "transform")
+        .map_err(|e| format!("Missing transform export: {}", e))?;
+
+    let bytes = input.as_bytes();
+    let ptr = alloc
+        .call(&mut store, bytes.len() as i32)
+        .map_err(|e| format!("alloc trapped: {}", e))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, bytes)
+        .map_err(|e| format!("Failed to write input: {}", e))?;
+    let packed = transform
+        .call(&mut store, (ptr, bytes.len() as i32))
+        .map_err(|e| format!("transform trapped: {}", e))?;
+    let out_ptr = (packed as u64 >> 32) as usize;
+    let out_len = (packed as u64 & 0xffff_ffff) as usize;
+    if out_len > MAX_OUTPUT_BYTES {
+        return Err(format!("Output too large ({} bytes)", out_len));
+    }
+    let mut out = vec![0u8; out_len];
+    memory
+        .read(&store, out_ptr, &mut out)
+        .map_err(|e| format!("Failed to read output: {}", e))?;
+    String::from_utf8(out).map_err(|e| format!("Output is not UTF-8: {}", e))
+}
+
+/// Run `text` through every enabled plugin in order. A plugin that fails is
+/// skipped (its input passes through unchanged) and its error is kept for the UI.
+pub fn transform(text: &str) -> (String, Vec<PluginAction>) {
+    let mut text = text.to_string();
+    let mut actions = vec![];
+    let Ok(mut guard) = HOST.lock() else {
+        return (text, actions);
+    };
+    let Some(host) = guard.as_mut() else {
+        return (text, actions);
+    };
+    for plugin in &host.plugins {
+        match call_transform(&host.engine, &plugin.module, &text) {
+            Ok(out) => {
+                let parsed = if out.trim_start().starts_with('{') {
+                    serde_json::from_str::<PluginOutput>(&out).ok()
+                } else {
+                    None
+                };
+                match parsed {
+                    Some(output) => {
+                        text = output.text;
+                        actions.extend(output.actions);
+                    }
+                    None => text = out,
+                }
+            }
+            Err(e) => {
+                app_err!("[plugins] {}: {}", plugin.file_name, e);
+                host.errors.retain(|(n, _)| *n != plugin.file_name);
+                host.errors.push((plugin.file_name.clone(), e));
+            }
+        }
+    }
+    (text, actions)
+}
@@ -0,0 +1,144 @@
+// Runtime binding to Picovoice Porcupine (`wake-word` feature). Nothing from
+// Picovoice ships with Mango Chat: the user points settings at the library,
+// model (.pv) and keyword (.ppn) files from their own Picovoice download, and
+// this loads the library with libloading and calls its C API directly.
+
+use libloading::Library;
+use std::ffi::{c_char, c_float, c_void, CStr, CString};
+
+type InitFn = unsafe extern "C" fn(
+    access_key: *const c_char,
+    model_path: *const c_char,
+    num_keywords: i32,
+    keyword_paths: *const *const c_char,
+    sensitivities: *const c_float,
+    object: *mut *mut c_void,
+) -> i32;
+type DeleteFn = unsafe extern "C" fn(object: *mut c_void);
+type ProcessFn =
+    unsafe extern "C" fn(object: *mut c_void, pcm: *const i16, keyword_index: *mut i32) -> i32;
+type IntFn = unsafe extern "C" fn() -> i32;
+type StatusToStringFn = unsafe extern "C" fn(status: i32) -> *const c_char;
+
+/// One keyword spotter. Frames passed to `process` must be exactly
+/// `frame_length` samples at `sample_rate`.
+pub struct Porcupine {
+    object: *mut c_void,
+    process_fn: ProcessFn,
+    delete_fn: DeleteFn,
+    status_to_string: StatusToStringFn,
+    frame_length: usize,
+    sample_rate: u32,
+    // Declared last so the library is unloaded after `drop` deletes the object.
+    _library: Library,
+}
+
+// The Porcupine object is only touched from the thread that owns this value.
+unsafe impl Send for Porcupine {}
+
+impl Porcupine {
+    pub fn load(
+        library_path: &str,
+        model_path: &str,
+        keyword_path: &str,
+        access_key: &str,
+        sensitivity: f32,
+    ) -> Result<Self, String> {
+        let cstr = |what: &str, s: &str| {
+            CString::new(s).map_err(|_| format!("{} contains a NUL character", what))
+        };
+        let access_key = cstr("Access key", access_key)?;
+        let model_path = cstr("Model path", model_path)?;
+        let keyword_path = cstr("Keyword path", keyword_path)?;
+
+        let library = unsafe { Library::new(library_path) }
+            .map_err(|e| format!("Failed to load Porcupine library {}: {}", library_path, e))?;
+        let init_fn: InitFn = symbol(&library, b"pv_porcupine_init\0")?;
+        let delete_fn: DeleteFn = symbol(&library, b"pv_porcupine_delete\0")?;
+        let process_fn: ProcessFn = symbol(&library, b"pv_porcupine_process\0")?;
+        let frame_length_fn: IntFn = symbol(&library, b"pv_porcupine_frame_length\0")?;
+        let sample_rate_fn: IntFn = symbol(&library, b"pv_sample_rate\0")?;
+        let status_to_string: StatusToStringFn = symbol(&library, b"pv_status_to_string\0")?;
+
+        let keyword_paths = [keyword_path.as_ptr()];
+        let sensitivities = [sensitivity as c_float];
+        let mut object: *mut c_void = std::ptr::null_mut();
+        let status = unsafe {
+            init_fn(
+                access_key.as_ptr(),
+                model_path.as_ptr(),
+                1,
+                keyword_paths.as_ptr(),
+                sensitivities.as_ptr(),
+                &mut object,
+            )
+        };
+        if status != 0 || object.is_null() {
+            return Err(format!(
+                "Porcupine init failed: {}",
+                status_name(status_to_string, status)
+            ));
+        }
+        let frame_length = unsafe { frame_length_fn() }.max(0) as usize;
+        let sample_rate = unsafe { sample_rate_fn() }.max(0) as u32;
+        Ok(Self {
+            object,
+            process_fn,
+            delete_fn,
+            status_to_string,
+            frame_length,
+            sample_rate,
+            _library: library,
+        })
+    }
+
+    /// True when the keyword was heard in this frame.
+    pub fn process(&self, frame: &[i16]) -> Result<bool, String> {
+        if frame.len() != self.frame_length {
+            return Err(format!(
+                "frame has {} samples, expected {}",
+                frame.len(),
+                self.frame_length
+            ));
+        }
+        let mut index: i32 = -1;
+        let status = unsafe { (self.process_fn)(self.object, frame.as_ptr(), &mut index) };
+        if status != 0 {
+            return Err(status_name(self.status_to_string, status));
+        }
+        Ok(index >= 0)
+    }
+
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+impl Drop for Porcupine {
+    fn drop(&mut self) {
+        unsafe { (self.delete_fn)(self.object) };
+    }
+}
+
+/// Copy a function pointer out of the library. The caller keeps the library
+/// alive for as long as the pointer is used.
+fn symbol<T: Copy>(library: &Library, name: &[u8]) -> Result<T, String> {
+    unsafe { library.get::<T>(name) }.map(|s| *s).map_err(|e| {
+        let name = String::from_utf8_lossy(&name[..name.len() - 1]);
+        format!("Porcupine library has no {}: {}", name, e)
+    })
+}
+
+fn status_name(status_to_string: StatusToStringFn, status: i32) -> String {
+    let name = unsafe { status_to_string(status) };
+    if name.is_null() {
+        return format!("status {}", status);
+    }
+    unsafe { CStr::from_ptr(name) }
+        .to_string_lossy()
+        .into_owned()
+}
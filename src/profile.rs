@@ -0,0 +1,95 @@
+// Named profiles. `--profile work` gives this run its own settings, keys,
+// usage history, logs and single-instance lock, so `--profile work` and
+// `--profile personal` can run side by side. Without the flag everything
+// stays where it always was.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const MAX_NAME_LEN: usize = 32;
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Letters, digits, '-' and '_' only, since the name ends up in a folder,
+/// a mutex and a pipe name.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The `--profile` value in `args` (without the program name). Read before
+/// logging starts, because the log folder depends on it.
+pub fn from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.split_once('=') {
+            Some(("--profile", v)) => v.to_string(),
+            None if arg == "--profile" => iter.next().cloned().unwrap_or_default(),
+            _ => continue,
+        };
+        let value = value.trim().to_ascii_lowercase();
+        if value.is_empty() || value == "default" {
+            return None;
+        }
+        if is_valid_name(&value) {
+            return Some(value);
+        }
+        app_err!(
+            "[profile] ignoring invalid profile name \"{}\" (letters, digits, - and _ only)",
+            value
+        );
+        return None;
+    }
+    None
+}
+
+/// Select the profile for this run. Call once, before anything resolves a
+/// data path.
+pub fn set(name: Option<String>) {
+    let _ = PROFILE.set(name);
+}
+
+/// The active profile, or None for the default one.
+pub fn name() -> Option<&'static str> {
+    PROFILE.get().and_then(|p| p.as_deref())
+}
+
+/// Root of the data folder shared by all profiles.
+fn base_dir() -> Option<PathBuf> {
+    if let Some(dir) = dirs::data_local_dir() {
+        return Some(dir.join("MangoChat"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        return Some(home.join(".mangochat"));
+    }
+    None
+}
+
+/// Data folder for the active profile: the base folder for the default
+/// profile, `profiles/<name>` under it otherwise.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = base_dir()?;
+    Some(match name() {
+        Some(name) => base.join("profiles").join(name),
+        None => base,
+    })
+}
+
+/// `base` with the profile name appended, for per-profile OS object names.
+pub fn scoped(base: &str) -> String {
+    match name() {
+        Some(name) => format!("{}.{}", base, name),
+        None => base.to_string(),
+    }
+}
+
+/// Window and tray title, naming the profile when one is active.
+pub fn window_title() -> String {
+    match name() {
+        Some(name) => format!("Mango Chat ({})", name),
+        None => "Mango Chat".to_string(),
+    }
+}
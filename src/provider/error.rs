@@ -0,0 +1,385 @@
+// Typed provider failures. Connection errors, close frames and provider error
+// events are sorted into kinds by HTTP status and error code, so a session can
+// tell a bad key (stop and say so) from a rate limit (back off longer) from a
+// network drop (retry soon).
+
+use serde_json::Value;
+use std::time::Duration;
+use thiserror::Error;
+use tokio_tungstenite::tungstenite;
+
+pub const RECONNECT_BASE_MS: u64 = 800;
+const RECONNECT_MAX_MS: u64 = 30_000;
+const RECONNECT_MAX_RETRIES: u32 = 12;
+const RATE_LIMIT_BASE_MS: u64 = 5_000;
+const RATE_LIMIT_MAX_MS: u64 = 120_000;
+
+#[derive(Debug, Clone, Error)]
+pub enum ProviderError {
+    /// The key was rejected. Retrying won't help.
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+    /// Out of credit or over the plan's quota. Retrying won't help.
+    #[error("Quota exceeded: {0}")]
+    Quota(String),
+    /// Too many requests; `retry_after` is the provider's hint when it sent one.
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// DNS, TCP, TLS or a dropped socket.
+    #[error("Network error: {0}")]
+    Network(String),
+    /// The provider closed the connection without a more specific reason.
+    #[error("Disconnected: {code} {reason}")]
+    Closed { code: u16, reason: String },
+    /// We sent something the provider refused, or couldn't build the request.
+    #[error("Request rejected: {0}")]
+    Protocol(String),
+    /// An error event the provider reported mid-session.
+    #[error("{0}")]
+    Provider(String),
+}
+
+impl ProviderError {
+    /// Sort a failed WebSocket connect by HTTP status. Anything that never got
+    /// a response is a network failure.
+    pub fn from_connect(err: &tungstenite::Error) -> Self {
+        match err {
+            tungstenite::Error::Http(resp) => {
+                let body = resp
+                    .body()
+                    .as_ref()
+                    .map(|b| String::from_utf8_lossy(b).trim().to_string())
+                    .filter(|b| !b.is_empty())
+                    .unwrap_or_else(|| resp.status().to_string());
+                let retry_after = resp
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                Self::from_status(resp.status().as_u16(), body, retry_after)
+            }
+            tungstenite::Error::Url(e) => Self::Protocol(e.to_string()),
+            _ => Self::Network(err.to_string()),
+        }
+    }
+
+    /// Sort a close frame by its code, or by a structured error in the reason
+    /// when the provider sends one. Providers use 4xxx codes for auth and
+    /// quota problems.
+    pub fn from_close(code: u16, reason: &str) -> Self {
+        if let Some(err) = Self::from_payload(reason) {
+            return err;
+        }
+        match code {
+            4001 | 4003 => Self::Auth(reason.to_string()),
+            4002 => Self::Quota(reason.to_string()),
+            4029 => Self::RateLimited {
+                message: reason.to_string(),
+                retry_after: None,
+            },
+            _ => Self::Closed {
+                code,
+                reason: reason.to_string(),
+            },
+        }
+    }
+
+    /// Sort an error event from `SttProvider::parse_event`. Events are the
+    /// provider's JSON payload; plain text stays a generic provider error.
+    pub fn from_event(message: &str) -> Self {
+        Self::from_payload(message).unwrap_or_else(|| {
+            let text = serde_json::from_str::<Value>(message)
+                .ok()
+                .and_then(|v| error_text(&v))
+                .unwrap_or_else(|| message.to_string());
+            Self::Provider(text)
+        })
+    }
+
+    /// Sort by HTTP status. 408, 425 and 5xx are transient; any other 3xx or
+    /// 4xx means the request itself was refused.
+    fn from_status(status: u16, message: String, retry_after: Option<Duration>) -> Self {
+        match status {
+            401 | 403 => Self::Auth(message),
+            402 => Self::Quota(message),
+            429 => Self::RateLimited {
+                message,
+                retry_after,
+            },
+            408 | 425 => Self::Network(message),
+            300..=499 => Self::Protocol(message),
+            _ => Self::Network(message),
+        }
+    }
+
+    /// Read the error code fields of a JSON error payload. Free text is never
+    /// matched: a transcript or message that mentions "401" or "quota" must not
+    /// end the session.
+    fn from_payload(text: &str) -> Option<Self> {
+        let event = serde_json::from_str::<Value>(text).ok()?;
+        let message = error_text(&event).unwrap_or_else(|| text.to_string());
+        let error = event.get("error").filter(|e| e.is_object());
+        let field = |name: &str| error.and_then(|e| e.get(name)).or_else(|| event.get(name));
+        let status = ["status", "status_code", "code"]
+            .iter()
+            .find_map(|name| field(name).and_then(|v| v.as_u64()))
+            .and_then(|s| u16::try_from(s).ok())
+            .filter(|s| (400..=599).contains(s));
+        if let Some(err) = ["code", "type", "error_code", "message_type"]
+            .iter()
+            .filter_map(|name| field(name).and_then(|v| v.as_str()))
+            .find_map(|code| Self::classify(code, &message))
+        {
+            return Some(err);
+        }
+        status.map(|s| Self::from_status(s, message, None))
+    }
+
+    /// Recognize a provider error code. Codes are compared whole, never
+    /// searched for inside longer text.
+    fn classify(code: &str, message: &str) -> Option<Self> {
+        let message = message.to_string();
+        match code.to_ascii_lowercase().as_str() {
+            "invalid_api_key"
+            | "invalid_auth"
+            | "auth_error"
+            | "authentication_error"
+            | "unauthorized"
+            | "permission_denied" => Some(Self::Auth(message)),
+            "insufficient_quota"
+            | "quota_exceeded"
+            | "insufficient_credits"
+            | "billing_hard_limit_reached"
+            | "payment_required" => Some(Self::Quota(message)),
+            "rate_limit_exceeded" | "rate_limit_error" | "rate_limited" | "too_many_requests" => {
+                Some(Self::RateLimited {
+                    message,
+                    retry_after: None,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Short kind name for logs and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Auth(_) => "auth",
+            Self::Quota(_) => "quota",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Network(_) => "network",
+            Self::Closed { .. } => "closed",
+            Self::Protocol(_) => "protocol",
+            Self::Provider(_) => "provider",
+        }
+    }
+
+    /// True when the session can't continue and should give up now.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Auth(_) | Self::Quota(_) | Self::Protocol(_))
+    }
+
+    /// How long to wait before reconnect `attempt` (1-based), or None to give
+    /// up: never for fatal errors, and only up to a retry limit otherwise.
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        if self.is_fatal() || attempt >= RECONNECT_MAX_RETRIES {
+            return None;
+        }
+        let factor = 1u64 << attempt.saturating_sub(1).min(10);
+        let ms = match self {
+            Self::RateLimited {
+                retry_after: Some(after),
+                ..
+            } => return Some(*after),
+            Self::RateLimited { .. } => RATE_LIMIT_BASE_MS
+                .saturating_mul(factor)
+                .min(RATE_LIMIT_MAX_MS),
+            _ => RECONNECT_BASE_MS
+                .saturating_mul(factor)
+                .min(RECONNECT_MAX_MS),
+        };
+        Some(Duration::from_millis(ms))
+    }
+
+    /// Status line for the UI, naming what the user can do about it.
+    pub fn user_message(&self, provider_name: &str) -> String {
+        match self {
+            Self::Auth(_) => format!(
+                "{} rejected the API key. Check it under Settings > Provider.",
+                provider_name
+            ),
+            Self::Quota(_) => format!(
+                "{} account is out of credit or quota. Check your plan.",
+                provider_name
+            ),
+            Self::RateLimited { .. } => format!("{} is rate limiting requests", provider_name),
+            Self::Network(e) => format!("Can't reach {}: {}", provider_name, e),
+            Self::Closed { code, reason } => format!("Disconnected: {} {}", code, reason),
+            Self::Protocol(e) => format!("{} refused the request: {}", provider_name, e),
+            Self::Provider(e) => e.clone(),
+        }
+    }
+}
+
+/// The human-readable message of a JSON error payload.
+fn error_text(event: &Value) -> Option<String> {
+    let error = event.get("error");
+    error
+        .and_then(|e| e.get("message"))
+        .or_else(|| event.get("message"))
+        .or_else(|| error.filter(|e| e.is_string()))
+        .or_else(|| event.get("description"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::http::Response;
+
+    fn http_error(status: u16, retry_after: Option<&str>) -> tungstenite::Error {
+        let mut builder = Response::builder().status(status);
+        if let Some(after) = retry_after {
+            builder = builder.header("retry-after", after);
+        }
+        tungstenite::Error::Http(builder.body(Some(b"refused".to_vec())).unwrap())
+    }
+
+    #[test]
+    fn classify_matches_whole_codes_only() {
+        assert!(matches!(
+            ProviderError::classify("invalid_api_key", "bad key"),
+            Some(ProviderError::Auth(m)) if m == "bad key"
+        ));
+        assert!(matches!(
+            ProviderError::classify("INSUFFICIENT_QUOTA", ""),
+            Some(ProviderError::Quota(_))
+        ));
+        assert!(matches!(
+            ProviderError::classify("rate_limit_exceeded", ""),
+            Some(ProviderError::RateLimited {
+                retry_after: None,
+                ..
+            })
+        ));
+        assert!(ProviderError::classify("not_an_invalid_api_key_error", "").is_none());
+        assert!(ProviderError::classify("server_error", "").is_none());
+    }
+
+    #[test]
+    fn free_text_never_classifies() {
+        for text in [
+            "error 401 while reading quota for billing",
+            "Too many words, rate limit the speaker",
+            r#"{"type":"error","error":{"message":"insufficient credits 402"}}"#,
+        ] {
+            let err = ProviderError::from_event(text);
+            assert!(matches!(err, ProviderError::Provider(_)), "{text}");
+            assert!(!err.is_fatal());
+        }
+    }
+
+    #[test]
+    fn events_classify_from_error_fields() {
+        let openai = r#"{"type":"error","error":{"type":"invalid_request_error","code":"insufficient_quota","message":"You exceeded your quota"}}"#;
+        assert!(matches!(
+            ProviderError::from_event(openai),
+            ProviderError::Quota(m) if m == "You exceeded your quota"
+        ));
+        let elevenlabs = r#"{"message_type":"auth_error","error":"Invalid key"}"#;
+        assert!(matches!(
+            ProviderError::from_event(elevenlabs),
+            ProviderError::Auth(m) if m == "Invalid key"
+        ));
+        let status = r#"{"type":"Error","status":429,"error":"slow down"}"#;
+        assert!(matches!(
+            ProviderError::from_event(status),
+            ProviderError::RateLimited { .. }
+        ));
+        let unknown = r#"{"type":"Error","error":"Audio too short"}"#;
+        assert!(matches!(
+            ProviderError::from_event(unknown),
+            ProviderError::Provider(m) if m == "Audio too short"
+        ));
+    }
+
+    #[test]
+    fn close_frames_use_codes_not_reason_text() {
+        assert!(matches!(
+            ProviderError::from_close(4001, "Not Authorized"),
+            ProviderError::Auth(_)
+        ));
+        assert!(matches!(
+            ProviderError::from_close(4029, ""),
+            ProviderError::RateLimited { .. }
+        ));
+        assert!(matches!(
+            ProviderError::from_close(1011, "upstream 401 quota"),
+            ProviderError::Closed { code: 1011, .. }
+        ));
+    }
+
+    #[test]
+    fn from_connect_sorts_by_status() {
+        let from = |status, after| ProviderError::from_connect(&http_error(status, after));
+        assert!(matches!(from(401, None), ProviderError::Auth(_)));
+        assert!(matches!(from(403, None), ProviderError::Auth(_)));
+        assert!(matches!(from(402, None), ProviderError::Quota(_)));
+        assert!(matches!(
+            from(429, Some("7")),
+            ProviderError::RateLimited { retry_after: Some(d), .. } if d == Duration::from_secs(7)
+        ));
+        assert!(matches!(
+            from(429, Some("soon")),
+            ProviderError::RateLimited {
+                retry_after: None,
+                ..
+            }
+        ));
+        for transient in [408, 425, 500, 503, 599] {
+            let err = from(transient, None);
+            assert!(matches!(err, ProviderError::Network(_)), "{transient}");
+            assert!(!err.is_fatal());
+        }
+        assert!(matches!(from(400, None), ProviderError::Protocol(_)));
+        assert!(matches!(from(404, None), ProviderError::Protocol(_)));
+        assert!(matches!(
+            ProviderError::from_connect(&tungstenite::Error::ConnectionClosed),
+            ProviderError::Network(_)
+        ));
+    }
+
+    #[test]
+    fn retry_delay_backs_off_and_gives_up() {
+        let network = ProviderError::Network("reset".into());
+        assert_eq!(network.retry_delay(1), Some(Duration::from_millis(800)));
+        assert_eq!(network.retry_delay(2), Some(Duration::from_millis(1_600)));
+        assert_eq!(network.retry_delay(3), Some(Duration::from_millis(3_200)));
+        assert_eq!(network.retry_delay(11), Some(Duration::from_millis(30_000)));
+        assert_eq!(network.retry_delay(RECONNECT_MAX_RETRIES), None);
+
+        let limited = ProviderError::RateLimited {
+            message: String::new(),
+            retry_after: None,
+        };
+        assert_eq!(limited.retry_delay(1), Some(Duration::from_millis(5_000)));
+        assert_eq!(
+            limited.retry_delay(10),
+            Some(Duration::from_millis(120_000))
+        );
+        let hinted = ProviderError::RateLimited {
+            message: String::new(),
+            retry_after: Some(Duration::from_secs(42)),
+        };
+        assert_eq!(hinted.retry_delay(3), Some(Duration::from_secs(42)));
+
+        assert_eq!(ProviderError::Auth(String::new()).retry_delay(1), None);
+        assert_eq!(ProviderError::Quota(String::new()).retry_delay(1), None);
+        assert_eq!(ProviderError::Protocol(String::new()).retry_delay(1), None);
+    }
+}
@@ -0,0 +1,276 @@
+// Mock provider for the --mock flag: a local WebSocket server that answers
+// like a manual-commit provider, so sessions, VAD commits, flush timing and
+// command matching can run without a network or an API key. The API key picks
+// a failure to inject; MANGOCHAT_MOCK_TRANSCRIPTS ('|'-separated) scripts
+// the finals.
+
+use super::{
+    AudioEncoding, CommitMessage, ConnectionConfig, ProviderEvent, ProviderSettings, SttProvider,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+
+pub const SAMPLE_RATE: u32 = 16_000;
+/// Keys that make the server fail on purpose instead of transcribing.
+pub const KEY_AUTH_FAIL: &str = "mock-auth-fail";
+pub const KEY_RATE_LIMIT: &str = "mock-rate-limit";
+pub const KEY_DROP: &str = "mock-drop";
+/// Delay between a commit and its final, like a real provider's latency.
+const FINAL_DELAY_MS: u64 = 150;
+const DEFAULT_TRANSCRIPTS: &[&str] = &[
+    "this is a mock transcript",
+    "the quick brown fox jumps over the lazy dog",
+    "testing one two three",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SERVER: OnceLock<Result<SocketAddr, String>> = OnceLock::new();
+
+/// Route every session to the mock provider for the rest of this run.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+    app_log!("[mock] mock provider and synthetic audio enabled");
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Address of the mock server, started on first use on its own thread so it
+/// outlives any one session's tasks.
+fn server_addr() -> Result<SocketAddr, String> {
+    SERVER
+        .get_or_init(|| {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0")
+                .map_err(|e| format!("Failed to bind mock server: {}", e))?;
+            listener
+                .set_nonblocking(true)
+                .map_err(|e| format!("Failed to configure mock server: {}", e))?;
+            let addr = listener
+                .local_addr()
+                .map_err(|e| format!("Failed to read mock server address: {}", e))?;
+            std::thread::spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(rt) => rt,
+                    Err(e) => {
+                        app_err!("[mock] failed to start runtime: {}", e);
+                        return;
+                    }
+                };
+                runtime.block_on(serve(listener));
+            });
+            app_log!("[mock] server listening on {}", addr);
+            Ok(addr)
+        })
+        .clone()
+}
+
+async fn serve(listener: std::net::TcpListener) {
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(l) => l,
+        Err(e) => {
+            app_err!("[mock] failed to listen: {}", e);
+            return;
+        }
+    };
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(handle_connection(stream));
+    }
+}
+
+fn transcripts() -> Vec<String> {
+    std::env::var("MANGOCHAT_MOCK_TRANSCRIPTS")
+        .ok()
+        .map(|v| {
+            v.split('|')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_TRANSCRIPTS.iter().map(|s| s.to_string()).collect())
+}
+
+fn reject(status: u16, message: &str, retry_after: Option<u64>) -> ErrorResponse {
+    let mut builder = http::Response::builder().status(status);
+    if let Some(secs) = retry_after {
+        builder = builder.header("retry-after", secs.to_string());
+    }
+    builder
+        .body(Some(message.to_string()))
+        .unwrap_or_else(|_| http::Response::new(Some(message.to_string())))
+}
+
+async fn handle_connection(stream: tokio::net::TcpStream) {
+    let mut key = String::new();
+    let check_key = |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        key = req
+            .headers()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or("")
+            .to_string();
+        match key.as_str() {
+            KEY_AUTH_FAIL => Err(reject(401, "invalid api key", None)),
+            KEY_RATE_LIMIT => Err(reject(429, "too many requests", Some(2))),
+            _ => Ok(resp),
+        }
+    };
+    let ws = match tokio_tungstenite::accept_hdr_async(stream, check_key).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            app_log!("[mock] handshake refused: {}", e);
+            return;
+        }
+    };
+    let (mut tx, mut rx) = ws.split();
+    let transcripts = transcripts();
+    let mut audio_bytes: usize = 0;
+    let mut commits: usize = 0;
+    while let Some(msg) = rx.next().await {
+        let msg = match msg {
+            Ok(m) => m,
+            Err(_) => break,
+        };
+        match msg {
+            Message::Binary(data) => audio_bytes += data.len(),
+            Message::Text(text) => {
+                let kind = serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(String::from))
+                    .unwrap_or_default();
+                match kind.as_str() {
+                    "commit" => {
+                        let audio_ms = audio_bytes * 1000 / (SAMPLE_RATE as usize * 2);
+                        audio_bytes = 0;
+                        if audio_ms == 0 {
+                            continue;
+                        }
+                        let text = transcripts[commits % transcripts.len()].clone();
+                        commits += 1;
+                        app_log!("[mock] commit {} with {} ms of audio", commits, audio_ms);
+                        let words: Vec<&str> = text.split_whitespace().collect();
+                        let partial = words[..words.len().div_ceil(2)].join(" ");
+                        let delta = json!({ "type": "delta", "text": partial });
+                        if tx
+                            .send(Message::Text(delta.to_string().into()))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        tokio::time::sleep(Duration::from_millis(FINAL_DELAY_MS)).await;
+                        let fin = json!({ "type": "final", "text": text, "audio_ms": audio_ms });
+                        if tx
+                            .send(Message::Text(fin.to_string().into()))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        if key == KEY_DROP {
+                            let _ = tx
+                                .send(Message::Close(Some(CloseFrame {
+                                    code: CloseCode::Error,
+                                    reason: "mock dropped the connection".into(),
+                                })))
+                                .await;
+                            break;
+                        }
+                    }
+                    "close" => {
+                        let _ = tx
+                            .send(Message::Close(Some(CloseFrame {
+                                code: CloseCode::Normal,
+                                reason: "".into(),
+                            })))
+                            .await;
+                        break;
+                    }
+                    _ => {
+                        let err = json!({
+                            "type": "error",
+                            "message": format!("unknown message: {}", text),
+                        });
+                        let _ = tx.send(Message::Text(err.to_string().into())).await;
+                    }
+                }
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+}
+
+pub struct MockProvider;
+
+impl SttProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    fn sample_rate_hint(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn connection_config(&self, settings: &ProviderSettings) -> ConnectionConfig {
+        // A port nothing listens on makes the connect fail like a network error.
+        let url = match server_addr() {
+            Ok(addr) => format!("ws://{}/v1/listen", addr),
+            Err(e) => {
+                app_err!("[mock] {}", e);
+                "ws://127.0.0.1:9/v1/listen".to_string()
+            }
+        };
+        ConnectionConfig {
+            url,
+            headers: vec![(
+                "Authorization".into(),
+                format!("Bearer {}", settings.api_key),
+            )],
+            init_message: None,
+            audio_encoding: AudioEncoding::RawBinary,
+            commit_message: CommitMessage::Json(json!({ "type": "commit" })),
+            close_message: Some(json!({ "type": "close" })),
+            keepalive_message: None,
+            keepalive_interval_secs: 5,
+            min_audio_chunk_ms: 0,
+            pre_commit_silence_ms: 0,
+            commit_flush_timeout_ms: 700,
+            sample_rate: SAMPLE_RATE,
+        }
+    }
+
+    fn parse_event(&self, text: &str) -> Vec<ProviderEvent> {
+        let event: Value = match serde_json::from_str(text) {
+            Ok(v) => v,
+            Err(e) => return vec![ProviderEvent::Error(format!("parse error: {}", e))],
+        };
+        let field = |name: &str| {
+            event
+                .get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        match field("type").as_str() {
+            "delta" => vec![ProviderEvent::TranscriptDelta(field("text"))],
+            "final" => vec![ProviderEvent::TranscriptFinal(field("text"))],
+            "error" => vec![ProviderEvent::Error(field("message"))],
+            _ => vec![ProviderEvent::Ignore],
+        }
+    }
+}
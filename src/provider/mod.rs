@@ -2,11 +2,35 @@ pub mod assemblyai;
 pub mod deepgram;
 pub mod openai;
 pub mod elevenlabs;
+pub mod error;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod session;
 
 use serde_json::Value;
 use std::sync::Arc;
 
+/// Provider ids and display names, in the order every frontend lists them.
+pub const PROVIDER_ROWS: &[(&str, &str)] = &[
+    ("deepgram", "Deepgram"),
+    ("assemblyai", "AssemblyAI"),
+    ("openai", "OpenAI Realtime"),
+    ("elevenlabs", "ElevenLabs Realtime"),
+];
+
+pub fn display_name(provider_id: &str) -> &str {
+    PROVIDER_ROWS
+        .iter()
+        .find(|(id, _)| *id == provider_id)
+        .map(|(_, name)| *name)
+        .unwrap_or(provider_id)
+}
+
+/// True for ids listed in `PROVIDER_ROWS`.
+pub fn is_known(provider_id: &str) -> bool {
+    PROVIDER_ROWS.iter().any(|(id, _)| *id == provider_id)
+}
+
 /// Events produced by parsing a provider's WebSocket messages.
 #[derive(Debug, Clone)]
 pub enum ProviderEvent {
@@ -78,6 +102,11 @@ pub struct ConnectionConfig {
     pub sample_rate: u32,
 }
 
+/// User limits for the endpointing overrides in settings.
+pub const PRE_COMMIT_SILENCE_MAX_MS: u32 = 1000;
+pub const COMMIT_FLUSH_TIMEOUT_MIN_MS: u32 = 100;
+pub const COMMIT_FLUSH_TIMEOUT_MAX_MS: u32 = 5000;
+
 /// Settings passed to a provider to build its ConnectionConfig.
 #[derive(Debug, Clone)]
 pub struct ProviderSettings {
@@ -85,6 +114,23 @@ pub struct ProviderSettings {
     pub model: String,
     pub transcription_model: String,
     pub language: String,
+    /// Overrides for the provider's `pre_commit_silence_ms`, if the user set one.
+    pub pre_commit_silence_ms: Option<u32>,
+    /// Overrides for the provider's `commit_flush_timeout_ms`, if the user set one.
+    pub commit_flush_timeout_ms: Option<u32>,
+}
+
+impl ProviderSettings {
+    /// Fill the endpointing overrides for `provider_id` from settings.
+    pub fn with_commit_timing(
+        mut self,
+        settings: &crate::settings::Settings,
+        provider_id: &str,
+    ) -> Self {
+        self.pre_commit_silence_ms = settings.pre_commit_silence_ms.get(provider_id).copied();
+        self.commit_flush_timeout_ms = settings.commit_flush_timeout_ms.get(provider_id).copied();
+        self
+    }
 }
 
 /// Trait that each STT provider implements.
@@ -102,8 +148,47 @@ pub trait SttProvider: Send + Sync {
     }
 }
 
-/// Create a provider instance by ID.
+/// A provider's built-in `(pre_commit_silence_ms, commit_flush_timeout_ms)`,
+/// shown next to the overrides in settings.
+pub fn commit_timing_defaults(id: &str) -> (u32, u32) {
+    let config = create_provider(id).connection_config(&ProviderSettings {
+        api_key: String::new(),
+        model: String::new(),
+        transcription_model: String::new(),
+        language: "en".into(),
+        pre_commit_silence_ms: None,
+        commit_flush_timeout_ms: None,
+    });
+    (config.pre_commit_silence_ms, config.commit_flush_timeout_ms)
+}
+
+/// Route every session to the mock provider for the rest of this run.
+#[cfg(any(test, feature = "mock"))]
+pub fn enable_mock() {
+    mock::enable();
+}
+
+#[cfg(not(any(test, feature = "mock")))]
+pub fn enable_mock() {
+    app_err!("[mock] --mock needs a build with the \"mock\" feature");
+}
+
+#[cfg(any(test, feature = "mock"))]
+pub fn mock_enabled() -> bool {
+    mock::enabled()
+}
+
+#[cfg(not(any(test, feature = "mock")))]
+pub fn mock_enabled() -> bool {
+    false
+}
+
+/// Create a provider instance by ID. Under --mock every id gets the mock.
 pub fn create_provider(id: &str) -> Arc<dyn SttProvider> {
+    #[cfg(any(test, feature = "mock"))]
+    if mock::enabled() {
+        return Arc::new(mock::MockProvider);
+    }
     match id {
         "deepgram" => Arc::new(deepgram::DeepgramProvider::new()),
         "elevenlabs" => Arc::new(elevenlabs::ElevenLabsProvider),
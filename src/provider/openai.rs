@@ -114,12 +114,9 @@ impl SttProvider for OpenAiProvider {
                 if code == "input_audio_buffer_commit_empty" {
                     return vec![ProviderEvent::Ignore];
                 }
-                let message = event
-                    .get("error")
-                    .and_then(|e| e.get("message"))
-                    .and_then(|m| m.as_str())
-                    .unwrap_or("OpenAI error");
-                vec![ProviderEvent::Error(message.to_string())]
+                // Pass the payload on whole so the session can sort it by
+                // its error code.
+                vec![ProviderEvent::Error(event.to_string())]
             }
             "rate_limits.updated" => {
                 if let Some(limits) = event.get("rate_limits").and_then(|v| v.as_array()) {
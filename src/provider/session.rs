@@ -1,5 +1,7 @@
+use super::error::{ProviderError, RECONNECT_BASE_MS};
 use super::{
     AudioEncoding, CommitMessage, ConnectionConfig, ProviderEvent, ProviderSettings, SttProvider,
+    PROVIDER_ROWS,
 };
 use crate::state::{AppEvent, AppState};
 use crate::typing;
@@ -12,13 +14,17 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex};
-use tokio_tungstenite::{connect_async, tungstenite};
+use tokio_tungstenite::{connect_async, connect_async_with_config, tungstenite};
 
 type WsSink = futures_util::stream::SplitSink<
     tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
     tungstenite::Message,
 >;
 
+/// How long one connect attempt (connect plus init message) may take before
+/// the watchdog gives up on the session.
+const CONNECT_WATCHDOG_SECS: u64 = 20;
+
 #[derive(Default)]
 struct CommitLatencyState {
     current_commit_id: u64,
@@ -28,7 +34,9 @@ struct CommitLatencyState {
     first_final_logged: bool,
 }
 
-fn build_ws_request(config: &ConnectionConfig) -> Result<tungstenite::http::Request<()>, String> {
+fn build_ws_request(
+    config: &ConnectionConfig,
+) -> Result<tungstenite::http::Request<()>, ProviderError> {
     let mut request = tungstenite::http::Request::builder()
         .uri(&config.url)
         .header("Connection", "Upgrade")
@@ -45,22 +53,19 @@ fn build_ws_request(config: &ConnectionConfig) -> Result<tungstenite::http::Requ
 
     request
         .body(())
-        .map_err(|e| format!("Failed to build request: {}", e))
+        .map_err(|e| ProviderError::Protocol(format!("Failed to build request: {}", e)))
 }
 
 pub async fn validate_key(
     provider: Arc<dyn SttProvider>,
     settings: ProviderSettings,
-) -> Result<(), String> {
+) -> Result<(), ProviderError> {
     let config = provider.connection_config(&settings);
     let request = build_ws_request(&config)?;
-    let provider_name = provider.name();
 
     let ws_stream = match connect_async(request).await {
         Ok((stream, _)) => stream,
-        Err(e) => {
-            return Err(format!("{} auth failed: {}", provider_name, e));
-        }
+        Err(e) => return Err(ProviderError::from_connect(&e)),
     };
 
     let (mut ws_tx, _) = ws_stream.split();
@@ -70,7 +75,10 @@ pub async fn validate_key(
             .send(tungstenite::Message::Text(init.to_string().into()))
             .await
         {
-            return Err(format!("{} init failed: {}", provider_name, e));
+            return Err(ProviderError::Network(format!(
+                "Failed to send init: {}",
+                e
+            )));
         }
     }
 
@@ -85,7 +93,87 @@ fn emit_status(tx: &EventSender<AppEvent>, status: &str, message: &str) {
     });
 }
 
-fn emit_transcript(tx: &EventSender<AppEvent>, text: &str, is_final: bool) {
+/// Log and show `err`, then return how long to wait before reconnect
+/// `attempt`, or None when the session should end.
+fn handle_failure(
+    tx: &EventSender<AppEvent>,
+    provider_id: &str,
+    provider_name: &str,
+    err: &ProviderError,
+    attempt: u32,
+) -> Option<Duration> {
+    app_err!("[{}] {} error: {}", provider_name, err.kind(), err);
+    crate::metrics::record_error(provider_id);
+    let delay = err.retry_delay(attempt);
+    let message = match delay {
+        Some(delay) => format!(
+            "{} (retry {} in {}s)",
+            err.user_message(provider_name),
+            attempt,
+            delay.as_secs().max(1)
+        ),
+        None if err.is_fatal() => err.user_message(provider_name),
+        None => format!(
+            "{} (gave up after {} retries)",
+            err.user_message(provider_name),
+            attempt
+        ),
+    };
+    emit_status(tx, "error", &message);
+    delay
+}
+
+/// Where a session's transcripts go.
+#[derive(Clone)]
+pub enum TranscriptSink {
+    /// Show deltas in the UI and type finals into the focused app.
+    Dictation,
+    /// Append finals to a meeting document under `speaker`. Nothing is typed
+    /// and the session never stops for inactivity.
+    Meeting {
+        speaker: String,
+        tx: std::sync::mpsc::Sender<crate::meeting::MeetingLine>,
+    },
+    /// Show transcripts in the UI but save finals as voice notes instead of
+    /// typing them.
+    VoiceNote(std::sync::mpsc::Sender<String>),
+    /// Only report transcripts as events, for a host embedding the DLL that
+    /// handles the text itself. Only the DLL builds this.
+    #[allow(dead_code)]
+    Embedded,
+}
+
+impl TranscriptSink {
+    /// Finals are typed (or answered with an attached snip) and become the
+    /// last transcript.
+    fn types_finals(&self) -> bool {
+        matches!(self, Self::Dictation)
+    }
+
+    /// Meetings run until stopped; other sessions end after inactivity.
+    fn times_out(&self) -> bool {
+        !matches!(self, Self::Meeting { .. })
+    }
+}
+
+fn emit_transcript(
+    tx: &EventSender<AppEvent>,
+    sink: &TranscriptSink,
+    text: &str,
+    is_final: bool,
+) {
+    match sink {
+        TranscriptSink::Meeting { speaker, tx: lines } => {
+            if is_final {
+                let _ = lines.send(crate::meeting::MeetingLine::new(speaker, text));
+            }
+            return;
+        }
+        TranscriptSink::VoiceNote(notes) if is_final => {
+            let _ = notes.send(text.to_string());
+        }
+        _ => {}
+    }
     if is_final {
         let _ = tx.send(AppEvent::TranscriptFinal(text.into()));
     } else {
@@ -93,6 +181,78 @@ fn emit_transcript(tx: &EventSender<AppEvent>, text: &str, is_final: bool) {
     }
 }
 
+/// Everything a final transcript sets off: commit latency, usage counters and
+/// the sink, then for dictation a snip-region command, an answer about the
+/// attached snip, a question for the snip window, or typing.
+#[allow(clippy::too_many_arguments)]
+fn handle_final(
+    state: &AppState,
+    tx: &EventSender<AppEvent>,
+    sink: &TranscriptSink,
+    latency: &std::sync::Mutex<CommitLatencyState>,
+    provider_name: &str,
+    provider_id: &str,
+    gen: u64,
+    transcript: String,
+) {
+    if let Ok(mut s) = latency.lock() {
+        if s.window_open {
+            if let Some(start) = s.current_commit_at {
+                let cid = s.current_commit_id;
+                if cid > 0 && !s.first_final_logged {
+                    app_log!(
+                        "[{}] [{}] first_final_after_commit_ms id={} ms={}",
+                        provider_name,
+                        wall_ts(),
+                        cid,
+                        start.elapsed().as_millis()
+                    );
+                    crate::metrics::observe_final_latency(
+                        provider_id,
+                        start.elapsed().as_millis() as u64,
+                    );
+                    s.first_final_logged = true;
+                }
+            }
+            // Close this commit window once a final is observed.
+            s.window_open = false;
+        }
+    }
+    if sink.types_finals() {
+        state.remember_transcript(&transcript);
+    }
+    emit_transcript(tx, sink, &transcript, true);
+    if let Ok(mut usage) = state.usage.lock() {
+        usage.finals = usage.finals.saturating_add(1);
+    }
+    state.add_leg_usage(gen, |session| {
+        session.finals = session.finals.saturating_add(1);
+    });
+    if let Ok(mut pt) = state.provider_totals.lock() {
+        let entry = pt.entry(provider_id.to_string()).or_default();
+        entry.finals = entry.finals.saturating_add(1);
+    }
+    if !sink.types_finals() {
+        return;
+    }
+    let commands = state.commands.load_full();
+    if let Some(name) = typing::match_region_command(&transcript, &commands.snip_regions) {
+        app_log!("[{}] snip region command: \"{}\"", provider_name, name);
+        let _ = tx.send(AppEvent::SnipPreset(name));
+    } else if let Some(img) = state.attached_snip.lock().ok().and_then(|mut g| g.take()) {
+        let tx = tx.clone();
+        tokio::task::spawn_blocking(move || {
+            crate::vision::answer_with_snip(&commands.vision, &transcript, &img, &tx);
+        });
+    } else if state.snip_in_window.swap(false, Ordering::SeqCst) {
+        let _ = tx.send(AppEvent::SnipQuestion(transcript));
+    } else {
+        tokio::task::spawn_blocking(move || {
+            typing::process_transcript(&transcript, &commands);
+        });
+    }
+}
+
 fn now_ms() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
     SystemTime::now()
@@ -105,39 +265,17 @@ fn wall_ts() -> String {
     Local::now().format("%H:%M:%S%.3f").to_string()
 }
 
-const RECONNECT_BASE_MS: u64 = 800;
-const RECONNECT_MAX_MS: u64 = 30_000;
-const RECONNECT_MAX_RETRIES: u32 = 12;
-
-fn reconnect_delay_ms(attempt: u32) -> u64 {
-    let exp = attempt.saturating_sub(1).min(10);
-    let factor = 1u64 << exp;
-    (RECONNECT_BASE_MS.saturating_mul(factor)).min(RECONNECT_MAX_MS)
-}
-
-fn is_permanent_connect_error(err: &tungstenite::Error) -> bool {
-    match err {
-        tungstenite::Error::Http(resp) => {
-            let code = resp.status().as_u16();
-            code == 401 || code == 403
-        }
-        _ => {
-            let text = err.to_string();
-            text.contains("401") || text.contains("403")
-        }
-    }
-}
-
+/// Provider id for a display name from `PROVIDER_ROWS`; names outside the
+/// table (the mock, test providers) are lowercased.
 fn provider_id_from_name(name: &str) -> String {
-    match name {
-        "Deepgram" => "deepgram".to_string(),
-        "OpenAI Realtime" => "openai".to_string(),
-        "ElevenLabs Realtime" => "elevenlabs".to_string(),
-        "AssemblyAI" => "assemblyai".to_string(),
-        _ => name.to_lowercase(),
-    }
+    PROVIDER_ROWS
+        .iter()
+        .find(|(_, row_name)| *row_name == name)
+        .map(|(id, _)| id.to_string())
+        .unwrap_or_else(|| name.to_lowercase())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn send_audio_chunk(
     ws_tx: &mut WsSink,
     pcm_data: Vec<u8>,
@@ -146,6 +284,7 @@ async fn send_audio_chunk(
     activity_ms: &Arc<AtomicU64>,
     sample_rate: u32,
     provider_name: &str,
+    gen: u64,
 ) -> Result<(), ()> {
     if pcm_data.is_empty() {
         return Ok(());
@@ -177,9 +316,15 @@ async fn send_audio_chunk(
         AudioEncoding::RawBinary => tungstenite::Message::Binary(pcm_data.into()),
     };
 
+    let send_started = Instant::now();
     if ws_tx.send(ws_msg).await.is_err() {
         return Err(());
     }
+    let send_us = send_started.elapsed().as_micros() as u64;
+    state_send.ws_send_latency_us.store(send_us, Ordering::Relaxed);
+    state_send
+        .ws_send_latency_peak_us
+        .fetch_max(send_us, Ordering::Relaxed);
     activity_ms.store(now_ms(), Ordering::SeqCst);
 
     if let Ok(mut usage) = state_send.usage.lock() {
@@ -187,13 +332,11 @@ async fn send_audio_chunk(
         usage.ms_sent = usage.ms_sent.saturating_add(chunk_ms);
         usage.last_update_ms = now_ms();
     }
-    if let Ok(mut session) = state_send.session_usage.lock() {
-        if session.started_ms != 0 {
-            session.bytes_sent = session.bytes_sent.saturating_add(chunk_bytes);
-            session.ms_sent = session.ms_sent.saturating_add(chunk_ms);
-            session.updated_ms = now_ms();
-        }
-    }
+    state_send.add_leg_usage(gen, |session| {
+        session.bytes_sent = session.bytes_sent.saturating_add(chunk_bytes);
+        session.ms_sent = session.ms_sent.saturating_add(chunk_ms);
+        session.updated_ms = now_ms();
+    });
     if let Ok(mut pt) = state_send.provider_totals.lock() {
         let entry = pt.entry(provider_name.to_string()).or_default();
         entry.bytes_sent = entry.bytes_sent.saturating_add(chunk_bytes);
@@ -202,6 +345,45 @@ async fn send_audio_chunk(
     Ok(())
 }
 
+/// Deadline for the connect attempt in flight, in ms since the session
+/// started; 0 while connected or waiting to retry.
+struct Watchdog {
+    started: Instant,
+    deadline_ms: AtomicU64,
+}
+
+impl Watchdog {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            deadline_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        // Offset by one so a deadline is never 0, which means disarmed.
+        self.started.elapsed().as_millis() as u64 + 1
+    }
+
+    fn arm(&self, secs: u64) {
+        let deadline = self.now_ms() + secs * 1000;
+        self.deadline_ms.store(deadline, Ordering::SeqCst);
+    }
+
+    fn disarm(&self) {
+        self.deadline_ms.store(0, Ordering::SeqCst);
+    }
+
+    fn expired(&self) -> bool {
+        let deadline = self.deadline_ms.load(Ordering::SeqCst);
+        deadline != 0 && self.now_ms() > deadline
+    }
+}
+
+/// Run a provider session until its audio channel closes or it fails for
+/// good. A watchdog cancels it if a connect attempt hangs (no error, no
+/// progress) for `CONNECT_WATCHDOG_SECS`, and reports that so the user can
+/// start again; the caller's cleanup then runs as for any other ending.
 pub async fn run_session(
     provider: Arc<dyn SttProvider>,
     event_tx: EventSender<AppEvent>,
@@ -209,17 +391,73 @@ pub async fn run_session(
     settings: ProviderSettings,
     audio_rx: mpsc::Receiver<Vec<u8>>,
     inactivity_timeout_secs: u64,
+    sink: TranscriptSink,
+) {
+    let gen = state.session_gen.load(Ordering::SeqCst);
+    let provider_name = provider.name().to_string();
+    let watchdog = Arc::new(Watchdog::new());
+    let mut task = tokio::spawn(session_loop(
+        provider,
+        event_tx.clone(),
+        state.clone(),
+        settings,
+        audio_rx,
+        inactivity_timeout_secs,
+        sink,
+        watchdog.clone(),
+    ));
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            _ = &mut task => return,
+            _ = tick.tick() => {
+                if !watchdog.expired() {
+                    continue;
+                }
+                task.abort();
+                let _ = task.await;
+                app_err!(
+                    "[{}] no progress connecting after {}s; session cancelled",
+                    provider_name,
+                    CONNECT_WATCHDOG_SECS
+                );
+                crate::metrics::record_error(&provider_id_from_name(&provider_name));
+                if state.session_gen.load(Ordering::SeqCst) == gen {
+                    emit_status(
+                        &event_tx,
+                        "error",
+                        &format!(
+                            "{} didn't respond. Stop and start dictation to try again.",
+                            provider_name
+                        ),
+                    );
+                }
+                return;
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn session_loop(
+    provider: Arc<dyn SttProvider>,
+    event_tx: EventSender<AppEvent>,
+    state: Arc<AppState>,
+    settings: ProviderSettings,
+    audio_rx: mpsc::Receiver<Vec<u8>>,
+    inactivity_timeout_secs: u64,
+    sink: TranscriptSink,
+    watchdog: Arc<Watchdog>,
 ) {
     let audio_rx = Arc::new(Mutex::new(audio_rx));
+    // A newer session (a mid-recording provider switch) bumps the generation;
+    // this one then drains quietly instead of reporting itself idle.
+    let gen = state.session_gen.load(Ordering::SeqCst);
     let mut attempts: u32 = 0;
     loop {
         attempts += 1;
         if attempts > 1 {
-            app_log!(
-                "[{}] reconnecting (attempt {})",
-                provider.name(),
-                attempts
-            );
+            app_log!("[{}] reconnecting (attempt {})", provider.name(), attempts);
         }
 
         // If the audio channel is gone, stop.
@@ -227,585 +465,875 @@ pub async fn run_session(
             return;
         }
 
-    let config = provider.connection_config(&settings);
-    let provider_name = provider.name();
-    let provider_id = provider_id_from_name(provider_name);
-    app_log!(
-        "[{}] starting session: url={}",
-        provider_name, config.url
-    );
-
-    let request = match build_ws_request(&config) {
-        Ok(req) => req,
-        Err(e) => {
-            emit_status(&event_tx, "error", &e);
-            return;
+        // Don't spend retries while the network monitor reports offline.
+        if !state.online.load(Ordering::SeqCst) {
+            emit_status(&event_tx, "error", "Offline, waiting for network...");
+            while !state.online.load(Ordering::SeqCst) {
+                if audio_rx.lock().await.is_closed() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            attempts = 1;
         }
-    };
 
-    emit_status(&event_tx, "live", "Connecting...");
+        let mut config = provider.connection_config(&settings);
+        if let Some(ms) = settings.pre_commit_silence_ms {
+            config.pre_commit_silence_ms = ms;
+        }
+        if let Some(ms) = settings.commit_flush_timeout_ms {
+            config.commit_flush_timeout_ms = ms;
+        }
+        let provider_name = provider.name();
+        let provider_id = provider_id_from_name(provider_name);
+        app_log!("[{}] starting session: url={}", provider_name, config.url);
 
-    let ws_stream = match connect_async(request).await {
-        Ok((stream, _)) => stream,
-        Err(e) => {
-            if is_permanent_connect_error(&e) {
-                emit_status(
-                    &event_tx,
-                    "error",
-                    &format!("Authentication failed: {}", e),
-                );
+        let request = match build_ws_request(&config) {
+            Ok(req) => req,
+            Err(e) => {
+                handle_failure(&event_tx, &provider_id, provider_name, &e, attempts);
                 return;
             }
-            if attempts >= RECONNECT_MAX_RETRIES {
-                emit_status(
-                    &event_tx,
-                    "error",
-                    &format!(
-                        "Connection failed after {} retries: {}",
-                        RECONNECT_MAX_RETRIES, e
-                    ),
-                );
-                return;
+        };
+
+        emit_status(&event_tx, "live", "Connecting...");
+        watchdog.arm(CONNECT_WATCHDOG_SECS);
+
+        let nodelay = state.ws_tcp_nodelay.load(Ordering::Relaxed);
+        let ws_stream = match connect_async_with_config(request, None, nodelay).await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                let err = ProviderError::from_connect(&e);
+                match handle_failure(&event_tx, &provider_id, provider_name, &err, attempts) {
+                    Some(delay) => {
+                        watchdog.disarm();
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+                    None => return,
+                }
             }
-            let delay_ms = reconnect_delay_ms(attempts);
-            emit_status(
-                &event_tx,
-                "error",
-                &format!("Connection failed (retry {}): {}", attempts, e),
-            );
-            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-            continue;
-        }
-    };
-    attempts = 0;
-    app_log!("[{}] websocket connected", provider_name);
+        };
+        let connect_attempts = attempts;
+        attempts = 0;
+        app_log!("[{}] websocket connected", provider_name);
 
-    let (mut ws_tx, mut ws_rx) = ws_stream.split();
+        let (mut ws_tx, mut ws_rx) = ws_stream.split();
 
-    // Send init message if the provider requires one.
-    if let Some(ref init) = config.init_message {
-        app_log!("[{}] sending init message", provider_name);
-        if let Err(e) = ws_tx
-            .send(tungstenite::Message::Text(init.to_string().into()))
-            .await
-        {
-            emit_status(
-                &event_tx,
-                "error",
-                &format!("Failed to send init: {}", e),
-            );
-            return;
+        // Send init message if the provider requires one.
+        if let Some(ref init) = config.init_message {
+            app_log!("[{}] sending init message", provider_name);
+            if let Err(e) = ws_tx
+                .send(tungstenite::Message::Text(init.to_string().into()))
+                .await
+            {
+                let err = ProviderError::Network(format!("Failed to send init: {}", e));
+                attempts = connect_attempts;
+                match handle_failure(&event_tx, &provider_id, provider_name, &err, attempts) {
+                    Some(delay) => {
+                        watchdog.disarm();
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+                    None => return,
+                }
+            }
         }
-    }
 
-    emit_status(&event_tx, "live", "Listening");
-
-    let tx_send = event_tx.clone();
-    let tx_send_task = tx_send.clone();
-    let tx_recv = event_tx.clone();
-    let state_recv = state.clone();
-    let provider_recv = provider.clone();
-
-    let (ctrl_tx, mut ctrl_rx) = mpsc::channel::<serde_json::Value>(32);
-    let (flush_tx, mut flush_rx) = mpsc::channel::<()>(8);
-
-    let audio_encoding = config.audio_encoding.clone();
-    let commit_message = config.commit_message.clone();
-    let close_message = config.close_message.clone();
-    let keepalive_message = config.keepalive_message.clone();
-    let keepalive_secs = config.keepalive_interval_secs;
-    let sample_rate = config.sample_rate.max(1);
-    let min_audio_chunk_ms = config.min_audio_chunk_ms;
-    let pre_commit_silence_ms = config.pre_commit_silence_ms;
-    let commit_flush_timeout_ms = config.commit_flush_timeout_ms.max(100);
-    let pname_send = provider_name.to_string();
-    let inactivity_timeout_secs = inactivity_timeout_secs.clamp(5, 300);
-    let inactivity_timeout_ms = inactivity_timeout_secs.saturating_mul(1000);
-    let activity_id = Arc::new(AtomicU64::new(0));
-    let last_activity_ms = Arc::new(AtomicU64::new(now_ms()));
-    let commit_seq = Arc::new(AtomicU64::new(0));
-    let latency_state = Arc::new(std::sync::Mutex::new(CommitLatencyState::default()));
-    let state_send = state.clone();
-    let provider_id_send = provider_id.clone();
-
-    // Task: forward audio from channel to WebSocket.
-    let activity_id_send = activity_id.clone();
-    let last_activity_send = last_activity_ms.clone();
-    let commit_seq_send = commit_seq.clone();
-    let latency_state_send = latency_state.clone();
-    let audio_rx_send = audio_rx.clone();
-    let send_task = tokio::spawn(async move {
-        let mut rx = audio_rx_send.lock().await;
-        let mut timed_out = false;
-        let mut frames: u64 = 0;
-        let mut bytes: u64 = 0;
-        let bytes_per_ms = (sample_rate as usize * 2) / 1000;
-        let min_chunk_bytes = if min_audio_chunk_ms > 0 {
-            (bytes_per_ms * min_audio_chunk_ms as usize).max(2)
-        } else {
-            0
-        };
-        let mut pending_pcm: Vec<u8> = Vec::new();
-        let keepalive_dur = if keepalive_secs > 0 {
-            Duration::from_secs(keepalive_secs)
-        } else {
-            Duration::from_secs(3600) // effectively disabled
-        };
-        let mut keepalive_interval = tokio::time::interval(keepalive_dur);
-        keepalive_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-        // Skip the first immediate tick.
-        keepalive_interval.tick().await;
-        let mut inactivity_check = tokio::time::interval(Duration::from_secs(1));
-        inactivity_check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-        inactivity_check.tick().await;
-
-        loop {
-            tokio::select! {
-                audio = async {
-                    rx.recv().await
-                } => {
-                    let mut pcm_data = match audio {
-                        Some(d) => d,
-                        None => break,
-                    };
-                    // Empty buffer = commit signal (VAD detected end of speech).
-                    if pcm_data.is_empty() {
-                        app_log!("[{}] VAD commit", pname_send);
-                        let commit_activity = activity_id_send.load(Ordering::SeqCst);
-                        if !pending_pcm.is_empty() {
-                            if min_chunk_bytes > 0 && pending_pcm.len() < min_chunk_bytes {
-                                pending_pcm.resize(min_chunk_bytes, 0);
-                            }
-                            let to_send = std::mem::take(&mut pending_pcm);
-                            if send_audio_chunk(
-                                &mut ws_tx,
-                                to_send,
-                                &audio_encoding,
-                                &state_send,
-                                &last_activity_send,
-                                sample_rate,
-                                &provider_id_send,
-                            )
-                            .await
-                            .is_err()
-                            {
-                                break;
-                            }
-                        }
-                        if pre_commit_silence_ms > 0 {
-                            let silence_bytes =
-                                ((sample_rate as usize * 2 * pre_commit_silence_ms as usize) / 1000)
-                                    .max(2);
-                            let silence = vec![0u8; silence_bytes];
-                            if send_audio_chunk(
-                                &mut ws_tx,
-                                silence,
-                                &audio_encoding,
-                                &state_send,
-                                &last_activity_send,
-                                sample_rate,
-                                &provider_id_send,
-                            )
-                            .await
-                            .is_err()
-                            {
-                                break;
-                            }
-                        }
-                        match &commit_message {
-                            CommitMessage::Json(msg) => {
-                                app_log!("[{}] sending commit message", pname_send);
-                                if ws_tx
-                                    .send(tungstenite::Message::Text(msg.to_string().into()))
-                                    .await
-                                    .is_err()
+        watchdog.disarm();
+        emit_status(&event_tx, "live", "Listening");
+        crate::metrics::record_connection(&provider_id);
+
+        let tx_send = event_tx.clone();
+        let tx_send_task = tx_send.clone();
+        let tx_recv = event_tx.clone();
+        let state_recv = state.clone();
+        let provider_recv = provider.clone();
+
+        let (ctrl_tx, mut ctrl_rx) = mpsc::channel::<serde_json::Value>(32);
+        let (flush_tx, mut flush_rx) = mpsc::channel::<()>(8);
+
+        let audio_encoding = config.audio_encoding.clone();
+        let commit_message = config.commit_message.clone();
+        let close_message = config.close_message.clone();
+        let keepalive_message = config.keepalive_message.clone();
+        let keepalive_secs = config.keepalive_interval_secs;
+        let sample_rate = config.sample_rate.max(1);
+        let min_audio_chunk_ms = config.min_audio_chunk_ms;
+        let pre_commit_silence_ms = config.pre_commit_silence_ms;
+        let commit_flush_timeout_ms = config.commit_flush_timeout_ms.max(100);
+        let pname_send = provider_name.to_string();
+        let inactivity_timeout_secs = inactivity_timeout_secs.clamp(5, 300);
+        let inactivity_timeout_ms = inactivity_timeout_secs.saturating_mul(1000);
+        let inactivity_enabled = sink.times_out();
+        let activity_id = Arc::new(AtomicU64::new(0));
+        let last_activity_ms = Arc::new(AtomicU64::new(now_ms()));
+        let commit_seq = Arc::new(AtomicU64::new(0));
+        let latency_state = Arc::new(std::sync::Mutex::new(CommitLatencyState::default()));
+        let state_send = state.clone();
+        let provider_id_send = provider_id.clone();
+
+        // Task: forward audio from channel to WebSocket.
+        let activity_id_send = activity_id.clone();
+        let last_activity_send = last_activity_ms.clone();
+        let commit_seq_send = commit_seq.clone();
+        let latency_state_send = latency_state.clone();
+        let audio_rx_send = audio_rx.clone();
+        // Set by the receive task when an error ends the connection.
+        let recv_failed = Arc::new(tokio::sync::Notify::new());
+        let recv_failed_send = recv_failed.clone();
+        let send_task = tokio::spawn(async move {
+            let mut rx = audio_rx_send.lock().await;
+            let mut timed_out = false;
+            let mut frames: u64 = 0;
+            let mut bytes: u64 = 0;
+            let bytes_per_ms = (sample_rate as usize * 2) / 1000;
+            let min_chunk_bytes = if min_audio_chunk_ms > 0 {
+                (bytes_per_ms * min_audio_chunk_ms as usize).max(2)
+            } else {
+                0
+            };
+            let mut pending_pcm: Vec<u8> = Vec::new();
+            let keepalive_dur = if keepalive_secs > 0 {
+                Duration::from_secs(keepalive_secs)
+            } else {
+                Duration::from_secs(3600) // effectively disabled
+            };
+            let mut keepalive_interval = tokio::time::interval(keepalive_dur);
+            keepalive_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // Skip the first immediate tick.
+            keepalive_interval.tick().await;
+            let mut inactivity_check = tokio::time::interval(Duration::from_secs(1));
+            inactivity_check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            inactivity_check.tick().await;
+
+            loop {
+                tokio::select! {
+                    audio = async {
+                        rx.recv().await
+                    } => {
+                        let mut pcm_data = match audio {
+                            Some(d) => d,
+                            None => break,
+                        };
+                        // Empty buffer = commit signal (VAD detected end of speech).
+                        if pcm_data.is_empty() {
+                            app_log!("[{}] VAD commit", pname_send);
+                            let commit_activity = activity_id_send.load(Ordering::SeqCst);
+                            if !pending_pcm.is_empty() {
+                                if min_chunk_bytes > 0 && pending_pcm.len() < min_chunk_bytes {
+                                    pending_pcm.resize(min_chunk_bytes, 0);
+                                }
+                                let to_send = std::mem::take(&mut pending_pcm);
+                                if send_audio_chunk(
+                                    &mut ws_tx,
+                                    to_send,
+                                    &audio_encoding,
+                                    &state_send,
+                                    &last_activity_send,
+                                    sample_rate,
+                                    &provider_id_send,
+                                    gen,
+                                )
+                                .await
+                                .is_err()
                                 {
                                     break;
                                 }
-                                last_activity_send.store(now_ms(), Ordering::SeqCst);
-                                let commit_id = commit_seq_send.fetch_add(1, Ordering::SeqCst) + 1;
-                                let committed_at = Instant::now();
-                                if let Ok(mut s) = latency_state_send.lock() {
-                                    s.current_commit_id = commit_id;
-                                    s.current_commit_at = Some(committed_at);
-                                    s.window_open = true;
-                                    s.first_delta_logged = false;
-                                    s.first_final_logged = false;
-                                }
-                                app_log!(
-                                    "[{}] [{}] commit_sent id={}",
-                                    pname_send,
-                                    wall_ts(),
-                                    commit_id
-                                );
-                                // Count commit
-                                if let Ok(mut usage) = state_send.usage.lock() {
-                                    usage.commits = usage.commits.saturating_add(1);
-                                    usage.last_update_ms = now_ms();
-                                }
-                                if let Ok(mut session) = state_send.session_usage.lock() {
-                                    if session.started_ms != 0 {
-                                        session.commits = session.commits.saturating_add(1);
-                                        session.updated_ms = now_ms();
-                                    }
+                            }
+                            if pre_commit_silence_ms > 0 {
+                                let silence_bytes =
+                                    ((sample_rate as usize * 2 * pre_commit_silence_ms as usize) / 1000)
+                                        .max(2);
+                                let silence = vec![0u8; silence_bytes];
+                                if send_audio_chunk(
+                                    &mut ws_tx,
+                                    silence,
+                                    &audio_encoding,
+                                    &state_send,
+                                    &last_activity_send,
+                                    sample_rate,
+                                    &provider_id_send,
+                                    gen,
+                                )
+                                .await
+                                .is_err()
+                                {
+                                    break;
                                 }
-                                let flush_tx_delayed = flush_tx.clone();
-                                let pname_flush = pname_send.clone();
-                                let activity_id_flush = activity_id_send.clone();
-                                let latency_state_flush = latency_state_send.clone();
-                                tokio::spawn(async move {
-                                    tokio::time::sleep(Duration::from_millis(
-                                        commit_flush_timeout_ms as u64,
-                                    ))
-                                    .await;
-                                    if activity_id_flush.load(Ordering::SeqCst) != commit_activity {
-                                        return;
+                            }
+                            match &commit_message {
+                                CommitMessage::Json(msg) => {
+                                    app_log!("[{}] sending commit message", pname_send);
+                                    if ws_tx
+                                        .send(tungstenite::Message::Text(msg.to_string().into()))
+                                        .await
+                                        .is_err()
+                                    {
+                                        break;
                                     }
-                                    let should_flush = if let Ok(mut s) = latency_state_flush.lock() {
-                                        if s.window_open && s.current_commit_id == commit_id {
-                                            s.window_open = false;
-                                            true
-                                        } else {
-                                            false
-                                        }
-                                    } else {
-                                        false
-                                    };
-                                    if !should_flush {
-                                        return;
+                                    last_activity_send.store(now_ms(), Ordering::SeqCst);
+                                    let commit_id = commit_seq_send.fetch_add(1, Ordering::SeqCst) + 1;
+                                    let committed_at = Instant::now();
+                                    if let Ok(mut s) = latency_state_send.lock() {
+                                        s.current_commit_id = commit_id;
+                                        s.current_commit_at = Some(committed_at);
+                                        s.window_open = true;
+                                        s.first_delta_logged = false;
+                                        s.first_final_logged = false;
                                     }
                                     app_log!(
-                                        "[{}] [{}] commit_timeout_flush id={} after={}ms",
-                                        pname_flush,
+                                        "[{}] [{}] commit_sent id={}",
+                                        pname_send,
                                         wall_ts(),
-                                        commit_id,
-                                        committed_at.elapsed().as_millis()
+                                        commit_id
                                     );
-                                    let _ = flush_tx_delayed.send(()).await;
-                                });
+                                    // Count commit
+                                    if let Ok(mut usage) = state_send.usage.lock() {
+                                        usage.commits = usage.commits.saturating_add(1);
+                                        usage.last_update_ms = now_ms();
+                                    }
+                                    state_send.add_leg_usage(gen, |session| {
+                                        session.commits = session.commits.saturating_add(1);
+                                        session.updated_ms = now_ms();
+                                    });
+                                    let flush_tx_delayed = flush_tx.clone();
+                                    let pname_flush = pname_send.clone();
+                                    let activity_id_flush = activity_id_send.clone();
+                                    let latency_state_flush = latency_state_send.clone();
+                                    tokio::spawn(async move {
+                                        tokio::time::sleep(Duration::from_millis(
+                                            commit_flush_timeout_ms as u64,
+                                        ))
+                                        .await;
+                                        if activity_id_flush.load(Ordering::SeqCst) != commit_activity {
+                                            return;
+                                        }
+                                        let should_flush = if let Ok(mut s) = latency_state_flush.lock() {
+                                            if s.window_open && s.current_commit_id == commit_id {
+                                                s.window_open = false;
+                                                true
+                                            } else {
+                                                false
+                                            }
+                                        } else {
+                                            false
+                                        };
+                                        if !should_flush {
+                                            return;
+                                        }
+                                        app_log!(
+                                            "[{}] [{}] commit_timeout_flush id={} after={}ms",
+                                            pname_flush,
+                                            wall_ts(),
+                                            commit_id,
+                                            committed_at.elapsed().as_millis()
+                                        );
+                                        let _ = flush_tx_delayed.send(()).await;
+                                    });
+                                }
+                                CommitMessage::None => {}
                             }
-                            CommitMessage::None => {}
+                            // Don't flush locally here — let the server respond to
+                            // the commit/Finalize message with speech_final or
+                            // UtteranceEnd, which parse_event handles correctly.
+                            // Flushing immediately races with incoming server
+                            // segments and creates fragmented transcripts.
+                            continue;
                         }
-                        // Don't flush locally here — let the server respond to
-                        // the commit/Finalize message with speech_final or
-                        // UtteranceEnd, which parse_event handles correctly.
-                        // Flushing immediately races with incoming server
-                        // segments and creates fragmented transcripts.
-                        continue;
-                    }
 
-                    // Reset keepalive timer since we just sent real audio.
-                    keepalive_interval.reset();
-                    activity_id_send.fetch_add(1, Ordering::SeqCst);
-
-                    frames += 1;
-                    bytes += pcm_data.len() as u64;
-                    if frames % 200 == 0 {
-                        let mut peak: i32 = 0;
-                        for chunk in pcm_data.chunks_exact(2) {
-                            let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
-                            let abs = (sample as i32).abs();
-                            if abs > peak {
-                                peak = abs;
+                        // Reset keepalive timer since we just sent real audio.
+                        keepalive_interval.reset();
+                        activity_id_send.fetch_add(1, Ordering::SeqCst);
+
+                        frames += 1;
+                        bytes += pcm_data.len() as u64;
+                        if frames % 200 == 0 {
+                            let mut peak: i32 = 0;
+                            for chunk in pcm_data.chunks_exact(2) {
+                                let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
+                                let abs = (sample as i32).abs();
+                                if abs > peak {
+                                    peak = abs;
+                                }
                             }
+                            app_log!(
+                                "[{}] audio sent: frames={}, bytes_total={}, peak={}",
+                                pname_send, frames, bytes, peak
+                            );
                         }
-                        app_log!(
-                            "[{}] audio sent: frames={}, bytes_total={}, peak={}",
-                            pname_send, frames, bytes, peak
-                        );
-                    }
 
-                    if min_chunk_bytes > 0 {
-                        pending_pcm.append(&mut pcm_data);
-                        let mut send_failed = false;
-                        while pending_pcm.len() >= min_chunk_bytes {
-                            let to_send: Vec<u8> = pending_pcm.drain(..min_chunk_bytes).collect();
-                            if send_audio_chunk(
-                                &mut ws_tx,
-                                to_send,
-                                &audio_encoding,
-                                &state_send,
-                                &last_activity_send,
-                                sample_rate,
-                                &provider_id_send,
-                            )
-                            .await
-                            .is_err()
-                            {
-                                send_failed = true;
+                        if min_chunk_bytes > 0 {
+                            pending_pcm.append(&mut pcm_data);
+                            let mut send_failed = false;
+                            while pending_pcm.len() >= min_chunk_bytes {
+                                let to_send: Vec<u8> = pending_pcm.drain(..min_chunk_bytes).collect();
+                                if send_audio_chunk(
+                                    &mut ws_tx,
+                                    to_send,
+                                    &audio_encoding,
+                                    &state_send,
+                                    &last_activity_send,
+                                    sample_rate,
+                                    &provider_id_send,
+                                    gen,
+                                )
+                                .await
+                                .is_err()
+                                {
+                                    send_failed = true;
+                                    break;
+                                }
+                            }
+                            if send_failed {
                                 break;
                             }
-                        }
-                        if send_failed {
+                        } else if send_audio_chunk(
+                            &mut ws_tx,
+                            pcm_data,
+                            &audio_encoding,
+                            &state_send,
+                            &last_activity_send,
+                            sample_rate,
+                            &provider_id_send,
+                            gen,
+                        )
+                        .await
+                        .is_err()
+                        {
                             break;
                         }
-                    } else if send_audio_chunk(
-                        &mut ws_tx,
-                        pcm_data,
-                        &audio_encoding,
-                        &state_send,
-                        &last_activity_send,
-                        sample_rate,
-                        &provider_id_send,
-                    )
-                    .await
-                    .is_err()
-                    {
-                        break;
                     }
-                }
-                ctrl = ctrl_rx.recv() => {
-                    let msg = match ctrl {
-                        Some(m) => m,
-                        None => continue,
-                    };
-                    let _ = ws_tx
-                        .send(tungstenite::Message::Text(msg.to_string().into()))
-                        .await;
-                    last_activity_send.store(now_ms(), Ordering::SeqCst);
-                }
-                _ = keepalive_interval.tick(), if keepalive_message.is_some() => {
-                    if let Some(ref msg) = keepalive_message {
-                        app_log!("[{}] keepalive", pname_send);
+                    _ = recv_failed_send.notified() => break,
+                    ctrl = ctrl_rx.recv() => {
+                        let msg = match ctrl {
+                            Some(m) => m,
+                            None => continue,
+                        };
                         let _ = ws_tx
                             .send(tungstenite::Message::Text(msg.to_string().into()))
                             .await;
                         last_activity_send.store(now_ms(), Ordering::SeqCst);
                     }
-                }
-                _ = inactivity_check.tick() => {
-                    let last = last_activity_send.load(Ordering::SeqCst);
-                    let idle_for_ms = now_ms().saturating_sub(last);
-                    if idle_for_ms >= inactivity_timeout_ms {
-                        app_log!(
-                            "[{}] inactivity timeout hit: {}s (idle={}ms), stopping session",
-                            pname_send, inactivity_timeout_secs, idle_for_ms
-                        );
-                        let _ = tx_send_task.send(AppEvent::SessionInactivityTimeout {
-                            seconds: inactivity_timeout_secs,
-                        });
-                        timed_out = true;
-                        break;
+                    _ = keepalive_interval.tick(), if keepalive_message.is_some() => {
+                        if let Some(ref msg) = keepalive_message {
+                            app_log!("[{}] keepalive", pname_send);
+                            let _ = ws_tx
+                                .send(tungstenite::Message::Text(msg.to_string().into()))
+                                .await;
+                            last_activity_send.store(now_ms(), Ordering::SeqCst);
+                        }
+                    }
+                    _ = inactivity_check.tick(), if inactivity_enabled => {
+                        let last = last_activity_send.load(Ordering::SeqCst);
+                        let idle_for_ms = now_ms().saturating_sub(last);
+                        if idle_for_ms >= inactivity_timeout_ms {
+                            app_log!(
+                                "[{}] inactivity timeout hit: {}s (idle={}ms), stopping session",
+                                pname_send, inactivity_timeout_secs, idle_for_ms
+                            );
+                            let _ = tx_send_task.send(AppEvent::SessionInactivityTimeout {
+                                seconds: inactivity_timeout_secs,
+                            });
+                            timed_out = true;
+                            break;
+                        }
                     }
                 }
             }
-        }
 
-        // Send close message or trailing commit before closing.
-        if let Some(ref msg) = close_message {
-            app_log!("[{}] sending close message", pname_send);
-            let _ = ws_tx
-                .send(tungstenite::Message::Text(msg.to_string().into()))
-                .await;
-        } else {
-            app_log!("[{}] audio channel closed; sending trailing commit", pname_send);
-            match &commit_message {
-                CommitMessage::Json(msg) => {
-                    let _ = ws_tx
-                        .send(tungstenite::Message::Text(msg.to_string().into()))
-                        .await;
+            // Send close message or trailing commit before closing.
+            if let Some(ref msg) = close_message {
+                app_log!("[{}] sending close message", pname_send);
+                let _ = ws_tx
+                    .send(tungstenite::Message::Text(msg.to_string().into()))
+                    .await;
+            } else {
+                app_log!(
+                    "[{}] audio channel closed; sending trailing commit",
+                    pname_send
+                );
+                match &commit_message {
+                    CommitMessage::Json(msg) => {
+                        let _ = ws_tx
+                            .send(tungstenite::Message::Text(msg.to_string().into()))
+                            .await;
+                    }
+                    CommitMessage::None => {}
                 }
-                CommitMessage::None => {}
             }
-        }
-        tokio::time::sleep(Duration::from_millis(2000)).await;
-        app_log!("[{}] closing websocket", pname_send);
-        let _ = ws_tx.close().await;
-        timed_out
-    });
+            tokio::time::sleep(Duration::from_millis(2000)).await;
+            app_log!("[{}] closing websocket", pname_send);
+            let _ = ws_tx.close().await;
+            timed_out
+        });
 
-    let pname_recv = provider_recv.name().to_string();
-    let latency_state_recv = latency_state.clone();
-    let last_activity_recv = last_activity_ms.clone();
-    let provider_id_recv = provider_id.clone();
-
-    // Task: receive events from provider WebSocket.
-    let recv_task = tokio::spawn(async move {
-        let t0 = Instant::now();
-
-        loop {
-            let events: Vec<ProviderEvent> = tokio::select! {
-                msg = ws_rx.next() => {
-                    let msg = match msg {
-                        Some(Ok(m)) => m,
-                        Some(Err(e)) => {
-                            app_err!("[{}] websocket error: {}", pname_recv, e);
-                            break;
-                        }
-                        None => break,
-                    };
-
-                    let text = match msg {
-                        tungstenite::Message::Text(t) => t,
-                        tungstenite::Message::Close(frame) => {
-                            if let Some(frame) = frame {
-                                app_err!(
-                                    "[{}] websocket closed: {} {}",
-                                    pname_recv, frame.code, frame.reason
-                                );
-                                emit_status(
-                                    &tx_recv,
-                                    "error",
-                                    &format!("Disconnected: {} {}", frame.code, frame.reason),
-                                );
-                            } else {
-                                app_err!("[{}] websocket closed", pname_recv);
-                                emit_status(&tx_recv, "error", "Disconnected");
-                            }
-                            break;
-                        }
-                        _ => continue,
-                    };
+        let pname_recv = provider_recv.name().to_string();
+        let latency_state_recv = latency_state.clone();
+        let last_activity_recv = last_activity_ms.clone();
+        let provider_id_recv = provider_id.clone();
+        let sink_recv = sink.clone();
 
-                    last_activity_recv.store(now_ms(), Ordering::SeqCst);
-                    provider_recv.parse_event(&text)
-                }
-                _ = flush_rx.recv() => {
-                    provider_recv.flush()
-                }
-            };
+        // Task: receive events from provider WebSocket. Returns the error that
+        // ended the connection, if any.
+        let recv_task = tokio::spawn(async move {
+            let t0 = Instant::now();
+            let mut failure: Option<ProviderError> = None;
 
-            let ts = t0.elapsed().as_secs_f32();
+            'recv: loop {
+                let events: Vec<ProviderEvent> = tokio::select! {
+                    msg = ws_rx.next() => {
+                        let msg = match msg {
+                            Some(Ok(m)) => m,
+                            Some(Err(e)) => {
+                                failure = Some(ProviderError::Network(e.to_string()));
+                                break;
+                            }
+                            None => break,
+                        };
 
-            for event in events {
-                match event {
-                    ProviderEvent::TranscriptDelta(delta) => {
-                        if let Ok(mut s) = latency_state_recv.lock() {
-                            if s.window_open {
-                                if let Some(start) = s.current_commit_at {
-                                    let cid = s.current_commit_id;
-                                    if cid > 0 && !s.first_delta_logged {
-                                    app_log!(
-                                        "[{}] [{}] first_delta_after_commit_ms id={} ms={}",
-                                        pname_recv,
-                                        wall_ts(),
-                                        cid,
-                                        start.elapsed().as_millis()
-                                    );
-                                        s.first_delta_logged = true;
+                        let text = match msg {
+                            tungstenite::Message::Text(t) => t,
+                            tungstenite::Message::Close(frame) => {
+                                let normal = tungstenite::protocol::frame::coding::CloseCode::Normal;
+                                match frame {
+                                    Some(frame) if frame.code != normal => {
+                                        failure = Some(ProviderError::from_close(
+                                            u16::from(frame.code),
+                                            &frame.reason,
+                                        ));
+                                    }
+                                    Some(_) => app_log!("[{}] websocket closed", pname_recv),
+                                    None => {
+                                        app_err!("[{}] websocket closed", pname_recv);
+                                        emit_status(&tx_recv, "error", "Disconnected");
                                     }
                                 }
+                                break;
                             }
-                        }
-                        app_log!("[{}] [{:.1}s] transcript delta: {}", pname_recv, ts, delta);
-                        emit_transcript(&tx_recv, &delta, false);
+                            _ => continue,
+                        };
+
+                        last_activity_recv.store(now_ms(), Ordering::SeqCst);
+                        provider_recv.parse_event(&text)
                     }
-                    ProviderEvent::TranscriptFinal(transcript) => {
-                        if let Ok(mut s) = latency_state_recv.lock() {
-                            if s.window_open {
-                                if let Some(start) = s.current_commit_at {
-                                    let cid = s.current_commit_id;
-                                    if cid > 0 && !s.first_final_logged {
-                                        app_log!(
-                                            "[{}] [{}] first_final_after_commit_ms id={} ms={}",
-                                            pname_recv,
-                                            wall_ts(),
-                                            cid,
-                                            start.elapsed().as_millis()
-                                        );
-                                        s.first_final_logged = true;
+                    _ = flush_rx.recv() => {
+                        provider_recv.flush()
+                    }
+                };
+
+                let ts = t0.elapsed().as_secs_f32();
+
+                for event in events {
+                    match event {
+                        ProviderEvent::TranscriptDelta(delta) => {
+                            if let Ok(mut s) = latency_state_recv.lock() {
+                                if s.window_open {
+                                    if let Some(start) = s.current_commit_at {
+                                        let cid = s.current_commit_id;
+                                        if cid > 0 && !s.first_delta_logged {
+                                            app_log!(
+                                                "[{}] [{}] first_delta_after_commit_ms id={} ms={}",
+                                                pname_recv,
+                                                wall_ts(),
+                                                cid,
+                                                start.elapsed().as_millis()
+                                            );
+                                            s.first_delta_logged = true;
+                                        }
                                     }
                                 }
-                                // Close this commit window once a final is observed.
-                                s.window_open = false;
                             }
+                            app_log!("[{}] [{:.1}s] transcript delta: {}", pname_recv, ts, delta);
+                            emit_transcript(&tx_recv, &sink_recv, &delta, false);
                         }
-                        app_log!(
-                            "[{}] [{:.1}s] transcript final: \"{}\"",
-                            pname_recv, ts, transcript
-                        );
-                        emit_transcript(&tx_recv, &transcript, true);
-                        if let Ok(mut usage) = state_recv.usage.lock() {
-                            usage.finals = usage.finals.saturating_add(1);
+                        ProviderEvent::TranscriptFinal(transcript) => {
+                            app_log!(
+                                "[{}] [{:.1}s] transcript final: \"{}\"",
+                                pname_recv,
+                                ts,
+                                transcript
+                            );
+                            handle_final(
+                                &state_recv,
+                                &tx_recv,
+                                &sink_recv,
+                                &latency_state_recv,
+                                &pname_recv,
+                                &provider_id_recv,
+                                gen,
+                                transcript,
+                            );
                         }
-                        if let Ok(mut session) = state_recv.session_usage.lock() {
-                            if session.started_ms != 0 {
-                                session.finals = session.finals.saturating_add(1);
-                            }
+                        ProviderEvent::SendControl(msg) => {
+                            app_log!("[{}] [{:.1}s] sending control message", pname_recv, ts);
+                            let _ = ctrl_tx.send(msg).await;
                         }
-                        if let Ok(mut pt) = state_recv.provider_totals.lock() {
-                            let entry = pt.entry(provider_id_recv.clone()).or_default();
-                            entry.finals = entry.finals.saturating_add(1);
+                        ProviderEvent::Error(msg) => {
+                            let err = ProviderError::from_event(&msg);
+                            // Auth, quota and rate limits end this connection so
+                            // the session can stop or back off.
+                            if err.is_fatal() || matches!(err, ProviderError::RateLimited { .. }) {
+                                failure = Some(err);
+                                break 'recv;
+                            }
+                            app_err!("[{}] [{:.1}s] error: {}", pname_recv, ts, msg);
+                            crate::metrics::record_error(&provider_id_recv);
+                            emit_status(&tx_recv, "error", &err.user_message(&pname_recv));
                         }
-                        if let Ok(mut last) = state_recv.last_transcript.lock() {
-                            *last = transcript.clone();
+                        ProviderEvent::Status(msg) => {
+                            app_log!("[{}] [{:.1}s] {}", pname_recv, ts, msg);
                         }
-                        let chrome = state_recv.chrome_path.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                        let paint = state_recv.paint_path.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                        let urls = state_recv.url_commands.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                        let aliases = state_recv.alias_commands.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                        let apps = state_recv.app_shortcuts.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                        let text = transcript;
-                        tokio::task::spawn_blocking(move || {
-                            typing::process_transcript(&text, &chrome, &paint, &urls, &aliases, &apps);
-                        });
+                        ProviderEvent::Ignore => {}
                     }
-                    ProviderEvent::SendControl(msg) => {
-                        app_log!("[{}] [{:.1}s] sending control message", pname_recv, ts);
-                        let _ = ctrl_tx.send(msg).await;
-                    }
-                    ProviderEvent::Error(msg) => {
-                        app_err!("[{}] [{:.1}s] error: {}", pname_recv, ts, msg);
-                        emit_status(&tx_recv, "error", &msg);
-                    }
-                    ProviderEvent::Status(msg) => {
-                        app_log!("[{}] [{:.1}s] {}", pname_recv, ts, msg);
-                    }
-                    ProviderEvent::Ignore => {}
                 }
             }
-        }
 
-        // Flush any remaining segments on disconnect.
-        let remaining = provider_recv.flush();
-        for event in remaining {
-            if let ProviderEvent::TranscriptFinal(transcript) = event {
-                let ts = t0.elapsed().as_secs_f32();
-                app_log!(
-                    "[{}] [{:.1}s] flush final: \"{}\"",
-                    pname_recv, ts, transcript
+            // Flush any remaining segments on disconnect.
+            let remaining = provider_recv.flush();
+            for event in remaining {
+                if let ProviderEvent::TranscriptFinal(transcript) = event {
+                    let ts = t0.elapsed().as_secs_f32();
+                    app_log!(
+                        "[{}] [{:.1}s] flush final: \"{}\"",
+                        pname_recv,
+                        ts,
+                        transcript
+                    );
+                    handle_final(
+                        &state_recv,
+                        &tx_recv,
+                        &sink_recv,
+                        &latency_state_recv,
+                        &pname_recv,
+                        &provider_id_recv,
+                        gen,
+                        transcript,
+                    );
+                }
+            }
+
+            if failure.is_some() {
+                recv_failed.notify_one();
+            } else if state_recv.session_gen.load(Ordering::SeqCst) == gen {
+                emit_status(&tx_recv, "idle", "Disconnected");
+            }
+            failure
+        });
+
+        let (send_result, recv_result) = tokio::join!(send_task, recv_task);
+        let timed_out = send_result.unwrap_or(false);
+        if timed_out {
+            return;
+        }
+        let failure = recv_result.ok().flatten();
+        // A newer session took over; it reports its own state.
+        if state.session_gen.load(Ordering::SeqCst) != gen {
+            return;
+        }
+        // Retry unless audio channel is closed.
+        if audio_rx.lock().await.is_closed() {
+            if let Some(err) = failure.filter(|e| e.is_fatal()) {
+                handle_failure(
+                    &tx_send,
+                    &provider_id,
+                    provider_name,
+                    &err,
+                    connect_attempts,
                 );
-                emit_transcript(&tx_recv, &transcript, true);
-                if let Ok(mut usage) = state_recv.usage.lock() {
-                    usage.finals = usage.finals.saturating_add(1);
+            } else {
+                emit_status(&tx_send, "idle", "Ready");
+            }
+            return;
+        }
+        let delay = match failure {
+            Some(err) => {
+                // Keep escalating while connections keep failing right away.
+                attempts = connect_attempts;
+                match handle_failure(&tx_send, &provider_id, provider_name, &err, attempts) {
+                    Some(delay) => delay,
+                    None => return,
                 }
-                if let Ok(mut session) = state_recv.session_usage.lock() {
-                    if session.started_ms != 0 {
-                        session.finals = session.finals.saturating_add(1);
+            }
+            None => {
+                emit_status(&tx_send, "idle", "Ready");
+                Duration::from_millis(RECONNECT_BASE_MS)
+            }
+        };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::{MockProvider, SAMPLE_RATE};
+    use crate::state::SessionUsage;
+    use std::sync::mpsc::Receiver as EventReceiver;
+
+    /// 20 ms of silence at the mock's rate, as the capture path sends it.
+    const CHUNK_BYTES: usize = (SAMPLE_RATE as usize / 50) * 2;
+
+    fn settings(flush_ms: Option<u32>, silence_ms: Option<u32>) -> ProviderSettings {
+        ProviderSettings {
+            api_key: "test-key".into(),
+            model: String::new(),
+            transcription_model: String::new(),
+            language: "en".into(),
+            pre_commit_silence_ms: silence_ms,
+            commit_flush_timeout_ms: flush_ms,
+        }
+    }
+
+    /// Mock provider that records when the session asks it to flush, and
+    /// flushes its last delta as a final like the segmenting providers do.
+    #[derive(Default)]
+    struct FlushProbe {
+        flushes: std::sync::Mutex<Vec<Instant>>,
+        pending: std::sync::Mutex<String>,
+    }
+
+    impl SttProvider for FlushProbe {
+        fn name(&self) -> &str {
+            "Mock"
+        }
+
+        fn sample_rate_hint(&self) -> u32 {
+            SAMPLE_RATE
+        }
+
+        fn connection_config(&self, settings: &ProviderSettings) -> ConnectionConfig {
+            MockProvider.connection_config(settings)
+        }
+
+        fn parse_event(&self, text: &str) -> Vec<ProviderEvent> {
+            let events = MockProvider.parse_event(text);
+            for event in &events {
+                if let ProviderEvent::TranscriptDelta(text) = event {
+                    if let Ok(mut pending) = self.pending.lock() {
+                        *pending = text.clone();
                     }
                 }
-                if let Ok(mut pt) = state_recv.provider_totals.lock() {
-                    let entry = pt.entry(provider_id_recv.clone()).or_default();
-                    entry.finals = entry.finals.saturating_add(1);
-                }
-                if let Ok(mut last) = state_recv.last_transcript.lock() {
-                    *last = transcript.clone();
+            }
+            events
+        }
+
+        fn flush(&self) -> Vec<ProviderEvent> {
+            if let Ok(mut f) = self.flushes.lock() {
+                f.push(Instant::now());
+            }
+            match self.pending.lock().map(|mut p| std::mem::take(&mut *p)) {
+                Ok(text) if !text.is_empty() => vec![ProviderEvent::TranscriptFinal(text)],
+                _ => vec![],
+            }
+        }
+    }
+
+    struct Harness {
+        state: Arc<AppState>,
+        events: EventReceiver<AppEvent>,
+        audio: mpsc::Sender<Vec<u8>>,
+        session: tokio::task::JoinHandle<()>,
+    }
+
+    fn start(provider: Arc<dyn SttProvider>, settings: ProviderSettings) -> Harness {
+        let state = Arc::new(AppState::new());
+        state.session_usage.store(Arc::new(SessionUsage {
+            started_ms: 1,
+            ..SessionUsage::default()
+        }));
+        let (event_tx, events) = std::sync::mpsc::channel();
+        let (audio, audio_rx) = mpsc::channel(256);
+        let session = tokio::spawn(run_session(
+            provider,
+            event_tx,
+            state.clone(),
+            settings,
+            audio_rx,
+            60,
+            TranscriptSink::Embedded,
+        ));
+        Harness {
+            state,
+            events,
+            audio,
+            session,
+        }
+    }
+
+    impl Harness {
+        async fn speak(&self, chunks: usize) {
+            for _ in 0..chunks {
+                self.audio.send(vec![0u8; CHUNK_BYTES]).await.unwrap();
+            }
+        }
+
+        /// An empty buffer is the VAD's end-of-speech commit.
+        async fn commit(&self) -> Instant {
+            let sent = Instant::now();
+            self.audio.send(Vec::new()).await.unwrap();
+            sent
+        }
+
+        /// Next final within `within`, skipping other events.
+        async fn next_final(&self, within: Duration) -> Option<String> {
+            let deadline = Instant::now() + within;
+            while Instant::now() < deadline {
+                while let Ok(event) = self.events.try_recv() {
+                    if let AppEvent::TranscriptFinal(text) = event {
+                        return Some(text);
+                    }
                 }
-                let chrome = state_recv.chrome_path.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                let paint = state_recv.paint_path.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                let urls = state_recv.url_commands.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                let aliases = state_recv.alias_commands.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                let apps = state_recv.app_shortcuts.lock().ok().map(|g| g.clone()).unwrap_or_default();
-                let text = transcript;
-                tokio::task::spawn_blocking(move || {
-                    typing::process_transcript(&text, &chrome, &paint, &urls, &aliases, &apps);
-                });
+                tokio::time::sleep(Duration::from_millis(10)).await;
             }
+            None
         }
 
-        emit_status(&tx_recv, "idle", "Disconnected");
-    });
+        async fn finish(self) -> Vec<(String, String)> {
+            drop(self.audio);
+            tokio::time::timeout(Duration::from_secs(10), self.session)
+                .await
+                .expect("session did not end after its audio closed")
+                .unwrap();
+            self.events
+                .try_iter()
+                .filter_map(|e| match e {
+                    AppEvent::StatusUpdate { status, message } => Some((status, message)),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
 
-    let (send_result, _) = tokio::join!(send_task, recv_task);
-    let timed_out = send_result.unwrap_or(false);
-    if timed_out {
-        return;
+    #[test]
+    fn provider_ids_come_from_the_provider_table() {
+        for (id, name) in PROVIDER_ROWS {
+            assert_eq!(provider_id_from_name(name), *id);
+        }
+        assert_eq!(provider_id_from_name("Mock"), "mock");
     }
-    emit_status(&tx_send, "idle", "Ready");
-    // Retry unless audio channel is closed.
-    if audio_rx.lock().await.is_closed() {
-        return;
+
+    #[tokio::test]
+    async fn vad_commit_returns_final() {
+        let h = start(Arc::new(MockProvider), settings(None, None));
+        h.speak(25).await;
+        h.commit().await;
+        let text = h.next_final(Duration::from_secs(5)).await;
+        assert_eq!(text.as_deref(), Some("this is a mock transcript"));
+        let statuses = h.finish().await;
+        assert!(statuses.iter().all(|(status, _)| status != "error"));
+        assert_eq!(statuses.last().map(|(s, _)| s.as_str()), Some("idle"));
+    }
+
+    #[tokio::test]
+    async fn usage_counts_audio_commits_and_finals() {
+        let h = start(Arc::new(MockProvider), settings(None, Some(100)));
+        h.speak(25).await;
+        h.commit().await;
+        assert!(h.next_final(Duration::from_secs(5)).await.is_some());
+        let state = h.state.clone();
+        h.finish().await;
+
+        // 25 chunks of 20 ms plus 100 ms of pre-commit silence.
+        let bytes = (25 * CHUNK_BYTES + SAMPLE_RATE as usize / 10 * 2) as u64;
+        let usage = state.usage.lock().unwrap();
+        assert_eq!(usage.bytes_sent, bytes);
+        assert_eq!(usage.ms_sent, 600);
+        assert_eq!(usage.commits, 1);
+        assert_eq!(usage.finals, 1);
+        let session = state.session_usage.load();
+        assert_eq!(session.bytes_sent, bytes);
+        assert_eq!(session.ms_sent, 600);
+        assert_eq!(session.commits, 1);
+        assert_eq!(session.finals, 1);
     }
-    tokio::time::sleep(Duration::from_millis(RECONNECT_BASE_MS)).await;
+
+    #[tokio::test]
+    async fn swapped_out_leg_counts_into_the_draining_row() {
+        let h = start(Arc::new(MockProvider), settings(None, Some(100)));
+        h.speak(5).await;
+        // A provider switch: the running row drains while the next leg's
+        // row takes over.
+        let state = h.state.clone();
+        state.drain_session_usage();
+        state.session_gen.fetch_add(1, Ordering::SeqCst);
+        state.session_usage.store(Arc::new(SessionUsage {
+            started_ms: 2,
+            ..SessionUsage::default()
+        }));
+        h.speak(20).await;
+        h.commit().await;
+        assert!(h.next_final(Duration::from_secs(5)).await.is_some());
+        h.finish().await;
+
+        let drained = state.take_draining_usage();
+        assert_eq!(drained.ms_sent, 600);
+        assert_eq!(drained.commits, 1);
+        assert_eq!(drained.finals, 1);
+        let next = state.session_usage.load();
+        assert_eq!((next.bytes_sent, next.commits, next.finals), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn commit_flushes_when_final_is_late() {
+        // The mock answers 150 ms after a commit; flush at the 100 ms floor.
+        let probe = Arc::new(FlushProbe::default());
+        let h = start(probe.clone(), settings(Some(100), None));
+        h.speak(25).await;
+        let committed = h.commit().await;
+        let first = h.next_final(Duration::from_secs(5)).await;
+        assert_eq!(first.as_deref(), Some("this is a"));
+        let flushed = probe.flushes.lock().unwrap().first().copied();
+        let flushed = flushed.expect("no flush after the commit");
+        assert!(flushed.duration_since(committed) >= Duration::from_millis(100));
+        h.finish().await;
+    }
+
+    #[tokio::test]
+    async fn commit_does_not_flush_when_final_arrives() {
+        let probe = Arc::new(FlushProbe::default());
+        let h = start(probe.clone(), settings(Some(700), None));
+        h.speak(25).await;
+        h.commit().await;
+        let text = h.next_final(Duration::from_secs(5)).await;
+        assert_eq!(text.as_deref(), Some("this is a mock transcript"));
+        tokio::time::sleep(Duration::from_millis(900)).await;
+        assert!(probe.flushes.lock().unwrap().is_empty());
+        h.finish().await;
+    }
+
+    #[tokio::test]
+    async fn speech_after_commit_cancels_flush() {
+        // No audio means no answer from the mock, so only the timer could
+        // flush, and the new speech cancels it.
+        let probe = Arc::new(FlushProbe::default());
+        let h = start(probe.clone(), settings(Some(200), None));
+        h.commit().await;
+        h.speak(5).await;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(probe.flushes.lock().unwrap().is_empty());
+        h.finish().await;
+    }
+
+    #[tokio::test]
+    async fn rejected_key_ends_session_without_retrying() {
+        let key = crate::provider::mock::KEY_AUTH_FAIL;
+        let h = start(
+            Arc::new(MockProvider),
+            ProviderSettings {
+                api_key: key.into(),
+                ..settings(None, None)
+            },
+        );
+        let session_ended = tokio::time::timeout(Duration::from_secs(5), async {
+            while !h.session.is_finished() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(session_ended.is_ok(), "session retried a rejected key");
+        let statuses = h.finish().await;
+        assert!(statuses.iter().any(|(status, _)| status == "error"));
+        assert!(statuses.iter().all(|(_, message)| message != "Listening"));
     }
 }
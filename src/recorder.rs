@@ -0,0 +1,425 @@
+use chrono::Local;
+use image::{imageops, RgbaImage};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const RECORD_FPS: u32 = 12;
+const GIF_FPS: u32 = 8;
+/// GIFs are meant for quick bug clips; cap length and size to keep encoding fast.
+const GIF_MAX_SECS: u64 = 15;
+const GIF_MAX_DIMENSION: u32 = 800;
+const AUDIO_SAMPLE_RATE: u32 = 48_000;
+/// 100ns units per second (Media Foundation timestamps).
+const HNS_PER_SEC: i64 = 10_000_000;
+
+/// Region to record, in physical pixels relative to the monitor origin.
+pub struct RecordRegion {
+    pub monitor_x: i32,
+    pub monitor_y: i32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// H.264 MP4 with an optional AAC mic track.
+    Mp4,
+    /// Looping animated GIF, capped at `GIF_MAX_SECS`.
+    Gif,
+}
+
+/// Records a screen region to MP4 or GIF on a background thread until
+/// stopped (GIFs also stop on their own at the length cap).
+pub struct RegionRecorder {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Result<PathBuf, String>>>,
+}
+
+impl RegionRecorder {
+    pub fn start(
+        region: RecordRegion,
+        format: RecordFormat,
+        mic_device: Option<String>,
+        record_audio: bool,
+        keep_count: usize,
+    ) -> Result<Self, String> {
+        let dir = crate::snip::snip_dir()?;
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snip dir: {}", e))?;
+        let path = dir.join(format!(
+            "{}.{}",
+            Local::now().format("record-%Y-%m-%d-%H%M%S"),
+            if format == RecordFormat::Gif { "gif" } else { "mp4" }
+        ));
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_thread = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let result = match format {
+                RecordFormat::Mp4 => {
+                    record_loop(&region, &path, mic_device, record_audio, &stop_thread)
+                }
+                RecordFormat::Gif => record_gif(&region, &path, &stop_thread),
+            };
+            let _ = crate::snip::prune_old_snips(&dir, keep_count.max(1));
+            result.map(|_| path)
+        });
+
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// True once the recording thread has exited (stopped, capped or failed).
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().map(|h| h.is_finished()).unwrap_or(true)
+    }
+
+    /// Signal the recorder to stop and wait for the file to be finalized.
+    pub fn stop(mut self) -> Result<PathBuf, String> {
+        self.stop.store(true, Ordering::SeqCst);
+        match self.handle.take() {
+            Some(h) => h
+                .join()
+                .map_err(|_| "Recorder thread panicked".to_string())?,
+            None => Err("Recorder already stopped".into()),
+        }
+    }
+}
+
+fn find_monitor(region: &RecordRegion) -> Result<xcap::Monitor, String> {
+    let monitors = xcap::Monitor::all().map_err(|e| format!("xcap monitors error: {:?}", e))?;
+    monitors
+        .into_iter()
+        .find(|m| m.x().ok() == Some(region.monitor_x) && m.y().ok() == Some(region.monitor_y))
+        .ok_or_else(|| "Recording monitor not found".to_string())
+}
+
+/// Crop a frame and convert RGBA to BGRA (what the RGB32 input type expects).
+fn frame_bgra(img: &RgbaImage, region: &RecordRegion, w: u32, h: u32) -> Vec<u8> {
+    let mut frame = if img.width() >= region.x + w && img.height() >= region.y + h {
+        imageops::crop_imm(img, region.x, region.y, w, h).to_image()
+    } else {
+        // Monitor resolution changed mid-recording: keep the output size stable.
+        imageops::resize(img, w, h, imageops::FilterType::Triangle)
+    };
+    for px in frame.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+    frame.into_raw()
+}
+
+fn record_loop(
+    region: &RecordRegion,
+    path: &std::path::Path,
+    mic_device: Option<String>,
+    record_audio: bool,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    // H.264 needs even dimensions.
+    let w = (region.width & !1).max(2);
+    let h = (region.height & !1).max(2);
+    let monitor = find_monitor(region)?;
+
+    let audio_buf: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
+    let mic = if record_audio {
+        match crate::audio::RawCapture::start(
+            mic_device.as_deref(),
+            AUDIO_SAMPLE_RATE,
+            audio_buf.clone(),
+        ) {
+            Ok(c) => Some(c),
+            Err(e) => {
+                app_err!("[record] mic unavailable, recording without audio: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let audio_rate = mic.as_ref().map(|_| AUDIO_SAMPLE_RATE);
+
+    let mut writer = Mp4Writer::create(path, w, h, RECORD_FPS, audio_rate)?;
+    app_log!(
+        "[record] started {}x{} @ {}fps audio={} -> {}",
+        w,
+        h,
+        RECORD_FPS,
+        audio_rate.is_some(),
+        path.display()
+    );
+
+    let frame_interval = Duration::from_millis(1000 / RECORD_FPS as u64);
+    let frame_dur = HNS_PER_SEC / RECORD_FPS as i64;
+    let started = Instant::now();
+    let mut audio_samples_written: i64 = 0;
+    let mut frames: u64 = 0;
+    while !stop.load(Ordering::SeqCst) {
+        let tick = Instant::now();
+        match monitor.capture_image() {
+            Ok(img) => {
+                let ts = started.elapsed().as_nanos() as i64 / 100;
+                writer.write_video(&frame_bgra(&img, region, w, h), ts, frame_dur)?;
+                frames += 1;
+            }
+            Err(e) => app_err!("[record] frame capture error: {:?}", e),
+        }
+        if let Some(rate) = audio_rate {
+            let pcm: Vec<i16> = audio_buf
+                .lock()
+                .map(|mut b| std::mem::take(&mut *b))
+                .unwrap_or_default();
+            if !pcm.is_empty() {
+                let ts = audio_samples_written * HNS_PER_SEC / rate as i64;
+                let dur = pcm.len() as i64 * HNS_PER_SEC / rate as i64;
+                writer.write_audio(&pcm, ts, dur)?;
+                audio_samples_written += pcm.len() as i64;
+            }
+        }
+        if let Some(rest) = frame_interval.checked_sub(tick.elapsed()) {
+            std::thread::sleep(rest);
+        }
+    }
+
+    writer.finish()?;
+    app_log!(
+        "[record] stopped after {:.1}s ({} frames)",
+        started.elapsed().as_secs_f32(),
+        frames
+    );
+    Ok(())
+}
+
+fn record_gif(region: &RecordRegion, path: &std::path::Path, stop: &AtomicBool) -> Result<(), String> {
+    use image::codecs::gif::{GifEncoder, Repeat};
+    use image::{Delay, Frame};
+
+    let monitor = find_monitor(region)?;
+    let w = region.width.max(1);
+    let h = region.height.max(1);
+    let scale = (GIF_MAX_DIMENSION as f32 / w.max(h) as f32).min(1.0);
+    let out_w = ((w as f32 * scale).round() as u32).max(1);
+    let out_h = ((h as f32 * scale).round() as u32).max(1);
+
+    let frame_interval = Duration::from_millis(1000 / GIF_FPS as u64);
+    let started = Instant::now();
+    let mut frames: Vec<RgbaImage> = Vec::new();
+    app_log!("[record] GIF started {}x{} @ {}fps", out_w, out_h, GIF_FPS);
+    while !stop.load(Ordering::SeqCst) && started.elapsed().as_secs() < GIF_MAX_SECS {
+        let tick = Instant::now();
+        match monitor.capture_image() {
+            Ok(img) => {
+                let x = region.x.min(img.width().saturating_sub(1));
+                let y = region.y.min(img.height().saturating_sub(1));
+                let cropped = imageops::crop_imm(&img, x, y, w, h).to_image();
+                frames.push(if scale < 1.0 {
+                    imageops::resize(&cropped, out_w, out_h, imageops::FilterType::Triangle)
+                } else {
+                    cropped
+                });
+            }
+            Err(e) => app_err!("[record] frame capture error: {:?}", e),
+        }
+        if let Some(rest) = frame_interval.checked_sub(tick.elapsed()) {
+            std::thread::sleep(rest);
+        }
+    }
+    if frames.is_empty() {
+        return Err("No frames captured".into());
+    }
+
+    let file = std::fs::File::create(path).map_err(|e| format!("Failed to create GIF: {}", e))?;
+    let mut encoder = GifEncoder::new_with_speed(std::io::BufWriter::new(file), 10);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|e| format!("GIF encode error: {}", e))?;
+    let delay = Delay::from_numer_denom_ms(1000, GIF_FPS);
+    let count = frames.len();
+    encoder
+        .encode_frames(frames.into_iter().map(|f| Frame::from_parts(f, 0, 0, delay)))
+        .map_err(|e| format!("GIF encode error: {}", e))?;
+    app_log!(
+        "[record] GIF stopped after {:.1}s ({} frames)",
+        started.elapsed().as_secs_f32(),
+        count
+    );
+    Ok(())
+}
+
+#[cfg(windows)]
+struct Mp4Writer {
+    writer: windows::Win32::Media::MediaFoundation::IMFSinkWriter,
+    video_stream: u32,
+    audio_stream: Option<u32>,
+}
+
+#[cfg(windows)]
+impl Mp4Writer {
+    fn create(
+        path: &std::path::Path,
+        width: u32,
+        height: u32,
+        fps: u32,
+        audio_rate: Option<u32>,
+    ) -> Result<Self, String> {
+        use windows::core::HSTRING;
+        use windows::Win32::Media::MediaFoundation::*;
+        use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+        let err = |what: &str, e: windows::core::Error| format!("Failed to {}: {}", what, e);
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            MFStartup(MF_VERSION, MFSTARTUP_FULL).map_err(|e| err("start Media Foundation", e))?;
+
+            let writer = MFCreateSinkWriterFromURL(&HSTRING::from(path.as_os_str()), None, None)
+                .map_err(|e| err("create MP4 writer", e))?;
+
+            let frame_size = ((width as u64) << 32) | height as u64;
+            let frame_rate = ((fps as u64) << 32) | 1;
+            let aspect = (1u64 << 32) | 1;
+
+            let out_type = MFCreateMediaType().map_err(|e| err("create media type", e))?;
+            out_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video).map_err(|e| err("configure video", e))?;
+            out_type.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_H264).map_err(|e| err("configure video", e))?;
+            out_type
+                .SetUINT32(&MF_MT_AVG_BITRATE, (width * height * fps / 4).clamp(500_000, 12_000_000))
+                .map_err(|e| err("configure video", e))?;
+            out_type
+                .SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)
+                .map_err(|e| err("configure video", e))?;
+            out_type.SetUINT64(&MF_MT_FRAME_SIZE, frame_size).map_err(|e| err("configure video", e))?;
+            out_type.SetUINT64(&MF_MT_FRAME_RATE, frame_rate).map_err(|e| err("configure video", e))?;
+            out_type.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, aspect).map_err(|e| err("configure video", e))?;
+            let video_stream = writer.AddStream(&out_type).map_err(|e| err("add video stream", e))?;
+
+            let in_type = MFCreateMediaType().map_err(|e| err("create media type", e))?;
+            in_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video).map_err(|e| err("configure video", e))?;
+            in_type.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_RGB32).map_err(|e| err("configure video", e))?;
+            in_type
+                .SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)
+                .map_err(|e| err("configure video", e))?;
+            in_type.SetUINT64(&MF_MT_FRAME_SIZE, frame_size).map_err(|e| err("configure video", e))?;
+            in_type.SetUINT64(&MF_MT_FRAME_RATE, frame_rate).map_err(|e| err("configure video", e))?;
+            in_type.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, aspect).map_err(|e| err("configure video", e))?;
+            // Positive stride = top-down rows, matching the captured frames.
+            in_type.SetUINT32(&MF_MT_DEFAULT_STRIDE, width * 4).map_err(|e| err("configure video", e))?;
+            writer
+                .SetInputMediaType(video_stream, &in_type, None)
+                .map_err(|e| err("set video input", e))?;
+
+            let audio_stream = match audio_rate {
+                Some(rate) => {
+                    let out_type = MFCreateMediaType().map_err(|e| err("create media type", e))?;
+                    out_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Audio).map_err(|e| err("configure audio", e))?;
+                    out_type.SetGUID(&MF_MT_SUBTYPE, &MFAudioFormat_AAC).map_err(|e| err("configure audio", e))?;
+                    out_type.SetUINT32(&MF_MT_AUDIO_BITS_PER_SAMPLE, 16).map_err(|e| err("configure audio", e))?;
+                    out_type.SetUINT32(&MF_MT_AUDIO_SAMPLES_PER_SECOND, rate).map_err(|e| err("configure audio", e))?;
+                    out_type.SetUINT32(&MF_MT_AUDIO_NUM_CHANNELS, 1).map_err(|e| err("configure audio", e))?;
+                    out_type
+                        .SetUINT32(&MF_MT_AUDIO_AVG_BYTES_PER_SECOND, 16_000)
+                        .map_err(|e| err("configure audio", e))?;
+                    let stream = writer.AddStream(&out_type).map_err(|e| err("add audio stream", e))?;
+
+                    let in_type = MFCreateMediaType().map_err(|e| err("create media type", e))?;
+                    in_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Audio).map_err(|e| err("configure audio", e))?;
+                    in_type.SetGUID(&MF_MT_SUBTYPE, &MFAudioFormat_PCM).map_err(|e| err("configure audio", e))?;
+                    in_type.SetUINT32(&MF_MT_AUDIO_BITS_PER_SAMPLE, 16).map_err(|e| err("configure audio", e))?;
+                    in_type.SetUINT32(&MF_MT_AUDIO_SAMPLES_PER_SECOND, rate).map_err(|e| err("configure audio", e))?;
+                    in_type.SetUINT32(&MF_MT_AUDIO_NUM_CHANNELS, 1).map_err(|e| err("configure audio", e))?;
+                    in_type.SetUINT32(&MF_MT_AUDIO_BLOCK_ALIGNMENT, 2).map_err(|e| err("configure audio", e))?;
+                    in_type
+                        .SetUINT32(&MF_MT_AUDIO_AVG_BYTES_PER_SECOND, rate * 2)
+                        .map_err(|e| err("configure audio", e))?;
+                    writer
+                        .SetInputMediaType(stream, &in_type, None)
+                        .map_err(|e| err("set audio input", e))?;
+                    Some(stream)
+                }
+                None => None,
+            };
+
+            writer.BeginWriting().map_err(|e| err("start MP4 writer", e))?;
+            Ok(Self {
+                writer,
+                video_stream,
+                audio_stream,
+            })
+        }
+    }
+
+    fn write_bytes(&self, stream: u32, bytes: &[u8], time: i64, duration: i64) -> Result<(), String> {
+        use windows::Win32::Media::MediaFoundation::{MFCreateMemoryBuffer, MFCreateSample};
+
+        let err = |e: windows::core::Error| format!("Failed to write sample: {}", e);
+        unsafe {
+            let buffer = MFCreateMemoryBuffer(bytes.len() as u32).map_err(err)?;
+            let mut ptr: *mut u8 = std::ptr::null_mut();
+            buffer.Lock(&mut ptr, None, None).map_err(err)?;
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+            buffer.Unlock().map_err(err)?;
+            buffer.SetCurrentLength(bytes.len() as u32).map_err(err)?;
+
+            let sample = MFCreateSample().map_err(err)?;
+            sample.AddBuffer(&buffer).map_err(err)?;
+            sample.SetSampleTime(time).map_err(err)?;
+            sample.SetSampleDuration(duration).map_err(err)?;
+            self.writer.WriteSample(stream, &sample).map_err(err)
+        }
+    }
+
+    fn write_video(&mut self, bgra: &[u8], time: i64, duration: i64) -> Result<(), String> {
+        self.write_bytes(self.video_stream, bgra, time, duration)
+    }
+
+    fn write_audio(&mut self, pcm: &[i16], time: i64, duration: i64) -> Result<(), String> {
+        let Some(stream) = self.audio_stream else {
+            return Ok(());
+        };
+        let bytes: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+        self.write_bytes(stream, &bytes, time, duration)
+    }
+
+    fn finish(self) -> Result<(), String> {
+        unsafe {
+            let result = self
+                .writer
+                .Finalize()
+                .map_err(|e| format!("Failed to finalize MP4: {}", e));
+            let _ = windows::Win32::Media::MediaFoundation::MFShutdown();
+            result
+        }
+    }
+}
+
+#[cfg(not(windows))]
+struct Mp4Writer;
+
+#[cfg(not(windows))]
+impl Mp4Writer {
+    fn create(
+        _path: &std::path::Path,
+        _width: u32,
+        _height: u32,
+        _fps: u32,
+        _audio_rate: Option<u32>,
+    ) -> Result<Self, String> {
+        Err("Screen recording is only supported on Windows".into())
+    }
+
+    fn write_video(&mut self, _bgra: &[u8], _time: i64, _duration: i64) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn write_audio(&mut self, _pcm: &[i16], _time: i64, _duration: i64) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+// Phone as a remote microphone. Serves a small page on the LAN; the phone's
+// browser captures its mic and streams 16kHz 16-bit mono PCM back over a
+// WebSocket, which feeds the normal dictation pipeline in place of the local
+// mic. Every URL carries a per-launch token, so only someone who can see the
+// address in Settings can connect.
+//
+// Phone browsers only allow the microphone on secure pages, so the page and
+// socket are served over TLS with a self-signed certificate made at startup.
+// The phone warns about it once per launch; audio and the token are encrypted
+// on the network either way.
+//
+// Phone -> server: binary frames of little-endian i16 samples, and
+//                  {"action": "start"} / {"action": "stop"}.
+// Server -> phone: {"event": "state", "recording": bool}
+
+use crate::state::AppEvent;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Sender as EventSender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Rate the page resamples to before sending.
+pub const SAMPLE_RATE: u32 = 16000;
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+/// How long a client gets to send its request head after the TLS handshake.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+const PAGE: &str = include_str!("../assets/remote_mic.html");
+
+/// Where incoming samples go while the phone is the session's mic.
+type FeedSlot = Arc<Mutex<Option<SyncSender<Vec<f32>>>>>;
+
+pub struct RemoteMic {
+    port: u16,
+    token: String,
+    feed: FeedSlot,
+    /// Connection allowed to feed audio; a newer phone takes over.
+    active_conn: Arc<AtomicU64>,
+}
+
+impl RemoteMic {
+    /// Address to open on the phone.
+    pub fn url(&self) -> String {
+        let host = lan_ip().unwrap_or_else(|| "<this-pc-ip>".into());
+        format!("https://{}:{}/?t={}", host, self.port, self.token)
+    }
+
+    /// Route the phone's audio into `feed` for the session that is starting.
+    pub fn attach(&self, feed: SyncSender<Vec<f32>>) {
+        if let Ok(mut slot) = self.feed.lock() {
+            *slot = Some(feed);
+        }
+    }
+
+    /// Drop the feed, which ends the session's audio stream.
+    pub fn detach(&self) {
+        if let Ok(mut slot) = self.feed.lock() {
+            *slot = None;
+        }
+    }
+}
+
+/// 128 bits from the OS random source, hex-encoded. Also guards the service
+/// IPC port.
+pub fn new_token() -> String {
+    use ring::rand::SecureRandom;
+    let mut bytes = [0u8; 16];
+    ring::rand::SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("OS random source unavailable");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The address other devices on the LAN reach us at. Connecting a UDP socket
+/// picks the outbound interface without sending anything.
+fn lan_ip() -> Option<String> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}
+
+fn query_token(uri: &str) -> Option<&str> {
+    let query = uri.split_once('?')?.1;
+    query.split('&').find_map(|pair| pair.strip_prefix("t="))
+}
+
+/// Read the request head to tell a page load from a WebSocket upgrade.
+/// Returns it with every byte read so far, which the WebSocket handshake has
+/// to see again.
+async fn read_head<S: AsyncRead + Unpin>(stream: &mut S) -> Option<(String, Vec<u8>)> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let end = buf
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .map(|i| i + 4)
+            .or((buf.len() >= MAX_HEADER_BYTES).then_some(buf.len()));
+        if let Some(end) = end {
+            return Some((String::from_utf8_lossy(&buf[..end]).into_owned(), buf));
+        }
+    }
+}
+
+async fn serve_page<S: AsyncWrite + Unpin>(mut stream: S, head: &str, token: &str) {
+    let path = head.split_whitespace().nth(1).unwrap_or("/");
+    let authorized = query_token(path) == Some(token);
+    let (status, body) = if authorized {
+        ("200 OK", PAGE)
+    } else {
+        ("403 Forbidden", "Open the address shown in Mango Chat's settings.")
+    };
+    let content_type = if authorized {
+        "text/html; charset=utf-8"
+    } else {
+        "text/plain; charset=utf-8"
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn serve_socket<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    conn_id: u64,
+    token: String,
+    feed: FeedSlot,
+    active_conn: Arc<AtomicU64>,
+    event_tx: EventSender<AppEvent>,
+) -> Result<(), String> {
+    let check = |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        if query_token(&req.uri().to_string()) == Some(token.as_str()) {
+            Ok(resp)
+        } else {
+            let mut denied = ErrorResponse::new(Some("bad token".into()));
+            *denied.status_mut() = tokio_tungstenite::tungstenite::http::StatusCode::FORBIDDEN;
+            Err(denied)
+        }
+    };
+    let ws = tokio_tungstenite::accept_hdr_async(stream, check)
+        .await
+        .map_err(|e| format!("Failed to accept phone connection: {}", e))?;
+    let (mut sink, mut source) = ws.split();
+    active_conn.store(conn_id, Ordering::SeqCst);
+    app_log!("[remote-mic] phone connected");
+    let mut streaming = false;
+
+    while let Some(msg) = source.next().await {
+        let msg = msg.map_err(|e| format!("Phone connection error: {}", e))?;
+        match msg {
+            Message::Binary(bytes) => {
+                if active_conn.load(Ordering::SeqCst) != conn_id {
+                    continue;
+                }
+                let samples: Vec<f32> = bytes
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                    .collect();
+                if let Ok(slot) = feed.lock() {
+                    if let Some(tx) = slot.as_ref() {
+                        let _ = tx.try_send(samples);
+                    }
+                }
+            }
+            Message::Text(text) => {
+                let action = serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .and_then(|v| v.get("action").and_then(Value::as_str).map(str::to_string))
+                    .unwrap_or_default();
+                match action.as_str() {
+                    "start" => {
+                        active_conn.store(conn_id, Ordering::SeqCst);
+                        streaming = true;
+                        let _ = event_tx.send(AppEvent::RemoteMicStart);
+                    }
+                    "stop" => {
+                        streaming = false;
+                        let _ = event_tx.send(AppEvent::RemoteMicStop);
+                    }
+                    other => app_err!("[remote-mic] unknown action \"{}\"", other),
+                }
+                let state = json!({ "event": "state", "recording": streaming }).to_string();
+                sink.send(Message::Text(state))
+                    .await
+                    .map_err(|e| format!("Failed to send state: {}", e))?;
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    if streaming && active_conn.load(Ordering::SeqCst) == conn_id {
+        let _ = event_tx.send(AppEvent::RemoteMicStop);
+    }
+    app_log!("[remote-mic] phone disconnected");
+    Ok(())
+}
+
+/// Listen on all interfaces at `port` for the phone page and its audio.
+pub fn start_server(
+    runtime: &tokio::runtime::Runtime,
+    event_tx: EventSender<AppEvent>,
+    port: u16,
+) -> RemoteMic {
+    let remote = RemoteMic {
+        port,
+        token: new_token(),
+        feed: Arc::new(Mutex::new(None)),
+        active_conn: Arc::new(AtomicU64::new(0)),
+    };
+    let (token, feed, active_conn) = (
+        remote.token.clone(),
+        remote.feed.clone(),
+        remote.active_conn.clone(),
+    );
+    runtime.spawn(async move {
+        let ip = lan_ip().and_then(|ip| ip.parse().ok());
+        let tls = match self_signed_cert(ip).and_then(|(cert, key)| tls_acceptor(cert, key)) {
+            Ok(tls) => tls,
+            Err(e) => {
+                app_err!("[remote-mic] {}", e);
+                return;
+            }
+        };
+        let listener = match TcpListener::bind(("0.0.0.0", port)).await {
+            Ok(l) => l,
+            Err(e) => {
+                app_err!("[remote-mic] failed to bind port {}: {}", port, e);
+                return;
+            }
+        };
+        app_log!("[remote-mic] listening on port {}", port);
+        let mut next_conn = 0u64;
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    app_err!("[remote-mic] accept error: {}", e);
+                    continue;
+                }
+            };
+            next_conn += 1;
+            let conn_id = next_conn;
+            let (tls, token, feed, active_conn, event_tx) = (
+                tls.clone(),
+                token.clone(),
+                feed.clone(),
+                active_conn.clone(),
+                event_tx.clone(),
+            );
+            tokio::spawn(async move {
+                // A phone that hasn't accepted the certificate yet drops the
+                // handshake; that's expected, so it isn't an error.
+                let mut stream = match tls.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        app_log!("[remote-mic] TLS handshake failed: {}", e);
+                        return;
+                    }
+                };
+                let Ok(Some((head, read))) =
+                    tokio::time::timeout(HEAD_TIMEOUT, read_head(&mut stream)).await
+                else {
+                    return;
+                };
+                if !head.to_ascii_lowercase().contains("upgrade: websocket") {
+                    serve_page(stream, &head, &token).await;
+                    return;
+                }
+                // Hand the handshake the bytes read above, then the rest.
+                let (reader, writer) = tokio::io::split(stream);
+                let stream = tokio::io::join(std::io::Cursor::new(read).chain(reader), writer);
+                if let Err(e) =
+                    serve_socket(stream, conn_id, token, feed, active_conn, event_tx).await
+                {
+                    app_err!("[remote-mic] {}", e);
+                }
+            });
+        }
+    });
+    remote
+}
+
+/// TLS for the page and socket with the certificate made for this launch.
+fn tls_acceptor(
+    cert: CertificateDer<'static>,
+    key: PrivateKeyDer<'static>,
+) -> Result<TlsAcceptor, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS setup failed: {}", e))?
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)
+        .map_err(|e| format!("TLS setup failed: {}", e))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// A self-signed certificate naming `ip` and localhost, with its key. Kept in
+/// memory only.
+fn self_signed_cert(
+    ip: Option<IpAddr>,
+) -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>), String> {
+    let mut names = vec!["localhost".to_string()];
+    names.extend(ip.map(|ip| ip.to_string()));
+    let certified = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| format!("Failed to create TLS certificate: {}", e))?;
+    let cert = CertificateDer::from(certified.cert.der().to_vec());
+    let key = PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der());
+    Ok((cert, PrivateKeyDer::Pkcs8(key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn phone_can_verify_the_served_certificate() {
+        let ip = IpAddr::from([192, 168, 1, 20]);
+        let (cert, key) = self_signed_cert(Some(ip)).unwrap();
+        let acceptor = tls_acceptor(cert.clone(), key).unwrap();
+
+        // A client that trusts exactly this certificate and checks it against
+        // the LAN address, like a phone that has accepted the warning.
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(cert).unwrap();
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+
+        let (client, server) = tokio::io::duplex(16 * 1024);
+        let server = tokio::spawn(async move { acceptor.accept(server).await.map(|_| ()) });
+        let name = rustls::pki_types::ServerName::IpAddress(ip.into());
+        connector.connect(name, client).await.unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn tokens_are_random_hex() {
+        let (a, b) = (new_token(), new_token());
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn page_urls_are_https() {
+        let remote = RemoteMic {
+            port: 8443,
+            token: "abc".into(),
+            feed: Arc::new(Mutex::new(None)),
+            active_conn: Arc::new(AtomicU64::new(0)),
+        };
+        assert!(remote.url().starts_with("https://"));
+        assert!(remote.url().ends_with(":8443/?t=abc"));
+    }
+}
@@ -11,13 +11,9 @@ struct SecretsFile {
 }
 
 fn secrets_path() -> Result<PathBuf, String> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Ok(dir.join("MangoChat").join("secrets.json"));
-    }
-    if let Some(home) = dirs::home_dir() {
-        return Ok(home.join(".mangochat").join("secrets.json"));
-    }
-    Err("Failed to resolve data directory".into())
+    crate::profile::data_dir()
+        .map(|d| d.join("secrets.json"))
+        .ok_or_else(|| "Failed to resolve data directory".into())
 }
 
 fn legacy_secrets_path() -> Result<PathBuf, String> {
@@ -0,0 +1,762 @@
+// Background dictation service. `mangochat --service` runs the hotkey
+// listener, microphone and provider sessions without a window, so dictation
+// keeps going if the window crashes or is closed. With Settings > Background
+// service on, the window starts the service when it isn't running and
+// becomes its client; snips, meetings and the integrations stay in the
+// window. Snip-region voice commands and questions about an attached snip
+// are sent back to the window to handle.
+//
+// The service listens on a loopback port and writes the port and a
+// per-launch token to service.json in the profile's data folder. A client's
+// first message must carry the token. Messages are JSON, one per line:
+//
+// Client -> service: {"cmd": "hello", "token": "..."} | {"cmd": "start"} |
+//                    {"cmd": "stop"} | {"cmd": "toggle"} | {"cmd": "shutdown"} |
+//                    {"cmd": "snip_attached"}
+// Service -> client: {"event": "recording", "active": bool} |
+//                    {"event": "status", "status": "live", "message": "..."} |
+//                    {"event": "delta" | "final" | "snip_question", "text": "..."} |
+//                    {"event": "snip_preset", "name": "..."} |
+//                    {"event": "voice_note_saved"}
+
+use crate::dictation::{Dictation, Refusal};
+use crate::settings::Settings;
+use crate::state::{AppEvent, AppState};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{RecvTimeoutError, Sender as EventSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const SERVICE_ID: &str = "MangoChat.Service";
+/// Longest message either side accepts.
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+/// How long a new connection has to send its token.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+/// A client that stops reading is dropped rather than stalling the service.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(500);
+/// Failed connects between attempts to start the service again.
+const RESPAWN_EVERY: u32 = 20;
+/// How often the service checks for a shutdown request while idle.
+const SHUTDOWN_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    Hello { token: String },
+    Start,
+    Stop,
+    Toggle,
+    Shutdown,
+    SnipAttached,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Notice {
+    Recording { active: bool },
+    Status { status: String, message: String },
+    Delta { text: String },
+    Final { text: String },
+    SnipPreset { name: String },
+    SnipQuestion { text: String },
+    VoiceNoteSaved,
+}
+
+/// Contents of service.json.
+#[derive(Debug, Serialize, Deserialize)]
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+fn endpoint_path() -> Result<PathBuf, String> {
+    crate::profile::data_dir()
+        .map(|dir| dir.join("service.json"))
+        .ok_or_else(|| "No data folder for service.json".to_string())
+}
+
+fn read_endpoint() -> Result<Endpoint, String> {
+    let path = endpoint_path()?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Bad {}: {}", path.display(), e))
+}
+
+fn write_endpoint(endpoint: &Endpoint) -> Result<(), String> {
+    let path = endpoint_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string(endpoint)
+        .map_err(|e| format!("Failed to serialize service.json: {}", e))?;
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Next message from `reader`, or None once the other side hangs up.
+fn read_message<T: DeserializeOwned>(reader: &mut impl BufRead) -> Result<Option<T>, String> {
+    let mut line = String::new();
+    let read = reader
+        .by_ref()
+        .take(MAX_MESSAGE_BYTES)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read from the service socket: {}", e))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| format!("Bad service message: {}", e))
+}
+
+fn write_message(stream: &mut TcpStream, message: &impl Serialize) -> Result<(), String> {
+    let mut line = serde_json::to_string(message)
+        .map_err(|e| format!("Failed to serialize service message: {}", e))?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write to the service socket: {}", e))
+}
+
+/// Connected windows, plus what a new one is told on connect.
+#[derive(Default)]
+struct Clients {
+    streams: Mutex<Vec<TcpStream>>,
+    recording: AtomicBool,
+    shutdown: AtomicBool,
+}
+
+impl Clients {
+    /// Send `notice` to every client, dropping the ones that have gone away.
+    fn broadcast(&self, notice: &Notice) {
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.retain_mut(|stream| write_message(stream, notice).is_ok());
+        }
+    }
+}
+
+/// Bind a loopback port, publish it in service.json and serve clients on
+/// background threads.
+fn listen(
+    clients: Arc<Clients>,
+    state: Arc<AppState>,
+    event_tx: EventSender<AppEvent>,
+) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to bind the service port: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read the service port: {}", e))?
+        .port();
+    let token = crate::remote_mic::new_token();
+    write_endpoint(&Endpoint {
+        port,
+        token: token.clone(),
+    })?;
+    app_log!("[service] listening on 127.0.0.1:{}", port);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    app_err!("[service] accept error: {}", e);
+                    continue;
+                }
+            };
+            let (clients, state, event_tx) = (clients.clone(), state.clone(), event_tx.clone());
+            let token = token.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = serve_client(stream, &token, &clients, &state, &event_tx) {
+                    app_err!("[service] {}", e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+fn serve_client(
+    stream: TcpStream,
+    token: &str,
+    clients: &Clients,
+    state: &AppState,
+    event_tx: &EventSender<AppEvent>,
+) -> Result<(), String> {
+    let socket_err = |e: std::io::Error| format!("Service socket error: {}", e);
+    stream
+        .set_read_timeout(Some(HELLO_TIMEOUT))
+        .map_err(socket_err)?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(socket_err)?);
+    match read_message::<Request>(&mut reader)? {
+        Some(Request::Hello { token: t }) if t == token => {}
+        _ => return Err("Rejected a client without the service token".into()),
+    }
+    stream.set_read_timeout(None).map_err(socket_err)?;
+    stream
+        .set_write_timeout(Some(WRITE_TIMEOUT))
+        .map_err(socket_err)?;
+    {
+        let mut writer = stream.try_clone().map_err(socket_err)?;
+        let mut streams = clients
+            .streams
+            .lock()
+            .map_err(|_| "Client list is poisoned")?;
+        let active = clients.recording.load(Ordering::SeqCst);
+        write_message(&mut writer, &Notice::Recording { active })?;
+        streams.push(writer);
+    }
+    app_log!("[service] client connected");
+    while let Some(request) = read_message::<Request>(&mut reader)? {
+        let event = match request {
+            Request::Hello { .. } => continue,
+            Request::Start => AppEvent::SetRecording(true),
+            Request::Stop => AppEvent::SetRecording(false),
+            Request::Toggle => AppEvent::TrayToggleRecording,
+            Request::Shutdown => {
+                app_log!("[service] shutdown requested");
+                clients.shutdown.store(true, Ordering::SeqCst);
+                return Ok(());
+            }
+            Request::SnipAttached => {
+                state.snip_in_window.store(true, Ordering::SeqCst);
+                continue;
+            }
+        };
+        let _ = event_tx.send(event);
+    }
+    app_log!("[service] client disconnected");
+    Ok(())
+}
+
+/// The dictation pipeline, driven from the service's main thread.
+struct Service {
+    state: Arc<AppState>,
+    runtime: tokio::runtime::Runtime,
+    clients: Arc<Clients>,
+    settings: Settings,
+    dictation: Dictation,
+}
+
+impl Service {
+    fn is_recording(&self) -> bool {
+        self.clients.recording.load(Ordering::SeqCst)
+    }
+
+    fn set_recording(&self, active: bool) {
+        self.clients.recording.store(active, Ordering::SeqCst);
+        self.clients.broadcast(&Notice::Recording { active });
+    }
+
+    fn set_status(&self, message: &str, status: &str) {
+        self.clients.broadcast(&Notice::Status {
+            status: status.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn handle(&mut self, event: AppEvent) {
+        match event {
+            AppEvent::HotkeyPush | AppEvent::SetRecording(true) => self.start(),
+            AppEvent::HotkeyRelease | AppEvent::SetRecording(false) => self.stop(),
+            AppEvent::TrayToggleRecording => {
+                if self.is_recording() {
+                    self.stop();
+                } else {
+                    self.start();
+                }
+            }
+            AppEvent::StatusUpdate { status, message } => {
+                self.clients.broadcast(&Notice::Status { status, message });
+            }
+            AppEvent::TranscriptDelta(text) => self.clients.broadcast(&Notice::Delta { text }),
+            AppEvent::TranscriptFinal(text) => self.clients.broadcast(&Notice::Final { text }),
+            AppEvent::SnipPreset(name) => self.clients.broadcast(&Notice::SnipPreset { name }),
+            AppEvent::SnipQuestion(text) => {
+                self.clients.broadcast(&Notice::SnipQuestion { text });
+            }
+            AppEvent::VoiceNoteSaved => self.clients.broadcast(&Notice::VoiceNoteSaved),
+            AppEvent::SessionInactivityTimeout { seconds } => {
+                if self.is_recording() {
+                    self.stop();
+                    self.set_status(&format!("Stopped after {}s inactivity", seconds), "idle");
+                }
+            }
+            AppEvent::SessionMaxDurationReached { token, minutes } => {
+                if self.is_recording() && self.dictation.is_current_limit(token) {
+                    self.stop();
+                    let message = format!("Stopped at max session length ({}m)", minutes);
+                    self.set_status(&message, "idle");
+                }
+            }
+            AppEvent::AudioInputLost { message } => {
+                app_err!("[service] audio input lost: {}", message);
+                self.stop();
+                self.set_status("Mic disconnected", "error");
+            }
+            AppEvent::SettingsFileChanged => {
+                self.settings = crate::settings::load();
+                apply_settings(&self.state, &self.settings);
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self) {
+        if self.is_recording() {
+            return;
+        }
+        // Keys and the provider may have changed in the window since the last
+        // session; saving keys doesn't touch settings.json.
+        self.settings = crate::settings::load();
+        // The same checks the window runs before a session of its own.
+        let mut plan = match self.dictation.plan(&self.settings, true) {
+            Ok(plan) => plan,
+            Err(refusal) => {
+                self.refuse(refusal);
+                return;
+            }
+        };
+        if let Some(alert) = plan.budget_alert.take() {
+            self.set_status(&alert, "idle");
+        }
+        let provider_id = plan.provider_id.clone();
+        if let Err(refusal) = self
+            .dictation
+            .start(&self.settings, &self.runtime, plan, None)
+        {
+            self.refuse(refusal);
+            return;
+        }
+        app_log!("[service] started with {}", provider_id);
+        self.set_recording(true);
+        self.set_status("Connecting...", "live");
+    }
+
+    fn refuse(&self, refusal: Refusal) {
+        let (message, status) = refusal.status();
+        self.set_status(&message, status);
+    }
+
+    fn stop(&mut self) {
+        if !self.is_recording() {
+            return;
+        }
+        if let Err(e) = crate::start_cue::play_stop_cue() {
+            app_err!("[service] stop cue error: {}", e);
+        }
+        self.dictation.stop_input(None);
+        if let Ok(mut tx) = self.state.audio_tx.lock() {
+            *tx = None;
+        }
+        self.state.session_active.store(false, Ordering::SeqCst);
+        self.state.hotkey_recording.store(false, Ordering::SeqCst);
+        app_log!("[service] stopped");
+        self.set_recording(false);
+        self.set_status("Ready", "idle");
+        self.end_usage_session();
+    }
+
+    /// Record the finished session and write the totals it added to. The
+    /// window leaves the totals to the service while it runs.
+    fn end_usage_session(&mut self) {
+        let session = self.dictation.end_usage();
+        let alert = self.dictation.close_usage_row(&self.settings, &session);
+        if session.started_ms == 0 || session.bytes_sent == 0 {
+            return;
+        }
+        let totals = self.state.usage.lock().map(|u| u.clone());
+        let providers = self.state.provider_totals.lock().map(|p| p.clone());
+        if let (Ok(totals), Ok(providers)) = (totals, providers) {
+            crate::persist::save_usage(totals, providers);
+        }
+        if let Some(alert) = alert {
+            self.set_status(&alert, "idle");
+        }
+    }
+}
+
+/// Push settings into the state read by the hotkey and session threads.
+/// Snip hotkeys stay off here; the window handles them.
+fn apply_settings(state: &AppState, settings: &Settings) {
+    state.load_commands(settings);
+    crate::plugins::reload(&settings.enabled_plugins);
+    crate::hooks::reload(settings.hooks_enabled);
+    state
+        .session_hotkey_enabled
+        .store(settings.session_hotkey_enabled, Ordering::SeqCst);
+    state
+        .ws_tcp_nodelay
+        .store(settings.ws_tcp_nodelay, Ordering::Relaxed);
+}
+
+/// Entry point for `mangochat --service`. `args` are the flags after
+/// `--service`; only `--mock` and `--profile` apply.
+pub fn run(args: &[String]) {
+    let _guard = match crate::single_instance::acquire(&crate::profile::scoped(SERVICE_ID)) {
+        Some(g) => g,
+        None => {
+            app_log!("[service] already running");
+            return;
+        }
+    };
+    if crate::cli::parse(args).mock {
+        crate::provider::enable_mock();
+    }
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(r) => r,
+        Err(e) => {
+            app_err!("[service] failed to create tokio runtime: {}", e);
+            return;
+        }
+    };
+    let settings = crate::settings::load();
+    let state = Arc::new(AppState::new());
+    apply_settings(&state, &settings);
+    // Nothing is shown while this loads, so there's no reason to defer it.
+    if let Ok(mut usage) = state.usage.lock() {
+        usage.absorb(crate::usage::load_usage());
+    }
+    if let Ok(mut totals) = state.provider_totals.lock() {
+        for (provider, usage) in crate::usage::load_provider_totals() {
+            totals.entry(provider).or_default().absorb(usage);
+        }
+    }
+    state.usage_loaded.store(true, Ordering::SeqCst);
+
+    let (event_tx, event_rx) = std::sync::mpsc::channel::<AppEvent>();
+    let clients = Arc::new(Clients::default());
+    if let Err(e) = listen(clients.clone(), state.clone(), event_tx.clone()) {
+        app_err!("[service] {}", e);
+        return;
+    }
+    crate::persist::start(event_tx.clone());
+    crate::hotkey::start_listener(state.clone(), event_tx.clone());
+    crate::settings::start_file_watcher(event_tx.clone());
+    app_log!("[service] running");
+
+    let mut service = Service {
+        dictation: Dictation::new(state.clone(), event_tx, "service"),
+        state,
+        runtime,
+        clients,
+        settings,
+    };
+    while !service.clients.shutdown.load(Ordering::SeqCst) {
+        match event_rx.recv_timeout(SHUTDOWN_POLL) {
+            Ok(event) => service.handle(event),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    service.stop();
+    if let Ok(path) = endpoint_path() {
+        let _ = std::fs::remove_file(path);
+    }
+    crate::persist::flush();
+    app_log!("[service] exited");
+}
+
+/// Launch `mangochat --service` for this profile, detached from the window.
+fn spawn_service() {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            app_err!("[service] failed to resolve current exe: {}", e);
+            return;
+        }
+    };
+    let mut cmd = std::process::Command::new(exe);
+    cmd.arg("--service");
+    if let Some(name) = crate::profile::name() {
+        cmd.args(["--profile", name]);
+    }
+    if crate::provider::mock_enabled() {
+        cmd.arg("--mock");
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    match cmd.spawn() {
+        Ok(_) => app_log!("[service] started the background service"),
+        Err(e) => app_err!("[service] failed to start the background service: {}", e),
+    }
+}
+
+fn connect() -> Result<TcpStream, String> {
+    let endpoint = read_endpoint()?;
+    let addr = SocketAddr::from(([127, 0, 0, 1], endpoint.port));
+    let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|e| format!("Failed to connect to the service: {}", e))?;
+    write_message(
+        &mut stream,
+        &Request::Hello {
+            token: endpoint.token,
+        },
+    )?;
+    Ok(stream)
+}
+
+/// Ask a service left running from an earlier launch to exit, now that the
+/// setting is off.
+pub fn stop_running() {
+    if let Ok(mut stream) = connect() {
+        match write_message(&mut stream, &Request::Shutdown) {
+            Ok(()) => app_log!("[service] asked the background service to exit"),
+            Err(e) => app_err!("[service] {}", e),
+        }
+    }
+}
+
+/// The window's side of the connection. Commands sent while disconnected are
+/// dropped with a log line; the status shows the service is unavailable.
+pub struct ServiceClient {
+    stream: Arc<Mutex<Option<TcpStream>>>,
+}
+
+impl ServiceClient {
+    /// Connect to the service, starting it if needed, and forward what it
+    /// reports to `event_tx`. Reconnects, and restarts it, if it goes away.
+    pub fn start(state: Arc<AppState>, event_tx: EventSender<AppEvent>) -> Self {
+        let stream = Arc::new(Mutex::new(None));
+        let slot = stream.clone();
+        std::thread::spawn(move || {
+            let mut failures = 0u32;
+            loop {
+                match connect() {
+                    Ok(stream) => {
+                        failures = 0;
+                        app_log!("[service] connected to the background service");
+                        if let Err(e) = pump(&stream, &slot, &state, &event_tx) {
+                            app_err!("[service] {}", e);
+                        }
+                        if let Ok(mut slot) = slot.lock() {
+                            *slot = None;
+                        }
+                        let _ = stream.shutdown(Shutdown::Both);
+                        let _ = event_tx.send(AppEvent::ServiceRecording(false));
+                        let _ = event_tx.send(AppEvent::StatusUpdate {
+                            status: "error".into(),
+                            message: "Dictation service stopped, restarting".into(),
+                        });
+                    }
+                    Err(e) => {
+                        if failures % RESPAWN_EVERY == 0 {
+                            app_log!("[service] {}", e);
+                            spawn_service();
+                        }
+                        failures = failures.wrapping_add(1);
+                    }
+                }
+                std::thread::sleep(RECONNECT_INTERVAL);
+            }
+        });
+        Self { stream }
+    }
+
+    pub fn set_recording(&self, start: bool) {
+        self.send(&if start { Request::Start } else { Request::Stop });
+    }
+
+    /// Have the service hand the next final back as a `SnipQuestion`.
+    pub fn snip_attached(&self) {
+        self.send(&Request::SnipAttached);
+    }
+
+    fn send(&self, request: &Request) {
+        let Ok(mut slot) = self.stream.lock() else {
+            return;
+        };
+        let result = match slot.as_mut() {
+            Some(stream) => write_message(stream, request),
+            None => Err("The background service isn't connected".into()),
+        };
+        if let Err(e) = result {
+            app_err!("[service] {:?} not sent: {}", request, e);
+        }
+    }
+}
+
+/// Relay notices from a connected service until it hangs up.
+fn pump(
+    stream: &TcpStream,
+    slot: &Mutex<Option<TcpStream>>,
+    state: &AppState,
+    event_tx: &EventSender<AppEvent>,
+) -> Result<(), String> {
+    let socket_err = |e: std::io::Error| format!("Service socket error: {}", e);
+    if let Ok(mut slot) = slot.lock() {
+        *slot = Some(stream.try_clone().map_err(socket_err)?);
+    }
+    let mut reader = BufReader::new(stream.try_clone().map_err(socket_err)?);
+    while let Some(notice) = read_message::<Notice>(&mut reader)? {
+        let event = match notice {
+            Notice::Recording { active } => AppEvent::ServiceRecording(active),
+            Notice::Status { status, message } => AppEvent::StatusUpdate { status, message },
+            Notice::Delta { text } => AppEvent::TranscriptDelta(text),
+            Notice::Final { text } => {
+                // The service's state holds its copy; keep one for the copy actions here.
+                state.remember_transcript(&text);
+                AppEvent::TranscriptFinal(text)
+            }
+            Notice::SnipPreset { name } => AppEvent::SnipPreset(name),
+            Notice::SnipQuestion { text } => AppEvent::SnipQuestion(text),
+            Notice::VoiceNoteSaved => AppEvent::VoiceNoteSaved,
+        };
+        if event_tx.send(event).is_err() {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::mpsc::Receiver;
+
+    #[test]
+    fn messages_match_the_documented_wire_format() {
+        let hello: Request = serde_json::from_str(r#"{"cmd": "hello", "token": "abc"}"#).unwrap();
+        assert!(matches!(hello, Request::Hello { token } if token == "abc"));
+        assert!(matches!(
+            serde_json::from_str(r#"{"cmd": "snip_attached"}"#),
+            Ok(Request::SnipAttached)
+        ));
+        assert!(serde_json::from_str::<Request>(r#"{"cmd": "format"}"#).is_err());
+
+        let json = |notice: &Notice| serde_json::to_string(notice).unwrap();
+        assert_eq!(
+            json(&Notice::Recording { active: true }),
+            r#"{"event":"recording","active":true}"#
+        );
+        assert_eq!(
+            json(&Notice::SnipPreset { name: "top".into() }),
+            r#"{"event":"snip_preset","name":"top"}"#
+        );
+        assert_eq!(
+            json(&Notice::SnipQuestion {
+                text: "what is this".into()
+            }),
+            r#"{"event":"snip_question","text":"what is this"}"#
+        );
+        assert_eq!(
+            json(&Notice::VoiceNoteSaved),
+            r#"{"event":"voice_note_saved"}"#
+        );
+    }
+
+    #[test]
+    fn read_message_reads_one_line_at_a_time() {
+        let mut reader = Cursor::new(b"{\"cmd\":\"start\"}\n{\"cmd\":\"stop\"}\n".to_vec());
+        assert!(matches!(
+            read_message(&mut reader),
+            Ok(Some(Request::Start))
+        ));
+        assert!(matches!(read_message(&mut reader), Ok(Some(Request::Stop))));
+        assert!(matches!(read_message::<Request>(&mut reader), Ok(None)));
+
+        let long = "a".repeat(MAX_MESSAGE_BYTES as usize);
+        let line = format!("{{\"cmd\":\"hello\",\"token\":\"{}\"}}\n", long);
+        assert!(read_message::<Request>(&mut Cursor::new(line.into_bytes())).is_err());
+    }
+
+    struct Served {
+        client: TcpStream,
+        reader: BufReader<TcpStream>,
+        clients: Arc<Clients>,
+        state: Arc<AppState>,
+        events: Receiver<AppEvent>,
+        server: std::thread::JoinHandle<Result<(), String>>,
+    }
+
+    /// Serve one connection with token "secret" on a loopback port.
+    fn serve_one() -> Served {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let clients = Arc::new(Clients::default());
+        let state = Arc::new(AppState::new());
+        let (event_tx, events) = std::sync::mpsc::channel();
+        let server = {
+            let (clients, state) = (clients.clone(), state.clone());
+            std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                serve_client(stream, "secret", &clients, &state, &event_tx)
+            })
+        };
+        let client = TcpStream::connect(addr).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let reader = BufReader::new(client.try_clone().unwrap());
+        Served {
+            client,
+            reader,
+            clients,
+            state,
+            events,
+            server,
+        }
+    }
+
+    #[test]
+    fn clients_without_the_token_are_rejected() {
+        let hellos = [
+            Request::Hello {
+                token: "guess".into(),
+            },
+            Request::Start,
+        ];
+        for hello in hellos {
+            let mut served = serve_one();
+            write_message(&mut served.client, &hello).unwrap();
+            assert!(served.server.join().unwrap().is_err());
+            assert!(served.events.try_recv().is_err());
+            assert!(served.clients.streams.lock().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn an_authenticated_client_drives_the_service() {
+        let mut served = serve_one();
+        let hello = Request::Hello {
+            token: "secret".into(),
+        };
+        write_message(&mut served.client, &hello).unwrap();
+        assert!(matches!(
+            read_message(&mut served.reader),
+            Ok(Some(Notice::Recording { active: false }))
+        ));
+
+        let next_event =
+            |served: &Served| served.events.recv_timeout(Duration::from_secs(5)).unwrap();
+        write_message(&mut served.client, &Request::Start).unwrap();
+        assert!(matches!(next_event(&served), AppEvent::SetRecording(true)));
+        write_message(&mut served.client, &Request::SnipAttached).unwrap();
+        write_message(&mut served.client, &Request::Toggle).unwrap();
+        assert!(matches!(next_event(&served), AppEvent::TrayToggleRecording));
+        assert!(served.state.snip_in_window.load(Ordering::SeqCst));
+
+        served.clients.broadcast(&Notice::SnipPreset {
+            name: "chart".into(),
+        });
+        assert!(matches!(
+            read_message(&mut served.reader),
+            Ok(Some(Notice::SnipPreset { name })) if name == "chart"
+        ));
+
+        write_message(&mut served.client, &Request::Shutdown).unwrap();
+        assert!(served.server.join().unwrap().is_ok());
+        assert!(served.clients.shutdown.load(Ordering::SeqCst));
+    }
+}
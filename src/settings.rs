@@ -1,18 +1,26 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Version written to settings.json. Bump it and append to `MIGRATIONS`
+/// whenever a field is renamed or restructured.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// `api_keys` entry holding the obs-websocket password, so it is stored with
+/// the provider keys in the secrets file instead of in settings.json.
+pub const OBS_PASSWORD_KEY: &str = "obs";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
+    /// Files written before versioning have no field and load as 0.
+    #[serde(default)]
+    pub schema_version: u32,
     #[serde(default = "default_provider")]
     pub provider: String,
     /// Per-provider API keys: {"openai": "sk-...", "deepgram": "dg-...", ...}
     #[serde(default, skip_serializing)]
     pub api_keys: HashMap<String, String>,
-    /// Legacy single key - migrated to api_keys on load, not saved.
-    #[serde(default, skip_serializing)]
-    api_key: String,
     #[serde(default = "default_model")]
     pub model: String,
     #[serde(default = "default_transcription_model")]
@@ -34,27 +42,104 @@ pub struct Settings {
     #[serde(default = "default_start_cue")]
     pub start_cue: String,
     #[serde(default = "default_theme")]
-    pub theme: String, // dark only
+    pub theme: String, // dark | light | auto
     #[serde(default = "default_text_size")]
-    pub text_size: String, // small | medium | large
+    pub text_size: String, // small | medium | large | custom
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32, // zoom factor used when text_size = custom
     #[serde(default = "default_accent_color")]
-    pub accent_color: String, // green | purple | blue | orange | pink
+    pub accent_color: String, // green | purple | blue | orange | pink | #rrggbb
     #[serde(default)]
     pub compact_background_enabled: bool,
     #[serde(default)]
     pub auto_minimize: bool,
+    /// Replace the compact window with a ~90px mic dot + visualizer pill.
+    #[serde(default)]
+    pub pill_mode: bool,
+    #[serde(default = "default_compact_opacity")]
+    pub compact_opacity: u8, // compact background opacity, percent 20-100
+    /// Spectrum bars over the visualizer; off skips the FFT entirely.
+    #[serde(default = "default_true")]
+    pub visualizer_enabled: bool,
+    #[serde(default = "default_visualizer_bands")]
+    pub visualizer_bands: u32, // FFT bands, 8-100
+    #[serde(default = "default_visualizer_fps")]
+    pub visualizer_fps: u32, // spectrum updates and redraws per second, 5-60
+    /// Slide the compact window off its anchored edge until hovered or recording.
+    #[serde(default)]
+    pub edge_dock: bool,
+    /// Hide the compact window while another app is fullscreen.
+    #[serde(default)]
+    pub hide_when_fullscreen: bool,
+    /// Let clicks pass through the compact window; Scroll Lock toggles it.
+    #[serde(default)]
+    pub click_through: bool,
+    /// Opt-in Windows toasts for errors, auto-stops and available updates.
+    #[serde(default)]
+    pub toast_notifications: bool,
+    /// Run hotkeys, audio and provider sessions in `mangochat --service`
+    /// (service.rs) and use the window as its client. Read at launch.
+    #[serde(default)]
+    pub background_service: bool,
     #[serde(default)]
     pub update_feed_url_override: String,
+    /// "stable", "beta" or "nightly"; see `updater::UPDATE_CHANNELS`.
+    #[serde(default = "default_update_channel")]
+    pub update_channel: String,
+    /// Require a valid Authenticode signature on downloaded installers.
+    #[serde(default = "default_true")]
+    pub update_verify_signature: bool,
+    /// Download updates in the background and install them silently on quit.
+    #[serde(default)]
+    pub update_install_on_exit: bool,
+    /// Optional file of `provider=key` lines read at startup. Keys from it
+    /// (and from MANGOCHAT_<PROVIDER>_KEY env vars) win over stored keys.
+    #[serde(default)]
+    pub api_key_file: String,
+    /// Where each externally sourced key came from, e.g. "env MANGOCHAT_OPENAI_KEY".
+    /// Those keys are never written to the secure store.
+    #[serde(skip)]
+    pub external_key_sources: HashMap<String, String>,
     #[serde(default = "default_window_monitor_mode")]
     pub window_monitor_mode: String, // follow_cursor | fixed
     #[serde(default)]
     pub window_monitor_id: String, // Win32 monitor device id (e.g. \\.\DISPLAY1) when mode=fixed
     #[serde(default = "default_window_anchor")]
-    pub window_anchor: String, // top_left | top_center | top_right | bottom_left | bottom_center | bottom_right
+    pub window_anchor: String, // top_left | top_center | top_right | bottom_left | bottom_center | bottom_right | custom
+    /// Dragged compact-window positions per monitor id, as physical offsets
+    /// from that monitor's work-area origin. Used when window_anchor = custom.
+    #[serde(default)]
+    pub window_positions: HashMap<String, [i32; 2]>,
     #[serde(default)]
     pub snip_editor_path: String,
     #[serde(default = "default_snip_edit_revert")]
     pub snip_edit_revert: String, // stay | image | path
+    #[serde(default = "default_snip_format")]
+    pub snip_format: String, // jpeg | png | webp
+    #[serde(default = "default_snip_quality")]
+    pub snip_quality: u8, // JPEG quality 50-100
+    #[serde(default)]
+    pub snip_max_dimension: u32, // longest edge in px; 0 = no downscale
+    #[serde(default)]
+    pub snip_preview_max_dimension: u32, // overlay background; 0 = full resolution
+    #[serde(default = "default_snip_memory_cap_mb")]
+    pub snip_memory_cap_mb: u32,
+    #[serde(default)]
+    pub snip_record_audio: bool,
+    #[serde(default = "default_true")]
+    pub snip_magnifier: bool, // crosshair guides and pixel loupe in the overlay
+    /// The last snip rectangle, captured again by Ctrl + Print Screen.
+    #[serde(default)]
+    pub snip_last_region: Option<SnipRegion>,
+    /// Named snip rectangles, captured by voice ("snip <name>") or hotkey.
+    #[serde(default)]
+    pub snip_region_presets: Vec<RegionPreset>,
+    #[serde(default = "default_vision_endpoint")]
+    pub vision_endpoint: String, // OpenAI-compatible chat completions URL
+    #[serde(default = "default_vision_model")]
+    pub vision_model: String,
+    #[serde(default = "default_vision_prompt")]
+    pub vision_prompt: String, // describe | table | text
     #[serde(default = "default_browser")]
     pub default_browser: String, // chrome | edge | firefox
     #[serde(default = "default_chrome_path")]
@@ -65,12 +150,133 @@ pub struct Settings {
     pub provider_inactivity_timeout_secs: u64,
     #[serde(default = "default_max_session_length_minutes")]
     pub max_session_length_minutes: u64,
+    /// Silence sent before each commit, in ms, per provider id. Missing = the
+    /// provider's own default.
+    #[serde(default)]
+    pub pre_commit_silence_ms: HashMap<String, u32>,
+    /// How long to wait for a final after a commit before flushing locally, in
+    /// ms, per provider id. Missing = the provider's own default.
+    #[serde(default)]
+    pub commit_flush_timeout_ms: HashMap<String, u32>,
+    /// Send audio frames as soon as they're written (TCP_NODELAY) instead of
+    /// letting the OS batch small packets.
+    #[serde(default = "default_true")]
+    pub ws_tcp_nodelay: bool,
     #[serde(default = "default_url_commands")]
     pub url_commands: Vec<UrlCommand>,
     #[serde(default = "default_alias_commands")]
     pub alias_commands: Vec<AliasCommand>,
     #[serde(default = "default_app_shortcuts")]
     pub app_shortcuts: Vec<AppShortcut>,
+    #[serde(default)]
+    pub script_commands: Vec<ScriptCommand>,
+    /// File names of `.wasm` plugins in the plugins folder that are turned on.
+    #[serde(default)]
+    pub enabled_plugins: Vec<String>,
+    /// Run `on_final` / `on_command` from the Rhai hooks script.
+    #[serde(default)]
+    pub hooks_enabled: bool,
+    #[serde(default)]
+    pub app_overrides: Vec<AppOverride>,
+    /// Monthly spend limit in USD per provider id. Missing or 0 = no limit.
+    #[serde(default)]
+    pub monthly_budgets: HashMap<String, f64>,
+    /// Refuse new sessions on a provider once its monthly budget is spent.
+    #[serde(default)]
+    pub budget_block_at_limit: bool,
+    /// Session history older than this many days is pruned at startup. 0 = keep forever.
+    #[serde(default = "default_session_retention_days")]
+    pub session_retention_days: u32,
+    /// error | warn | info | debug | trace; changeable without a restart.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Debug overlay with frame times and queue depths (Left Ctrl + Shift + F12).
+    #[serde(default)]
+    pub perf_overlay: bool,
+    /// Write the session log as JSON lines for log shippers.
+    #[serde(default)]
+    pub log_json: bool,
+    /// Rotated session logs kept at startup: count, total size and age. 0 = no limit.
+    #[serde(default = "default_log_max_files")]
+    pub log_max_files: u32,
+    #[serde(default = "default_log_max_total_mb")]
+    pub log_max_total_mb: u32,
+    #[serde(default = "default_log_max_age_days")]
+    pub log_max_age_days: u32,
+    /// Keep at most this many sessions in history. 0 = no limit.
+    #[serde(default = "default_session_retention_max")]
+    pub session_retention_max: u32,
+    /// Keep final transcripts in the local usage database for later export.
+    #[serde(default)]
+    pub transcript_archive_enabled: bool,
+    /// Serve Prometheus metrics on 127.0.0.1:`metrics_port`/metrics.
+    #[serde(default)]
+    pub metrics_enabled: bool,
+    #[serde(default = "default_metrics_port")]
+    pub metrics_port: u16,
+    /// Serve MCP tools on 127.0.0.1:`mcp_port`/mcp for AI assistants.
+    #[serde(default)]
+    pub mcp_enabled: bool,
+    #[serde(default = "default_mcp_port")]
+    pub mcp_port: u16,
+    /// Mirror transcripts into OBS over obs-websocket.
+    #[serde(default)]
+    pub obs_enabled: bool,
+    #[serde(default = "default_obs_host")]
+    pub obs_host: String,
+    #[serde(default = "default_obs_port")]
+    pub obs_port: u16,
+    /// Text source that shows the live caption; empty = none.
+    #[serde(default)]
+    pub obs_text_source: String,
+    #[serde(default = "default_true")]
+    pub obs_stream_captions: bool,
+    /// WebSocket endpoint for the Stream Deck plugin on 127.0.0.1:`streamdeck_port`.
+    #[serde(default)]
+    pub streamdeck_enabled: bool,
+    #[serde(default = "default_streamdeck_port")]
+    pub streamdeck_port: u16,
+    /// gRPC transcript stream (proto/mangochat.proto) on 127.0.0.1:`grpc_port`.
+    #[serde(default)]
+    pub grpc_enabled: bool,
+    #[serde(default = "default_grpc_port")]
+    pub grpc_port: u16,
+    /// Serve the phone microphone page to the LAN on `remote_mic_port`.
+    #[serde(default)]
+    pub remote_mic_enabled: bool,
+    #[serde(default = "default_remote_mic_port")]
+    pub remote_mic_port: u16,
+    /// Listen for a wake word while idle and start dictation when it's heard.
+    /// The Picovoice access key lives in `api_keys` under "picovoice".
+    #[serde(default)]
+    pub wake_word_enabled: bool,
+    /// The user's own Porcupine library (libpv_porcupine.dll/.so/.dylib) and
+    /// model file (porcupine_params.pv) from the Picovoice download.
+    #[serde(default)]
+    pub wake_word_library_path: String,
+    #[serde(default)]
+    pub wake_word_model_path: String,
+    /// Porcupine keyword file (.ppn), e.g. a trained "hey mango".
+    #[serde(default)]
+    pub wake_word_keyword_path: String,
+    #[serde(default = "default_wake_word_sensitivity")]
+    pub wake_word_sensitivity: f32, // 0.0-1.0; higher = fewer misses, more false triggers
+    /// Where meeting transcripts are written; empty = Documents\MangoChat\Meetings.
+    #[serde(default)]
+    pub meeting_folder: String,
+    /// Also transcribe system audio (the other side of a call) in meeting mode.
+    #[serde(default = "default_true")]
+    pub meeting_system_audio: bool,
+    /// Save each utterance as a WAV + JSON voice note instead of typing it.
+    #[serde(default)]
+    pub voice_notes_mode: bool,
+    /// Where voice notes are saved; empty = Documents\MangoChat\Notes.
+    #[serde(default)]
+    pub voice_notes_folder: String,
+    /// Fields this build doesn't know about (e.g. written by a newer version),
+    /// kept so they survive a save instead of being dropped.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -95,6 +301,60 @@ pub struct AppShortcut {
     pub builtin: bool,
 }
 
+/// Voice command that runs an .ahk/.ps1 (or other) script.
+/// Any words spoken after the trigger are passed as a single argument.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ScriptCommand {
+    pub trigger: String,
+    pub path: String,
+}
+
+/// A snip rectangle in capture pixels on one monitor, which is identified
+/// by its top-left corner as reported when the snip was taken.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SnipRegion {
+    pub monitor_x: i32,
+    pub monitor_y: i32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A named snip rectangle, captured without the overlay by saying
+/// "snip <name>" or by its Ctrl + Shift + digit hotkey.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RegionPreset {
+    pub name: String,
+    pub region: SnipRegion,
+    /// Number-row digit for the hotkey, 1-9; 0 for none.
+    #[serde(default)]
+    pub hotkey: u8,
+}
+
+/// Dictation settings applied while a given app is in the foreground.
+/// Empty fields fall back to the global setting.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AppOverride {
+    /// Executable name, e.g. "slack" or "WindowsTerminal.exe".
+    pub process: String,
+    pub provider: String,
+    pub language: String,
+    pub vad_mode: String,
+    /// "type" (simulated keystrokes) or "paste" (clipboard + Ctrl+V).
+    pub output: String,
+}
+
+impl AppOverride {
+    pub fn matches(&self, process: &str) -> bool {
+        let want = self.process.trim();
+        let want = want.strip_suffix(".exe").unwrap_or(want);
+        let have = process.strip_suffix(".exe").unwrap_or(process);
+        !want.is_empty() && want.eq_ignore_ascii_case(have)
+    }
+}
+
 impl Settings {
     /// Get the API key for a given provider.
     pub fn api_key_for(&self, provider: &str) -> &str {
@@ -106,7 +366,9 @@ impl Settings {
 
     /// True when at least one provider key is configured.
     pub fn has_any_api_key(&self) -> bool {
-        self.api_keys.values().any(|k| !k.trim().is_empty())
+        crate::provider::PROVIDER_ROWS
+            .iter()
+            .any(|(id, _)| !self.api_key_for(id).trim().is_empty())
     }
 
     /// Return the browser executable path based on the selected default browser.
@@ -120,6 +382,36 @@ impl Settings {
         }
     }
 
+    /// First override whose process name matches the foreground app.
+    pub fn override_for(&self, process: &str) -> Option<&AppOverride> {
+        self.app_overrides.iter().find(|o| o.matches(process))
+    }
+
+    /// Provider, language and VAD mode for a session under `app_override`.
+    /// Empty override fields, and an override provider without a key, fall
+    /// back to the global settings.
+    pub fn session_options(&self, app_override: &AppOverride) -> (String, String, String) {
+        let provider = if !app_override.provider.is_empty()
+            && !self.api_key_for(&app_override.provider).trim().is_empty()
+        {
+            app_override.provider.clone()
+        } else {
+            self.provider.clone()
+        };
+        let or_global = |value: &String, global: &String| {
+            if value.is_empty() {
+                global.clone()
+            } else {
+                value.clone()
+            }
+        };
+        (
+            provider,
+            or_global(&app_override.language, &self.language),
+            or_global(&app_override.vad_mode, &self.vad_mode),
+        )
+    }
+
     /// Set the API key for a given provider.
     pub fn set_api_key(&mut self, provider: &str, key: String) {
         if key.is_empty() {
@@ -161,9 +453,9 @@ impl Settings {
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             provider: default_provider(),
             api_keys: HashMap::new(),
-            api_key: String::new(),
             model: default_model(),
             transcription_model: default_transcription_model(),
             language: default_language(),
@@ -176,23 +468,95 @@ impl Default for Settings {
             start_cue: default_start_cue(),
             theme: default_theme(),
             text_size: default_text_size(),
+            ui_scale: default_ui_scale(),
             accent_color: default_accent_color(),
             compact_background_enabled: true,
             auto_minimize: false,
+            pill_mode: false,
+            compact_opacity: default_compact_opacity(),
+            visualizer_enabled: true,
+            visualizer_bands: default_visualizer_bands(),
+            visualizer_fps: default_visualizer_fps(),
+            click_through: false,
+            hide_when_fullscreen: false,
+            edge_dock: false,
+            toast_notifications: false,
+            background_service: false,
             update_feed_url_override: String::new(),
+            update_channel: default_update_channel(),
+            update_verify_signature: true,
+            update_install_on_exit: false,
+            api_key_file: String::new(),
+            external_key_sources: HashMap::new(),
             window_monitor_mode: default_window_monitor_mode(),
             window_monitor_id: String::new(),
             window_anchor: default_window_anchor(),
+            window_positions: HashMap::new(),
             snip_editor_path: String::new(),
             snip_edit_revert: default_snip_edit_revert(),
+            snip_format: default_snip_format(),
+            snip_quality: default_snip_quality(),
+            snip_max_dimension: 0,
+            snip_preview_max_dimension: 0,
+            snip_memory_cap_mb: default_snip_memory_cap_mb(),
+            snip_record_audio: false,
+            snip_magnifier: true,
+            snip_last_region: None,
+            snip_region_presets: Vec::new(),
+            vision_endpoint: default_vision_endpoint(),
+            vision_model: default_vision_model(),
+            vision_prompt: default_vision_prompt(),
             default_browser: default_browser(),
             chrome_path: default_chrome_path(),
             paint_path: default_paint_path(),
             provider_inactivity_timeout_secs: default_provider_inactivity_timeout_secs(),
             max_session_length_minutes: default_max_session_length_minutes(),
+            pre_commit_silence_ms: HashMap::new(),
+            commit_flush_timeout_ms: HashMap::new(),
+            ws_tcp_nodelay: true,
             url_commands: default_url_commands(),
             alias_commands: default_alias_commands(),
             app_shortcuts: default_app_shortcuts(),
+            script_commands: Vec::new(),
+            enabled_plugins: Vec::new(),
+            hooks_enabled: false,
+            app_overrides: Vec::new(),
+            monthly_budgets: HashMap::new(),
+            budget_block_at_limit: false,
+            session_retention_days: default_session_retention_days(),
+            session_retention_max: default_session_retention_max(),
+            log_level: default_log_level(),
+            log_json: false,
+            perf_overlay: false,
+            log_max_files: default_log_max_files(),
+            log_max_total_mb: default_log_max_total_mb(),
+            log_max_age_days: default_log_max_age_days(),
+            transcript_archive_enabled: false,
+            metrics_enabled: false,
+            metrics_port: default_metrics_port(),
+            mcp_enabled: false,
+            mcp_port: default_mcp_port(),
+            obs_enabled: false,
+            obs_host: default_obs_host(),
+            obs_port: default_obs_port(),
+            obs_text_source: String::new(),
+            obs_stream_captions: true,
+            streamdeck_enabled: false,
+            streamdeck_port: default_streamdeck_port(),
+            grpc_enabled: false,
+            grpc_port: default_grpc_port(),
+            remote_mic_enabled: false,
+            remote_mic_port: default_remote_mic_port(),
+            wake_word_enabled: false,
+            wake_word_library_path: String::new(),
+            wake_word_model_path: String::new(),
+            wake_word_keyword_path: String::new(),
+            wake_word_sensitivity: default_wake_word_sensitivity(),
+            meeting_folder: String::new(),
+            meeting_system_audio: true,
+            voice_notes_mode: false,
+            voice_notes_folder: String::new(),
+            extra: serde_json::Map::new(),
         }
     }
 }
@@ -222,12 +586,60 @@ fn default_start_cue() -> String {
 fn default_screenshot_retention_count() -> u32 {
     10
 }
+fn default_session_retention_days() -> u32 {
+    365
+}
+fn default_session_retention_max() -> u32 {
+    10_000
+}
+fn default_update_channel() -> String {
+    "stable".into()
+}
+fn default_log_level() -> String {
+    "info".into()
+}
+fn default_log_max_files() -> u32 {
+    10
+}
+fn default_log_max_total_mb() -> u32 {
+    50
+}
+fn default_log_max_age_days() -> u32 {
+    30
+}
+fn default_metrics_port() -> u16 {
+    9464
+}
+pub fn default_mcp_port() -> u16 {
+    9465
+}
+fn default_obs_host() -> String {
+    "127.0.0.1".into()
+}
+fn default_obs_port() -> u16 {
+    4455
+}
+fn default_streamdeck_port() -> u16 {
+    9466
+}
+fn default_grpc_port() -> u16 {
+    9467
+}
+fn default_remote_mic_port() -> u16 {
+    9468
+}
+fn default_wake_word_sensitivity() -> f32 {
+    0.5
+}
 fn default_theme() -> String {
     "dark".into()
 }
 fn default_text_size() -> String {
     "medium".into()
 }
+fn default_ui_scale() -> f32 {
+    1.0
+}
 fn default_accent_color() -> String {
     "orange".into()
 }
@@ -240,6 +652,33 @@ fn default_window_anchor() -> String {
 fn default_snip_edit_revert() -> String {
     "stay".into()
 }
+fn default_snip_format() -> String {
+    "jpeg".into()
+}
+fn default_snip_memory_cap_mb() -> u32 {
+    512
+}
+fn default_snip_quality() -> u8 {
+    90
+}
+fn default_visualizer_bands() -> u32 {
+    50
+}
+fn default_visualizer_fps() -> u32 {
+    30
+}
+fn default_compact_opacity() -> u8 {
+    100
+}
+fn default_vision_endpoint() -> String {
+    "https://api.openai.com/v1/chat/completions".into()
+}
+fn default_vision_model() -> String {
+    "gpt-4o-mini".into()
+}
+fn default_vision_prompt() -> String {
+    "describe".into()
+}
 fn default_browser() -> String {
     "chrome".into()
 }
@@ -315,20 +754,197 @@ fn default_app_shortcuts() -> Vec<AppShortcut> {
 }
 
 pub fn settings_path() -> Result<PathBuf, String> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Ok(dir.join("MangoChat").join("settings.json"));
-    }
-    if let Some(home) = dirs::home_dir() {
-        return Ok(home.join(".mangochat").join("settings.json"));
-    }
-    Err("Failed to resolve data directory".into())
+    crate::profile::data_dir()
+        .map(|d| d.join("settings.json"))
+        .ok_or_else(|| "Failed to resolve data directory".into())
 }
 
 fn legacy_settings_path() -> Result<PathBuf, String> {
     Err("Legacy settings path disabled".into())
 }
 
+/// False on first run, before any settings file has been written.
+pub fn settings_file_exists() -> bool {
+    settings_path().map(|p| p.exists()).unwrap_or(false)
+        || legacy_settings_path().map(|p| p.exists()).unwrap_or(false)
+}
+
 pub fn load() -> Settings {
+    let mut settings = load_stored();
+    apply_external_keys(&mut settings);
+    apply_run_overrides(&mut settings);
+    settings
+}
+
+/// Values from command-line flags that replace settings for this run only.
+#[derive(Debug, Default)]
+pub struct RunOverrides {
+    pub provider: Option<String>,
+    pub language: Option<String>,
+    pub monitor_id: Option<String>,
+}
+
+static RUN_OVERRIDES: std::sync::OnceLock<RunOverrides> = std::sync::OnceLock::new();
+
+/// Install command-line overrides. Call once, before the first `load`.
+pub fn set_run_overrides(overrides: RunOverrides) {
+    let _ = RUN_OVERRIDES.set(overrides);
+}
+
+fn apply_run_overrides(settings: &mut Settings) {
+    let Some(o) = RUN_OVERRIDES.get() else {
+        return;
+    };
+    if let Some(provider) = &o.provider {
+        settings.provider = provider.clone();
+    }
+    if let Some(language) = &o.language {
+        settings.language = language.clone();
+    }
+    if let Some(id) = &o.monitor_id {
+        settings.window_monitor_mode = "fixed".into();
+        settings.window_monitor_id = id.clone();
+    }
+}
+
+/// Put the on-disk values back for fields still holding a run override, so
+/// saving during an overridden run doesn't make the override permanent.
+fn restore_overridden_fields(settings: &mut Settings) {
+    let Some(o) = RUN_OVERRIDES.get() else {
+        return;
+    };
+    let stored = read_stored_file().unwrap_or_default();
+    if o.provider.as_ref() == Some(&settings.provider) {
+        settings.provider = stored.provider;
+    }
+    if o.language.as_ref() == Some(&settings.language) {
+        settings.language = stored.language;
+    }
+    if o.monitor_id.as_ref() == Some(&settings.window_monitor_id)
+        && settings.window_monitor_mode == "fixed"
+    {
+        settings.window_monitor_mode = stored.window_monitor_mode;
+        settings.window_monitor_id = stored.window_monitor_id;
+    }
+}
+
+/// Parse settings.json as-is: no key resolution and no writes.
+fn read_stored_file() -> Option<Settings> {
+    let text = fs::read_to_string(settings_path().ok()?).ok()?;
+    parse_settings(&text).ok().map(|(s, _)| s)
+}
+
+/// Re-read settings after an external edit. Unlike `load`, a file that
+/// doesn't parse (e.g. caught mid-write) is an error rather than defaults.
+pub fn reload() -> Result<Settings, String> {
+    let path = settings_path()?;
+    let text =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read settings: {}", e))?;
+    parse_settings(&text)?;
+    Ok(load())
+}
+
+/// How often the settings file's modified time is polled.
+const WATCH_INTERVAL_MS: u64 = 1000;
+
+/// Poll settings.json and send `SettingsFileChanged` when it is rewritten.
+/// Our own saves trigger it too; the UI ignores reloads that change nothing.
+pub fn start_file_watcher(event_tx: std::sync::mpsc::Sender<crate::state::AppEvent>) {
+    let Ok(path) = settings_path() else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+        let mut last = modified(&path);
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(WATCH_INTERVAL_MS));
+            let now = modified(&path);
+            if now.is_some() && now != last {
+                last = now;
+                if event_tx
+                    .send(crate::state::AppEvent::SettingsFileChanged)
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    });
+}
+
+fn key_env_var(provider: &str) -> String {
+    format!("MANGOCHAT_{}_KEY", provider.to_ascii_uppercase())
+}
+
+/// Overlay keys from the key file and then env vars onto the stored ones.
+fn apply_external_keys(settings: &mut Settings) {
+    settings.external_key_sources.clear();
+    let key_file = std::env::var("MANGOCHAT_KEY_FILE")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| settings.api_key_file.trim().to_string());
+    if !key_file.is_empty() {
+        match read_key_file(Path::new(&key_file)) {
+            Ok(keys) => {
+                for (provider, key) in keys {
+                    settings.api_keys.insert(provider.clone(), key);
+                    settings
+                        .external_key_sources
+                        .insert(provider, format!("file {}", key_file));
+                }
+            }
+            Err(e) => app_err!("[settings] {}", e),
+        }
+    }
+    for (provider, _) in crate::provider::PROVIDER_ROWS {
+        let var = key_env_var(provider);
+        if let Ok(key) = std::env::var(&var) {
+            let key = key.trim().to_string();
+            if !key.is_empty() {
+                settings.api_keys.insert(provider.to_string(), key);
+                settings
+                    .external_key_sources
+                    .insert(provider.to_string(), format!("env {}", var));
+            }
+        }
+    }
+    for (provider, source) in &settings.external_key_sources {
+        app_log!("[settings] {} key from {}", provider, source);
+    }
+}
+
+/// Parse `provider=key` lines. `#` starts a comment; the env var names
+/// (MANGOCHAT_OPENAI_KEY=...) are accepted as provider names too.
+fn read_key_file(path: &Path) -> Result<HashMap<String, String>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read key file {}: {}", path.display(), e))?;
+    let mut keys = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, key)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        let key = key.trim().trim_matches('"');
+        let provider = crate::provider::PROVIDER_ROWS
+            .iter()
+            .map(|(id, _)| *id)
+            .find(|p| name.eq_ignore_ascii_case(p) || name.eq_ignore_ascii_case(&key_env_var(p)));
+        match provider {
+            Some(p) if !key.is_empty() => {
+                keys.insert(p.to_string(), key.to_string());
+            }
+            Some(_) => {}
+            None => app_log!("[settings] key file: unknown provider \"{}\"", name),
+        }
+    }
+    Ok(keys)
+}
+
+fn load_stored() -> Settings {
     let path = match settings_path() {
         Ok(p) => p,
         Err(_) => return Settings::default(),
@@ -341,21 +957,26 @@ pub fn load() -> Settings {
             Err(_) => return Settings::default(),
         }
     };
-    let mut settings: Settings = match fs::read_to_string(&read_path) {
-        Ok(text) => serde_json::from_str(&text).unwrap_or_default(),
+    let text = match fs::read_to_string(&read_path) {
+        Ok(text) => text,
         Err(_) => return Settings::default(),
     };
-
-    let had_plaintext_keys = !settings.api_keys.is_empty() || !settings.api_key.is_empty();
-
-    // Migrate legacy single api_key to per-provider map.
-    if !settings.api_key.is_empty() && !settings.api_keys.contains_key("openai") {
-        settings
-            .api_keys
-            .insert("openai".into(), settings.api_key.clone());
-        settings.api_key.clear();
+    let (mut settings, from_version) = parse_settings(&text).unwrap_or_else(|e| {
+        app_err!("[settings] {}", e);
+        (Settings::default(), SCHEMA_VERSION)
+    });
+    if from_version < SCHEMA_VERSION {
+        backup_before_migration(&read_path, from_version);
+    } else if from_version > SCHEMA_VERSION {
+        app_log!(
+            "[settings] file is schema v{} (this build knows v{}); unknown fields kept",
+            from_version,
+            SCHEMA_VERSION
+        );
     }
 
+    let had_plaintext_keys = !settings.api_keys.is_empty();
+
     let mut resolved_api_keys = settings.api_keys.clone();
     match crate::secrets::load_api_keys() {
         Ok(secure_keys) => {
@@ -372,7 +993,6 @@ pub fn load() -> Settings {
         match crate::secrets::save_api_keys(&resolved_api_keys) {
             Ok(()) => {
                 settings.api_keys.clear();
-                settings.api_key.clear();
                 let _ = save_settings_without_api_keys(&settings);
             }
             Err(e) => app_err!("[settings] secure key migration failed: {}", e),
@@ -380,20 +1000,101 @@ pub fn load() -> Settings {
     }
     settings.api_keys = resolved_api_keys;
 
-    // Migrate deprecated provider id.
-    if settings.provider == "deepgram-flux" {
-        settings.provider = "deepgram".into();
+    normalize(&mut settings);
+    if from_version < SCHEMA_VERSION {
+        match save_settings_without_api_keys(&settings) {
+            Ok(()) => app_log!(
+                "[settings] migrated schema v{} -> v{}",
+                from_version,
+                SCHEMA_VERSION
+            ),
+            Err(e) => app_err!("[settings] failed to write migrated settings: {}", e),
+        }
+    }
+    settings
+}
+
+type Migration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to `n + 1`. Migrations work
+/// on raw JSON so renamed or moved fields can be carried over before serde
+/// would drop them.
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+/// v0 -> v1: fold the single legacy `api_key` into `api_keys` and retire the
+/// `deepgram-flux` provider id.
+fn migrate_v0_to_v1(doc: &mut serde_json::Map<String, serde_json::Value>) {
+    if let Some(serde_json::Value::String(key)) = doc.remove("api_key") {
+        if !key.is_empty() {
+            let keys = doc
+                .entry("api_keys")
+                .or_insert_with(|| serde_json::Value::Object(Default::default()));
+            if let Some(keys) = keys.as_object_mut() {
+                keys.entry("openai").or_insert(serde_json::Value::String(key));
+            }
+        }
+    }
+    if doc.get("provider").and_then(|v| v.as_str()) == Some("deepgram-flux") {
+        doc.insert("provider".into(), "deepgram".into());
+    }
+}
+
+/// Parse a settings document, running any pending migrations first. Returns
+/// the settings and the schema version the document was written with.
+fn parse_settings(text: &str) -> Result<(Settings, u32), String> {
+    let mut value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Failed to parse settings: {}", e))?;
+    let doc = value
+        .as_object_mut()
+        .ok_or_else(|| "Failed to parse settings: not a JSON object".to_string())?;
+    let from_version = doc
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    for (version, migrate) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        migrate(doc);
+        doc.insert("schema_version".into(), (version as u32 + 1).into());
+    }
+    let settings = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    Ok((settings, from_version))
+}
+
+/// Keep the pre-migration file next to settings.json as settings.v<N>.json,
+/// minus the plaintext secrets the migrations move into the secrets file.
+fn backup_before_migration(path: &Path, from_version: u32) {
+    let backup = path.with_file_name(format!("settings.v{}.json", from_version));
+    if backup.exists() {
+        return;
     }
+    let result = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| without_plaintext_secrets(&text))
+        .and_then(|json| fs::write(&backup, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        app_err!("[settings] failed to back up settings before migration: {}", e);
+    }
+}
+
+fn without_plaintext_secrets(text: &str) -> Result<String, String> {
+    let mut value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if let Some(doc) = value.as_object_mut() {
+        for field in ["api_key", "api_keys"] {
+            doc.remove(field);
+        }
+    }
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+/// Migrate deprecated values and clamp everything back into supported ranges.
+fn normalize(settings: &mut Settings) {
     // Keep provider unset unless it's a known provider id.
-    if settings.provider != "openai"
-        && settings.provider != "deepgram"
-        && settings.provider != "elevenlabs"
-        && settings.provider != "assemblyai"
-    {
+    if !crate::provider::is_known(&settings.provider) {
         settings.provider.clear();
     }
-    // App is dark-theme only.
-    settings.theme = default_theme();
+    if settings.theme != "dark" && settings.theme != "light" && settings.theme != "auto" {
+        settings.theme = default_theme();
+    }
     // App supports strict/lenient VAD only.
     if settings.vad_mode == "off" {
         settings.vad_mode = default_vad_mode();
@@ -454,6 +1155,9 @@ pub fn load() -> Settings {
             settings.paint_path = paint.path.clone();
         }
     }
+    settings
+        .script_commands
+        .retain(|c| !c.trigger.trim().is_empty() || !c.path.trim().is_empty());
     if settings.default_browser != "chrome"
         && settings.default_browser != "edge"
         && settings.default_browser != "firefox"
@@ -464,10 +1168,19 @@ pub fn load() -> Settings {
     if settings.text_size != "small"
         && settings.text_size != "medium"
         && settings.text_size != "large"
+        && settings.text_size != "custom"
     {
         settings.text_size = default_text_size();
     }
-    if settings.accent_color != "green"
+    if !settings.ui_scale.is_finite() {
+        settings.ui_scale = default_ui_scale();
+    }
+    settings.ui_scale = settings.ui_scale.clamp(0.75, 2.0);
+    let custom_accent = settings.accent_color.len() == 7
+        && settings.accent_color.starts_with('#')
+        && settings.accent_color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !custom_accent
+        && settings.accent_color != "green"
         && settings.accent_color != "purple"
         && settings.accent_color != "blue"
         && settings.accent_color != "orange"
@@ -484,6 +1197,7 @@ pub fn load() -> Settings {
         && settings.window_anchor != "bottom_left"
         && settings.window_anchor != "bottom_center"
         && settings.window_anchor != "bottom_right"
+        && settings.window_anchor != "custom"
     {
         settings.window_anchor = default_window_anchor();
     }
@@ -493,18 +1207,200 @@ pub fn load() -> Settings {
     {
         settings.snip_edit_revert = default_snip_edit_revert();
     }
+    if settings.snip_format != "jpeg"
+        && settings.snip_format != "png"
+        && settings.snip_format != "webp"
+    {
+        settings.snip_format = default_snip_format();
+    }
+    settings.snip_quality = settings.snip_quality.clamp(50, 100);
+    settings.compact_opacity = settings.compact_opacity.clamp(20, 100);
+    settings.visualizer_bands = settings.visualizer_bands.clamp(8, 100);
+    settings.visualizer_fps = settings.visualizer_fps.clamp(5, 60);
+    if settings.snip_max_dimension != 0 {
+        settings.snip_max_dimension = settings.snip_max_dimension.clamp(256, 8192);
+    }
+    if settings.snip_preview_max_dimension != 0 {
+        settings.snip_preview_max_dimension = settings.snip_preview_max_dimension.clamp(1024, 8192);
+    }
+    settings.snip_memory_cap_mb = settings.snip_memory_cap_mb.clamp(128, 4096);
+    if settings
+        .snip_last_region
+        .is_some_and(|r| r.width == 0 || r.height == 0)
+    {
+        settings.snip_last_region = None;
+    }
+    settings
+        .snip_region_presets
+        .retain(|p| p.region.width > 0 && p.region.height > 0);
+    for preset in &mut settings.snip_region_presets {
+        if preset.hotkey > 9 {
+            preset.hotkey = 0;
+        }
+    }
+    if settings.vision_prompt != "describe"
+        && settings.vision_prompt != "table"
+        && settings.vision_prompt != "text"
+    {
+        settings.vision_prompt = default_vision_prompt();
+    }
+    settings.vision_endpoint = settings.vision_endpoint.trim().to_string();
+    if settings.vision_endpoint.is_empty() {
+        settings.vision_endpoint = default_vision_endpoint();
+    }
+    settings.vision_model = settings.vision_model.trim().to_string();
+    if settings.vision_model.is_empty() {
+        settings.vision_model = default_vision_model();
+    }
     settings.provider_inactivity_timeout_secs =
         settings.provider_inactivity_timeout_secs.clamp(5, 300);
     settings.max_session_length_minutes = settings.max_session_length_minutes.clamp(1, 120);
+    for ms in settings.pre_commit_silence_ms.values_mut() {
+        *ms = (*ms).min(crate::provider::PRE_COMMIT_SILENCE_MAX_MS);
+    }
+    for ms in settings.commit_flush_timeout_ms.values_mut() {
+        *ms = (*ms).clamp(
+            crate::provider::COMMIT_FLUSH_TIMEOUT_MIN_MS,
+            crate::provider::COMMIT_FLUSH_TIMEOUT_MAX_MS,
+        );
+    }
     settings.update_feed_url_override = settings.update_feed_url_override.trim().to_string();
+    if !crate::updater::UPDATE_CHANNELS
+        .iter()
+        .any(|(id, _)| *id == settings.update_channel)
+    {
+        settings.update_channel = default_update_channel();
+    }
     settings
+        .monthly_budgets
+        .retain(|_, limit| limit.is_finite() && *limit > 0.0);
+    settings.session_retention_days = settings.session_retention_days.min(3650);
+    settings.session_retention_max = settings.session_retention_max.min(1_000_000);
+    if !crate::diagnostics::LOG_LEVELS.contains(&settings.log_level.as_str()) {
+        settings.log_level = default_log_level();
+    }
+    settings.log_max_files = settings.log_max_files.min(1000);
+    settings.log_max_total_mb = settings.log_max_total_mb.min(10_000);
+    settings.log_max_age_days = settings.log_max_age_days.min(3650);
+    if settings.metrics_port < 1024 {
+        settings.metrics_port = default_metrics_port();
+    }
+    if settings.mcp_port < 1024 {
+        settings.mcp_port = default_mcp_port();
+    }
+    settings.obs_host = settings.obs_host.trim().to_string();
+    if settings.obs_host.is_empty() {
+        settings.obs_host = default_obs_host();
+    }
+    if settings.obs_port == 0 {
+        settings.obs_port = default_obs_port();
+    }
+    if settings.streamdeck_port < 1024 {
+        settings.streamdeck_port = default_streamdeck_port();
+    }
+    if settings.grpc_port < 1024 {
+        settings.grpc_port = default_grpc_port();
+    }
+    if settings.remote_mic_port < 1024 {
+        settings.remote_mic_port = default_remote_mic_port();
+    }
+    settings.wake_word_library_path = settings.wake_word_library_path.trim().to_string();
+    settings.wake_word_model_path = settings.wake_word_model_path.trim().to_string();
+    settings.wake_word_keyword_path = settings.wake_word_keyword_path.trim().to_string();
+    if !settings.wake_word_sensitivity.is_finite() {
+        settings.wake_word_sensitivity = default_wake_word_sensitivity();
+    }
+    settings.wake_word_sensitivity = settings.wake_word_sensitivity.clamp(0.0, 1.0);
+    settings.meeting_folder = settings.meeting_folder.trim().to_string();
+    settings.voice_notes_folder = settings.voice_notes_folder.trim().to_string();
+    settings.app_overrides.retain(|o| !o.process.trim().is_empty());
+    for o in settings.app_overrides.iter_mut() {
+        o.process = o.process.trim().to_string();
+        o.language = o.language.trim().to_string();
+        if !crate::provider::is_known(&o.provider) {
+            o.provider.clear();
+        }
+        if o.vad_mode != "strict" && o.vad_mode != "lenient" {
+            o.vad_mode.clear();
+        }
+        if o.output != "type" && o.output != "paste" {
+            o.output.clear();
+        }
+    }
 }
 
 pub fn save(settings: &Settings) -> Result<(), String> {
-    crate::secrets::save_api_keys(&settings.api_keys)?;
+    let mut keys = settings.api_keys.clone();
+    if !settings.external_key_sources.is_empty() {
+        // Keep whatever was stored for externally sourced providers.
+        let stored = crate::secrets::load_api_keys().unwrap_or_default();
+        for provider in settings.external_key_sources.keys() {
+            match stored.get(provider) {
+                Some(key) => keys.insert(provider.clone(), key.clone()),
+                None => keys.remove(provider),
+            };
+        }
+    }
+    crate::secrets::save_api_keys(&keys)?;
     save_settings_without_api_keys(settings)
 }
 
+pub fn default_bundle_path() -> Result<PathBuf, String> {
+    let dir = crate::diagnostics::data_dir()?.join("backups");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backups dir: {}", e))?;
+    Ok(dir.join("MangoChat-settings.json"))
+}
+
+/// Write every setting to a single JSON file. API keys and the OBS password are only included
+/// when asked for, in plain text, so the file should be treated as a secret.
+pub fn export_bundle(settings: &Settings, path: &Path, include_api_keys: bool) -> Result<(), String> {
+    let mut value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if include_api_keys {
+        let keys: HashMap<&String, &String> = settings
+            .api_keys
+            .iter()
+            .filter(|(p, k)| {
+                !k.trim().is_empty() && !settings.external_key_sources.contains_key(*p)
+            })
+            .collect();
+        value["api_keys"] = serde_json::to_value(keys)
+            .map_err(|e| format!("Failed to serialize API keys: {}", e))?;
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create export dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write settings bundle: {}", e))?;
+    app_log!(
+        "[settings] exported bundle to {} (api keys: {})",
+        path.display(),
+        include_api_keys
+    );
+    Ok(())
+}
+
+/// Read a bundle written by `export_bundle`. Keys missing from the bundle are
+/// kept from `current` so importing a key-less export doesn't wipe them.
+pub fn import_bundle(path: &Path, current: &Settings) -> Result<Settings, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read settings bundle: {}", e))?;
+    let (mut settings, _) =
+        parse_settings(&text).map_err(|e| format!("Invalid settings bundle: {}", e))?;
+    let mut keys = current.api_keys.clone();
+    for (provider, key) in settings.api_keys.drain() {
+        if !key.trim().is_empty() {
+            keys.insert(provider, key);
+        }
+    }
+    settings.api_keys = keys;
+    normalize(&mut settings);
+    apply_external_keys(&mut settings);
+    app_log!("[settings] imported bundle from {}", path.display());
+    Ok(settings)
+}
+
 fn save_settings_without_api_keys(settings: &Settings) -> Result<(), String> {
     let path = settings_path()?;
     if let Some(parent) = path.parent() {
@@ -512,9 +1408,49 @@ fn save_settings_without_api_keys(settings: &Settings) -> Result<(), String> {
     }
     let mut clean = settings.clone();
     clean.api_keys.clear();
-    clean.api_key.clear();
+    restore_overridden_fields(&mut clean);
+    clean.schema_version = clean.schema_version.max(SCHEMA_VERSION);
     let json = serde_json::to_string_pretty(&clean)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
     fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v0_document_migrates_and_round_trips() {
+        let v0 = r#"{"api_key":"sk-1","provider":"deepgram-flux","language":"de"}"#;
+        let (settings, from_version) = parse_settings(v0).unwrap();
+        assert_eq!(from_version, 0);
+        assert_eq!(settings.schema_version, SCHEMA_VERSION);
+        assert_eq!(settings.provider, "deepgram");
+        assert_eq!(settings.api_key_for("openai"), "sk-1");
+        assert!(!settings.extra.contains_key("api_key"));
+
+        let saved = serde_json::to_string(&settings).unwrap();
+        assert!(!saved.contains("sk-1"));
+        let (reloaded, from_version) = parse_settings(&saved).unwrap();
+        assert_eq!(from_version, SCHEMA_VERSION);
+        assert_eq!(reloaded.provider, "deepgram");
+        assert_eq!(reloaded.language, "de");
+    }
+
+    #[test]
+    fn v0_api_key_does_not_replace_existing_entry() {
+        let mut doc = serde_json::json!({"api_key": "sk-old", "api_keys": {"openai": "sk-new"}});
+        migrate_v0_to_v1(doc.as_object_mut().unwrap());
+        assert_eq!(doc, serde_json::json!({"api_keys": {"openai": "sk-new"}}));
+    }
+
+    #[test]
+    fn migration_backup_drops_plaintext_secrets() {
+        let v0 = r#"{"api_key":"sk-1","api_keys":{"deepgram":"dg-2"},"obs_port":4455}"#;
+        let backup = without_plaintext_secrets(v0).unwrap();
+        assert!(!backup.contains("sk-1"));
+        assert!(!backup.contains("dg-2"));
+        assert!(backup.contains("4455"));
+    }
+}
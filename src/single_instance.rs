@@ -1,9 +1,25 @@
 #[cfg(windows)]
 mod imp {
+    use std::io::{Read, Write};
+    use std::os::windows::io::FromRawHandle;
+    use std::time::Duration;
     use windows::core::PCWSTR;
-    use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE};
+    use windows::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, ERROR_PIPE_CONNECTED, HANDLE,
+    };
+    use windows::Win32::Storage::FileSystem::PIPE_ACCESS_INBOUND;
+    use windows::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_TYPE_BYTE, PIPE_WAIT,
+    };
     use windows::Win32::System::Threading::CreateMutexW;
 
+    /// Largest forwarded argument list accepted over the pipe.
+    const MAX_FORWARD_BYTES: u64 = 64 * 1024;
+    const ERROR_FILE_NOT_FOUND: i32 = 2;
+    const ERROR_PIPE_BUSY: i32 = 231;
+    const FORWARD_ATTEMPTS: u32 = 20;
+
     pub struct SingleInstanceGuard {
         handle: HANDLE,
     }
@@ -32,6 +48,77 @@ mod imp {
             Some(SingleInstanceGuard { handle })
         }
     }
+
+    fn pipe_name(app_id: &str) -> String {
+        format!("\\\\.\\pipe\\{}", app_id)
+    }
+
+    /// Accept argument lists from later invocations, one connection at a time.
+    /// The default pipe DACL only lets the same user (or an admin) write.
+    pub fn listen(app_id: &str, on_args: impl Fn(Vec<String>) + Send + 'static) {
+        let mut name: Vec<u16> = pipe_name(app_id).encode_utf16().collect();
+        name.push(0);
+        std::thread::spawn(move || loop {
+            let pipe = unsafe {
+                CreateNamedPipeW(
+                    PCWSTR(name.as_ptr()),
+                    PIPE_ACCESS_INBOUND,
+                    PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                    1,
+                    0,
+                    MAX_FORWARD_BYTES as u32,
+                    0,
+                    None,
+                )
+            };
+            if pipe.is_invalid() {
+                app_err!("[single-instance] failed to create pipe: {:?}", unsafe {
+                    GetLastError()
+                });
+                return;
+            }
+            let connected = unsafe { ConnectNamedPipe(pipe, None) }.is_ok()
+                || unsafe { GetLastError() } == ERROR_PIPE_CONNECTED;
+            // The File owns the handle from here and closes it on drop.
+            let pipe = unsafe { std::fs::File::from_raw_handle(pipe.0) };
+            if !connected {
+                continue;
+            }
+            let mut text = String::new();
+            if let Err(e) = (&pipe).take(MAX_FORWARD_BYTES).read_to_string(&mut text) {
+                app_err!("[single-instance] failed to read forwarded args: {}", e);
+                continue;
+            }
+            match serde_json::from_str::<Vec<String>>(&text) {
+                Ok(args) => on_args(args),
+                Err(e) => app_err!("[single-instance] bad forwarded args: {}", e),
+            }
+        });
+    }
+
+    /// Send `args` to the running instance. Retries while its listener is
+    /// busy with another client or between connections.
+    pub fn forward(app_id: &str, args: &[String]) -> Result<(), String> {
+        let payload = serde_json::to_vec(args)
+            .map_err(|e| format!("Failed to serialize args: {}", e))?;
+        let name = pipe_name(app_id);
+        let mut attempt = 0;
+        let mut pipe = loop {
+            match std::fs::OpenOptions::new().write(true).open(&name) {
+                Ok(pipe) => break pipe,
+                Err(e)
+                    if attempt < FORWARD_ATTEMPTS
+                        && matches!(e.raw_os_error(), Some(ERROR_PIPE_BUSY | ERROR_FILE_NOT_FOUND)) =>
+                {
+                    attempt += 1;
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(format!("Failed to open instance pipe: {}", e)),
+            }
+        };
+        pipe.write_all(&payload)
+            .map_err(|e| format!("Failed to forward args: {}", e))
+    }
 }
 
 #[cfg(not(windows))]
@@ -41,7 +128,12 @@ mod imp {
     pub fn acquire(_app_id: &str) -> Option<SingleInstanceGuard> {
         Some(SingleInstanceGuard)
     }
-}
 
-pub use imp::acquire;
+    pub fn listen(_app_id: &str, _on_args: impl Fn(Vec<String>) + Send + 'static) {}
+
+    pub fn forward(_app_id: &str, _args: &[String]) -> Result<(), String> {
+        Err("Argument forwarding is only supported on Windows".into())
+    }
+}
 
+pub use imp::{acquire, forward, listen};
@@ -5,6 +5,23 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+/// How saved/copied snips are encoded.
+pub struct SnipOutput {
+    pub format: String, // jpeg | png | webp
+    pub quality: u8,
+    pub max_dimension: u32,
+}
+
+impl SnipOutput {
+    fn extension(&self) -> &'static str {
+        match self.format.as_str() {
+            "png" => "png",
+            "webp" => "webp",
+            _ => "jpg",
+        }
+    }
+}
+
 /// Monitor bounds in physical pixels.
 pub struct MonitorBounds {
     pub x: i32,
@@ -65,14 +82,100 @@ pub fn capture_screen(
     Ok((image, bounds))
 }
 
-pub fn crop_and_save(
-    img: &RgbaImage,
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
-    keep_count: usize,
-) -> Result<(PathBuf, RgbaImage), String> {
+/// Capture every monitor and stitch them into one image using their
+/// desktop positions. Gaps between monitors are left transparent.
+/// Capture `region` from the monitor it was taken on, cropped. Fails rather
+/// than grabbing another screen if that monitor has gone.
+pub fn capture_region(region: &crate::settings::SnipRegion) -> Result<RgbaImage, String> {
+    let (img, bounds) = capture_screen(Some((region.monitor_x, region.monitor_y)))?;
+    if bounds.x != region.monitor_x || bounds.y != region.monitor_y {
+        return Err("The monitor for that region is no longer connected".into());
+    }
+    crop_region(&img, region.x, region.y, region.width, region.height)
+}
+
+pub fn capture_all_monitors() -> Result<RgbaImage, String> {
+    let monitors = xcap::Monitor::all().map_err(|e| format!("xcap monitors error: {:?}", e))?;
+    let mut shots = Vec::new();
+    for monitor in monitors.iter() {
+        let (Ok(x), Ok(y)) = (monitor.x(), monitor.y()) else {
+            continue;
+        };
+        let image = monitor
+            .capture_image()
+            .map_err(|e| format!("xcap capture error: {:?}", e))?;
+        shots.push((x, y, image));
+    }
+    if shots.is_empty() {
+        return Err("No monitors found".into());
+    }
+
+    let min_x = shots.iter().map(|(x, _, _)| *x).min().unwrap_or(0);
+    let min_y = shots.iter().map(|(_, y, _)| *y).min().unwrap_or(0);
+    let max_x = shots
+        .iter()
+        .map(|(x, _, img)| *x + img.width() as i32)
+        .max()
+        .unwrap_or(0);
+    let max_y = shots
+        .iter()
+        .map(|(_, y, img)| *y + img.height() as i32)
+        .max()
+        .unwrap_or(0);
+
+    let mut canvas = RgbaImage::new((max_x - min_x).max(1) as u32, (max_y - min_y).max(1) as u32);
+    for (x, y, img) in shots.iter() {
+        imageops::overlay(&mut canvas, img, (*x - min_x) as i64, (*y - min_y) as i64);
+    }
+    Ok(canvas)
+}
+
+/// Visible top-level windows overlapping the captured monitor, topmost first.
+/// Rects are (x, y, w, h) in physical pixels relative to the monitor origin,
+/// clipped to the monitor.
+pub fn window_rects(bounds: &MonitorBounds) -> Vec<(u32, u32, u32, u32)> {
+    let windows = match xcap::Window::all() {
+        Ok(w) => w,
+        Err(e) => {
+            app_err!("[snip] xcap windows error: {:?}", e);
+            return Vec::new();
+        }
+    };
+    let mon_w = bounds.width as i32;
+    let mon_h = bounds.height as i32;
+    let mut out = Vec::new();
+    for window in windows.iter() {
+        if window.is_minimized().unwrap_or(true) {
+            continue;
+        }
+        let title = window.title().unwrap_or_default();
+        if title.is_empty() || title == "Mango Chat" {
+            continue;
+        }
+        let (Ok(wx), Ok(wy), Ok(ww), Ok(wh)) =
+            (window.x(), window.y(), window.width(), window.height())
+        else {
+            continue;
+        };
+        let left = (wx - bounds.x).max(0);
+        let top = (wy - bounds.y).max(0);
+        let right = (wx - bounds.x + ww as i32).min(mon_w);
+        let bottom = (wy - bounds.y + wh as i32).min(mon_h);
+        if right - left < 5 || bottom - top < 5 {
+            continue;
+        }
+        out.push((
+            left as u32,
+            top as u32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ));
+    }
+    out
+}
+
+/// Crop a region out of a capture, clamping it to the image bounds.
+pub fn crop_region(img: &RgbaImage, x: u32, y: u32, w: u32, h: u32) -> Result<RgbaImage, String> {
     let max_w = img.width();
     let max_h = img.height();
     if max_w == 0 || max_h == 0 {
@@ -84,34 +187,153 @@ pub fn crop_and_save(
     let w = w.min(max_w.saturating_sub(x)).max(1);
     let h = h.min(max_h.saturating_sub(y)).max(1);
 
-    let cropped = imageops::crop_imm(img, x, y, w, h).to_image();
+    Ok(imageops::crop_imm(img, x, y, w, h).to_image())
+}
+
+/// Pixelate (or black out) a region in place so it can't be read back.
+pub fn redact_region(img: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32, pixelate: bool) {
+    const BLOCK: u32 = 12;
+    let x_end = x.saturating_add(w).min(img.width());
+    let y_end = y.saturating_add(h).min(img.height());
+    if x >= x_end || y >= y_end {
+        return;
+    }
+    if !pixelate {
+        for py in y..y_end {
+            for px in x..x_end {
+                img.put_pixel(px, py, image::Rgba([0, 0, 0, 255]));
+            }
+        }
+        return;
+    }
+    for by in (y..y_end).step_by(BLOCK as usize) {
+        for bx in (x..x_end).step_by(BLOCK as usize) {
+            let bw = BLOCK.min(x_end - bx);
+            let bh = BLOCK.min(y_end - by);
+            let mut sum = [0u32; 3];
+            for py in by..by + bh {
+                for px in bx..bx + bw {
+                    let p = img.get_pixel(px, py);
+                    sum[0] += p[0] as u32;
+                    sum[1] += p[1] as u32;
+                    sum[2] += p[2] as u32;
+                }
+            }
+            let n = bw * bh;
+            let avg = image::Rgba([
+                (sum[0] / n) as u8,
+                (sum[1] / n) as u8,
+                (sum[2] / n) as u8,
+                255,
+            ]);
+            for py in by..by + bh {
+                for px in bx..bx + bw {
+                    img.put_pixel(px, py, avg);
+                }
+            }
+        }
+    }
+}
+
+/// Downscale so the longest edge fits `max_dimension` (0 = leave as is).
+pub fn limit_dimension(img: RgbaImage, max_dimension: u32) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    if max_dimension == 0 || (w <= max_dimension && h <= max_dimension) {
+        return img;
+    }
+    let scale = max_dimension as f32 / w.max(h) as f32;
+    let nw = ((w as f32 * scale).round() as u32).max(1);
+    let nh = ((h as f32 * scale).round() as u32).max(1);
+    imageops::resize(&img, nw, nh, imageops::FilterType::Lanczos3)
+}
+
+/// Bytes of pixel data an RGBA image (or a texture made from it) holds.
+pub fn image_bytes(img: &RgbaImage) -> usize {
+    img.as_raw().len()
+}
+
+/// Smaller copy for the overlay background when the capture is over
+/// `max_dimension` on its longest edge (0 = any) or `max_bytes`. None means
+/// the full image is fine to show. Uses a cheap filter since it only sits
+/// under the dimmed overlay; crops always come from the full capture.
+pub fn preview_image(img: &RgbaImage, max_dimension: u32, max_bytes: usize) -> Option<RgbaImage> {
+    const MIN_EDGE: f32 = 1024.0;
+    let (w, h) = img.dimensions();
+    let longest = w.max(h) as f32;
+    let mut scale = (max_bytes as f32 / image_bytes(img) as f32).sqrt().min(1.0);
+    if max_dimension != 0 {
+        scale = scale.min(max_dimension as f32 / longest);
+    }
+    scale = scale.max((MIN_EDGE / longest).min(1.0));
+    if scale >= 1.0 {
+        return None;
+    }
+    let nw = ((w as f32 * scale).round() as u32).max(1);
+    let nh = ((h as f32 * scale).round() as u32).max(1);
+    Some(imageops::resize(
+        img,
+        nw,
+        nh,
+        imageops::FilterType::Triangle,
+    ))
+}
+
+pub fn encode_image(img: &RgbaImage, output: &SnipOutput) -> Result<Vec<u8>, String> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::codecs::png::PngEncoder;
+    use image::codecs::webp::WebPEncoder;
+    use image::ImageEncoder;
+
+    let (w, h) = img.dimensions();
+    let mut bytes = Vec::new();
+    match output.format.as_str() {
+        "png" => PngEncoder::new(&mut bytes)
+            .write_image(img.as_raw(), w, h, image::ExtendedColorType::Rgba8)
+            .map_err(|e| format!("PNG encode error: {}", e))?,
+        // The bundled WebP encoder is lossless only; quality does not apply.
+        "webp" => WebPEncoder::new_lossless(&mut bytes)
+            .write_image(img.as_raw(), w, h, image::ExtendedColorType::Rgba8)
+            .map_err(|e| format!("WebP encode error: {}", e))?,
+        _ => {
+            let rgb_data: Vec<u8> = img
+                .as_raw()
+                .chunks_exact(4)
+                .flat_map(|px| &px[..3])
+                .copied()
+                .collect();
+            JpegEncoder::new_with_quality(&mut bytes, output.quality.clamp(1, 100))
+                .write_image(&rgb_data, w, h, image::ExtendedColorType::Rgb8)
+                .map_err(|e| format!("JPEG encode error: {}", e))?
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn crop_and_save(
+    img: &RgbaImage,
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    keep_count: usize,
+    output: &SnipOutput,
+) -> Result<(PathBuf, RgbaImage), String> {
+    let cropped = limit_dimension(crop_region(img, x, y, w, h)?, output.max_dimension);
 
     let dir = snip_dir()?;
     fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snip dir: {}", e))?;
 
     let now = Local::now();
     let base = now.format("snip-%Y-%m-%d-%H%M%S").to_string();
-    let mut path = dir.join(format!("{}.jpg", base));
+    let ext = output.extension();
+    let mut path = dir.join(format!("{}.{}", base, ext));
     if path.exists() {
         let suffix = now.timestamp_millis() % 1000;
-        path = dir.join(format!("{}-{:03}.jpg", base, suffix));
+        path = dir.join(format!("{}-{:03}.{}", base, suffix, ext));
     }
 
-    let (w, h) = cropped.dimensions();
-    let rgb_data: Vec<u8> = cropped
-        .as_raw()
-        .chunks_exact(4)
-        .flat_map(|px| &px[..3])
-        .copied()
-        .collect();
-
-    use image::codecs::jpeg::JpegEncoder;
-    use image::ImageEncoder;
-    let mut jpeg_bytes = Vec::new();
-    JpegEncoder::new_with_quality(&mut jpeg_bytes, 90)
-        .write_image(&rgb_data, w, h, image::ExtendedColorType::Rgb8)
-        .map_err(|e| format!("JPEG encode error: {}", e))?;
-    fs::write(&path, jpeg_bytes).map_err(|e| format!("Failed to save snip: {}", e))?;
+    let bytes = encode_image(&cropped, output)?;
+    fs::write(&path, bytes).map_err(|e| format!("Failed to save snip: {}", e))?;
 
     let _ = prune_old_snips(&dir, keep_count.max(1));
 
@@ -147,6 +369,79 @@ pub fn copy_image_to_clipboard(img: &RgbaImage) -> Result<(), String> {
     Ok(())
 }
 
+/// Extract text from an image with the built-in Windows OCR engine
+/// (uses the user's profile languages). Lines are joined with newlines.
+#[cfg(windows)]
+pub fn ocr_image(img: &RgbaImage) -> Result<String, String> {
+    use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapPixelFormat, SoftwareBitmap};
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::DataWriter;
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    }
+
+    let (w, h) = img.dimensions();
+    let max_dim = OcrEngine::MaxImageDimension().unwrap_or(10_000);
+    if w > max_dim || h > max_dim {
+        return Err(format!("Selection too large for OCR (max {}px)", max_dim));
+    }
+
+    // SoftwareBitmap wants BGRA.
+    let mut bgra = img.as_raw().clone();
+    for px in bgra.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+
+    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+        .map_err(|e| format!("Failed to create OCR engine: {}", e))?;
+    let writer = DataWriter::new().map_err(|e| format!("Failed to create OCR buffer: {}", e))?;
+    writer
+        .WriteBytes(&bgra)
+        .map_err(|e| format!("Failed to fill OCR buffer: {}", e))?;
+    let buffer = writer
+        .DetachBuffer()
+        .map_err(|e| format!("Failed to fill OCR buffer: {}", e))?;
+    let bitmap = SoftwareBitmap::CreateCopyWithAlphaFromBuffer(
+        &buffer,
+        BitmapPixelFormat::Bgra8,
+        w as i32,
+        h as i32,
+        BitmapAlphaMode::Premultiplied,
+    )
+    .map_err(|e| format!("Failed to create OCR bitmap: {}", e))?;
+    let result = engine
+        .RecognizeAsync(&bitmap)
+        .and_then(|op| op.get())
+        .map_err(|e| format!("OCR failed: {}", e))?;
+    let lines = result
+        .Lines()
+        .map_err(|e| format!("OCR failed: {}", e))?;
+
+    let mut out = Vec::new();
+    for line in lines {
+        if let Ok(text) = line.Text() {
+            out.push(text.to_string_lossy());
+        }
+    }
+    Ok(out.join("\n"))
+}
+
+#[cfg(not(windows))]
+pub fn ocr_image(_img: &RgbaImage) -> Result<String, String> {
+    Err("OCR is only supported on Windows".into())
+}
+
+pub fn copy_text_to_clipboard(text: &str) -> Result<(), String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to init clipboard: {}", e))?;
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|e| format!("Failed to copy text to clipboard: {}", e))?;
+    Ok(())
+}
+
 pub fn snip_dir() -> Result<PathBuf, String> {
     if let Some(pictures) = dirs::picture_dir() {
         return Ok(pictures.join("MangoChat"));
@@ -202,7 +497,7 @@ pub fn open_in_editor(path: &Path, editor_path: Option<&str>) -> Result<(), Stri
     Err("Failed to launch editor (Paint)".into())
 }
 
-fn prune_old_snips(dir: &Path, keep: usize) -> Result<(), String> {
+pub fn prune_old_snips(dir: &Path, keep: usize) -> Result<(), String> {
     let mut files = Vec::new();
     let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read snip dir: {}", e))?;
     for entry in entries {
@@ -216,7 +511,7 @@ fn prune_old_snips(dir: &Path, keep: usize) -> Result<(), String> {
             .and_then(|e| e.to_str())
             .unwrap_or("")
             .to_lowercase();
-        if ext != "jpg" && ext != "jpeg" {
+        if !matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "webp" | "mp4" | "gif") {
             continue;
         }
         let modified = entry
@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::PathBuf;
+#[cfg(windows)]
 use windows::core::PCWSTR;
+#[cfg(windows)]
 use windows::Win32::Media::Audio::{
     PlaySoundW, SND_ASYNC, SND_FILENAME, SND_NODEFAULT,
 };
@@ -60,6 +62,7 @@ pub fn play_stop_cue() -> Result<(), String> {
     play_wave_path(&path)
 }
 
+#[cfg(windows)]
 fn play_wave_path(path: &PathBuf) -> Result<(), String> {
     let wide: Vec<u16> = path
         .as_os_str()
@@ -81,3 +84,18 @@ fn play_wave_path(path: &PathBuf) -> Result<(), String> {
         Err(format!("failed to play cue: {}", path.display()))
     }
 }
+
+/// afplay returns immediately when spawned, like SND_ASYNC.
+#[cfg(target_os = "macos")]
+fn play_wave_path(path: &PathBuf) -> Result<(), String> {
+    std::process::Command::new("afplay")
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("failed to play cue: {}", e))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn play_wave_path(path: &PathBuf) -> Result<(), String> {
+    Err(format!("cue playback not supported on this OS: {}", path.display()))
+}
@@ -1,9 +1,19 @@
+use arc_swap::ArcSwap;
 use image::RgbaImage;
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicU64};
-use std::sync::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 
+/// `cursor_pos` before the first mouse move. A real (-1, -1) packs to the
+/// same value; the snip then just falls back to the primary monitor.
+const NO_CURSOR: u64 = u64::MAX;
+/// `draining_gen` while no replaced provider leg is finishing.
+const NO_DRAINING_LEG: u64 = u64::MAX;
+/// Dictated finals kept for "Copy last transcript" and the recent list.
+pub const RECENT_TRANSCRIPTS_MAX: usize = 5;
+
 /// Events sent from background threads to the UI.
 #[derive(Debug, Clone)]
 pub enum AppEvent {
@@ -13,10 +23,63 @@ pub enum AppEvent {
     TranscriptDelta(String),
     TranscriptFinal(String),
     SnipTrigger,
+    /// Re-open the overlay on the previous capture (Shift + Right Alt).
+    SnipRecrop,
+    /// Capture without the overlay: the monitor under the cursor, or all monitors stitched.
+    SnipInstant { all_monitors: bool },
+    /// Capture the previous snip's rectangle again without the overlay (Ctrl + Print Screen).
+    SnipRepeat,
+    /// Capture a named region preset without the overlay (voice or Ctrl + Shift + digit).
+    SnipPreset(String),
     SessionInactivityTimeout { seconds: u64 },
     SessionMaxDurationReached { token: u64, minutes: u64 },
+    /// A provider leg replaced by a mid-session switch finished draining.
+    ProviderLegDrained { gen: u64 },
     ApiKeyValidated { provider: String, ok: bool, message: String },
+    /// The network monitor saw connectivity drop or come back.
+    ConnectivityChanged { online: bool },
+    /// Left Ctrl + Shift + F12: show or hide the performance overlay.
+    TogglePerfOverlay,
+    HealthChecksFinished(Vec<crate::health::HealthCheck>),
+    /// Start (true) or stop (false) dictation from an MCP client, the
+    /// Stream Deck or a forwarded command line.
+    SetRecording(bool),
+    /// A second launch without commands: bring the window back.
+    ShowWindow,
+    /// The wake-word listener heard its keyword.
+    WakeWordDetected,
+    /// A voice note (WAV + JSON sidecar) was written.
+    VoiceNoteSaved,
+    /// The background service started (true) or stopped (false) dictating.
+    ServiceRecording(bool),
+    /// A final from the background service to answer about the snip
+    /// attached in the window.
+    SnipQuestion(String),
+    /// Tray menu actions (menu events arrive on the tray thread).
+    TrayToggleRecording,
+    TrayToggleMeeting,
+    TrayToggleVoiceNotes,
+    TraySelectProvider(String),
+    TrayToggleScreenshots,
+    TrayTogglePill,
+    TrayToggleWindow,
+    /// Scroll Lock: pause or resume compact window click-through.
+    ToggleClickThrough,
+    /// Windows switched between light and dark app theme.
+    SystemThemeChanged,
+    /// settings.json was modified on disk.
+    SettingsFileChanged,
+    TrayCopyTranscript(usize),
+    /// Pause / Shift + Pause or the tray: rate the last final transcript.
+    RateLastTranscript { accurate: bool },
     AudioInputLost { message: String },
+    /// The phone remote microphone asked to start or stop dictating.
+    RemoteMicStart,
+    RemoteMicStop,
+    /// Usage totals finished loading in the background at startup.
+    UsageLoaded,
+    /// Input devices listed in the background at startup.
+    InputDevicesListed(Vec<String>),
 }
 
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
@@ -30,6 +93,8 @@ pub struct UsageTotals {
     pub commits: u64,
     pub finals: u64,
     pub last_update_ms: u64,
+    /// Audio chunks dropped because the provider couldn't keep up.
+    pub chunks_dropped: u64,
 }
 
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
@@ -45,6 +110,7 @@ pub struct SessionUsage {
     pub finals: u64,
     pub started_ms: u64,
     pub updated_ms: u64,
+    pub chunks_dropped: u64,
 }
 
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
@@ -56,63 +122,307 @@ pub struct ProviderUsage {
     pub finals: u64,
 }
 
+impl UsageTotals {
+    /// Add totals loaded from disk under what was counted since launch.
+    pub fn absorb(&mut self, stored: UsageTotals) {
+        if self.provider.is_empty() {
+            self.provider = stored.provider;
+        }
+        if self.model.is_empty() {
+            self.model = stored.model;
+        }
+        self.bytes_sent = self.bytes_sent.saturating_add(stored.bytes_sent);
+        self.ms_sent = self.ms_sent.saturating_add(stored.ms_sent);
+        self.ms_suppressed = self.ms_suppressed.saturating_add(stored.ms_suppressed);
+        self.commits = self.commits.saturating_add(stored.commits);
+        self.finals = self.finals.saturating_add(stored.finals);
+        self.last_update_ms = self.last_update_ms.max(stored.last_update_ms);
+        self.chunks_dropped = self.chunks_dropped.saturating_add(stored.chunks_dropped);
+    }
+}
+
+impl ProviderUsage {
+    pub fn absorb(&mut self, stored: ProviderUsage) {
+        self.ms_sent = self.ms_sent.saturating_add(stored.ms_sent);
+        self.ms_suppressed = self.ms_suppressed.saturating_add(stored.ms_suppressed);
+        self.bytes_sent = self.bytes_sent.saturating_add(stored.bytes_sent);
+        self.finals = self.finals.saturating_add(stored.finals);
+    }
+}
+
+/// Voice command tables, app paths and the vision config, copied out of the
+/// settings for the final-transcript path. Replaced whole on settings change.
+#[derive(Debug, Default, Clone)]
+pub struct CommandTables {
+    /// Configurable app path for Chrome (used by URL commands).
+    pub chrome_path: String,
+    /// Configurable app path for Paint.
+    pub paint_path: String,
+    /// URL voice commands: (trigger, url).
+    pub url_commands: Vec<(String, String)>,
+    /// Alias voice commands: (trigger, replacement text).
+    pub alias_commands: Vec<(String, String)>,
+    /// App shortcuts: (trigger, executable path).
+    pub app_shortcuts: Vec<(String, String)>,
+    /// Script commands: (trigger, script path).
+    pub script_commands: Vec<(String, String)>,
+    /// Snip region presets: (name, hotkey digit or 0).
+    pub snip_regions: Vec<(String, u8)>,
+    pub vision: crate::vision::VisionConfig,
+}
+
+/// State shared between the UI thread, the audio threads, the session tasks
+/// and the global hotkey hook.
+///
+/// Ownership: fields read on a hot path (audio processing, the hotkey hook,
+/// every UI frame) never take a lock. Flags and counters are atomics;
+/// `fft_data`, `session_usage` and `commands` are snapshots swapped whole,
+/// so a reader never waits on a writer and nothing can be poisoned. Each
+/// has a single writer except `session_usage` and `draining_usage`, which
+/// are updated through `add_session_usage`/`add_leg_usage` (retried until
+/// they land). The remaining mutexes
+/// guard cold data touched a few times per session, mostly by one side.
 pub struct AppState {
     pub audio_tx: Mutex<Option<mpsc::Sender<Vec<u8>>>>,
-    pub last_transcript: Mutex<String>,
-    pub session_active: Mutex<bool>,
+    /// Newest first, capped at `RECENT_TRANSCRIPTS_MAX`; written by dictation
+    /// sessions, read by the tray, the compact window and MCP.
+    pub recent_transcripts: Mutex<VecDeque<String>>,
+    /// Written by whoever starts or stops dictation (UI, C API).
+    pub session_active: AtomicBool,
     pub session_gen: AtomicU64,
     pub hotkey_recording: AtomicBool,
     pub session_hotkey_enabled: AtomicBool,
+    /// Win+H toggles dictation too (Windows' own panel is turned off).
+    pub win_h_enabled: AtomicBool,
     pub snip_image: Mutex<Option<RgbaImage>>,
     pub snip_active: AtomicBool,
     pub snip_started_ms: AtomicU64,
-    pub cursor_pos: Mutex<Option<(i32, i32)>>,
+    /// Last mouse position from the hotkey hook, packed by `set_cursor_pos`.
+    cursor_pos: AtomicU64,
     /// 0 = strict, 1 = lenient, 2 = legacy off (not user-selectable)
     pub vad_mode: AtomicU64,
     pub screenshot_enabled: AtomicBool,
     pub screenshot_hotkey_enabled: AtomicBool,
     pub usage: Mutex<UsageTotals>,
-    pub session_usage: Mutex<SessionUsage>,
+    /// False until the stored totals are loaded into `usage` and
+    /// `provider_totals`; nothing writes totals back before then.
+    pub usage_loaded: AtomicBool,
+    /// Counters for the running session; the UI starts and ends it.
+    pub session_usage: ArcSwap<SessionUsage>,
+    /// Counters of a provider leg replaced by a mid-session switch, kept
+    /// until that leg's session task exits; `draining_gen` is its
+    /// `session_gen`.
+    pub draining_usage: ArcSwap<SessionUsage>,
+    pub draining_gen: AtomicU64,
     pub provider_totals: Mutex<HashMap<String, ProviderUsage>>,
-    /// FFT magnitudes for the visualizer bars (0.0–1.0 range).
-    pub fft_data: Mutex<[f32; 50]>,
-    /// Configurable app path for Chrome (used by URL commands).
-    pub chrome_path: Mutex<String>,
-    /// Configurable app path for Paint.
-    pub paint_path: Mutex<String>,
-    /// Dynamic URL voice commands: (trigger, url).
-    pub url_commands: Mutex<Vec<(String, String)>>,
-    /// Dynamic alias voice commands: (trigger, replacement text).
-    pub alias_commands: Mutex<Vec<(String, String)>>,
-    /// Dynamic app shortcuts: (trigger, executable path).
-    pub app_shortcuts: Mutex<Vec<(String, String)>>,
+    /// FFT magnitudes for the visualizer bars (0.0–1.0 range), one per band;
+    /// empty while nothing is being captured. Written by the audio thread.
+    pub fft_data: ArcSwap<Vec<f32>>,
+    /// Set by the UI while the visualizer is enabled and on screen; the
+    /// audio thread skips the FFT otherwise.
+    pub visualizer_active: AtomicBool,
+    pub visualizer_bands: AtomicU64,
+    pub visualizer_interval_ms: AtomicU64,
+    /// Written by `load_commands` on the UI thread.
+    pub commands: ArcSwap<CommandTables>,
+    /// Snip attached as context for the next final transcript.
+    pub attached_snip: Mutex<Option<RgbaImage>>,
+    /// In the background service: the window holds an attached snip, so the
+    /// next final goes back to it as a `SnipQuestion` instead of being typed.
+    pub snip_in_window: AtomicBool,
+    /// Last result of the background connectivity probe.
+    pub online: AtomicBool,
+    /// Time the last audio websocket send took, and the slowest this session (µs).
+    pub ws_send_latency_us: AtomicU64,
+    pub ws_send_latency_peak_us: AtomicU64,
+    /// Set TCP_NODELAY on provider sockets (settings `ws_tcp_nodelay`).
+    pub ws_tcp_nodelay: AtomicBool,
+    /// Audio chunks dropped since launch; the UI warns when it grows.
+    pub audio_chunks_dropped: AtomicU64,
+    /// Connection state of the OBS caption client, shown under Integrations.
+    pub obs_status: Mutex<String>,
 }
 
 impl AppState {
+    /// Claim the snip slot for a new capture. Fails while another snip is
+    /// open, unless it started long enough ago to be stale.
+    pub fn try_begin_snip(&self) -> bool {
+        const STALE_MS: u64 = 30_000;
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        if self.snip_active.swap(true, Ordering::SeqCst)
+            && now_ms.saturating_sub(self.snip_started_ms.load(Ordering::SeqCst)) < STALE_MS
+        {
+            return false;
+        }
+        self.snip_started_ms.store(now_ms, Ordering::SeqCst);
+        true
+    }
+
+    pub fn set_cursor_pos(&self, x: i32, y: i32) {
+        let packed = ((x as u32 as u64) << 32) | y as u32 as u64;
+        self.cursor_pos.store(packed, Ordering::Relaxed);
+    }
+
+    /// None until the hook has seen the mouse move.
+    pub fn cursor_pos(&self) -> Option<(i32, i32)> {
+        match self.cursor_pos.load(Ordering::Relaxed) {
+            NO_CURSOR => None,
+            packed => Some(((packed >> 32) as u32 as i32, packed as u32 as i32)),
+        }
+    }
+
+    /// Apply `f` to the running session's counters; a no-op between
+    /// sessions. `f` may run more than once if another writer races it.
+    pub fn add_session_usage(&self, f: impl Fn(&mut SessionUsage)) {
+        self.session_usage.rcu(|current| {
+            let mut next = SessionUsage::clone(current);
+            if next.started_ms != 0 {
+                f(&mut next);
+            }
+            next
+        });
+    }
+
+    /// Apply `f` to the counters of the provider leg started as session
+    /// generation `gen`: the draining row after a provider switch, else the
+    /// running one. Legs that are neither are not counted.
+    pub fn add_leg_usage(&self, gen: u64, f: impl Fn(&mut SessionUsage)) {
+        let row = if self.draining_gen.load(Ordering::SeqCst) == gen {
+            &self.draining_usage
+        } else if self.session_gen.load(Ordering::SeqCst) == gen {
+            &self.session_usage
+        } else {
+            return;
+        };
+        row.rcu(|current| {
+            let mut next = SessionUsage::clone(current);
+            if next.started_ms != 0 {
+                f(&mut next);
+            }
+            next
+        });
+    }
+
+    /// Move the running row aside for the current leg to finish counting
+    /// into, leaving no running row. Returns the draining row it replaces.
+    pub fn drain_session_usage(&self) -> Arc<SessionUsage> {
+        let replaced = self.take_draining_usage();
+        self.draining_usage.store(self.session_usage.load_full());
+        self.draining_gen
+            .store(self.session_gen.load(Ordering::SeqCst), Ordering::SeqCst);
+        self.session_usage.store(Default::default());
+        replaced
+    }
+
+    /// Close the draining row and return it.
+    pub fn take_draining_usage(&self) -> Arc<SessionUsage> {
+        self.draining_gen.store(NO_DRAINING_LEG, Ordering::SeqCst);
+        self.draining_usage.swap(Default::default())
+    }
+
+    /// Keep a dictated final for the copy actions.
+    pub fn remember_transcript(&self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        if let Ok(mut recent) = self.recent_transcripts.lock() {
+            recent.push_front(text.to_string());
+            recent.truncate(RECENT_TRANSCRIPTS_MAX);
+        }
+    }
+
+    /// Recent finals, newest first.
+    pub fn recent_transcripts(&self) -> Vec<String> {
+        self.recent_transcripts
+            .lock()
+            .map(|r| r.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn last_transcript(&self) -> Option<String> {
+        self.recent_transcripts
+            .lock()
+            .ok()
+            .and_then(|r| r.front().cloned())
+    }
+
     pub fn new() -> Self {
         Self {
             audio_tx: Mutex::new(None),
-            last_transcript: Mutex::new(String::new()),
-            session_active: Mutex::new(false),
+            recent_transcripts: Mutex::new(VecDeque::with_capacity(RECENT_TRANSCRIPTS_MAX)),
+            session_active: AtomicBool::new(false),
             session_gen: AtomicU64::new(0),
             hotkey_recording: AtomicBool::new(false),
             session_hotkey_enabled: AtomicBool::new(true),
+            win_h_enabled: AtomicBool::new(false),
             snip_image: Mutex::new(None),
             snip_active: AtomicBool::new(false),
             snip_started_ms: AtomicU64::new(0),
-            cursor_pos: Mutex::new(None),
+            cursor_pos: AtomicU64::new(NO_CURSOR),
             vad_mode: AtomicU64::new(0),
             screenshot_enabled: AtomicBool::new(false),
             screenshot_hotkey_enabled: AtomicBool::new(true),
             usage: Mutex::new(UsageTotals::default()),
-            session_usage: Mutex::new(SessionUsage::default()),
+            usage_loaded: AtomicBool::new(false),
+            session_usage: ArcSwap::from_pointee(SessionUsage::default()),
+            draining_usage: ArcSwap::from_pointee(SessionUsage::default()),
+            draining_gen: AtomicU64::new(NO_DRAINING_LEG),
             provider_totals: Mutex::new(HashMap::new()),
-            fft_data: Mutex::new([0.0; 50]),
-            chrome_path: Mutex::new(r"C:\Program Files\Google\Chrome\Application\chrome.exe".into()),
-            paint_path: Mutex::new(r"C:\Windows\System32\mspaint.exe".into()),
-            url_commands: Mutex::new(vec![]),
-            alias_commands: Mutex::new(vec![]),
-            app_shortcuts: Mutex::new(vec![]),
+            fft_data: ArcSwap::from_pointee(Vec::new()),
+            visualizer_active: AtomicBool::new(true),
+            visualizer_bands: AtomicU64::new(50),
+            visualizer_interval_ms: AtomicU64::new(33),
+            commands: ArcSwap::from_pointee(CommandTables {
+                chrome_path: r"C:\Program Files\Google\Chrome\Application\chrome.exe".into(),
+                paint_path: r"C:\Windows\System32\mspaint.exe".into(),
+                ..CommandTables::default()
+            }),
+            attached_snip: Mutex::new(None),
+            snip_in_window: AtomicBool::new(false),
+            online: AtomicBool::new(true),
+            ws_send_latency_us: AtomicU64::new(0),
+            ws_send_latency_peak_us: AtomicU64::new(0),
+            ws_tcp_nodelay: AtomicBool::new(true),
+            audio_chunks_dropped: AtomicU64::new(0),
+            obs_status: Mutex::new(String::new()),
         }
     }
+
+    /// Copy app paths, voice command tables and the vision config out of
+    /// `settings`, for the typing and vision code that reads them from here.
+    pub fn load_commands(&self, settings: &crate::settings::Settings) {
+        self.commands.store(Arc::new(CommandTables {
+            chrome_path: settings.resolved_browser_path(),
+            paint_path: settings.paint_path.clone(),
+            url_commands: settings
+                .url_commands
+                .iter()
+                .map(|c| (c.trigger.clone(), c.url.clone()))
+                .collect(),
+            alias_commands: settings
+                .alias_commands
+                .iter()
+                .map(|c| (c.trigger.clone(), c.replacement.clone()))
+                .collect(),
+            app_shortcuts: settings
+                .app_shortcuts
+                .iter()
+                .map(|c| (c.trigger.clone(), c.path.clone()))
+                .collect(),
+            script_commands: settings
+                .script_commands
+                .iter()
+                .map(|c| (c.trigger.clone(), c.path.clone()))
+                .collect(),
+            snip_regions: settings
+                .snip_region_presets
+                .iter()
+                .map(|p| (p.name.clone(), p.hotkey))
+                .collect(),
+            vision: crate::vision::VisionConfig::from_settings(settings),
+        }));
+    }
 }
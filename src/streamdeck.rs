@@ -0,0 +1,190 @@
+// Local WebSocket endpoint for the Stream Deck plugin in
+// integrations/streamdeck. The plugin relays key presses here and mirrors
+// the state we push back onto its buttons.
+//
+// Server -> plugin: {"event": "state", "recording": bool, "provider": "deepgram",
+//                    "provider_name": "Deepgram", "providers": ["deepgram", ...]}
+// Plugin -> server: {"action": "toggle_recording" | "start_recording" |
+//                    "stop_recording" | "snip" | "next_provider" | "select_provider",
+//                    "provider": "openai"}
+
+use crate::state::{AppEvent, AppState};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::mpsc::Sender as EventSender;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio_tungstenite::accept_hdr_async;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::{http, Message};
+
+/// What the deck buttons show. Published by the UI thread whenever it changes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeckState {
+    pub recording: bool,
+    pub provider: String,
+    /// Providers with an API key, in the order the deck cycles through them.
+    pub providers: Vec<String>,
+}
+
+impl DeckState {
+    fn to_message(&self) -> String {
+        json!({
+            "event": "state",
+            "recording": self.recording,
+            "provider": self.provider,
+            "provider_name": crate::provider::display_name(&self.provider),
+            "providers": self.providers,
+        })
+        .to_string()
+    }
+
+    fn next_provider(&self) -> Option<String> {
+        if self.providers.is_empty() {
+            return None;
+        }
+        let next = self
+            .providers
+            .iter()
+            .position(|p| *p == self.provider)
+            .map(|i| (i + 1) % self.providers.len())
+            .unwrap_or(0);
+        Some(self.providers[next].clone())
+    }
+}
+
+fn handle_action(
+    msg: &Value,
+    deck: &DeckState,
+    state: &AppState,
+    event_tx: &EventSender<AppEvent>,
+) {
+    let action = msg.get("action").and_then(Value::as_str).unwrap_or("");
+    app_log!("[streamdeck] {}", action);
+    let event = match action {
+        "toggle_recording" => AppEvent::TrayToggleRecording,
+        "start_recording" => AppEvent::SetRecording(true),
+        "stop_recording" => AppEvent::SetRecording(false),
+        "snip" => {
+            // A double press must not open two overlays.
+            if !state.try_begin_snip() {
+                return;
+            }
+            AppEvent::SnipTrigger
+        }
+        "next_provider" => match deck.next_provider() {
+            Some(p) => AppEvent::TraySelectProvider(p),
+            None => return,
+        },
+        "select_provider" => match msg.get("provider").and_then(Value::as_str) {
+            Some(p) if deck.providers.iter().any(|id| id == p) => {
+                AppEvent::TraySelectProvider(p.to_string())
+            }
+            _ => return,
+        },
+        _ => {
+            app_err!("[streamdeck] unknown action \"{}\"", action);
+            return;
+        }
+    };
+    let _ = event_tx.send(event);
+}
+
+/// The plugin's pages load from disk, so Stream Deck sends a `file://`
+/// origin; anything else must be a local page, as for the MCP endpoint.
+/// A website (or its sandboxed "null" frame) can't reach the deck actions.
+fn origin_allowed(origin: Option<&str>) -> bool {
+    match origin {
+        Some(o) if o.starts_with("file://") => true,
+        _ => crate::mcp::origin_allowed(origin),
+    }
+}
+
+async fn serve_client(
+    stream: TcpStream,
+    mut deck_rx: watch::Receiver<DeckState>,
+    state: Arc<AppState>,
+    event_tx: EventSender<AppEvent>,
+) -> Result<(), String> {
+    let check_origin = |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        let origin = req.headers().get("origin").and_then(|v| v.to_str().ok());
+        if origin_allowed(origin) {
+            return Ok(resp);
+        }
+        app_err!("[streamdeck] rejected connection from origin {:?}", origin);
+        let mut reject = http::Response::new(Some("origin not allowed".to_string()));
+        *reject.status_mut() = http::StatusCode::FORBIDDEN;
+        Err(reject)
+    };
+    let ws = accept_hdr_async(stream, check_origin)
+        .await
+        .map_err(|e| format!("Failed to accept Stream Deck connection: {}", e))?;
+    let (mut sink, mut source) = ws.split();
+    let initial = deck_rx.borrow_and_update().to_message();
+    sink.send(Message::Text(initial))
+        .await
+        .map_err(|e| format!("Failed to send state: {}", e))?;
+    loop {
+        tokio::select! {
+            changed = deck_rx.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+                let msg = deck_rx.borrow_and_update().to_message();
+                sink.send(Message::Text(msg))
+                    .await
+                    .map_err(|e| format!("Failed to send state: {}", e))?;
+            }
+            msg = source.next() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Value>(&text) {
+                    Ok(msg) => {
+                        let deck = deck_rx.borrow().clone();
+                        handle_action(&msg, &deck, &state, &event_tx);
+                    }
+                    Err(e) => app_err!("[streamdeck] bad message: {}", e),
+                },
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(format!("Stream Deck connection error: {}", e)),
+            },
+        }
+    }
+}
+
+/// Listen on ws://127.0.0.1:`port` for the Stream Deck plugin. The returned
+/// sender is how the UI publishes button state.
+pub fn start_server(
+    runtime: &tokio::runtime::Runtime,
+    state: Arc<AppState>,
+    event_tx: EventSender<AppEvent>,
+    port: u16,
+) -> watch::Sender<DeckState> {
+    let (deck_tx, deck_rx) = watch::channel(DeckState::default());
+    runtime.spawn(async move {
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(l) => l,
+            Err(e) => {
+                app_err!("[streamdeck] failed to bind 127.0.0.1:{}: {}", port, e);
+                return;
+            }
+        };
+        app_log!("[streamdeck] listening on ws://127.0.0.1:{}", port);
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    app_err!("[streamdeck] accept error: {}", e);
+                    continue;
+                }
+            };
+            let (deck_rx, state, event_tx) = (deck_rx.clone(), state.clone(), event_tx.clone());
+            tokio::spawn(async move {
+                if let Err(e) = serve_client(stream, deck_rx, state, event_tx).await {
+                    app_err!("[streamdeck] {}", e);
+                }
+            });
+        }
+    });
+    deck_tx
+}
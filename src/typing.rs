@@ -1,4 +1,7 @@
 use enigo::{Enigo, Key, Keyboard, Settings};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 #[cfg(windows)]
 use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
 #[cfg(windows)]
@@ -6,6 +9,17 @@ use windows::Win32::UI::WindowsAndMessaging::{
     EnumWindows, GetClassNameW, IsWindowVisible, SetForegroundWindow, ShowWindow, SW_RESTORE,
 };
 
+/// Modifier for copy/paste/undo shortcuts: Command on macOS, Ctrl elsewhere.
+#[cfg(target_os = "macos")]
+const SHORTCUT_MODIFIER: Key = Key::Meta;
+#[cfg(not(target_os = "macos"))]
+const SHORTCUT_MODIFIER: Key = Key::Control;
+/// Modifier that moves the caret by word: Option on macOS, Ctrl elsewhere.
+#[cfg(target_os = "macos")]
+const WORD_MODIFIER: Key = Key::Alt;
+#[cfg(not(target_os = "macos"))]
+const WORD_MODIFIER: Key = Key::Control;
+
 /// Strip punctuation, lowercase, collapse whitespace.
 /// "Mango Chat: back, back." -> "mango chat back back"
 fn normalize(text: &str) -> String {
@@ -52,6 +66,24 @@ const COMMANDS: &[(&str, fn())] = &[
 
 const WAKE_WORDS: &[&str] = &["mangochat", "mango"];
 
+/// Most recent texts pasted by `paste_text` (newest first).
+static CLIPBOARD_HISTORY: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+const CLIPBOARD_HISTORY_MAX: usize = 10;
+/// Time given to the target app to read the clipboard after Ctrl+V before the
+/// previous contents are put back.
+const CLIPBOARD_SETTLE_MS: u64 = 120;
+
+/// Dictated text is pasted instead of typed (per-app override for the session).
+static PASTE_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_paste_output(paste: bool) {
+    PASTE_OUTPUT.store(paste, Ordering::SeqCst);
+}
+
+const NUMBER_WORDS: &[&str] = &[
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
 fn cmd_new_line()       { press_enter(); }
 fn cmd_new_paragraph()  { press_enter(); press_enter(); }
 fn cmd_delete_word()    { delete_word(); }
@@ -61,6 +93,60 @@ fn cmd_copy()           { press_ctrl_key(Key::Unicode('c')); }
 fn cmd_paste()          { press_ctrl_key(Key::Unicode('v')); }
 fn cmd_cut()            { press_ctrl_key(Key::Unicode('x')); }
 fn cmd_select_all()     { press_ctrl_key(Key::Unicode('a')); }
+/// Push pasted text onto the history, moving a repeat to the front.
+fn remember_pasted(text: String) {
+    if text.is_empty() {
+        return;
+    }
+    if let Ok(mut history) = CLIPBOARD_HISTORY.lock() {
+        history.retain(|t| *t != text);
+        history.push_front(text);
+        history.truncate(CLIPBOARD_HISTORY_MAX);
+    }
+}
+
+/// Snapshot of the clipboard history, newest first.
+pub fn clipboard_history() -> Vec<String> {
+    CLIPBOARD_HISTORY
+        .lock()
+        .map(|h| h.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Put history item `index` (1 = newest) on the clipboard and paste it.
+fn paste_history_item(index: usize) {
+    let Some(text) = clipboard_history().get(index.saturating_sub(1)).cloned() else {
+        app_log!("[typing] clipboard history has no item {}", index);
+        return;
+    };
+    match arboard::Clipboard::new() {
+        Ok(mut clipboard) => {
+            if let Err(e) = clipboard.set_text(text) {
+                log::error!("Failed to copy to clipboard: {}", e);
+                return;
+            }
+        }
+        Err(e) => {
+            log::error!("Failed to create clipboard: {}", e);
+            return;
+        }
+    }
+    press_ctrl_key(Key::Unicode('v'));
+}
+
+/// "paste previous" -> 2, "paste item three" / "paste item 3" -> 3.
+fn match_clipboard_paste(phrase: &str) -> Option<usize> {
+    if phrase == "paste previous" {
+        return Some(2);
+    }
+    let item = phrase.strip_prefix("paste item ")?;
+    let index = item
+        .parse::<usize>()
+        .ok()
+        .or_else(|| NUMBER_WORDS.iter().position(|w| *w == item).map(|i| i + 1))?;
+    (1..=CLIPBOARD_HISTORY_MAX).contains(&index).then_some(index)
+}
+
 /// Open a URL in the user's chosen browser.
 /// Tries the explicit path first, then a bare command name derived from the
 /// path (so Firefox falls back to "firefox", Edge to "msedge", Chrome to
@@ -86,7 +172,19 @@ pub fn open_url_in_chrome(browser_path: &str, url: &str) {
             .args(["url.dll,FileProtocolHandler", url])
             .spawn();
     }
-    #[cfg(not(windows))]
+    #[cfg(target_os = "macos")]
+    {
+        // `open -a` takes an app bundle path or name; without one, the default browser.
+        let app = browser_path.trim().trim_matches('"');
+        let mut cmd = std::process::Command::new("open");
+        if !app.is_empty() {
+            cmd.args(["-a", app]);
+        }
+        if let Err(e) = cmd.arg(url).spawn() {
+            app_err!("[typing] failed to open {}: {}", url, e);
+        }
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
     {
         let _ = (browser_path, url);
         app_log!("[typing] open_url_in_browser not supported on this OS");
@@ -101,7 +199,166 @@ pub fn launch_app(path: &str) {
     let _ = std::process::Command::new(path).spawn();
 }
 
-/// Open a path in Windows File Explorer.
+/// How a script is started on Windows. Neither way goes through a cmd.exe
+/// command line we build, so a dictated `a & calc` stays one literal argument.
+#[cfg(any(windows, test))]
+#[derive(Debug, PartialEq)]
+enum ScriptLaunch {
+    /// Run `program` directly; std quotes each argument (and escapes them
+    /// for cmd.exe when `program` is itself a batch file).
+    Direct { program: String, args: Vec<String> },
+    /// Open `file` through its association (e.g. .ahk) with `ShellExecuteW`,
+    /// passing `params` as the command line tail.
+    Associated { file: String, params: String },
+}
+
+#[cfg(any(windows, test))]
+fn script_launch(script: &str, arg: &str) -> ScriptLaunch {
+    let ext = std::path::Path::new(script)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let host: Option<(&str, &[&str])> = match ext.as_str() {
+        "ps1" => Some((
+            "powershell.exe",
+            &["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"],
+        )),
+        "vbs" | "js" | "wsf" => Some(("wscript.exe", &[])),
+        "bat" | "cmd" | "exe" => Some((script, &[])),
+        _ => None,
+    };
+    match host {
+        Some((program, leading)) => {
+            let mut args: Vec<String> = leading.iter().map(|a| a.to_string()).collect();
+            if program != script {
+                args.push(script.to_string());
+            }
+            if !arg.is_empty() {
+                args.push(arg.to_string());
+            }
+            ScriptLaunch::Direct {
+                program: program.to_string(),
+                args,
+            }
+        }
+        None => ScriptLaunch::Associated {
+            file: script.to_string(),
+            params: if arg.is_empty() {
+                String::new()
+            } else {
+                quote_windows_arg(arg)
+            },
+        },
+    }
+}
+
+/// Quote `arg` so `CommandLineToArgvW` (and the MSVC runtime) read it back
+/// as exactly one argument.
+#[cfg(any(windows, test))]
+fn quote_windows_arg(arg: &str) -> String {
+    let mut out = String::with_capacity(arg.len() + 2);
+    out.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are literal only when doubled.
+                out.push_str(&"\\".repeat(backslashes * 2 + 1));
+                out.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                out.push_str(&"\\".repeat(backslashes));
+                out.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    out.push_str(&"\\".repeat(backslashes * 2));
+    out.push('"');
+    out
+}
+
+/// Run a user script, passing `arg` (if any) as a single argument.
+/// .ps1 files go through PowerShell, .vbs/.js through Windows Script Host and
+/// .bat/.cmd run directly; everything else (e.g. .ahk) is opened through its
+/// file association.
+pub fn run_script(path: &str, arg: &str) {
+    let script = path.trim().trim_matches('"');
+    if script.is_empty() {
+        return;
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        use windows::core::{HSTRING, PCWSTR};
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::Shell::ShellExecuteW;
+        use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        match script_launch(script, arg) {
+            ScriptLaunch::Direct { program, args } => {
+                let mut cmd = std::process::Command::new(program);
+                cmd.args(args).creation_flags(CREATE_NO_WINDOW);
+                if let Err(e) = cmd.spawn() {
+                    app_err!("[typing] failed to run script {}: {}", script, e);
+                }
+            }
+            ScriptLaunch::Associated { file, params } => {
+                // Values above 32 mean success.
+                let result = unsafe {
+                    ShellExecuteW(
+                        HWND::default(),
+                        &HSTRING::from("open"),
+                        &HSTRING::from(file.as_str()),
+                        &HSTRING::from(params.as_str()),
+                        PCWSTR::null(),
+                        SW_SHOWNORMAL,
+                    )
+                };
+                if result.0 as isize <= 32 {
+                    app_err!(
+                        "[typing] failed to run script {}: ShellExecute error {}",
+                        script,
+                        result.0 as isize
+                    );
+                }
+            }
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // Shell scripts run through sh; anything else (e.g. .scpt, .app) via `open`.
+        let mut cmd = if script.ends_with(".sh") {
+            let mut c = std::process::Command::new("sh");
+            c.arg(script);
+            c
+        } else {
+            let mut c = std::process::Command::new("open");
+            c.arg(script);
+            if !arg.is_empty() {
+                c.arg("--args");
+            }
+            c
+        };
+        if !arg.is_empty() {
+            cmd.arg(arg);
+        }
+        if let Err(e) = cmd.spawn() {
+            app_err!("[typing] failed to run script {}: {}", script, e);
+        }
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let _ = arg;
+        app_log!("[typing] script command not supported on this OS: {}", script);
+    }
+}
+
+/// Open a path in Windows File Explorer (Finder on macOS).
 pub fn open_in_explorer(path: &str) {
     #[cfg(windows)]
     {
@@ -111,7 +368,13 @@ pub fn open_in_explorer(path: &str) {
             .arg(arg)
             .spawn();
     }
-    #[cfg(not(windows))]
+    #[cfg(target_os = "macos")]
+    {
+        let target = path.trim().trim_matches('"');
+        let arg = if target.is_empty() { "/" } else { target };
+        let _ = std::process::Command::new("open").arg(arg).spawn();
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
     {
         let _ = path;
         app_log!("[typing] explorer command not supported on this OS");
@@ -131,7 +394,14 @@ fn focus_or_launch_chrome(chrome_path: &str) {
             .args(["/C", "start", "", "chrome"])
             .spawn();
     }
-    #[cfg(not(windows))]
+    #[cfg(target_os = "macos")]
+    {
+        // `open -a` focuses the running instance or launches it.
+        let app = chrome_path.trim().trim_matches('"');
+        let app = if app.is_empty() { "Google Chrome" } else { app };
+        let _ = std::process::Command::new("open").args(["-a", app]).spawn();
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
     {
         let _ = chrome_path;
         app_log!("[typing] chrome command not supported on this OS");
@@ -198,6 +468,51 @@ fn focus_existing_chrome_window() -> bool {
     false
 }
 
+/// Executable file name of the foreground window's process, e.g.
+/// "slack.exe". None when Mango Chat itself (or nothing) has focus.
+#[cfg(windows)]
+pub fn foreground_process_name() -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 || pid == std::process::id() {
+            return None;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buf.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        ok.ok()?;
+        let path = String::from_utf16_lossy(&buf[..len as usize]);
+        std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(not(windows))]
+pub fn foreground_process_name() -> Option<String> {
+    None
+}
+
 fn match_command(phrase: &str) -> Option<(&'static str, fn())> {
     for (keyword, action) in COMMANDS {
         if phrase == *keyword {
@@ -207,14 +522,119 @@ fn match_command(phrase: &str) -> Option<(&'static str, fn())> {
     None
 }
 
-pub fn process_transcript(
+/// What a transcript resolves to after command matching.
+/// Produced without side effects so the settings test console can preview it.
+#[derive(Clone)]
+pub enum TranscriptAction {
+    /// Open a URL command target in the configured browser.
+    OpenUrl { trigger: String, url: String },
+    /// Open a path in File Explorer (the "explorer" URL command).
+    OpenExplorer { trigger: String, path: String },
+    /// Focus an existing Chrome window or launch it.
+    FocusChrome { path: String },
+    /// Launch an application by path.
+    LaunchApp { trigger: String, path: String },
+    /// Run a user script with the spoken remainder as its argument.
+    RunScript {
+        trigger: String,
+        path: String,
+        remainder: String,
+    },
+    /// Type the alias replacement text.
+    Alias { trigger: String, replacement: String },
+    /// Run a built-in command, then type any remainder.
+    Command {
+        keyword: &'static str,
+        action: fn(),
+        remainder: String,
+    },
+    /// Paste an entry from the clipboard history (1 = newest).
+    PasteHistory { index: usize },
+    /// Type the transcript as-is.
+    Type { text: String, unknown_command: bool },
+}
+
+impl TranscriptAction {
+    /// Short label for the kind of match.
+    pub fn kind_label(&self) -> &'static str {
+        match self {
+            TranscriptAction::OpenUrl { .. } => "Browser command",
+            TranscriptAction::OpenExplorer { .. } => "Explorer command",
+            TranscriptAction::FocusChrome { .. } => "App command",
+            TranscriptAction::LaunchApp { .. } => "App shortcut",
+            TranscriptAction::RunScript { .. } => "Script command",
+            TranscriptAction::Alias { .. } => "Text alias",
+            TranscriptAction::Command { .. } => "Mango Chat command",
+            TranscriptAction::PasteHistory { .. } => "Clipboard history",
+            TranscriptAction::Type {
+                unknown_command: true,
+                ..
+            } => "Unknown command",
+            TranscriptAction::Type { .. } => "No match",
+        }
+    }
+
+    /// Human-readable description of what would happen.
+    pub fn describe(&self) -> String {
+        match self {
+            TranscriptAction::OpenUrl { trigger, url } => {
+                format!("\"{}\" opens {}", trigger, url)
+            }
+            TranscriptAction::OpenExplorer { trigger, path } => {
+                format!("\"{}\" opens {} in File Explorer", trigger, path)
+            }
+            TranscriptAction::FocusChrome { path } => {
+                format!("Focus Chrome, or launch {}", path)
+            }
+            TranscriptAction::LaunchApp { trigger, path } => {
+                format!("\"{}\" launches {}", trigger, path)
+            }
+            TranscriptAction::RunScript {
+                trigger,
+                path,
+                remainder,
+            } => {
+                if remainder.is_empty() {
+                    format!("\"{}\" runs {}", trigger, path)
+                } else {
+                    format!("\"{}\" runs {} with \"{}\"", trigger, path, remainder)
+                }
+            }
+            TranscriptAction::Alias {
+                trigger,
+                replacement,
+            } => format!("\"{}\" types \"{}\"", trigger, replacement),
+            TranscriptAction::Command {
+                keyword, remainder, ..
+            } => {
+                if remainder.is_empty() {
+                    format!("Runs \"{}\"", keyword)
+                } else {
+                    format!("Runs \"{}\", then types \"{}\"", keyword, remainder)
+                }
+            }
+            TranscriptAction::PasteHistory { index } => {
+                match clipboard_history().get(index.saturating_sub(1)) {
+                    Some(text) => format!("Pastes clipboard item {}: \"{}\"", index, text),
+                    None => format!("Pastes clipboard item {} (currently empty)", index),
+                }
+            }
+            TranscriptAction::Type { text, .. } => format!("Types \"{}\"", text),
+        }
+    }
+}
+
+/// Match a transcript against URL commands, app commands, script commands,
+/// aliases and built-in commands (in that order) without executing anything.
+pub fn resolve_transcript(
     text: &str,
     chrome_path: &str,
     paint_path: &str,
     url_commands: &[(String, String)],
     alias_commands: &[(String, String)],
     app_shortcuts: &[(String, String)],
-) {
+    script_commands: &[(String, String)],
+) -> TranscriptAction {
     let norm = normalize(text);
     let mut parts = norm.split_whitespace();
     let first = parts.next().unwrap_or("");
@@ -235,26 +655,29 @@ pub fn process_transcript(
             || phrase == format!("open {} com", t)
         {
             if t == "explorer" {
-                app_log!("[typing] explorer command: \"{}\" -> {}", trigger, url);
-                open_in_explorer(url);
-            } else {
-                app_log!("[typing] url command: \"{}\" -> {}", trigger, url);
-                open_url_in_chrome(chrome_path, url);
+                return TranscriptAction::OpenExplorer {
+                    trigger: trigger.clone(),
+                    path: url.clone(),
+                };
             }
-            return;
+            return TranscriptAction::OpenUrl {
+                trigger: trigger.clone(),
+                url: url.clone(),
+            };
         }
     }
 
     // 2. App-launch commands.
     if phrase == "chrome" || phrase == "open chrome" {
-        app_log!("[typing] command: focus chrome");
-        focus_or_launch_chrome(chrome_path);
-        return;
+        return TranscriptAction::FocusChrome {
+            path: chrome_path.to_string(),
+        };
     }
     if phrase == "paint" || phrase == "open paint" {
-        app_log!("[typing] command: launch paint");
-        launch_app(paint_path);
-        return;
+        return TranscriptAction::LaunchApp {
+            trigger: "paint".into(),
+            path: paint_path.to_string(),
+        };
     }
 
     // 3. App shortcut commands (dynamic, from settings).
@@ -265,53 +688,212 @@ pub fn process_transcript(
         }
         if phrase == t || phrase == format!("open {}", t) {
             if t == "chrome" {
-                app_log!("[typing] app shortcut: focus/launch chrome -> {}", path);
-                focus_or_launch_chrome(path);
-            } else if t == "paint" {
-                app_log!("[typing] app shortcut: launch paint -> {}", path);
-                launch_app(path);
-            } else {
-                app_log!("[typing] app shortcut: launch {} -> {}", trigger, path);
-                launch_app(path);
+                return TranscriptAction::FocusChrome { path: path.clone() };
             }
-            return;
+            return TranscriptAction::LaunchApp {
+                trigger: trigger.clone(),
+                path: path.clone(),
+            };
         }
     }
 
-    // 4. Alias commands (dynamic, from settings): exact match trigger -> type replacement.
+    // 4. Script commands (dynamic, from settings): trigger prefix, remainder is the argument.
+    for (trigger, path) in script_commands {
+        let t = normalize(trigger);
+        if t.is_empty() {
+            continue;
+        }
+        if phrase == t || phrase.starts_with(&format!("{} ", t)) {
+            return TranscriptAction::RunScript {
+                trigger: trigger.clone(),
+                path: path.clone(),
+                remainder: phrase[t.len()..].trim().to_string(),
+            };
+        }
+    }
+
+    // 5. Alias commands (dynamic, from settings): exact match trigger -> type replacement.
     for (trigger, replacement) in alias_commands {
         let t = normalize(trigger);
         if !t.is_empty() && phrase == t {
-            app_log!("[typing] alias command: \"{}\" -> \"{}\"", trigger, replacement);
-            type_text(replacement);
-            return;
+            return TranscriptAction::Alias {
+                trigger: trigger.clone(),
+                replacement: replacement.clone(),
+            };
         }
     }
 
-    // 5. Static commands.
+    // 6. Clipboard history ("paste previous", "paste item three").
+    if let Some(index) = match_clipboard_paste(&phrase) {
+        return TranscriptAction::PasteHistory { index };
+    }
+
+    // 7. Static commands.
     if has_wake {
         for (keyword, action) in COMMANDS {
             if phrase == *keyword || phrase.starts_with(&format!("{} ", keyword)) {
-                app_log!("[typing] command: \"{}\"", keyword);
-                action();
-                let remainder = phrase[keyword.len()..].trim();
-                if !remainder.is_empty() {
-                    app_log!("[typing] typing remainder: \"{}\"", remainder);
-                    type_text(remainder);
-                }
-                return;
+                return TranscriptAction::Command {
+                    keyword,
+                    action: *action,
+                    remainder: phrase[keyword.len()..].trim().to_string(),
+                };
             }
         }
         // Wake word but no known command — type original.
-        app_log!("[typing] unknown command in: \"{}\"", phrase);
-        type_text(text);
-    } else {
+        TranscriptAction::Type {
+            text: text.to_string(),
+            unknown_command: true,
+        }
+    } else if let Some((keyword, action)) = match_command(&phrase) {
         // Standalone: exact match only.
-        if let Some((keyword, action)) = match_command(&phrase) {
+        TranscriptAction::Command {
+            keyword,
+            action,
+            remainder: String::new(),
+        }
+    } else {
+        TranscriptAction::Type {
+            text: text.to_string(),
+            unknown_command: false,
+        }
+    }
+}
+
+/// Name of the snip region preset a transcript asks for: "snip <name>" or
+/// "capture <name>", with or without the wake word.
+pub fn match_region_command(text: &str, regions: &[(String, u8)]) -> Option<String> {
+    let norm = normalize(text);
+    let phrase = match norm.split_once(' ') {
+        Some((first, rest)) if WAKE_WORDS.contains(&first) => rest,
+        _ => norm.as_str(),
+    };
+    let target = phrase
+        .strip_prefix("snip ")
+        .or_else(|| phrase.strip_prefix("capture "))?;
+    regions
+        .iter()
+        .find(|(name, _)| normalize(name) == target)
+        .map(|(name, _)| name.clone())
+}
+
+pub fn process_transcript(text: &str, commands: &crate::state::CommandTables) {
+    let (text, mut plugin_actions) = crate::plugins::transform(text);
+    let (text, hook_actions) = crate::hooks::on_final(&text);
+    plugin_actions.extend(hook_actions);
+    let handled = wake_command(&text)
+        .and_then(|(trigger, args)| crate::hooks::on_command(&trigger, &args));
+    if let Some(hook_actions) = handled {
+        plugin_actions.extend(hook_actions);
+    } else if !text.trim().is_empty() {
+        run_transcript_action(
+            &text,
+            &commands.chrome_path,
+            &commands.paint_path,
+            &commands.url_commands,
+            &commands.alias_commands,
+            &commands.app_shortcuts,
+            &commands.script_commands,
+        );
+    }
+    for action in plugin_actions {
+        match action {
+            crate::plugins::PluginAction::OpenUrl { url } => {
+                app_log!("[plugins] open url {}", url);
+                open_url_in_chrome(&commands.chrome_path, &url);
+            }
+            crate::plugins::PluginAction::PressEnter => press_enter(),
+            crate::plugins::PluginAction::CopyToClipboard { text } => copy_to_clipboard(&text),
+            crate::plugins::PluginAction::TypeText { text } => output_text(&text),
+        }
+    }
+}
+
+/// Trigger word and the rest of a phrase that starts with a wake word
+/// ("mango deploy staging" -> ("deploy", "staging")).
+fn wake_command(text: &str) -> Option<(String, String)> {
+    let norm = normalize(text);
+    let mut parts = norm.split_whitespace();
+    if !WAKE_WORDS.contains(&parts.next()?) {
+        return None;
+    }
+    let trigger = parts.next()?.to_string();
+    Some((trigger, parts.collect::<Vec<_>>().join(" ")))
+}
+
+fn run_transcript_action(
+    text: &str,
+    chrome_path: &str,
+    paint_path: &str,
+    url_commands: &[(String, String)],
+    alias_commands: &[(String, String)],
+    app_shortcuts: &[(String, String)],
+    script_commands: &[(String, String)],
+) {
+    let action = resolve_transcript(
+        text,
+        chrome_path,
+        paint_path,
+        url_commands,
+        alias_commands,
+        app_shortcuts,
+        script_commands,
+    );
+    match action {
+        TranscriptAction::OpenUrl { trigger, url } => {
+            app_log!("[typing] url command: \"{}\" -> {}", trigger, url);
+            open_url_in_chrome(chrome_path, &url);
+        }
+        TranscriptAction::OpenExplorer { trigger, path } => {
+            app_log!("[typing] explorer command: \"{}\" -> {}", trigger, path);
+            open_in_explorer(&path);
+        }
+        TranscriptAction::FocusChrome { path } => {
+            app_log!("[typing] command: focus chrome -> {}", path);
+            focus_or_launch_chrome(&path);
+        }
+        TranscriptAction::LaunchApp { trigger, path } => {
+            app_log!("[typing] app shortcut: launch {} -> {}", trigger, path);
+            launch_app(&path);
+        }
+        TranscriptAction::RunScript {
+            trigger,
+            path,
+            remainder,
+        } => {
+            app_log!("[typing] script command: \"{}\" -> {} \"{}\"", trigger, path, remainder);
+            run_script(&path, &remainder);
+        }
+        TranscriptAction::Alias {
+            trigger,
+            replacement,
+        } => {
+            app_log!("[typing] alias command: \"{}\" -> \"{}\"", trigger, replacement);
+            output_text(&replacement);
+        }
+        TranscriptAction::Command {
+            keyword,
+            action,
+            remainder,
+        } => {
             app_log!("[typing] command: \"{}\"", keyword);
             action();
-        } else {
-            type_text(text);
+            if !remainder.is_empty() {
+                app_log!("[typing] typing remainder: \"{}\"", remainder);
+                output_text(&remainder);
+            }
+        }
+        TranscriptAction::PasteHistory { index } => {
+            app_log!("[typing] paste clipboard history item {}", index);
+            paste_history_item(index);
+        }
+        TranscriptAction::Type {
+            text,
+            unknown_command,
+        } => {
+            if unknown_command {
+                app_log!("[typing] unknown command in: \"{}\"", normalize(&text));
+            }
+            output_text(&text);
         }
     }
 }
@@ -345,6 +927,40 @@ pub fn type_text(text: &str) {
     }
 }
 
+/// Type or paste dictated text, depending on the session's output method.
+fn output_text(text: &str) {
+    if PASTE_OUTPUT.load(Ordering::SeqCst) {
+        paste_text(text);
+    } else {
+        type_text(text);
+    }
+}
+
+/// Paste via the clipboard, then put back whatever text was there before.
+fn paste_text(text: &str) {
+    let mut clipboard = match arboard::Clipboard::new() {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Failed to create clipboard: {}", e);
+            type_text(text);
+            return;
+        }
+    };
+    let previous = clipboard.get_text().ok();
+    let pasted = format!("{} ", text);
+    if let Err(e) = clipboard.set_text(pasted.clone()) {
+        log::error!("Failed to copy to clipboard: {}", e);
+        type_text(text);
+        return;
+    }
+    press_ctrl_key(Key::Unicode('v'));
+    remember_pasted(pasted);
+    std::thread::sleep(std::time::Duration::from_millis(CLIPBOARD_SETTLE_MS));
+    if let Some(previous) = previous {
+        let _ = clipboard.set_text(previous);
+    }
+}
+
 pub fn press_enter() {
     let Some(mut enigo) = make_enigo() else { return };
     release_modifiers(&mut enigo);
@@ -354,22 +970,22 @@ pub fn press_enter() {
     }
 }
 
-/// Ctrl+Backspace — delete previous word
+/// Ctrl+Backspace (Option+Backspace on macOS) — delete previous word
 fn delete_word() {
     let Some(mut enigo) = make_enigo() else { return };
     release_modifiers(&mut enigo);
     // Select previous word, then delete selection for consistent behavior.
-    let _ = enigo.key(Key::Control, enigo::Direction::Press);
+    let _ = enigo.key(WORD_MODIFIER, enigo::Direction::Press);
     let _ = enigo.key(Key::Shift, enigo::Direction::Press);
     let _ = enigo.key(Key::LeftArrow, enigo::Direction::Click);
     let _ = enigo.key(Key::Shift, enigo::Direction::Release);
-    let _ = enigo.key(Key::Control, enigo::Direction::Release);
+    let _ = enigo.key(WORD_MODIFIER, enigo::Direction::Release);
     let _ = enigo.key(Key::Backspace, enigo::Direction::Click);
     // Remove trailing space that type_text appends.
     let _ = enigo.key(Key::Backspace, enigo::Direction::Click);
 }
 
-/// Press Ctrl+<key>
+/// Press Ctrl+<key> (Command+<key> on macOS)
 fn press_ctrl_key(key: Key) {
     let Some(mut enigo) = make_enigo() else { return };
     release_modifiers(&mut enigo);
@@ -377,9 +993,9 @@ fn press_ctrl_key(key: Key) {
 }
 
 fn press_ctrl_key_with(enigo: &mut Enigo, key: Key) {
-    let _ = enigo.key(Key::Control, enigo::Direction::Press);
+    let _ = enigo.key(SHORTCUT_MODIFIER, enigo::Direction::Press);
     let _ = enigo.key(key, enigo::Direction::Click);
-    let _ = enigo.key(Key::Control, enigo::Direction::Release);
+    let _ = enigo.key(SHORTCUT_MODIFIER, enigo::Direction::Release);
 }
 
 /// Press a single key
@@ -419,4 +1035,221 @@ pub fn copy_to_clipboard(text: &str) {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    fn resolve(text: &str) -> TranscriptAction {
+        resolve_transcript(
+            text,
+            "chrome.exe",
+            "mspaint.exe",
+            &pairs(&[("github", "https://github.com"), ("explorer", "C:\\Users")]),
+            &pairs(&[("sig", "Best regards")]),
+            &pairs(&[("notepad", "notepad.exe")]),
+            &pairs(&[("deploy", "deploy.ps1")]),
+        )
+    }
+
+    #[test]
+    fn normalize_strips_punctuation_and_case() {
+        assert_eq!(normalize("Mango Chat: back, back."), "mango chat back back");
+        assert_eq!(normalize("  Mango-Chat   Enter. "), "mango chat enter");
+    }
+
+    #[test]
+    fn url_commands_match_spoken_forms() {
+        for text in ["GitHub", "Open GitHub.", "github.com", "Mango, open github"] {
+            assert!(
+                matches!(resolve(text), TranscriptAction::OpenUrl { url, .. } if url == "https://github.com"),
+                "{}",
+                text
+            );
+        }
+        assert!(matches!(
+            resolve("open github please"),
+            TranscriptAction::Type { .. }
+        ));
+        assert!(matches!(
+            resolve("open explorer"),
+            TranscriptAction::OpenExplorer { .. }
+        ));
+    }
+
+    #[test]
+    fn app_commands_match() {
+        assert!(matches!(
+            resolve("Open Chrome."),
+            TranscriptAction::FocusChrome { .. }
+        ));
+        assert!(matches!(
+            resolve("paint"),
+            TranscriptAction::LaunchApp { path, .. } if path == "mspaint.exe"
+        ));
+        assert!(matches!(
+            resolve("open notepad"),
+            TranscriptAction::LaunchApp { path, .. } if path == "notepad.exe"
+        ));
+    }
+
+    #[test]
+    fn script_commands_pass_the_remainder() {
+        assert!(matches!(
+            resolve("deploy staging now"),
+            TranscriptAction::RunScript { path, remainder, .. }
+                if path == "deploy.ps1" && remainder == "staging now"
+        ));
+        assert!(matches!(
+            resolve("Deploy."),
+            TranscriptAction::RunScript { remainder, .. } if remainder.is_empty()
+        ));
+        assert!(matches!(
+            resolve("deployment"),
+            TranscriptAction::Type { .. }
+        ));
+    }
 
+    /// Split a command line tail the way `CommandLineToArgvW` does.
+    fn split_windows_args(line: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut chars = line.chars().peekable();
+        loop {
+            while chars.peek() == Some(&' ') {
+                chars.next();
+            }
+            if chars.peek().is_none() {
+                return args;
+            }
+            let (mut arg, mut quoted, mut backslashes) = (String::new(), false, 0);
+            for c in chars.by_ref() {
+                match c {
+                    '\\' => backslashes += 1,
+                    '"' => {
+                        arg.push_str(&"\\".repeat(backslashes / 2));
+                        if backslashes % 2 == 1 {
+                            arg.push('"');
+                        } else {
+                            quoted = !quoted;
+                        }
+                        backslashes = 0;
+                    }
+                    ' ' if !quoted => break,
+                    _ => {
+                        arg.push_str(&"\\".repeat(backslashes));
+                        arg.push(c);
+                        backslashes = 0;
+                    }
+                }
+            }
+            arg.push_str(&"\\".repeat(backslashes));
+            args.push(arg);
+        }
+    }
+
+    #[test]
+    fn script_remainder_is_one_literal_argument() {
+        for remainder in ["a & calc", "x | y > out ^z", r#"say "hi" \"#, r"C:\dir\"] {
+            match script_launch(r"C:\scripts\hotkeys.ahk", remainder) {
+                ScriptLaunch::Associated { params, .. } => {
+                    assert_eq!(split_windows_args(&params), vec![remainder.to_string()]);
+                }
+                other => panic!("expected an associated launch, got {:?}", other),
+            }
+        }
+        assert_eq!(
+            script_launch("deploy.ps1", "a & calc"),
+            ScriptLaunch::Direct {
+                program: "powershell.exe".into(),
+                args: ["-NoProfile", "-ExecutionPolicy", "Bypass", "-File", "deploy.ps1"]
+                    .into_iter()
+                    .chain(["a & calc"])
+                    .map(String::from)
+                    .collect(),
+            }
+        );
+        // Batch files run directly so std escapes the argument for cmd.exe.
+        assert_eq!(
+            script_launch("build.BAT", "a & calc"),
+            ScriptLaunch::Direct {
+                program: "build.BAT".into(),
+                args: vec!["a & calc".into()],
+            }
+        );
+        assert!(matches!(
+            script_launch("tool.ahk", ""),
+            ScriptLaunch::Associated { params, .. } if params.is_empty()
+        ));
+    }
+
+    #[test]
+    fn aliases_need_an_exact_match() {
+        assert!(matches!(
+            resolve("Sig."),
+            TranscriptAction::Alias { replacement, .. } if replacement == "Best regards"
+        ));
+        assert!(matches!(resolve("sig here"), TranscriptAction::Type { .. }));
+    }
+
+    #[test]
+    fn builtin_commands_with_and_without_wake_word() {
+        assert!(matches!(
+            resolve("Back, back."),
+            TranscriptAction::Command {
+                keyword: "back back",
+                ..
+            }
+        ));
+        assert!(matches!(
+            resolve("Mango, new line then more"),
+            TranscriptAction::Command { keyword: "new line", remainder, .. }
+                if remainder == "then more"
+        ));
+        // Standalone commands only match the whole phrase.
+        assert!(matches!(
+            resolve("back to work"),
+            TranscriptAction::Type {
+                unknown_command: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            resolve("Mango dance"),
+            TranscriptAction::Type { unknown_command: true, text } if text == "Mango dance"
+        ));
+    }
+
+    #[test]
+    fn clipboard_history_phrases() {
+        assert_eq!(match_clipboard_paste("paste previous"), Some(2));
+        assert_eq!(match_clipboard_paste("paste item three"), Some(3));
+        assert_eq!(match_clipboard_paste("paste item 10"), Some(10));
+        assert_eq!(match_clipboard_paste("paste item 11"), None);
+        assert_eq!(match_clipboard_paste("paste item zero"), None);
+        assert!(matches!(
+            resolve("Paste item two."),
+            TranscriptAction::PasteHistory { index: 2 }
+        ));
+    }
+
+    #[test]
+    fn region_commands_match_preset_names() {
+        let regions = vec![("Top Left".to_string(), 1), ("chart".to_string(), 0)];
+        assert_eq!(
+            match_region_command("Mango, snip top left.", &regions).as_deref(),
+            Some("Top Left")
+        );
+        assert_eq!(
+            match_region_command("Capture chart", &regions).as_deref(),
+            Some("chart")
+        );
+        assert_eq!(match_region_command("snip bottom", &regions), None);
+        assert_eq!(match_region_command("chart", &regions), None);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::settings::Settings;
 use std::collections::HashMap;
 
-use super::theme::PROVIDER_ROWS;
+use super::theme::{accent_palette, hex_color, PROVIDER_ROWS};
 use super::window::WINDOW_MONITOR_MODE_FIXED;
 
 #[allow(dead_code)]
@@ -18,23 +18,98 @@ pub struct FormState {
     pub screenshot_retention_count: u32,
     pub start_cue: String,
     pub text_size: String,
+    pub ui_scale: f32,
+    pub theme: String,
     pub accent_color: String,
+    /// Hex text box for the custom accent; applied to `accent_color` when valid.
+    pub custom_accent_hex: String,
     pub compact_background_enabled: bool,
     pub auto_minimize: bool,
+    pub pill_mode: bool,
+    pub compact_opacity: u8,
+    pub visualizer_enabled: bool,
+    pub visualizer_bands: u32,
+    pub visualizer_fps: u32,
+    pub click_through: bool,
+    pub hide_when_fullscreen: bool,
+    pub edge_dock: bool,
+    pub toast_notifications: bool,
+    pub background_service: bool,
     pub update_feed_url_override: String,
+    pub update_channel: String,
+    pub update_verify_signature: bool,
+    pub update_install_on_exit: bool,
+    pub api_key_file: String,
+    /// Mirrors the Run registry entry rather than a settings field.
+    pub start_with_windows: bool,
+    /// Mirrors the registry like `start_with_windows`; not part of settings.json.
+    pub win_h_dictation: bool,
     pub window_monitor_mode: String,
     pub window_monitor_id: String,
     pub window_anchor: String,
     pub snip_editor_path: String,
     pub snip_edit_revert: String,
+    pub snip_format: String,
+    pub snip_quality: u8,
+    pub snip_max_dimension: u32,
+    pub snip_preview_max_dimension: u32,
+    pub snip_memory_cap_mb: u32,
+    pub snip_record_audio: bool,
+    pub snip_magnifier: bool,
+    pub vision_endpoint: String,
+    pub vision_model: String,
+    pub vision_prompt: String,
     pub default_browser: String,
     pub chrome_path: String,
     pub paint_path: String,
     pub provider_inactivity_timeout_secs: u64,
     pub max_session_length_minutes: u64,
+    pub pre_commit_silence_ms: HashMap<String, u32>,
+    pub commit_flush_timeout_ms: HashMap<String, u32>,
+    pub ws_tcp_nodelay: bool,
     pub url_commands: Vec<crate::settings::UrlCommand>,
     pub alias_commands: Vec<crate::settings::AliasCommand>,
     pub app_shortcuts: Vec<crate::settings::AppShortcut>,
+    pub script_commands: Vec<crate::settings::ScriptCommand>,
+    pub snip_region_presets: Vec<crate::settings::RegionPreset>,
+    pub enabled_plugins: Vec<String>,
+    pub hooks_enabled: bool,
+    pub app_overrides: Vec<crate::settings::AppOverride>,
+    pub monthly_budgets: HashMap<String, f64>,
+    pub budget_block_at_limit: bool,
+    pub session_retention_days: u32,
+    pub session_retention_max: u32,
+    pub log_level: String,
+    pub log_json: bool,
+    pub perf_overlay: bool,
+    pub log_max_files: u32,
+    pub log_max_total_mb: u32,
+    pub log_max_age_days: u32,
+    pub transcript_archive_enabled: bool,
+    pub metrics_enabled: bool,
+    pub metrics_port: u16,
+    pub mcp_enabled: bool,
+    pub mcp_port: u16,
+    pub obs_enabled: bool,
+    pub obs_host: String,
+    pub obs_port: u16,
+    pub obs_text_source: String,
+    pub obs_stream_captions: bool,
+    pub streamdeck_enabled: bool,
+    pub streamdeck_port: u16,
+    pub grpc_enabled: bool,
+    pub grpc_port: u16,
+    pub remote_mic_enabled: bool,
+    pub remote_mic_port: u16,
+    pub wake_word_enabled: bool,
+    pub wake_word_library_path: String,
+    pub wake_word_model_path: String,
+    pub wake_word_keyword_path: String,
+    pub wake_word_sensitivity: f32,
+    pub meeting_folder: String,
+    pub meeting_system_audio: bool,
+    pub voice_notes_mode: bool,
+    pub voice_notes_folder: String,
 }
 
 impl FormState {
@@ -56,23 +131,99 @@ impl FormState {
             screenshot_retention_count: settings.screenshot_retention_count,
             start_cue: settings.start_cue.clone(),
             text_size: settings.text_size.clone(),
+            ui_scale: settings.ui_scale,
+            theme: settings.theme.clone(),
             accent_color: settings.accent_color.clone(),
+            custom_accent_hex: if settings.accent_color.starts_with('#') {
+                settings.accent_color.clone()
+            } else {
+                hex_color(accent_palette(&settings.accent_color).base)
+            },
             compact_background_enabled: settings.compact_background_enabled,
             auto_minimize: settings.auto_minimize,
+            pill_mode: settings.pill_mode,
+            compact_opacity: settings.compact_opacity,
+            visualizer_enabled: settings.visualizer_enabled,
+            visualizer_bands: settings.visualizer_bands,
+            visualizer_fps: settings.visualizer_fps,
+            click_through: settings.click_through,
+            hide_when_fullscreen: settings.hide_when_fullscreen,
+            edge_dock: settings.edge_dock,
+            toast_notifications: settings.toast_notifications,
+            background_service: settings.background_service,
             update_feed_url_override: settings.update_feed_url_override.clone(),
+            update_channel: settings.update_channel.clone(),
+            update_verify_signature: settings.update_verify_signature,
+            update_install_on_exit: settings.update_install_on_exit,
+            api_key_file: settings.api_key_file.clone(),
+            start_with_windows: crate::autostart::is_enabled(),
+            win_h_dictation: crate::win_h::is_taken_over(),
             window_monitor_mode: WINDOW_MONITOR_MODE_FIXED.to_string(),
             window_monitor_id: settings.window_monitor_id.clone(),
             window_anchor: settings.window_anchor.clone(),
             snip_editor_path: settings.snip_editor_path.clone(),
             snip_edit_revert: settings.snip_edit_revert.clone(),
+            snip_format: settings.snip_format.clone(),
+            snip_quality: settings.snip_quality,
+            snip_max_dimension: settings.snip_max_dimension,
+            snip_preview_max_dimension: settings.snip_preview_max_dimension,
+            snip_memory_cap_mb: settings.snip_memory_cap_mb,
+            snip_record_audio: settings.snip_record_audio,
+            snip_magnifier: settings.snip_magnifier,
+            vision_endpoint: settings.vision_endpoint.clone(),
+            vision_model: settings.vision_model.clone(),
+            vision_prompt: settings.vision_prompt.clone(),
             default_browser: settings.default_browser.clone(),
             chrome_path: settings.chrome_path.clone(),
             paint_path: settings.paint_path.clone(),
             provider_inactivity_timeout_secs: settings.provider_inactivity_timeout_secs,
             max_session_length_minutes: settings.max_session_length_minutes,
+            pre_commit_silence_ms: settings.pre_commit_silence_ms.clone(),
+            commit_flush_timeout_ms: settings.commit_flush_timeout_ms.clone(),
+            ws_tcp_nodelay: settings.ws_tcp_nodelay,
             url_commands: settings.url_commands.clone(),
             alias_commands: settings.alias_commands.clone(),
             app_shortcuts: settings.app_shortcuts.clone(),
+            script_commands: settings.script_commands.clone(),
+            snip_region_presets: settings.snip_region_presets.clone(),
+            enabled_plugins: settings.enabled_plugins.clone(),
+            hooks_enabled: settings.hooks_enabled,
+            app_overrides: settings.app_overrides.clone(),
+            monthly_budgets: settings.monthly_budgets.clone(),
+            budget_block_at_limit: settings.budget_block_at_limit,
+            session_retention_days: settings.session_retention_days,
+            session_retention_max: settings.session_retention_max,
+            log_level: settings.log_level.clone(),
+            log_json: settings.log_json,
+            perf_overlay: settings.perf_overlay,
+            log_max_files: settings.log_max_files,
+            log_max_total_mb: settings.log_max_total_mb,
+            log_max_age_days: settings.log_max_age_days,
+            transcript_archive_enabled: settings.transcript_archive_enabled,
+            metrics_enabled: settings.metrics_enabled,
+            metrics_port: settings.metrics_port,
+            mcp_enabled: settings.mcp_enabled,
+            mcp_port: settings.mcp_port,
+            obs_enabled: settings.obs_enabled,
+            obs_host: settings.obs_host.clone(),
+            obs_port: settings.obs_port,
+            obs_text_source: settings.obs_text_source.clone(),
+            obs_stream_captions: settings.obs_stream_captions,
+            streamdeck_enabled: settings.streamdeck_enabled,
+            streamdeck_port: settings.streamdeck_port,
+            grpc_enabled: settings.grpc_enabled,
+            grpc_port: settings.grpc_port,
+            remote_mic_enabled: settings.remote_mic_enabled,
+            remote_mic_port: settings.remote_mic_port,
+            wake_word_enabled: settings.wake_word_enabled,
+            wake_word_library_path: settings.wake_word_library_path.clone(),
+            wake_word_model_path: settings.wake_word_model_path.clone(),
+            wake_word_keyword_path: settings.wake_word_keyword_path.clone(),
+            wake_word_sensitivity: settings.wake_word_sensitivity,
+            meeting_folder: settings.meeting_folder.clone(),
+            meeting_system_audio: settings.meeting_system_audio,
+            voice_notes_mode: settings.voice_notes_mode,
+            voice_notes_folder: settings.voice_notes_folder.clone(),
         }
     }
 
@@ -93,26 +244,119 @@ impl FormState {
         settings.screenshot_hotkey_enabled = self.screenshot_hotkey_enabled;
         settings.screenshot_retention_count = self.screenshot_retention_count.clamp(1, 200);
         settings.start_cue = self.start_cue.clone();
-        settings.theme = "dark".to_string();
+        settings.theme = self.theme.clone();
         settings.text_size = self.text_size.clone();
+        settings.ui_scale = self.ui_scale.clamp(0.75, 2.0);
         settings.accent_color = self.accent_color.clone();
         settings.compact_background_enabled = self.compact_background_enabled;
         settings.auto_minimize = self.auto_minimize;
+        settings.pill_mode = self.pill_mode;
+        settings.compact_opacity = self.compact_opacity.clamp(20, 100);
+        settings.visualizer_enabled = self.visualizer_enabled;
+        settings.visualizer_bands = self.visualizer_bands.clamp(8, 100);
+        settings.visualizer_fps = self.visualizer_fps.clamp(5, 60);
+        settings.click_through = self.click_through;
+        settings.hide_when_fullscreen = self.hide_when_fullscreen;
+        settings.edge_dock = self.edge_dock;
+        settings.toast_notifications = self.toast_notifications;
+        settings.background_service = self.background_service;
         settings.update_feed_url_override = self.update_feed_url_override.trim().to_string();
+        settings.update_channel = self.update_channel.clone();
+        settings.update_verify_signature = self.update_verify_signature;
+        settings.update_install_on_exit = self.update_install_on_exit;
+        settings.api_key_file = self.api_key_file.trim().to_string();
         settings.window_monitor_mode = WINDOW_MONITOR_MODE_FIXED.to_string();
         settings.window_monitor_id = self.window_monitor_id.clone();
         settings.window_anchor = self.window_anchor.clone();
         settings.snip_editor_path = self.snip_editor_path.clone();
         settings.snip_edit_revert = self.snip_edit_revert.clone();
+        settings.snip_format = self.snip_format.clone();
+        settings.snip_quality = self.snip_quality.clamp(50, 100);
+        settings.snip_record_audio = self.snip_record_audio;
+        settings.snip_magnifier = self.snip_magnifier;
+        settings.vision_endpoint = self.vision_endpoint.trim().to_string();
+        settings.vision_model = self.vision_model.trim().to_string();
+        settings.vision_prompt = self.vision_prompt.clone();
+        settings.snip_max_dimension = if self.snip_max_dimension == 0 {
+            0
+        } else {
+            self.snip_max_dimension.clamp(256, 8192)
+        };
+        settings.snip_preview_max_dimension = if self.snip_preview_max_dimension == 0 {
+            0
+        } else {
+            self.snip_preview_max_dimension.clamp(1024, 8192)
+        };
+        settings.snip_memory_cap_mb = self.snip_memory_cap_mb.clamp(128, 4096);
         settings.default_browser = self.default_browser.clone();
         settings.chrome_path = self.chrome_path.clone();
         settings.paint_path = self.paint_path.clone();
         settings.provider_inactivity_timeout_secs =
             self.provider_inactivity_timeout_secs.clamp(5, 300);
         settings.max_session_length_minutes = self.max_session_length_minutes.clamp(1, 120);
+        settings.pre_commit_silence_ms = self.pre_commit_silence_ms.clone();
+        settings.commit_flush_timeout_ms = self.commit_flush_timeout_ms.clone();
+        settings.ws_tcp_nodelay = self.ws_tcp_nodelay;
         settings.url_commands = self.url_commands.clone();
         settings.alias_commands = self.alias_commands.clone();
         settings.app_shortcuts = self.app_shortcuts.clone();
+        settings.script_commands = self.script_commands.clone();
+        settings.snip_region_presets = self.snip_region_presets.clone();
+        settings.enabled_plugins = self.enabled_plugins.clone();
+        settings.hooks_enabled = self.hooks_enabled;
+        settings.app_overrides = self
+            .app_overrides
+            .iter()
+            .filter(|o| !o.process.trim().is_empty())
+            .cloned()
+            .collect();
+        settings.monthly_budgets = self
+            .monthly_budgets
+            .iter()
+            .filter(|(_, limit)| **limit > 0.0)
+            .map(|(p, limit)| (p.clone(), *limit))
+            .collect();
+        settings.budget_block_at_limit = self.budget_block_at_limit;
+        settings.session_retention_days = self.session_retention_days.min(3650);
+        settings.session_retention_max = self.session_retention_max.min(1_000_000);
+        settings.log_level = self.log_level.clone();
+        settings.log_json = self.log_json;
+        settings.perf_overlay = self.perf_overlay;
+        settings.log_max_files = self.log_max_files.min(1000);
+        settings.log_max_total_mb = self.log_max_total_mb.min(10_000);
+        settings.log_max_age_days = self.log_max_age_days.min(3650);
+        settings.transcript_archive_enabled = self.transcript_archive_enabled;
+        settings.metrics_enabled = self.metrics_enabled;
+        settings.metrics_port = self.metrics_port.max(1024);
+        settings.mcp_enabled = self.mcp_enabled;
+        settings.mcp_port = self.mcp_port.max(1024);
+        settings.obs_enabled = self.obs_enabled;
+        settings.obs_host = self.obs_host.trim().to_string();
+        settings.obs_port = self.obs_port.max(1);
+        settings.obs_text_source = self.obs_text_source.trim().to_string();
+        settings.obs_stream_captions = self.obs_stream_captions;
+        settings.streamdeck_enabled = self.streamdeck_enabled;
+        settings.streamdeck_port = self.streamdeck_port.max(1024);
+        settings.grpc_enabled = self.grpc_enabled;
+        settings.grpc_port = self.grpc_port.max(1024);
+        settings.remote_mic_enabled = self.remote_mic_enabled;
+        settings.remote_mic_port = self.remote_mic_port.max(1024);
+        settings.wake_word_enabled = self.wake_word_enabled;
+        settings.wake_word_library_path = self.wake_word_library_path.trim().to_string();
+        settings.wake_word_model_path = self.wake_word_model_path.trim().to_string();
+        settings.wake_word_keyword_path = self.wake_word_keyword_path.trim().to_string();
+        settings.wake_word_sensitivity = self.wake_word_sensitivity.clamp(0.0, 1.0);
+        settings.meeting_folder = self.meeting_folder.trim().to_string();
+        settings.meeting_system_audio = self.meeting_system_audio;
+        settings.voice_notes_mode = self.voice_notes_mode;
+        settings.voice_notes_folder = self.voice_notes_folder.trim().to_string();
+        // Non-provider secrets kept alongside the provider keys.
+        for id in [
+            crate::wake::ACCESS_KEY_ID,
+            crate::settings::OBS_PASSWORD_KEY,
+        ] {
+            settings.set_api_key(id, self.api_keys.get(id).cloned().unwrap_or_default());
+        }
         if let Some(chrome) = settings
             .app_shortcuts
             .iter()
@@ -129,7 +373,9 @@ impl FormState {
         }
     }
 
-    pub fn reset_non_provider_defaults(&mut self) {
+    /// Dictation tab: microphone, hotkeys, wake word, session limits, meetings,
+    /// screenshots and vision.
+    pub fn reset_dictation_defaults(&mut self) {
         let defaults = Settings::non_provider_reset_defaults();
         self.mic = defaults.mic_device;
         self.vad_mode = defaults.vad_mode;
@@ -138,18 +384,76 @@ impl FormState {
         self.screenshot_hotkey_enabled = defaults.screenshot_hotkey_enabled;
         self.screenshot_retention_count = defaults.screenshot_retention_count;
         self.start_cue = defaults.start_cue;
+        self.snip_editor_path = defaults.snip_editor_path;
+        self.snip_edit_revert = defaults.snip_edit_revert;
+        self.snip_format = defaults.snip_format;
+        self.snip_quality = defaults.snip_quality;
+        self.snip_max_dimension = defaults.snip_max_dimension;
+        self.snip_preview_max_dimension = defaults.snip_preview_max_dimension;
+        self.snip_memory_cap_mb = defaults.snip_memory_cap_mb;
+        self.snip_record_audio = defaults.snip_record_audio;
+        self.snip_magnifier = defaults.snip_magnifier;
+        self.vision_endpoint = defaults.vision_endpoint;
+        self.vision_model = defaults.vision_model;
+        self.vision_prompt = defaults.vision_prompt;
+        self.provider_inactivity_timeout_secs = defaults.provider_inactivity_timeout_secs;
+        self.max_session_length_minutes = defaults.max_session_length_minutes;
+        self.pre_commit_silence_ms = defaults.pre_commit_silence_ms;
+        self.commit_flush_timeout_ms = defaults.commit_flush_timeout_ms;
+        self.ws_tcp_nodelay = defaults.ws_tcp_nodelay;
+        self.transcript_archive_enabled = defaults.transcript_archive_enabled;
+        self.wake_word_enabled = defaults.wake_word_enabled;
+        self.wake_word_library_path = defaults.wake_word_library_path;
+        self.wake_word_model_path = defaults.wake_word_model_path;
+        self.wake_word_keyword_path = defaults.wake_word_keyword_path;
+        self.wake_word_sensitivity = defaults.wake_word_sensitivity;
+        self.meeting_folder = defaults.meeting_folder;
+        self.meeting_system_audio = defaults.meeting_system_audio;
+        self.voice_notes_folder = defaults.voice_notes_folder;
+    }
+
+    /// Appearance tab: theme, sizing, compact window behaviour and placement.
+    pub fn reset_appearance_defaults(&mut self) {
+        let defaults = Settings::non_provider_reset_defaults();
         self.text_size = defaults.text_size;
+        self.ui_scale = defaults.ui_scale;
+        self.theme = defaults.theme;
+        self.custom_accent_hex = hex_color(accent_palette(&defaults.accent_color).base);
         self.accent_color = defaults.accent_color;
         self.compact_background_enabled = defaults.compact_background_enabled;
         self.auto_minimize = defaults.auto_minimize;
+        self.pill_mode = defaults.pill_mode;
+        self.compact_opacity = defaults.compact_opacity;
+        self.visualizer_enabled = defaults.visualizer_enabled;
+        self.visualizer_bands = defaults.visualizer_bands;
+        self.visualizer_fps = defaults.visualizer_fps;
+        self.click_through = defaults.click_through;
+        self.hide_when_fullscreen = defaults.hide_when_fullscreen;
+        self.edge_dock = defaults.edge_dock;
+        self.toast_notifications = defaults.toast_notifications;
         self.update_feed_url_override = defaults.update_feed_url_override;
+        self.update_channel = defaults.update_channel;
+        self.update_verify_signature = defaults.update_verify_signature;
+        self.update_install_on_exit = defaults.update_install_on_exit;
         self.window_monitor_mode = defaults.window_monitor_mode;
         self.window_monitor_id = defaults.window_monitor_id;
         self.window_anchor = defaults.window_anchor;
-        self.snip_editor_path = defaults.snip_editor_path;
-        self.snip_edit_revert = defaults.snip_edit_revert;
-        self.provider_inactivity_timeout_secs = defaults.provider_inactivity_timeout_secs;
-        self.max_session_length_minutes = defaults.max_session_length_minutes;
+    }
+
+    /// Commands tab: browser, aliases, app paths, scripts and per-app overrides.
+    pub fn reset_commands_defaults(&mut self) {
+        let defaults = Settings::non_provider_reset_defaults();
+        self.default_browser = defaults.default_browser;
+        self.chrome_path = defaults.chrome_path;
+        self.paint_path = defaults.paint_path;
+        self.url_commands = defaults.url_commands;
+        self.alias_commands = defaults.alias_commands;
+        self.app_shortcuts = defaults.app_shortcuts;
+        self.script_commands = defaults.script_commands;
+        self.snip_region_presets = defaults.snip_region_presets;
+        self.enabled_plugins = defaults.enabled_plugins;
+        self.hooks_enabled = defaults.hooks_enabled;
+        self.app_overrides = defaults.app_overrides;
     }
 }
 
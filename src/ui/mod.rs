@@ -1,5 +1,6 @@
 pub mod form_state;
 pub mod formatting;
+pub mod perf;
 pub mod snip_overlay;
 pub mod tabs;
 pub mod theme;
@@ -8,10 +9,10 @@ pub mod widgets;
 pub mod window;
 
 use crate::audio;
+use crate::dictation::{Dictation, Refusal};
 use crate::settings::Settings;
 use crate::state::{AppEvent, AppState, SessionUsage};
 use crate::updater::{self, CheckOutcome, ReleaseInfo, WorkerMessage};
-use crate::usage::{append_usage_line, session_usage_path};
 use eframe::egui;
 use egui::{
     pos2, vec2, Color32, Pos2, Rect, Sense, Stroke, TextureHandle, ViewportBuilder,
@@ -30,6 +31,12 @@ use tray::*;
 use widgets::*;
 use window::*;
 
+/// Repaint pacing: the visualizer's frame rate (a setting) while recording,
+/// then slow ticks for timers (status auto-recovery, update checks) when idle
+/// or hidden.
+const IDLE_REPAINT_MS: u64 = 250;
+const HIDDEN_REPAINT_MS: u64 = 1000;
+
 #[derive(Debug, Clone)]
 pub enum UpdateUiState {
     NotChecked,
@@ -37,6 +44,8 @@ pub enum UpdateUiState {
     Installing,
     UpToDate,
     Available { latest: ReleaseInfo },
+    /// Downloaded; installs silently when the app quits.
+    ReadyOnExit { version: String },
     Error(String),
 }
 
@@ -49,10 +58,14 @@ pub struct MangoChatApp {
     pub settings_open: bool,
     pub settings_tab: String,
     pub commands_sub_tab: String,
+    pub command_test_input: String,
+    /// Hooks script in the Commands > Hooks editor; read on first view.
+    pub hooks_source: Option<String>,
     pub status_text: String,
     pub status_state: String,
     pub is_recording: bool,
-    pub audio_capture: Option<crate::audio::AudioCapture>,
+    /// Audio input, timers and usage rows of this window's sessions.
+    dictation: Dictation,
     pub should_quit: bool,
     pub mic_devices: Vec<String>,
 
@@ -65,30 +78,111 @@ pub struct MangoChatApp {
     // Snip overlay state
     pub snip_overlay_active: bool,
     pub snip_texture: Option<TextureHandle>,
+    /// Full capture size in pixels; the texture may be a downscaled preview.
+    pub snip_image_size: [u32; 2],
     pub snip_drag_start: Option<Pos2>,
     pub snip_drag_current: Option<Pos2>,
     pub snip_bounds: Option<crate::snip::MonitorBounds>,
     pub snip_copy_image: bool,
     pub snip_edit_after: bool,
     pub snip_focus_pending: bool,
+    /// Overlay mode for the current snip: a capture mode ("region", "window",
+    /// "ocr", "vision", "attach", "record", "gif"), a redaction tool ("pixelate", "blackout") or
+    /// the "color" eyedropper.
+    pub snip_mode: String,
+    /// Active screen-region video recording, stopped by the snip hotkey.
+    pub region_recorder: Option<crate::recorder::RegionRecorder>,
+    /// Window rects on the captured monitor for window mode (image pixels, topmost first).
+    pub snip_window_rects: Vec<Rect>,
+    /// Previous full capture, kept so Shift + Right Alt can re-crop it.
+    pub last_snip_capture: Option<(image::RgbaImage, crate::snip::MonitorBounds)>,
 
     // Window positioning
     pub positioned: bool,
+    /// Compact window drag in progress: last seen physical position and when it
+    /// last changed. The OS owns the move loop, so the drop is detected by the
+    /// position settling rather than a mouse-up.
+    pub window_drag: Option<((i32, i32), std::time::Instant)>,
     pub initial_position_corrected: bool,
     pub compact_anchor_pos: Option<Pos2>,
 
     // Error auto-recovery
     pub error_time: Option<std::time::Instant>,
+    /// `AppState::audio_chunks_dropped` when last checked.
+    pub audio_drops_seen: u64,
 
     // Settings form fields
     pub form: FormState,
     pub key_check_inflight: HashSet<String>,
     pub key_check_result: HashMap<String, (bool, String)>,
+    /// Logs tab health check: last results and whether a run is in flight.
+    pub health_checks: Vec<crate::health::HealthCheck>,
+    pub health_check_running: bool,
+    /// Mirrors `AppState::online`; false shows the offline banner.
+    pub online: bool,
+    pub perf: perf::PerfStats,
+    /// Live caption client, running while OBS integration is enabled.
+    pub obs: Option<crate::obs::ObsClient>,
+    /// Button state for the Stream Deck plugin, when its endpoint is enabled.
+    pub streamdeck: Option<tokio::sync::watch::Sender<crate::streamdeck::DeckState>>,
+    pub grpc: Option<crate::grpc::Publisher>,
+    /// Connection to the background service that runs dictation, when
+    /// `background_service` is on.
+    pub service: Option<crate::service::ServiceClient>,
+    /// Phone microphone server, and whether the current session listens to it
+    /// instead of the local mic.
+    pub remote_mic: Option<crate::remote_mic::RemoteMic>,
+    pub remote_mic_session: bool,
+    /// Notes tab: voice notes on disk (newest first) and the search filter.
+    pub voice_notes: Vec<crate::notes::VoiceNote>,
+    pub voice_notes_filter: String,
+    /// Meeting transcription in progress, separate from push-to-talk dictation.
+    pub meeting: Option<crate::meeting::Meeting>,
+    /// Wake-word listener, running while enabled and no session is active.
+    pub wake: Option<crate::wake::WakeListener>,
+    /// Config that last failed to start, so a bad key isn't retried every frame.
+    wake_failed: Option<crate::wake::WakeConfig>,
     pub last_validated_provider: Option<String>,
     pub provider_default_explicitly_selected: bool,
     pub session_history: Vec<SessionUsage>,
+    /// Full session log, oldest first, backing the Usage tab charts.
+    pub usage_sessions: Vec<SessionUsage>,
+    /// Usage tab summary bucket: "day", "week" or "month".
+    pub usage_period: String,
+    /// (provider, model) of the last final transcript, until it is rated.
+    pub pending_rating: Option<(String, String)>,
+    /// Thumbs up/down counts per provider and model, shown on the Usage tab.
+    pub feedback_counts: Vec<crate::usage::FeedbackCounts>,
+    /// Days with archived transcripts (newest first) and the export selection.
+    pub archive_days: Vec<String>,
+    pub archive_export_day: String,
+    /// "md" or "txt".
+    pub archive_export_format: String,
+    /// First-run wizard step; `Some` while the wizard replaces the settings tabs.
+    pub onboarding_step: Option<usize>,
+    /// Scratch text box for the wizard's test dictation.
+    pub onboarding_sample: String,
+    /// Recording state last shown in the tray menu.
+    tray_menu_recording: bool,
+    /// Click-through temporarily suspended with the Scroll Lock hotkey.
+    click_through_paused: bool,
+    /// Mouse passthrough state last sent to the viewport.
+    mouse_passthrough: bool,
+    /// Edge dock: undocked window position, last hover and last position sent.
+    dock_home: Option<Pos2>,
+    dock_hover_at: Option<std::time::Instant>,
+    dock_last_pos: Option<Pos2>,
+    /// Compact window hidden because another app went fullscreen.
+    hidden_for_fullscreen: bool,
+    /// Hidden from the tray menu or started with `--minimized`.
+    pub hidden_to_tray: bool,
+    window_visible: bool,
+    fullscreen_last_check: Option<std::time::Instant>,
+    /// Overlay currently drawn on the tray icon.
+    tray_icon_status: TrayIconStatus,
+    /// Last toast shown and when, so repeated errors don't spam notifications.
+    last_toast: Option<(String, std::time::Instant)>,
     control_tooltip: Option<ControlTooltipState>,
-    recording_limit_token: u64,
     pub confirm_reset_totals: bool,
     pub confirm_reset_include_sessions: bool,
     pub selected_mic_unavailable: bool,
@@ -98,9 +192,23 @@ pub struct MangoChatApp {
     pub update_last_check: Option<std::time::Instant>,
     pub update_check_inflight: bool,
     pub update_install_inflight: bool,
+    /// (bytes downloaded, total bytes) while an installer downloads.
+    pub update_download_progress: Option<(u64, Option<u64>)>,
     pub update_startup_check_done: bool,
     pub faq_text_size: f32,
+    /// Logs tab: tail of the session log, level filter and last refresh.
+    pub log_lines: Vec<String>,
+    pub log_filter: String,
+    pub log_last_read: Option<std::time::Instant>,
+    pub logs_size_bytes: u64,
+    /// The previous run crashed or was killed; the Logs tab offers the crash folder.
+    pub previous_crash: bool,
     pub diagnostics_last_export_path: Option<String>,
+    /// About tab: settings backup file and whether API keys go into it.
+    pub settings_bundle_path: String,
+    pub settings_bundle_include_keys: bool,
+    /// `--start-recording`: begin a session once the window is placed.
+    pub start_recording_on_launch: bool,
 }
 
 impl MangoChatApp {
@@ -117,18 +225,19 @@ impl MangoChatApp {
             return;
         }
         self.settings.accent_color = self.form.accent_color.clone();
-        match crate::settings::save(&self.settings) {
-            Ok(()) => {
-                self._tray_icon = setup_tray(accent_palette(&self.settings.accent_color));
-            }
-            Err(e) => {
-                self.set_status(&format!("Save failed: {}", e), "error");
-            }
-        }
+        crate::persist::save_settings(&self.settings);
+        self._tray_icon = setup_tray(
+            accent_palette(&self.settings.accent_color),
+            theme_is_dark(&self.settings.theme),
+            &self.tray_menu_state(),
+        );
+        self.tray_icon_status = TrayIconStatus::Idle;
     }
 
     pub fn provider_form_dirty(&self) -> bool {
-        if self.form.provider != self.settings.provider {
+        if self.form.provider != self.settings.provider
+            || self.form.api_key_file.trim() != self.settings.api_key_file
+        {
             return true;
         }
         for (provider_id, _) in PROVIDER_ROWS {
@@ -147,6 +256,9 @@ impl MangoChatApp {
     }
 
     fn compact_window_width(&self) -> f32 {
+        if self.settings.pill_mode {
+            return PILL_WINDOW_W;
+        }
         let base = if self.settings.screenshot_enabled {
             COMPACT_WINDOW_W_WITH_SNIP
         } else {
@@ -160,6 +272,9 @@ impl MangoChatApp {
     }
 
     fn compact_window_height(&self) -> f32 {
+        if self.settings.pill_mode {
+            return PILL_WINDOW_H;
+        }
         let base = if self.settings.screenshot_enabled {
             COMPACT_WINDOW_H_WITH_SNIP
         } else {
@@ -194,6 +309,7 @@ impl MangoChatApp {
             WINDOW_ANCHOR_TOP_RIGHT => "Top Right",
             WINDOW_ANCHOR_BOTTOM_LEFT => "Bottom Left",
             WINDOW_ANCHOR_BOTTOM_CENTER => "Bottom Center",
+            WINDOW_ANCHOR_CUSTOM => "Where I drag it",
             _ => "Bottom Right",
         }
     }
@@ -209,11 +325,58 @@ impl MangoChatApp {
     }
 
     pub fn provider_display_name(provider_id: &str) -> &str {
-        PROVIDER_ROWS
-            .iter()
-            .find(|(id, _)| *id == provider_id)
-            .map(|(_, name)| *name)
-            .unwrap_or(provider_id)
+        crate::provider::display_name(provider_id)
+    }
+
+    /// Restart the OBS client when its settings changed.
+    fn sync_obs(&mut self) {
+        let config = crate::obs::ObsConfig::from_settings(&self.settings);
+        if self.obs.as_ref().map(|c| c.config()) == Some(&config) {
+            return;
+        }
+        self.obs = config
+            .enabled
+            .then(|| crate::obs::ObsClient::start(&self.runtime, self.state.clone(), config));
+    }
+
+    /// Push saved settings into the shared state read by the hotkey and
+    /// transcription threads, and rebuild the tray for the new accent.
+    fn sync_state_from_settings(&mut self) {
+        crate::plugins::reload(&self.settings.enabled_plugins);
+        crate::hooks::reload(self.settings.hooks_enabled);
+        self.sync_obs();
+        crate::diagnostics::set_log_level(&self.settings.log_level);
+        crate::diagnostics::set_json_logging(self.settings.log_json);
+        self.state.load_commands(&self.settings);
+        self._tray_icon = setup_tray(
+            self.current_accent(),
+            theme_is_dark(&self.settings.theme),
+            &self.tray_menu_state(),
+        );
+        self.tray_icon_status = TrayIconStatus::Idle;
+        self.state.session_hotkey_enabled.store(
+            self.settings.session_hotkey_enabled && self.service.is_none(),
+            Ordering::SeqCst,
+        );
+        self.state
+            .win_h_enabled
+            .store(crate::win_h::is_taken_over(), Ordering::SeqCst);
+        self.state
+            .screenshot_enabled
+            .store(self.settings.screenshot_enabled, Ordering::SeqCst);
+        self.state
+            .screenshot_hotkey_enabled
+            .store(self.settings.screenshot_hotkey_enabled, Ordering::SeqCst);
+        self.state
+            .ws_tcp_nodelay
+            .store(self.settings.ws_tcp_nodelay, Ordering::Relaxed);
+        self.state
+            .visualizer_bands
+            .store(self.settings.visualizer_bands as u64, Ordering::Relaxed);
+        self.state.visualizer_interval_ms.store(
+            1000 / self.settings.visualizer_fps.max(1) as u64,
+            Ordering::Relaxed,
+        );
     }
 
     fn sync_form_from_settings(&mut self) {
@@ -231,7 +394,7 @@ impl MangoChatApp {
         event_rx: EventReceiver<AppEvent>,
         runtime: Arc<tokio::runtime::Runtime>,
         settings: Settings,
-        _egui_ctx: egui::Context,
+        egui_ctx: egui::Context,
     ) -> Self {
         if let Ok(removed) = updater::cleanup_stale_temp_installers(7) {
             if removed > 0 {
@@ -242,32 +405,107 @@ impl MangoChatApp {
             }
         }
 
-        let mic_devices = audio::list_input_devices();
+        // Listing devices can take a while (drivers wake up); the window
+        // shouldn't wait for it.
+        {
+            let devices_tx = event_tx.clone();
+            std::thread::spawn(move || {
+                let devices = audio::list_input_devices();
+                let _ = devices_tx.send(AppEvent::InputDevicesListed(devices));
+            });
+        }
         let form = FormState::from_settings(&settings);
 
         let (update_worker_tx, update_worker_rx) = mpsc::channel::<WorkerMessage>();
 
         // Create tray icon here (inside the event loop) so it stays alive
-        let tray_icon = setup_tray(accent_palette(&settings.accent_color));
+        let tray_icon = setup_tray(
+            accent_palette(&settings.accent_color),
+            theme_is_dark(&settings.theme),
+            &TrayMenuState {
+                recording: false,
+                meeting: false,
+                voice_notes: settings.voice_notes_mode,
+                provider: settings.provider.clone(),
+                providers_with_keys: PROVIDER_ROWS
+                    .iter()
+                    .map(|(id, _)| (id.to_string(), !settings.api_key_for(id).trim().is_empty()))
+                    .collect(),
+                screenshots: settings.screenshot_enabled,
+                pill: settings.pill_mode,
+                window_hidden: false,
+                recent_transcripts: Vec::new(),
+                can_rate: false,
+            },
+        );
         app_log!("[tray] icon created: {}", tray_icon.is_some());
 
         // Background thread for tray events so quit is handled even if the UI thread stalls.
+        // Everything else is forwarded to the UI as an AppEvent.
         {
+            let tray_tx = event_tx.clone();
             std::thread::spawn(move || {
                 while let Ok(event) = tray_icon::menu::MenuEvent::receiver().recv() {
                     let id = event.id.0.as_str();
                     app_log!("[tray-thread] menu event: {}", id);
-                    match id {
+                    let forward = match id {
                         "quit" => {
                             app_log!("[tray-thread] quit — calling process::exit");
+                            crate::persist::flush();
+                            crate::updater::run_install_on_exit();
+                            crate::diagnostics::end_run_marker();
                             std::process::exit(0);
                         }
-                        _ => {}
+                        "toggle_recording" => Some(AppEvent::TrayToggleRecording),
+                        "toggle_meeting" => Some(AppEvent::TrayToggleMeeting),
+                        "toggle_voice_notes" => Some(AppEvent::TrayToggleVoiceNotes),
+                        "toggle_screenshots" => Some(AppEvent::TrayToggleScreenshots),
+                        "toggle_pill" => Some(AppEvent::TrayTogglePill),
+                        "toggle_window" => Some(AppEvent::TrayToggleWindow),
+                        "copy_last" => Some(AppEvent::TrayCopyTranscript(0)),
+                        "rate:up" => Some(AppEvent::RateLastTranscript { accurate: true }),
+                        "rate:down" => Some(AppEvent::RateLastTranscript { accurate: false }),
+                        _ => {
+                            if let Some(provider) = id.strip_prefix("provider:") {
+                                Some(AppEvent::TraySelectProvider(provider.to_string()))
+                            } else {
+                                id.strip_prefix("transcript:")
+                                    .and_then(|n| n.parse().ok())
+                                    .map(AppEvent::TrayCopyTranscript)
+                            }
+                        }
+                    };
+                    if let Some(ev) = forward {
+                        let _ = tray_tx.send(ev);
                     }
                 }
             });
         }
 
+        let obs_config = crate::obs::ObsConfig::from_settings(&settings);
+        let obs = obs_config
+            .enabled
+            .then(|| crate::obs::ObsClient::start(&runtime, state.clone(), obs_config));
+        let streamdeck = settings.streamdeck_enabled.then(|| {
+            crate::streamdeck::start_server(
+                &runtime,
+                state.clone(),
+                event_tx.clone(),
+                settings.streamdeck_port,
+            )
+        });
+        let grpc = settings
+            .grpc_enabled
+            .then(|| crate::grpc::start_server(&runtime, settings.grpc_port));
+        let service = settings
+            .background_service
+            .then(|| crate::service::ServiceClient::start(state.clone(), event_tx.clone()));
+        let remote_mic = settings.remote_mic_enabled.then(|| {
+            crate::remote_mic::start_server(&runtime, event_tx.clone(), settings.remote_mic_port)
+        });
+        let event_rx = wake_on_events(event_rx, egui_ctx);
+        let dictation = Dictation::new(state.clone(), event_tx.clone(), "ui");
+
         Self {
             state,
             event_tx,
@@ -277,34 +515,63 @@ impl MangoChatApp {
             settings_open: false,
             settings_tab: "provider".into(),
             commands_sub_tab: "browser".into(),
+            command_test_input: String::new(),
+            hooks_source: None,
             status_text: "Ready".into(),
             status_state: "idle".into(),
             is_recording: false,
-            audio_capture: None,
+            dictation,
             should_quit: false,
-            mic_devices,
+            mic_devices: Vec::new(),
             _tray_icon: tray_icon,
             positioned: false,
+            window_drag: None,
             initial_position_corrected: false,
             compact_anchor_pos: None,
             mango_texture: None,
             snip_overlay_active: false,
             snip_texture: None,
+            snip_image_size: [0, 0],
             snip_drag_start: None,
             snip_drag_current: None,
             snip_bounds: None,
             snip_copy_image: false,
             snip_edit_after: false,
             snip_focus_pending: false,
+            snip_mode: "region".into(),
+            snip_window_rects: Vec::new(),
+            last_snip_capture: None,
+            region_recorder: None,
             error_time: None,
+            audio_drops_seen: 0,
             form,
             key_check_inflight: HashSet::new(),
             key_check_result: HashMap::new(),
             last_validated_provider: None,
             provider_default_explicitly_selected: false,
             session_history: vec![],
+            usage_sessions: vec![],
+            usage_period: "week".into(),
+            pending_rating: None,
+            feedback_counts: vec![],
+            archive_days: vec![],
+            archive_export_day: String::new(),
+            archive_export_format: "md".into(),
+            tray_menu_recording: false,
+            onboarding_step: (!crate::settings::settings_file_exists()).then_some(0),
+            onboarding_sample: String::new(),
+            tray_icon_status: TrayIconStatus::Idle,
+            click_through_paused: false,
+            hidden_for_fullscreen: false,
+            hidden_to_tray: false,
+            window_visible: true,
+            dock_home: None,
+            dock_hover_at: None,
+            dock_last_pos: None,
+            fullscreen_last_check: None,
+            mouse_passthrough: false,
+            last_toast: None,
             control_tooltip: None,
-            recording_limit_token: 0,
             confirm_reset_totals: false,
             confirm_reset_include_sessions: false,
             selected_mic_unavailable: false,
@@ -314,10 +581,186 @@ impl MangoChatApp {
             update_last_check: None,
             update_check_inflight: false,
             update_install_inflight: false,
+            update_download_progress: None,
             update_startup_check_done: false,
             faq_text_size: 12.0,
+            log_lines: Vec::new(),
+            log_filter: "all".into(),
+            log_last_read: None,
+            logs_size_bytes: 0,
+            previous_crash: false,
+            health_checks: Vec::new(),
+            health_check_running: false,
+            online: true,
+            perf: perf::PerfStats::default(),
+            obs,
+            streamdeck,
+            grpc,
+            service,
+            remote_mic,
+            remote_mic_session: false,
+            voice_notes: Vec::new(),
+            voice_notes_filter: String::new(),
+            meeting: None,
+            wake: None,
+            wake_failed: None,
             diagnostics_last_export_path: None,
+            settings_bundle_path: crate::settings::default_bundle_path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            settings_bundle_include_keys: false,
+            start_recording_on_launch: false,
+        }
+    }
+
+    /// Run the health checks in the background; results arrive as
+    /// `AppEvent::HealthChecksFinished`.
+    pub fn run_health_checks(&mut self) {
+        if self.health_check_running {
+            return;
+        }
+        self.health_check_running = true;
+        let settings = self.settings.clone();
+        let event_tx = self.event_tx.clone();
+        self.runtime.spawn(async move {
+            let checks = crate::health::run_all(settings).await;
+            let failed = checks.iter().filter(|c| !c.passed).count();
+            app_log!("[health] {} checks, {} failed", checks.len(), failed);
+            let _ = event_tx.send(AppEvent::HealthChecksFinished(checks));
+        });
+    }
+
+    /// Check `key` against the provider in the background; the result arrives
+    /// as `AppEvent::ApiKeyValidated`.
+    pub fn validate_api_key(&mut self, provider_id: &str, key: String) {
+        self.key_check_inflight.insert(provider_id.to_string());
+        self.key_check_result.remove(provider_id);
+        self.last_validated_provider = Some(provider_id.to_string());
+        let provider_name = Self::provider_display_name(provider_id).to_string();
+        let provider = crate::provider::create_provider(provider_id);
+        let provider_settings = crate::provider::ProviderSettings {
+            api_key: key,
+            model: self.form.model.clone(),
+            transcription_model: self.settings.transcription_model.clone(),
+            language: self.form.language.clone(),
+            pre_commit_silence_ms: None,
+            commit_flush_timeout_ms: None,
+        };
+        let event_tx = self.event_tx.clone();
+        let validated_provider_id = provider_id.to_string();
+        self.runtime.spawn(async move {
+            let result =
+                crate::provider::session::validate_key(provider, provider_settings).await;
+            let (ok, message) = match result {
+                Ok(()) => (true, format!("{} API key is valid", provider_name)),
+                Err(e) => (false, format!("{} validation failed: {}", provider_name, e)),
+            };
+            let _ = event_tx.send(AppEvent::ApiKeyValidated {
+                provider: validated_provider_id,
+                ok,
+                message,
+            });
+        });
+    }
+
+    /// Persist the wizard's provider, key and mic so the test dictation uses them.
+    pub fn apply_onboarding_form(&mut self) {
+        self.form.apply_to_settings(&mut self.settings);
+        self.selected_mic_unavailable = self.selected_mic_unavailable_now();
+        crate::persist::save_settings(&self.settings);
+    }
+
+    pub fn finish_onboarding(&mut self, ctx: &egui::Context) {
+        if self.is_recording {
+            self.stop_recording();
+        }
+        self.apply_onboarding_form();
+        self.onboarding_step = None;
+        self.onboarding_sample.clear();
+        self.settings_tab = "provider".into();
+        self.compact_anchor_pos = None;
+        self.refresh_tray_menu();
+        self.settings_open = false;
+        self.apply_window_mode(ctx, false);
+        self.set_status("Ready", "idle");
+    }
+
+    pub fn reload_session_history(&mut self) {
+        self.usage_sessions = crate::usage::load_all_sessions();
+        self.session_history = self.usage_sessions.iter().rev().take(5).cloned().collect();
+        self.feedback_counts = crate::usage::load_feedback();
+        self.archive_days = crate::archive::days();
+        if !self.archive_days.contains(&self.archive_export_day) {
+            self.archive_export_day = self.archive_days.first().cloned().unwrap_or_default();
+        }
+    }
+
+    pub fn export_transcript_archive(&mut self) {
+        match crate::archive::export_day(&self.archive_export_day, &self.archive_export_format) {
+            Ok(path) => {
+                let text = format!("Transcripts exported: {}", path.to_string_lossy());
+                self.set_status(&text, "idle");
+            }
+            Err(e) => self.set_status(&e, "error"),
+        }
+    }
+
+    /// Copy a recent transcript (0 is the newest) to the clipboard.
+    fn copy_transcript(&mut self, index: usize) {
+        match self.state.recent_transcripts().get(index) {
+            Some(text) => {
+                crate::typing::copy_to_clipboard(text);
+                self.set_status("Transcript copied", "idle");
+            }
+            None => self.set_status("No transcript to copy", "idle"),
+        }
+    }
+
+    /// Copy actions for the compact window's and pill's right-click menu.
+    fn transcript_menu(&mut self, ui: &mut egui::Ui) {
+        let recent = self.state.recent_transcripts();
+        let copy_last = egui::Button::new("Copy last transcript");
+        if ui.add_enabled(!recent.is_empty(), copy_last).clicked() {
+            ui.close_menu();
+            self.copy_transcript(0);
+        }
+        ui.add_enabled_ui(recent.len() > 1, |ui| {
+            ui.menu_button("Recent transcripts", |ui| {
+                for (i, text) in recent.iter().enumerate() {
+                    if ui.button(transcript_label(text)).clicked() {
+                        ui.close_menu();
+                        self.copy_transcript(i);
+                    }
+                }
+            });
+        });
+    }
+
+    /// Record a thumbs up/down for the last final transcript. Each transcript
+    /// can be rated once.
+    fn rate_last_transcript(&mut self, accurate: bool) {
+        let Some((provider, model)) = self.pending_rating.take() else {
+            self.set_status("No transcript to rate", "idle");
+            return;
+        };
+        match crate::usage::record_feedback(&provider, &model, accurate) {
+            Ok(()) => {
+                self.feedback_counts = crate::usage::load_feedback();
+                self.set_status(
+                    if accurate {
+                        "Rated accurate"
+                    } else {
+                        "Rated inaccurate"
+                    },
+                    "idle",
+                );
+            }
+            Err(e) => {
+                app_err!("[usage] {}", e);
+                self.set_status("Could not save rating", "error");
+            }
         }
+        self.refresh_tray_menu();
     }
 
     pub fn trigger_update_check(&mut self) {
@@ -329,6 +772,7 @@ impl MangoChatApp {
         updater::spawn_check_with_override(
             self.update_worker_tx.clone(),
             Some(self.form.update_feed_url_override.clone()),
+            self.form.update_channel.clone(),
         );
     }
 
@@ -341,9 +785,18 @@ impl MangoChatApp {
             _ => return,
         };
         self.update_install_inflight = true;
+        self.update_download_progress = Some((0, None));
         self.update_state = UpdateUiState::Installing;
-        self.set_status("Downloading installer...", "idle");
-        updater::spawn_install(self.update_worker_tx.clone(), latest);
+        if self.settings.update_install_on_exit {
+            self.set_status("Downloading update in the background...", "idle");
+        } else {
+            self.set_status("Downloading installer...", "idle");
+        }
+        updater::spawn_install(
+            self.update_worker_tx.clone(),
+            latest,
+            self.form.update_verify_signature,
+        );
     }
 
     pub fn open_logs_folder(&mut self) {
@@ -353,12 +806,56 @@ impl MangoChatApp {
         }
     }
 
+    /// Logs tab level picker: takes effect and is saved right away.
+    pub fn set_log_level(&mut self, level: &str) {
+        self.form.log_level = level.to_string();
+        self.settings.log_level = level.to_string();
+        crate::diagnostics::set_log_level(level);
+        crate::persist::save_settings(&self.settings);
+    }
+
+    /// Logs tab JSON toggle: switches the log format immediately and saves.
+    pub fn set_json_logging(&mut self, enabled: bool) {
+        self.form.log_json = enabled;
+        self.settings.log_json = enabled;
+        crate::diagnostics::set_json_logging(enabled);
+        crate::persist::save_settings(&self.settings);
+    }
+
+    /// Show or hide the performance overlay and remember the choice.
+    pub fn set_perf_overlay(&mut self, enabled: bool) {
+        app_log!("[ui] performance overlay {}", if enabled { "on" } else { "off" });
+        if enabled {
+            self.perf = perf::PerfStats::default();
+        }
+        self.form.perf_overlay = enabled;
+        self.settings.perf_overlay = enabled;
+        crate::persist::save_settings(&self.settings);
+    }
+
+    /// Called at startup when the last run didn't exit cleanly.
+    pub fn report_previous_crash(&mut self) {
+        self.previous_crash = true;
+        match crate::diagnostics::latest_crash_file() {
+            Some(path) => app_log!(
+                "[diagnostics] previous run ended abnormally; latest crash report {}",
+                path.display()
+            ),
+            None => app_log!("[diagnostics] previous run ended abnormally"),
+        }
+        self.notify(
+            "Mango Chat closed unexpectedly",
+            "Crash details were saved. Open Settings > Logs to see the crash folder.",
+        );
+    }
+
     pub fn export_diagnostics_zip(&mut self) {
         let Some(path) = crate::diagnostics::default_export_zip_path().ok() else {
             self.set_status("Failed to resolve diagnostics export path", "error");
             return;
         };
-        match crate::diagnostics::export_diagnostics_zip_to(&path) {
+        let monitors = describe_monitors();
+        match crate::diagnostics::export_diagnostics_zip_to(&path, &self.settings, &monitors) {
             Ok(path) => {
                 let text = format!("Diagnostics exported: {}", path.to_string_lossy());
                 self.set_status(&text, "idle");
@@ -368,12 +865,85 @@ impl MangoChatApp {
         }
     }
 
-    fn selected_mic_unavailable_now(&self) -> bool {
-        if self.settings.mic_device.trim().is_empty() {
-            return false;
+    pub fn export_settings_bundle(&mut self) {
+        let path = std::path::PathBuf::from(self.settings_bundle_path.trim());
+        match crate::settings::export_bundle(
+            &self.settings,
+            &path,
+            self.settings_bundle_include_keys,
+        ) {
+            Ok(()) => {
+                let text = format!("Settings exported: {}", path.to_string_lossy());
+                self.set_status(&text, "idle");
+            }
+            Err(e) => self.set_status(&e, "error"),
+        }
+    }
+
+    pub fn import_settings_bundle(&mut self, ctx: &egui::Context) {
+        let path = std::path::PathBuf::from(self.settings_bundle_path.trim());
+        let imported = match crate::settings::import_bundle(&path, &self.settings) {
+            Ok(s) => s,
+            Err(e) => {
+                self.set_status(&e, "error");
+                return;
+            }
+        };
+        self.settings = imported;
+        crate::persist::save_settings(&self.settings);
+        self.selected_mic_unavailable = self.selected_mic_unavailable_now();
+        self.sync_state_from_settings();
+        self.sync_form_from_settings();
+        self.apply_appearance(ctx);
+        self.compact_anchor_pos = None;
+        self.set_status("Settings imported", "idle");
+    }
+
+    /// Pick up edits made to settings.json by other tools. Reloads that match
+    /// what is already applied (including our own saves) are ignored.
+    fn reload_settings_from_disk(&mut self, ctx: &egui::Context) {
+        if crate::persist::settings_pending() {
+            // Our own save is still queued; its write triggers another check.
+            return;
+        }
+        let fresh = match crate::settings::reload() {
+            Ok(s) => s,
+            Err(e) => {
+                app_err!("[settings] reload skipped: {}", e);
+                return;
+            }
+        };
+        let unchanged = serde_json::to_value(&fresh).ok()
+            == serde_json::to_value(&self.settings).ok()
+            && fresh.api_keys == self.settings.api_keys;
+        if unchanged {
+            return;
+        }
+        let provider_changed = fresh.provider != self.settings.provider
+            || fresh.api_key_for(&fresh.provider) != self.settings.api_key_for(&fresh.provider);
+        let mic_changed = fresh.mic_device != self.settings.mic_device;
+        self.settings = fresh;
+        self.selected_mic_unavailable = self.selected_mic_unavailable_now();
+        self.sync_state_from_settings();
+        self.apply_appearance(ctx);
+        if self.is_recording && (provider_changed || mic_changed) {
+            self.stop_recording();
+            self.start_recording();
+        }
+        app_log!("[settings] reloaded from disk");
+        if self.settings_open {
+            // Don't clobber unsaved edits; the form picks the file up next time.
+            self.set_status("Settings changed on disk. Reopen Settings to see them.", "idle");
+        } else {
+            self.sync_form_from_settings();
+            self.compact_anchor_pos = None;
+            self.apply_window_mode(ctx, false);
+            self.set_status("Settings reloaded from disk", "idle");
         }
-        let devices = crate::audio::list_input_devices();
-        !devices.iter().any(|d| d == &self.settings.mic_device)
+    }
+
+    fn selected_mic_unavailable_now(&self) -> bool {
+        crate::dictation::mic_unavailable(&self.settings)
     }
 
     fn apply_appearance(&self, ctx: &egui::Context) {
@@ -386,9 +956,19 @@ impl MangoChatApp {
         style.spacing.item_spacing = vec2(8.0, 6.0);
         style.spacing.button_padding = vec2(8.0, 5.0);
         style.spacing.interact_size.y = 24.0;
-        ctx.set_visuals(egui::Visuals::dark());
-        if (ctx.zoom_factor() - 1.0).abs() > 0.001 {
-            ctx.set_zoom_factor(1.0);
+        // Preview the theme from the form while settings are open, like the accent.
+        let theme = if self.settings_open {
+            &self.form.theme
+        } else {
+            &self.settings.theme
+        };
+        let dark = theme_is_dark(theme);
+        set_dark_theme(dark);
+        ctx.set_visuals(theme_visuals(dark));
+        // Also undoes egui's built-in Ctrl +/- zoom, which would fight window sizing.
+        let scale = ui_scale(&self.settings.text_size, self.settings.ui_scale);
+        if (ctx.zoom_factor() - scale).abs() > 0.001 {
+            ctx.set_zoom_factor(scale);
         }
         ctx.set_style(style);
     }
@@ -435,9 +1015,10 @@ impl MangoChatApp {
             }
         } else if self.settings.window_monitor_mode == WINDOW_MONITOR_MODE_FIXED {
             let _ = place_compact_fixed_native(
-                target,
+                target * ctx.zoom_factor(),
                 &self.settings.window_monitor_id,
                 &self.settings.window_anchor,
+                &self.settings.window_positions,
             );
         } else if let Some(anchor) = self.compact_anchor_pos {
             let pos = clamp_window_pos(
@@ -464,218 +1045,710 @@ impl MangoChatApp {
         ctx.send_viewport_cmd(ViewportCommand::InnerSize(target));
     }
 
-    pub fn set_status(&mut self, text: &str, state: &str) {
-        self.status_text = text.into();
-        self.status_state = state.into();
-        if state == "error" {
-            self.error_time = Some(std::time::Instant::now());
-        } else {
-            self.error_time = None;
-        }
-    }
-
-    fn start_recording(&mut self) {
-        if self.is_recording {
+    /// Save where the compact window was dropped, per monitor, and switch the
+    /// anchor to the remembered position.
+    fn track_window_drag(&mut self, ctx: &egui::Context) {
+        let Some((last, since)) = self.window_drag else {
             return;
-        }
-        let provider_selected = !self.settings.provider.trim().is_empty();
-        let selected_provider_has_key = provider_selected
-            && !self
-                .settings
-                .api_key_for(&self.settings.provider)
-                .trim()
-                .is_empty();
-        if !selected_provider_has_key {
-            if self.settings.has_any_api_key() {
-                self.set_status(
-                    "Select a default provider with an API key in Settings",
-                    "idle",
-                );
-            } else {
-                self.set_status("Set up provider keys in Settings", "idle");
-            }
+        };
+        ctx.request_repaint_after(Duration::from_millis(100));
+        let Some(pos) = window_pos_physical() else {
+            self.window_drag = None;
+            return;
+        };
+        if pos != last {
+            self.window_drag = Some((pos, std::time::Instant::now()));
             return;
         }
-        let unavailable_now = self.selected_mic_unavailable_now();
-        self.selected_mic_unavailable = unavailable_now;
-        if unavailable_now {
-            self.set_status("Device unavailable. Change in Settings.", "error");
+        if since.elapsed() < Duration::from_millis(600) {
             return;
         }
+        self.window_drag = None;
+        let Some(m) = monitor_at_physical(pos.0, pos.1) else {
+            return;
+        };
+        let offset = [pos.0 - m.work_px.left, pos.1 - m.work_px.top];
+        self.settings.window_positions.insert(m.id.clone(), offset);
+        self.settings.window_monitor_id = m.id.clone();
+        self.settings.window_anchor = WINDOW_ANCHOR_CUSTOM.to_string();
+        self.compact_anchor_pos = ctx.input(|i| i.viewport().outer_rect).map(|r| r.min);
+        crate::persist::save_settings(&self.settings);
+        app_log!("[ui] remembered window position {:?} on {}", offset, m.id);
+    }
 
-        if let Err(e) = crate::start_cue::play_start_cue(&self.settings.start_cue) {
-            app_err!("[ui] start cue error: {}", e);
+    fn tray_menu_state(&self) -> TrayMenuState {
+        TrayMenuState {
+            recording: self.is_recording,
+            meeting: self.meeting.is_some(),
+            voice_notes: self.settings.voice_notes_mode,
+            provider: self.settings.provider.clone(),
+            providers_with_keys: PROVIDER_ROWS
+                .iter()
+                .map(|(id, _)| {
+                    (id.to_string(), !self.settings.api_key_for(id).trim().is_empty())
+                })
+                .collect(),
+            screenshots: self.settings.screenshot_enabled,
+            pill: self.settings.pill_mode,
+            window_hidden: self.hidden_to_tray,
+            recent_transcripts: self.state.recent_transcripts(),
+            can_rate: self.pending_rating.is_some(),
         }
+    }
 
-        self.is_recording = true;
-        let mode = match self.settings.vad_mode.as_str() {
-            "lenient" => 1,
-            _ => 0,
+    /// Keep the wake-word listener running only while idle, so it never
+    /// holds the mic alongside a dictation session.
+    fn sync_wake(&mut self) {
+        let wanted = crate::wake::WakeConfig::from_settings(&self.settings)
+            .filter(|_| {
+                !self.is_recording && self.meeting.is_none() && !self.selected_mic_unavailable
+            });
+        if self.wake.as_ref().map(|w| w.config()) == wanted.as_ref() {
+            return;
+        }
+        self.wake = None;
+        let Some(config) = wanted else {
+            return;
         };
-        self.state.vad_mode.store(mode, Ordering::SeqCst);
-
-        let (audio_tx, audio_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
-        if let Ok(mut tx) = self.state.audio_tx.lock() {
-            *tx = Some(audio_tx.clone());
+        if self.wake_failed.as_ref() == Some(&config) {
+            return;
         }
-        if let Ok(mut active) = self.state.session_active.lock() {
-            *active = true;
+        match crate::wake::WakeListener::start(config.clone(), self.event_tx.clone()) {
+            Ok(listener) => {
+                self.wake = Some(listener);
+                self.wake_failed = None;
+            }
+            Err(e) => {
+                app_err!("[wake] {}", e);
+                self.set_status(&format!("Wake word: {}", e), "error");
+                self.wake_failed = Some(config);
+            }
         }
+    }
 
-        let provider = crate::provider::create_provider(&self.settings.provider);
-        let current_key = self
-            .settings
-            .api_key_for(&self.settings.provider)
-            .to_string();
-        let provider_settings = crate::provider::ProviderSettings {
-            api_key: current_key.clone(),
-            model: self.settings.model.clone(),
-            transcription_model: self.settings.transcription_model.clone(),
-            language: self.settings.language.clone(),
+    /// Push recording and provider state to connected Stream Deck buttons.
+    fn publish_deck_state(&self) {
+        let Some(deck) = &self.streamdeck else {
+            return;
+        };
+        let next = crate::streamdeck::DeckState {
+            recording: self.is_recording,
+            provider: self.settings.provider.clone(),
+            providers: PROVIDER_ROWS
+                .iter()
+                .filter(|(id, _)| !self.settings.api_key_for(id).trim().is_empty())
+                .map(|(id, _)| id.to_string())
+                .collect(),
         };
-        let sample_rate = provider.sample_rate_hint();
+        deck.send_if_modified(|current| {
+            if *current == next {
+                return false;
+            }
+            *current = next;
+            true
+        });
+    }
 
-        let mic = if self.settings.mic_device.is_empty() {
-            None
+    pub fn refresh_tray_menu(&self) {
+        if let Some(tray) = self._tray_icon.as_ref() {
+            tray.set_menu(Some(Box::new(build_tray_menu(&self.tray_menu_state()))));
+        }
+    }
+
+    /// Pulse the tray icon while recording and badge it while an error is shown.
+    fn sync_tray_icon(&mut self, ctx: &egui::Context) {
+        let status = if self.status_state == "error" {
+            TrayIconStatus::Error
+        } else if self.is_recording || self.meeting.is_some() {
+            let ms = (ctx.input(|i| i.time) * 1000.0) as u128;
+            let frame = (ms % TRAY_PULSE_PERIOD_MS) * TRAY_PULSE_FRAMES as u128
+                / TRAY_PULSE_PERIOD_MS;
+            TrayIconStatus::Recording(frame as u8)
         } else {
-            Some(self.settings.mic_device.as_str())
+            TrayIconStatus::Idle
         };
-        match audio::AudioCapture::start(
-            mic,
-            audio_tx,
-            self.event_tx.clone(),
-            self.state.clone(),
-            sample_rate,
-        ) {
-            Ok(capture) => {
-                app_log!("[ui] audio capture started");
-                self.audio_capture = Some(capture);
-            }
-            Err(e) => {
-                app_err!("[ui] audio capture error: {}", e);
-                self.set_status(&format!("Mic error: {}", e), "error");
-                self.is_recording = false;
+        if status == self.tray_icon_status {
+            return;
+        }
+        self.tray_icon_status = status;
+        if let Some(tray) = self._tray_icon.as_ref() {
+            update_tray_icon(
+                tray,
+                self.current_accent(),
+                theme_is_dark(&self.settings.theme),
+                status,
+            );
+        }
+    }
+
+    /// Show a toast if notifications are enabled, skipping repeats within a minute.
+    fn notify(&mut self, title: &str, body: &str) {
+        if !self.settings.toast_notifications {
+            return;
+        }
+        let key = format!("{}|{}", title, body);
+        if let Some((last, at)) = self.last_toast.as_ref() {
+            if *last == key && at.elapsed() < Duration::from_secs(60) {
                 return;
             }
         }
+        self.last_toast = Some((key, std::time::Instant::now()));
+        crate::notify::show_toast(title, body);
+    }
 
-        self.recording_limit_token = self.recording_limit_token.saturating_add(1);
-        let limit_token = self.recording_limit_token;
-        let max_minutes = self.settings.max_session_length_minutes.clamp(1, 120);
-        let max_duration = Duration::from_secs(max_minutes.saturating_mul(60));
-        let max_event_tx = self.event_tx.clone();
-        self.runtime.spawn(async move {
-            tokio::time::sleep(max_duration).await;
-            let _ = max_event_tx.send(AppEvent::SessionMaxDurationReached {
-                token: limit_token,
-                minutes: max_minutes,
-            });
-        });
+    /// Warn once per month as `provider` crosses 80% and 100% of its monthly
+    /// budget. With `enforce`, returns false when a new session should be refused.
+    fn check_budget(&mut self, provider: &str, enforce: bool) -> bool {
+        match self.dictation.check_budget(&self.settings, provider, enforce) {
+            Ok(alert) => {
+                self.announce_budget(alert);
+                true
+            }
+            Err(refusal) => {
+                self.show_refusal(refusal);
+                false
+            }
+        }
+    }
+
+    fn announce_budget(&mut self, alert: Option<String>) {
+        if let Some(text) = alert {
+            self.notify("Budget", &text);
+            self.set_status(&text, "idle");
+        }
+    }
+
+    /// Show why a session didn't start.
+    fn show_refusal(&mut self, refusal: Refusal) {
+        if let Refusal::BudgetReached {
+            alert: Some(text), ..
+        } = &refusal
+        {
+            self.notify("Budget", text);
+        }
+        let (message, status) = refusal.status();
+        self.set_status(&message, status);
+    }
 
-        if current_key.is_empty() {
-            self.set_status("Listening (no API key)", "live");
+    /// Hide the compact window while another app is fullscreen and bring it
+    /// back afterwards. Hotkeys keep working since the UI loop keeps running.
+    fn sync_fullscreen_hide(&mut self, ctx: &egui::Context) {
+        if self
+            .fullscreen_last_check
+            .map(|t| t.elapsed() < Duration::from_secs(1))
+            .unwrap_or(false)
+        {
             return;
         }
+        self.fullscreen_last_check = Some(std::time::Instant::now());
+        let hide = self.settings.hide_when_fullscreen
+            && !self.settings_open
+            && !self.snip_overlay_active
+            && foreground_is_fullscreen();
+        if hide != self.hidden_for_fullscreen {
+            app_log!(
+                "[ui] fullscreen app {}",
+                if hide { "detected, hiding" } else { "gone, restoring" }
+            );
+            self.hidden_for_fullscreen = hide;
+        }
+        self.sync_window_visible(ctx);
+    }
 
-        let gen = self.state.session_gen.fetch_add(1, Ordering::SeqCst) + 1;
-        let now = now_ms();
-        if let Ok(mut totals) = self.state.usage.lock() {
-            totals.provider = self.settings.provider.clone();
-            totals.model = self.settings.model.clone();
-            totals.last_update_ms = now;
-        }
-        if let Ok(mut session) = self.state.session_usage.lock() {
-            *session = crate::state::SessionUsage {
-                session_id: now,
-                provider: self.settings.provider.clone(),
-                model: self.settings.model.clone(),
-                bytes_sent: 0,
-                ms_sent: 0,
-                ms_suppressed: 0,
-                commits: 0,
-                finals: 0,
-                started_ms: now,
-                updated_ms: now,
-            };
+    fn sync_window_visible(&mut self, ctx: &egui::Context) {
+        let visible = !self.hidden_to_tray && !self.hidden_for_fullscreen;
+        if visible != self.window_visible {
+            self.window_visible = visible;
+            ctx.send_viewport_cmd(ViewportCommand::Visible(visible));
         }
+    }
 
-        let event_tx = self.event_tx.clone();
-        let state_clone = self.state.clone();
-        let inactivity_timeout_secs = self.settings.provider_inactivity_timeout_secs;
+    /// Slide the compact window to a thin strip on its anchored edge while idle,
+    /// and back out on hover or when recording starts.
+    fn sync_edge_dock(&mut self, ctx: &egui::Context) {
+        let now = std::time::Instant::now();
+        if ctx.input(|i| i.pointer.hover_pos().is_some()) {
+            self.dock_hover_at = Some(now);
+        }
+        let hovered_recently = self
+            .dock_hover_at
+            .map(|t| now.duration_since(t) < Duration::from_millis(1200))
+            .unwrap_or(false);
+        let want_docked = self.settings.edge_dock
+            && !self.settings_open
+            && !self.is_recording
+            && !self.snip_overlay_active
+            && self.window_drag.is_none()
+            && !hovered_recently;
+        let t = ctx.animate_bool_with_time(egui::Id::new("edge_dock"), want_docked, 0.18);
+
+        if t <= 0.0 {
+            if let Some(home) = self.dock_home.take() {
+                ctx.send_viewport_cmd(ViewportCommand::OuterPosition(home));
+                self.dock_last_pos = None;
+            }
+            return;
+        }
+        let Some(outer) = ctx.input(|i| i.viewport().outer_rect) else {
+            return;
+        };
+        let home = *self.dock_home.get_or_insert(outer.min);
+        let Some(work) = work_area_rect_logical(
+            ctx,
+            &self.settings.window_monitor_mode,
+            &self.settings.window_monitor_id,
+        ) else {
+            return;
+        };
+        let docked = docked_window_pos(
+            Rect::from_min_size(home, outer.size()),
+            work,
+            &self.settings.window_anchor,
+        );
+        let pos = home + (docked - home) * t;
+        if self.dock_last_pos.map(|p| p.distance(pos) > 0.5).unwrap_or(true) {
+            ctx.send_viewport_cmd(ViewportCommand::OuterPosition(pos));
+            self.dock_last_pos = Some(pos);
+        }
+    }
 
-        self.runtime.spawn(async move {
-            crate::provider::session::run_session(
-                provider,
-                event_tx,
-                state_clone.clone(),
-                provider_settings,
-                audio_rx,
-                inactivity_timeout_secs,
-            )
-            .await;
+    fn select_provider(&mut self, provider: &str) {
+        if self.settings.api_key_for(provider).trim().is_empty() {
+            return;
+        }
+        self.settings.provider = provider.to_string();
+        self.form.provider = provider.to_string();
+        crate::persist::save_settings(&self.settings);
+        // The service picks up the new provider from its next session.
+        if self.is_recording && self.service.is_none() {
+            self.swap_provider(provider);
+        } else {
+            self.set_status(&format!("Provider: {}", provider), "idle");
+        }
+        self.refresh_tray_menu();
+    }
 
-            if state_clone.session_gen.load(Ordering::SeqCst) == gen {
-                if let Ok(mut active) = state_clone.session_active.lock() {
-                    *active = false;
-                }
-                if let Ok(mut tx) = state_clone.audio_tx.lock() {
-                    *tx = None;
+    /// Switch to the next provider (in `PROVIDER_ROWS` order) that has an API key.
+    fn cycle_provider(&mut self) {
+        let with_keys: Vec<&str> = PROVIDER_ROWS
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !self.settings.api_key_for(id).trim().is_empty())
+            .collect();
+        let current = with_keys
+            .iter()
+            .position(|id| *id == self.settings.provider);
+        let next = match current {
+            Some(i) => with_keys[(i + 1) % with_keys.len()],
+            None => match with_keys.first() {
+                Some(id) => *id,
+                None => return,
+            },
+        };
+        if next != self.settings.provider {
+            self.select_provider(next);
+        }
+    }
+
+    pub fn set_status(&mut self, text: &str, state: &str) {
+        self.status_text = text.into();
+        self.status_state = state.into();
+        if state == "error" {
+            self.error_time = Some(std::time::Instant::now());
+        } else {
+            self.error_time = None;
+        }
+    }
+
+    fn start_recording(&mut self) {
+        if self.is_recording {
+            return;
+        }
+        if self.meeting.is_some() {
+            self.set_status("Stop meeting transcription to dictate", "idle");
+            return;
+        }
+        if let Some(service) = &self.service {
+            // The service runs the same checks as below when it starts; the
+            // phone mic only feeds sessions in this window.
+            if self.remote_mic_session {
+                self.set_status("Switch to the local mic to use the background service", "idle");
+                return;
+            }
+            service.set_recording(true);
+            return;
+        }
+        let local_mic = !self.remote_mic_session;
+        let mut plan = match self.dictation.plan(&self.settings, local_mic) {
+            Ok(plan) => plan,
+            Err(refusal) => {
+                if matches!(refusal, Refusal::MicUnavailable) {
+                    self.selected_mic_unavailable = true;
                 }
-                state_clone.hotkey_recording.store(false, Ordering::SeqCst);
+                self.show_refusal(refusal);
+                return;
             }
-        });
+        };
+        if local_mic {
+            self.selected_mic_unavailable = false;
+        }
+        self.announce_budget(plan.budget_alert.take());
 
-        self.set_status("Connecting...", "live");
+        self.is_recording = true;
+        let provider_id = plan.provider_id.clone();
+        let remote = self.remote_mic.as_ref().filter(|_| self.remote_mic_session);
+        match self.dictation.start(&self.settings, &self.runtime, plan, remote) {
+            Ok(session_id) => {
+                self.publish_session_started(session_id, &provider_id);
+                self.set_status("Connecting...", "live");
+            }
+            Err(refusal) => {
+                self.is_recording = false;
+                self.show_refusal(refusal);
+            }
+        }
+    }
+
+    /// Switch the recording session to `provider_id`: the current provider
+    /// session drains its last transcripts and closes while a new one opens.
+    /// The mic reopens at the new provider's sample rate; the session
+    /// timer keeps running. Each provider gets its own usage row; the old
+    /// one is closed once its session has drained.
+    fn swap_provider(&mut self, provider_id: &str) {
+        let current = self.session_provider();
+        if current == provider_id || !self.check_budget(provider_id, true) {
+            return;
+        }
+        let notes_dir = if self.settings.voice_notes_mode {
+            crate::notes::notes_dir(&self.settings).ok()
+        } else {
+            None
+        };
+        app_log!("[ui] switching provider mid-session: {} -> {}", current, provider_id);
+        self.dictation.stop_input(self.remote_mic.as_ref());
+        let replaced = self.state.drain_session_usage();
+        self.close_usage_row(&replaced);
+        let remote = self.remote_mic.as_ref().filter(|_| self.remote_mic_session);
+        match self
+            .dictation
+            .start_leg(&self.settings, &self.runtime, provider_id, notes_dir, remote)
+        {
+            Ok(session_id) => {
+                self.publish_session_started(session_id, provider_id);
+                self.set_status("Connecting...", "live");
+            }
+            Err(refusal) => {
+                self.stop_recording();
+                self.show_refusal(refusal);
+            }
+        }
     }
 
     fn stop_recording(&mut self) {
         if !self.is_recording {
             return;
         }
+        if let Some(service) = &self.service {
+            service.set_recording(false);
+            return;
+        }
         if let Err(e) = crate::start_cue::play_stop_cue() {
             app_err!("[ui] stop cue error: {}", e);
         }
         self.is_recording = false;
-        self.audio_capture = None;
+        self.dictation.stop_input(self.remote_mic.as_ref());
+        self.remote_mic_session = false;
 
         if let Ok(mut tx) = self.state.audio_tx.lock() {
             *tx = None;
         }
-        if let Ok(mut active) = self.state.session_active.lock() {
-            *active = false;
-        }
+        self.state.session_active.store(false, Ordering::SeqCst);
         self.state.hotkey_recording.store(false, Ordering::SeqCst);
 
-        if let Ok(mut data) = self.state.fft_data.lock() {
-            *data = [0.0; 50];
-        }
+        self.state.fft_data.store(Default::default());
 
         self.set_status("Ready", "idle");
+        self.end_usage_session();
+    }
 
-        if let Ok(mut session) = self.state.session_usage.lock() {
-            if session.started_ms != 0 && session.bytes_sent > 0 {
-                if let Ok(path) = session_usage_path() {
-                    let snapshot = session.clone();
-                    let _ = append_usage_line(&path, &snapshot);
-                }
+    /// Start per-session usage counters (and the log session tag) for `provider_id`.
+    fn begin_usage_session(&self, provider_id: &str) {
+        let session_id = self.dictation.begin_usage(&self.settings, provider_id);
+        self.publish_session_started(session_id, provider_id);
+    }
+
+    fn publish_session_started(&self, session_id: u64, provider_id: &str) {
+        if let Some(grpc) = &self.grpc {
+            grpc.session_started(session_id, provider_id, &self.settings.model);
+        }
+    }
+
+    /// Provider of the session in progress, or "" between sessions.
+    fn session_provider(&self) -> String {
+        self.state.session_usage.load().provider.clone()
+    }
+
+    /// Id of the session in progress, or 0 between sessions.
+    fn current_session_id(&self) -> u64 {
+        self.state.session_usage.load().session_id
+    }
+
+    /// Close the running session's usage row.
+    fn end_usage_session(&mut self) {
+        let session = self.dictation.end_usage();
+        self.close_usage_row(&session);
+    }
+
+    /// Record a finished usage row in the usage log and announce any budget
+    /// threshold it crossed.
+    fn close_usage_row(&mut self, session: &SessionUsage) {
+        if let Some(grpc) = self.grpc.as_ref().filter(|_| session.session_id != 0) {
+            grpc.session_ended(session.session_id);
+        }
+        // Announce a threshold this session crossed; blocking waits for the next start.
+        let alert = self.dictation.close_usage_row(&self.settings, session);
+        self.announce_budget(alert);
+    }
+
+    /// Switch voice notes mode and save it. Takes effect from the next session.
+    pub fn set_voice_notes_mode(&mut self, enabled: bool) {
+        self.settings.voice_notes_mode = enabled;
+        self.form.voice_notes_mode = enabled;
+        crate::persist::save_settings(&self.settings);
+        self.refresh_tray_menu();
+    }
+
+    pub fn reload_voice_notes(&mut self) {
+        self.voice_notes = match crate::notes::notes_dir(&self.settings) {
+            Ok(dir) => crate::notes::list(&dir),
+            Err(e) => {
+                app_err!("[notes] {}", e);
+                Vec::new()
+            }
+        };
+    }
+
+    pub fn toggle_meeting(&mut self) {
+        if self.meeting.is_some() {
+            self.stop_meeting();
+        } else {
+            self.start_meeting();
+        }
+    }
+
+    /// Transcribe mic and system audio into a Markdown document until stopped.
+    /// Push-to-talk dictation is unavailable meanwhile.
+    fn start_meeting(&mut self) {
+        if self.meeting.is_some() {
+            return;
+        }
+        if self.is_recording {
+            self.stop_recording();
+        }
+        let provider_id = self.settings.provider.clone();
+        if !self.check_budget(&provider_id, true) {
+            return;
+        }
+        // Release the wake listener's mic before opening it again.
+        self.wake = None;
+        match crate::meeting::Meeting::start(
+            &self.runtime,
+            self.state.clone(),
+            self.event_tx.clone(),
+            &self.settings,
+        ) {
+            Ok(meeting) => {
+                self.begin_usage_session(&provider_id);
+                self.meeting = Some(meeting);
+                self.set_status("Meeting transcription on", "live");
+            }
+            Err(e) => {
+                app_err!("[meeting] {}", e);
+                self.set_status(&format!("Meeting: {}", e), "error");
             }
-            *session = crate::state::SessionUsage::default();
         }
+        self.refresh_tray_menu();
+    }
+
+    fn stop_meeting(&mut self) {
+        let Some(meeting) = self.meeting.take() else {
+            return;
+        };
+        let path = meeting.path().display().to_string();
+        drop(meeting);
+        self.state.fft_data.store(Default::default());
+        self.end_usage_session();
+        self.set_status("Meeting saved", "idle");
+        self.notify("Meeting saved", &path);
+        self.refresh_tray_menu();
     }
 
-    fn process_events(&mut self) {
+    fn process_events(&mut self, ctx: &egui::Context) {
+        let mut drained = 0;
         while let Ok(event) = self.event_rx.try_recv() {
+            drained += 1;
             match event {
                 AppEvent::HotkeyPush => self.start_recording(),
                 AppEvent::HotkeyRelease => self.stop_recording(),
-                AppEvent::StatusUpdate { status, message } => self.set_status(&message, &status),
+                AppEvent::UsageLoaded => {
+                    if self.settings_open {
+                        self.reload_session_history();
+                    }
+                }
+                AppEvent::InputDevicesListed(devices) => {
+                    let mic = self.settings.mic_device.trim();
+                    self.selected_mic_unavailable =
+                        !mic.is_empty() && !devices.iter().any(|d| d == mic);
+                    self.mic_devices = devices;
+                }
+                AppEvent::StatusUpdate { status, message } => {
+                    if status == "error" {
+                        self.notify("Error", &message);
+                    }
+                    self.set_status(&message, &status);
+                }
                 AppEvent::TranscriptDelta(text) => {
-                    let _ = text;
+                    if let Some(grpc) = &self.grpc {
+                        grpc.transcript(self.current_session_id(), &text, false);
+                    }
+                    if let Some(obs) = &self.obs {
+                        let provider = Some(self.session_provider())
+                            .filter(|p| !p.is_empty())
+                            .unwrap_or_else(|| self.settings.provider.clone());
+                        obs.send(crate::obs::Caption::Delta {
+                            text,
+                            incremental: provider == "openai",
+                        });
+                    }
                 }
                 AppEvent::TranscriptFinal(text) => {
-                    let _ = text;
+                    if !text.trim().is_empty() {
+                        if let Some(obs) = &self.obs {
+                            obs.send(crate::obs::Caption::Final(text.clone()));
+                        }
+                        let session = Some(self.state.session_usage.load_full())
+                            .filter(|s| !s.provider.is_empty())
+                            .map(|s| (s.session_id, s.provider.clone(), s.model.clone()));
+                        let (session_id, provider, model) = session.unwrap_or_else(|| {
+                            (0, self.settings.provider.clone(), self.settings.model.clone())
+                        });
+                        if let Some(grpc) = &self.grpc {
+                            grpc.transcript(session_id, &text, true);
+                        }
+                        if self.settings.transcript_archive_enabled {
+                            if let Err(e) = crate::archive::record(session_id, &provider, &text) {
+                                app_err!("[archive] {}", e);
+                            }
+                        }
+                        self.pending_rating = Some((provider, model));
+                        self.refresh_tray_menu();
+                    }
+                }
+                AppEvent::TrayToggleRecording => {
+                    if self.is_recording {
+                        self.stop_recording();
+                    } else {
+                        self.start_recording();
+                    }
+                }
+                AppEvent::TrayToggleMeeting => self.toggle_meeting(),
+                AppEvent::TrayToggleVoiceNotes => {
+                    self.set_voice_notes_mode(!self.settings.voice_notes_mode);
+                }
+                AppEvent::ServiceRecording(active) => {
+                    self.is_recording = active;
+                    self.refresh_tray_menu();
+                }
+                AppEvent::VoiceNoteSaved => {
+                    if self.settings_tab == "notes" {
+                        self.reload_voice_notes();
+                    }
+                }
+                AppEvent::TraySelectProvider(provider) => self.select_provider(&provider),
+                AppEvent::TrayToggleScreenshots => {
+                    self.settings.screenshot_enabled = !self.settings.screenshot_enabled;
+                    self.state
+                        .screenshot_enabled
+                        .store(self.settings.screenshot_enabled, Ordering::SeqCst);
+                    crate::persist::save_settings(&self.settings);
+                    self.form.screenshot_enabled = self.settings.screenshot_enabled;
+                    if !self.settings_open {
+                        self.apply_window_mode(ctx, false);
+                    }
+                    self.refresh_tray_menu();
+                }
+                AppEvent::TrayToggleWindow => {
+                    self.hidden_to_tray = !self.hidden_to_tray;
+                    self.sync_window_visible(ctx);
+                    if !self.hidden_to_tray {
+                        ctx.send_viewport_cmd(ViewportCommand::Focus);
+                    }
+                    self.refresh_tray_menu();
+                }
+                AppEvent::ShowWindow => {
+                    if self.hidden_to_tray {
+                        self.hidden_to_tray = false;
+                        self.sync_window_visible(ctx);
+                        self.refresh_tray_menu();
+                    }
+                    ctx.send_viewport_cmd(ViewportCommand::Focus);
+                }
+                AppEvent::WakeWordDetected => {
+                    if !self.is_recording {
+                        self.start_recording();
+                    }
+                }
+                AppEvent::TrayTogglePill => {
+                    self.settings.pill_mode = !self.settings.pill_mode;
+                    self.form.pill_mode = self.settings.pill_mode;
+                    crate::persist::save_settings(&self.settings);
+                    if !self.settings_open {
+                        self.apply_window_mode(ctx, false);
+                    }
+                    self.refresh_tray_menu();
+                }
+                AppEvent::ToggleClickThrough => {
+                    if self.settings.click_through {
+                        self.click_through_paused = !self.click_through_paused;
+                        self.set_status(
+                            if self.click_through_paused {
+                                "Window interactive"
+                            } else {
+                                "Click-through on"
+                            },
+                            "idle",
+                        );
+                    }
+                }
+                AppEvent::SettingsFileChanged => self.reload_settings_from_disk(ctx),
+                AppEvent::SystemThemeChanged => {
+                    // Visuals follow on the next frame; the tray icon needs a rebuild.
+                    if let Some(tray) = self._tray_icon.as_ref() {
+                        update_tray_icon(
+                            tray,
+                            self.current_accent(),
+                            theme_is_dark(&self.settings.theme),
+                            self.tray_icon_status,
+                        );
+                    }
                 }
+                AppEvent::TrayCopyTranscript(index) => self.copy_transcript(index),
+                AppEvent::RateLastTranscript { accurate } => self.rate_last_transcript(accurate),
                 AppEvent::SnipTrigger => self.trigger_snip(),
+                AppEvent::SnipRecrop => self.recrop_last_snip(),
+                AppEvent::SnipInstant { all_monitors } => self.instant_snip(all_monitors),
+                AppEvent::SnipRepeat => self.repeat_last_snip(),
+                AppEvent::SnipPreset(name) => self.snip_region_preset(&name),
+                AppEvent::SnipQuestion(text) => {
+                    let img = self.state.attached_snip.lock().ok().and_then(|mut g| g.take());
+                    if let Some(img) = img {
+                        let commands = self.state.commands.load_full();
+                        let tx = self.event_tx.clone();
+                        std::thread::spawn(move || {
+                            crate::vision::answer_with_snip(&commands.vision, &text, &img, &tx);
+                        });
+                    }
+                }
                 AppEvent::SessionInactivityTimeout { seconds } => {
                     if self.is_recording {
                         self.stop_recording();
@@ -683,12 +1756,18 @@ impl MangoChatApp {
                     }
                 }
                 AppEvent::SessionMaxDurationReached { token, minutes } => {
-                    if self.is_recording && token == self.recording_limit_token {
+                    if self.is_recording && self.dictation.is_current_limit(token) {
                         self.stop_recording();
-                        self.set_status(
-                            &format!("Stopped at max session length ({}m)", minutes),
-                            "idle",
-                        );
+                        let message = format!("Stopped at max session length ({}m)", minutes);
+                        self.notify("Dictation stopped", &message);
+                        self.set_status(&message, "idle");
+                    }
+                }
+                AppEvent::ProviderLegDrained { gen } => {
+                    // Only a leg replaced by a provider switch has a row left.
+                    if self.state.draining_gen.load(Ordering::SeqCst) == gen {
+                        let drained = self.state.take_draining_usage();
+                        self.close_usage_row(&drained);
                     }
                 }
                 AppEvent::ApiKeyValidated {
@@ -697,14 +1776,57 @@ impl MangoChatApp {
                     message,
                 } => {
                     self.key_check_inflight.remove(&provider);
+                    if !ok {
+                        self.notify("API key invalid", &format!("{}: {}", provider, message));
+                    }
                     self.last_validated_provider = Some(provider.clone());
                     self.key_check_result.insert(provider, (ok, message));
                 }
+                AppEvent::ConnectivityChanged { online } => {
+                    self.online = online;
+                    if online {
+                        if self.status_text.starts_with("Offline") {
+                            self.set_status("Back online", "idle");
+                        }
+                    } else {
+                        self.notify("Offline", "Dictation resumes when the network is back.");
+                    }
+                }
+                AppEvent::TogglePerfOverlay => {
+                    let enabled = !self.settings.perf_overlay;
+                    self.set_perf_overlay(enabled);
+                }
+                AppEvent::HealthChecksFinished(checks) => {
+                    self.health_check_running = false;
+                    self.health_checks = checks;
+                }
+                AppEvent::SetRecording(start) => {
+                    if start && !self.is_recording {
+                        self.start_recording();
+                    } else if !start && self.is_recording {
+                        self.stop_recording();
+                    }
+                }
+                AppEvent::RemoteMicStart => {
+                    if !self.is_recording {
+                        self.remote_mic_session = true;
+                        self.start_recording();
+                        if !self.is_recording {
+                            self.remote_mic_session = false;
+                        }
+                    }
+                }
+                AppEvent::RemoteMicStop => {
+                    if self.is_recording && self.remote_mic_session {
+                        self.stop_recording();
+                    }
+                }
                 AppEvent::AudioInputLost { message } => {
                     app_err!("[ui] audio input lost: {}", message);
                     if self.is_recording {
                         self.stop_recording();
                     }
+                    self.stop_meeting();
                     if !self.settings.mic_device.trim().is_empty() {
                         self.selected_mic_unavailable = true;
                         self.set_status("Device unavailable. Change in Settings.", "error");
@@ -714,6 +1836,7 @@ impl MangoChatApp {
                 }
             }
         }
+        self.perf.record_events(drained);
 
         while let Ok(msg) = self.update_worker_rx.try_recv() {
             match msg {
@@ -725,18 +1848,43 @@ impl MangoChatApp {
                             self.update_state = UpdateUiState::UpToDate;
                         }
                         Ok(CheckOutcome::UpdateAvailable { latest }) => {
+                            self.notify(
+                                "Update available",
+                                &format!("Version {} is ready to install", latest.version),
+                            );
                             self.update_state = UpdateUiState::Available { latest };
+                            // Fetch in the background; it installs when the app quits.
+                            if self.settings.update_install_on_exit {
+                                self.trigger_update_install();
+                            }
                         }
                         Err(e) => {
                             self.update_state = UpdateUiState::Error(e.clone());
                         }
                     }
                 }
+                WorkerMessage::DownloadProgress { downloaded, total } => {
+                    self.update_download_progress = Some((downloaded, total));
+                }
                 WorkerMessage::InstallFinished(result) => {
                     self.update_install_inflight = false;
+                    self.update_download_progress = None;
                     match result {
-                        Ok(installer_path) => {
-                            match updater::schedule_silent_install_and_relaunch(&installer_path) {
+                        Ok(installer) if self.settings.update_install_on_exit => {
+                            self.set_status(
+                                &format!(
+                                    "Update {} downloaded; it installs when you quit",
+                                    installer.version
+                                ),
+                                "idle",
+                            );
+                            self.update_state = UpdateUiState::ReadyOnExit {
+                                version: installer.version.to_string(),
+                            };
+                            updater::set_install_on_exit(installer);
+                        }
+                        Ok(installer) => {
+                            match updater::schedule_silent_install(&installer, true) {
                                 Ok(()) => {
                                     self.set_status("Installing update...", "idle");
                                     self.should_quit = true;
@@ -818,8 +1966,91 @@ impl MangoChatApp {
             });
     }
 
+    fn open_settings(&mut self, ctx: &egui::Context) {
+        self.settings_open = true;
+        self.sync_form_from_settings();
+        self.reload_session_history();
+        self.apply_window_mode(ctx, true);
+    }
+
+    /// Pill mode: just a record dot and a tiny visualizer that doubles as the
+    /// drag handle. Right-click opens Settings.
+    fn render_pill(&mut self, ctx: &egui::Context) {
+        let p = theme_palette(is_dark_theme());
+        let accent = self.current_accent();
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().inner_margin(egui::Margin::symmetric(6.0, 0.0)))
+            .show(ctx, |ui| {
+                let bg_rect = ui.max_rect().expand2(vec2(5.0, -1.0));
+                let opacity = self.settings.compact_opacity as f32 / 100.0;
+                ui.painter().rect(
+                    bg_rect,
+                    bg_rect.height() / 2.0,
+                    p.settings_bg.gamma_multiply(opacity),
+                    Stroke::new(1.0, p.btn_border.gamma_multiply(opacity)),
+                );
+                let t = ctx.input(|i| i.time) as f32;
+                ui.horizontal_centered(|ui| {
+                    ui.spacing_mut().item_spacing.x = 4.0;
+                    let can_start = self.is_recording
+                        || !self
+                            .settings
+                            .api_key_for(&self.settings.provider)
+                            .trim()
+                            .is_empty();
+                    let dot = ui
+                        .add_enabled_ui(can_start, |ui| {
+                            pill_mic_dot(ui, self.is_recording, accent, t)
+                        })
+                        .inner;
+                    if dot.clicked() {
+                        if self.is_recording {
+                            self.stop_recording();
+                        } else {
+                            self.start_recording();
+                        }
+                    }
+                    let fft = self.state.fft_data.load_full();
+                    let (viz_rect, viz_resp) = ui.allocate_exact_size(
+                        vec2(ui.available_width(), 14.0),
+                        Sense::click_and_drag(),
+                    );
+                    if viz_resp.hovered() {
+                        ctx.set_cursor_icon(egui::CursorIcon::Grab);
+                    }
+                    if viz_resp.drag_started() {
+                        ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+                        self.window_drag = Some(((i32::MIN, i32::MIN), std::time::Instant::now()));
+                    }
+                    draw_dancing_strings(
+                        ui.painter(),
+                        viz_rect,
+                        t,
+                        if self.is_recording { Some(fft.as_slice()) } else { None },
+                        accent,
+                    );
+                    if !self.online {
+                        paint_offline_banner(ui.painter(), viz_rect);
+                    }
+                    let viz_resp = viz_resp.on_hover_text("Drag to move, right-click for more");
+                    viz_resp.context_menu(|ui| {
+                        self.transcript_menu(ui);
+                        ui.separator();
+                        if ui.button("Settings").clicked() {
+                            ui.close_menu();
+                            self.open_settings(ctx);
+                        }
+                    });
+                });
+            });
+    }
+
     fn render_main_ui(&mut self, ctx: &egui::Context) {
-        let p = theme_palette(true);
+        if !self.settings_open && self.settings.pill_mode {
+            self.render_pill(ctx);
+            return;
+        }
+        let p = theme_palette(is_dark_theme());
         let accent = self.current_accent();
         let show_screenshot_controls = self.settings.screenshot_enabled;
         // preset_btn closure removed — using widgets::preset_icon_button instead
@@ -852,15 +2083,20 @@ impl MangoChatApp {
             .show(ctx, |ui| {
                 if compact_bg {
                     let bg_rect = ui.max_rect().expand2(vec2(12.0, 8.0));
-                    ui.painter()
-                        .rect(bg_rect, 12.0, p.settings_bg, Stroke::new(1.0, p.btn_border));
+                    let opacity = self.settings.compact_opacity as f32 / 100.0;
+                    ui.painter().rect(
+                        bg_rect,
+                        12.0,
+                        p.settings_bg.gamma_multiply(opacity),
+                        Stroke::new(1.0, p.btn_border.gamma_multiply(opacity)),
+                    );
                 }
 
                 // --- Audio device label (compact mode only) ---
                 if !self.settings_open {
                     let max_chars = 55;
                     let mic_color;
-                    let text_color;
+                    let label_color;
                     let display_text;
                     let use_sparkle_icon;
                     let missing_provider_keys = !self.settings.has_any_api_key();
@@ -878,7 +2114,7 @@ impl MangoChatApp {
 
                     if self.is_recording {
                         mic_color = accent.base;
-                        text_color = accent.base;
+                        label_color = accent.base;
 
                         // Build the alternating messages
                         let dev = if self.settings.mic_device.trim().is_empty() {
@@ -942,8 +2178,8 @@ impl MangoChatApp {
 
                         ctx.request_repaint();
                     } else {
-                        mic_color = TEXT_COLOR;
-                        text_color = TEXT_MUTED;
+                        mic_color = text_color();
+                        label_color = text_muted();
                         if update_available || missing_provider_keys {
                             let mut messages = vec!["Not listening".to_string()];
                             if missing_provider_keys {
@@ -1002,7 +2238,22 @@ impl MangoChatApp {
                     let row_w = ui.available_width();
                     let icon_s = 16.0;
                     let icon_alloc = icon_s + 3.0;
-                    ui.allocate_ui(vec2(row_w, 16.0), |ui| {
+                    let badge = (!self.settings.provider.trim().is_empty()).then(|| {
+                        let name = MangoChatApp::provider_display_name(&self.settings.provider);
+                        (
+                            format!(
+                                "{} {}",
+                                name.chars().next().unwrap_or('?'),
+                                self.settings.language.to_uppercase()
+                            ),
+                            format!(
+                                "{} · {} (click to switch provider)",
+                                name, self.settings.language
+                            ),
+                            MangoChatApp::provider_color(&self.settings.provider, p),
+                        )
+                    });
+                    let badge_clicked = ui.allocate_ui(vec2(row_w, 16.0), |ui| {
                         ui.spacing_mut().interact_size.y = 16.0;
                         ui.horizontal(|ui| {
                             ui.spacing_mut().item_spacing.x = 0.0;
@@ -1028,16 +2279,33 @@ impl MangoChatApp {
                                     t,
                                 );
                             }
-                            ui.add(
-                                egui::Label::new(
-                                    egui::RichText::new(display_text)
-                                        .size(10.5)
-                                        .color(text_color),
-                                )
-                                .truncate(),
-                            );
-                        });
-                    });
+                            let badge_w = if badge.is_some() { 34.0 } else { 0.0 };
+                            ui.allocate_ui(vec2(ui.available_width() - badge_w, 16.0), |ui| {
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(display_text)
+                                            .size(10.5)
+                                            .color(label_color),
+                                    )
+                                    .truncate(),
+                                );
+                            });
+                            match &badge {
+                                Some((text, tip, color)) => {
+                                    ui.add_space((ui.available_width() - badge_w).max(0.0));
+                                    provider_badge(ui, text, *color)
+                                        .on_hover_text(tip.as_str())
+                                        .clicked()
+                                }
+                                None => false,
+                            }
+                        })
+                        .inner
+                    })
+                    .inner;
+                    if badge_clicked {
+                        self.cycle_provider();
+                    }
                     ui.add_space(2.0);
                 }
 
@@ -1075,21 +2343,97 @@ impl MangoChatApp {
                         }
                         let settings_w = 28.0;
                         let right_edge_pad = 6.0;
-                        let right_controls_w = settings_w + right_edge_pad;
+                        // Elapsed time and estimated cost while recording, so
+                        // runaway sessions are obvious.
+                        let ticker = if self.is_recording && !self.settings_open {
+                            let s = self.state.session_usage.load();
+                            (s.started_ms != 0).then(|| {
+                                let elapsed = now_ms().saturating_sub(s.started_ms) / 1000;
+                                (
+                                    format!("{:02}:{:02}", elapsed / 60, elapsed % 60),
+                                    format!(
+                                        "${:.2}",
+                                        crate::usage::estimated_cost(&s.provider, s.ms_sent)
+                                    ),
+                                )
+                            })
+                        } else {
+                            None
+                        };
+                        let ticker_w = if ticker.is_some() { 40.0 } else { 0.0 };
+                        let right_controls_w = settings_w + right_edge_pad + ticker_w;
                         let min_viz_w = 56.0;
                         let viz_w = (ui.available_width() - right_controls_w).max(min_viz_w);
-                        let fft = self.state.fft_data.lock().map(|d| *d).unwrap_or([0.0; 50]);
+                        let fft = self.state.fft_data.load_full();
                         let t = ctx.input(|i| i.time) as f32;
-                        let (viz_rect, _) =
-                            ui.allocate_exact_size(vec2(viz_w, 20.0), Sense::hover());
+                        // The visualizer strip doubles as the compact window's drag handle.
+                        let (viz_rect, viz_resp) = ui.allocate_exact_size(
+                            vec2(viz_w, 20.0),
+                            if self.settings_open {
+                                Sense::hover()
+                            } else {
+                                Sense::click_and_drag()
+                            },
+                        );
+                        if !self.settings_open {
+                            if viz_resp.hovered() {
+                                ctx.set_cursor_icon(egui::CursorIcon::Grab);
+                            }
+                            if viz_resp.drag_started() {
+                                ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+                                self.window_drag =
+                                    Some(((i32::MIN, i32::MIN), std::time::Instant::now()));
+                            }
+                            viz_resp.context_menu(|ui| self.transcript_menu(ui));
+                        }
                         draw_dancing_strings(
                             ui.painter(),
                             viz_rect,
                             t,
-                            if self.is_recording { Some(&fft) } else { None },
+                            if self.is_recording { Some(fft.as_slice()) } else { None },
                             accent,
                         );
+                        if !self.online {
+                            paint_offline_banner(ui.painter(), viz_rect);
+                        } else if let Some(meeting) = self.meeting.as_ref() {
+                            let minutes = (chrono::Local::now() - meeting.started()).num_minutes();
+                            paint_meeting_banner(
+                                ui.painter(),
+                                viz_rect,
+                                accent.base,
+                                &format!("MEETING {}m · {}", minutes, meeting.line_count()),
+                            );
+                        } else if let Some(wake) = self.wake.as_ref() {
+                            let resp = ui.interact(
+                                paint_wake_indicator(ui.painter(), viz_rect, accent.base, t),
+                                ui.id().with("wake_indicator"),
+                                Sense::hover(),
+                            );
+                            resp.on_hover_text(format!(
+                                "Listening for \"{}\"",
+                                wake.config().phrase()
+                            ));
+                        }
                         let viz_center = viz_rect.center();
+                        if let Some((elapsed, cost)) = &ticker {
+                            let (ticker_rect, _) =
+                                ui.allocate_exact_size(vec2(ticker_w, 24.0), Sense::hover());
+                            let painter = ui.painter();
+                            painter.text(
+                                pos2(ticker_rect.center().x, ticker_rect.min.y + 6.0),
+                                egui::Align2::CENTER_CENTER,
+                                elapsed,
+                                egui::FontId::monospace(10.0),
+                                accent.base,
+                            );
+                            painter.text(
+                                pos2(ticker_rect.center().x, ticker_rect.max.y - 6.0),
+                                egui::Align2::CENTER_CENTER,
+                                cost,
+                                egui::FontId::monospace(9.0),
+                                text_muted(),
+                            );
+                        }
                         let record_tip = if self.is_recording { "Stop" } else { "Start" };
                         self.paint_control_tooltip(
                             ctx,
@@ -1130,10 +2474,7 @@ impl MangoChatApp {
                                 Some(viz_center),
                             );
                             if settings_resp.clicked() {
-                                self.settings_open = true;
-                                self.sync_form_from_settings();
-                                self.session_history = crate::usage::load_recent_sessions(5);
-                                self.apply_window_mode(ctx, true);
+                                self.open_settings(ctx);
                             }
                         }
                         ui.add_space(right_edge_pad);
@@ -1213,6 +2554,25 @@ impl MangoChatApp {
                         .show(ui, |ui| {
                             ui.spacing_mut().item_spacing.y = 4.0;
 
+                            if !self.online {
+                                egui::Frame::none()
+                                    .fill(RED.gamma_multiply(0.15))
+                                    .stroke(Stroke::new(1.0, RED))
+                                    .rounding(4.0)
+                                    .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+                                    .show(ui, |ui| {
+                                        ui.set_min_width(ui.available_width());
+                                        ui.label(
+                                            egui::RichText::new(
+                                                "Offline. Dictation will connect when the network is back.",
+                                            )
+                                            .size(12.0)
+                                            .color(RED),
+                                        );
+                                    });
+                                ui.add_space(6.0);
+                            }
+
                             // ── Tab bar ──
                             let prev_tab = self.settings_tab.clone();
                             ui.horizontal_top(|ui| {
@@ -1229,12 +2589,28 @@ impl MangoChatApp {
                                         );
                                         ui.add_space(6.0);
 
+                                        if self.onboarding_step.is_some()
+                                            && widgets::tab_button(
+                                                ui,
+                                                "welcome",
+                                                "Setup",
+                                                self.settings_tab == "welcome",
+                                                accent,
+                                                nav_w - 8.0,
+                                            )
+                                            .clicked()
+                                        {
+                                            self.settings_tab = "welcome".into();
+                                        }
                                         for (id, label) in [
                                             ("provider", "Provider"),
                                             ("dictation", "Session"),
                                             ("commands", "Commands"),
                                             ("appearance", "Appearance"),
                                             ("usage", "Usage"),
+                                            ("notes", "Notes"),
+                                            ("integrations", "Integrations"),
+                                            ("logs", "Logs"),
                                             ("faq", "FAQ"),
                                             ("about", "About"),
                                         ] {
@@ -1259,8 +2635,10 @@ impl MangoChatApp {
                                 ui.add_space(8.0);
                                 ui.vertical(|ui| {
                                     if self.settings_tab == "usage" && prev_tab != "usage" {
-                                        self.session_history =
-                                            crate::usage::load_recent_sessions(5);
+                                        self.reload_session_history();
+                                    }
+                                    if self.settings_tab == "notes" && prev_tab != "notes" {
+                                        self.reload_voice_notes();
                                     }
                                     ui.add_space(2.0);
 
@@ -1273,6 +2651,7 @@ impl MangoChatApp {
                                             | "dictation"
                                             | "commands"
                                             | "appearance"
+                                            | "integrations"
                                             | "about"
                                     );
                                     let save_reserve = if has_save { 38.0 } else { 0.0 };
@@ -1299,12 +2678,24 @@ impl MangoChatApp {
                                             "usage" => {
                                                 tabs::usage::render(self, ui, ctx);
                                             }
+                                            "integrations" => {
+                                                tabs::integrations::render(self, ui, ctx);
+                                            }
+                                            "notes" => {
+                                                tabs::notes::render(self, ui, ctx);
+                                            }
                                             "about" => {
                                                 tabs::about::render_about(self, ui, ctx);
                                             }
                                             "faq" => {
                                                 tabs::about::render_faq(self, ui, ctx);
                                             }
+                                            "welcome" => {
+                                                tabs::onboarding::render(self, ui, ctx);
+                                            }
+                                            "logs" => {
+                                                tabs::logs::render(self, ui, ctx);
+                                            }
                                             _ => {}
                                         }
                                     });
@@ -1317,6 +2708,7 @@ impl MangoChatApp {
                                             | "dictation"
                                             | "commands"
                                             | "appearance"
+                                            | "integrations"
                                             | "about"
                                     ) {
                                         ui.add_space(6.0);
@@ -1348,20 +2740,20 @@ impl MangoChatApp {
                                                             .size(15.0)
                                                             .strong()
                                                             .color(if show_exit {
-                                                                TEXT_COLOR
+                                                                text_color()
                                                             } else {
                                                                 Color32::BLACK
                                                             }),
                                                     )
                                                     .fill(if show_exit {
-                                                        BTN_BG
+                                                        btn_bg()
                                                     } else {
                                                         accent.base
                                                     })
                                                     .stroke(Stroke::new(
                                                         1.0,
                                                         if show_exit {
-                                                            BTN_BORDER
+                                                            btn_border()
                                                         } else {
                                                             accent.ring
                                                         },
@@ -1399,103 +2791,51 @@ impl MangoChatApp {
                                                 self.form.apply_to_settings(&mut self.settings);
                                                 self.selected_mic_unavailable =
                                                     self.selected_mic_unavailable_now();
-                                                match crate::settings::save(&self.settings) {
-                                                    Ok(()) => {
-                                                        if let Ok(mut p) =
-                                                            self.state.chrome_path.lock()
-                                                        {
-                                                            *p = self
-                                                                .settings
-                                                                .resolved_browser_path();
-                                                        }
-                                                        if let Ok(mut p) =
-                                                            self.state.paint_path.lock()
-                                                        {
-                                                            *p = self.settings.paint_path.clone();
-                                                        }
-                                                        if let Ok(mut v) =
-                                                            self.state.url_commands.lock()
-                                                        {
-                                                            *v = self
-                                                                .settings
-                                                                .url_commands
-                                                                .iter()
-                                                                .map(|c| {
-                                                                    (
-                                                                        c.trigger.clone(),
-                                                                        c.url.clone(),
-                                                                    )
-                                                                })
-                                                                .collect();
-                                                        }
-                                                        if let Ok(mut v) =
-                                                            self.state.alias_commands.lock()
-                                                        {
-                                                            *v = self
-                                                                .settings
-                                                                .alias_commands
-                                                                .iter()
-                                                                .map(|c| {
-                                                                    (
-                                                                        c.trigger.clone(),
-                                                                        c.replacement.clone(),
-                                                                    )
-                                                                })
-                                                                .collect();
-                                                        }
-                                                        if let Ok(mut v) =
-                                                            self.state.app_shortcuts.lock()
-                                                        {
-                                                            *v = self
-                                                                .settings
-                                                                .app_shortcuts
-                                                                .iter()
-                                                                .map(|c| {
-                                                                    (
-                                                                        c.trigger.clone(),
-                                                                        c.path.clone(),
-                                                                    )
-                                                                })
-                                                                .collect();
-                                                        }
-                                                        self._tray_icon =
-                                                            setup_tray(self.current_accent());
-                                                        self.state.session_hotkey_enabled.store(
-                                                            self.settings.session_hotkey_enabled,
-                                                            Ordering::SeqCst,
-                                                        );
-                                                        self.state.screenshot_enabled.store(
-                                                            self.settings.screenshot_enabled,
-                                                            Ordering::SeqCst,
-                                                        );
-                                                        self.state.screenshot_hotkey_enabled.store(
-                                                            self.settings.screenshot_hotkey_enabled,
-                                                            Ordering::SeqCst,
-                                                        );
-                                                        if was_recording
-                                                            && (self.settings_tab == "provider"
-                                                                || mic_device_changed)
-                                                        {
-                                                            self.stop_recording();
-                                                            self.start_recording();
-                                                        }
-                                                        if self.settings_tab == "provider" {
-                                                            self.compact_anchor_pos = None;
-                                                            self.set_status("Saved", "idle");
-                                                            self.settings_open = false;
-                                                            self.apply_window_mode(ctx, false);
-                                                        } else {
-                                                            self.apply_appearance(ctx);
-                                                            self.compact_anchor_pos = None;
-                                                            self.set_status("Saved", "idle");
-                                                            self.settings_open = false;
-                                                            self.apply_window_mode(ctx, false);
-                                                        }
+                                                if self.form.start_with_windows
+                                                    != crate::autostart::is_enabled()
+                                                {
+                                                    if let Err(e) = crate::autostart::set_enabled(
+                                                        self.form.start_with_windows,
+                                                    ) {
+                                                        app_err!("[ui] {}", e);
+                                                    }
+                                                }
+                                                if self.form.win_h_dictation
+                                                    != crate::win_h::is_taken_over()
+                                                {
+                                                    if let Err(e) = crate::win_h::set_taken_over(
+                                                        self.form.win_h_dictation,
+                                                    ) {
+                                                        app_err!("[ui] {}", e);
                                                     }
-                                                    Err(e) => self.set_status(
-                                                        &format!("Save failed: {}", e),
-                                                        "error",
-                                                    ),
+                                                }
+                                                crate::persist::save_settings(&self.settings);
+                                                self.sync_state_from_settings();
+                                                let provider = self.settings.provider.clone();
+                                                if was_recording
+                                                    && !mic_device_changed
+                                                    && self.settings_tab == "provider"
+                                                    && self.session_provider() != provider
+                                                {
+                                                    self.swap_provider(&provider);
+                                                } else if was_recording
+                                                    && (self.settings_tab == "provider"
+                                                        || mic_device_changed)
+                                                {
+                                                    self.stop_recording();
+                                                    self.start_recording();
+                                                }
+                                                if self.settings_tab == "provider" {
+                                                    self.compact_anchor_pos = None;
+                                                    self.set_status("Saved", "idle");
+                                                    self.settings_open = false;
+                                                    self.apply_window_mode(ctx, false);
+                                                } else {
+                                                    self.apply_appearance(ctx);
+                                                    self.compact_anchor_pos = None;
+                                                    self.set_status("Saved", "idle");
+                                                    self.settings_open = false;
+                                                    self.apply_window_mode(ctx, false);
                                                 }
                                             }
                                         }
@@ -1506,7 +2846,7 @@ impl MangoChatApp {
                                                     "Sign up for free credits at Deepgram ($200) and AssemblyAI ($50).",
                                                 )
                                                 .size(11.0)
-                                                .color(TEXT_MUTED),
+                                                .color(text_muted()),
                                             );
                                         }
                                     }
@@ -1532,15 +2872,42 @@ impl MangoChatApp {
 impl eframe::App for MangoChatApp {
     fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
         if self.settings_open {
-            SETTINGS_BG.to_normalized_gamma_f32()
+            settings_bg().to_normalized_gamma_f32()
         } else {
             Color32::TRANSPARENT.to_normalized_gamma_f32()
         }
     }
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if self.settings.perf_overlay {
+            self.perf.begin_frame(frame.info().cpu_usage);
+        }
         self.apply_appearance(ctx);
-        self.process_events();
+        self.process_events(ctx);
+        self.publish_deck_state();
+        self.sync_wake();
+        if self.tray_menu_recording != self.is_recording {
+            self.tray_menu_recording = self.is_recording;
+            self.refresh_tray_menu();
+        }
+        self.sync_tray_icon(ctx);
+        self.sync_fullscreen_hide(ctx);
+        self.sync_edge_dock(ctx);
+        let passthrough =
+            self.settings.click_through && !self.click_through_paused && !self.settings_open;
+        if passthrough != self.mouse_passthrough {
+            self.mouse_passthrough = passthrough;
+            ctx.send_viewport_cmd(ViewportCommand::MousePassthrough(passthrough));
+        }
+        self.track_window_drag(ctx);
+        if self
+            .region_recorder
+            .as_ref()
+            .map(|r| r.is_finished())
+            .unwrap_or(false)
+        {
+            self.stop_region_recording();
+        }
 
         if !self.update_startup_check_done
             && !self.update_check_inflight
@@ -1556,9 +2923,10 @@ impl eframe::App for MangoChatApp {
             ctx.send_viewport_cmd(ViewportCommand::InnerSize(compact_size));
             if self.settings.window_monitor_mode == WINDOW_MONITOR_MODE_FIXED {
                 let placed = place_compact_fixed_native(
-                    compact_size,
+                    compact_size * ctx.zoom_factor(),
                     &self.settings.window_monitor_id,
                     &self.settings.window_anchor,
+                    &self.settings.window_positions,
                 );
                 self.positioned = placed;
                 self.initial_position_corrected = placed;
@@ -1591,6 +2959,21 @@ impl eframe::App for MangoChatApp {
                 }
             }
         }
+        // First run: open the setup wizard once the window has been placed.
+        if self.onboarding_step == Some(0)
+            && self.settings_tab != "welcome"
+            && self.positioned
+            && !self.settings_open
+        {
+            self.settings_open = true;
+            self.settings_tab = "welcome".into();
+            self.sync_form_from_settings();
+            self.apply_window_mode(ctx, true);
+        }
+        if self.start_recording_on_launch && self.positioned && !self.settings_open {
+            self.start_recording_on_launch = false;
+            self.start_recording();
+        }
         // Compact mode should never maximize/snap-maximize.
         if !self.settings_open && ctx.input(|i| i.viewport().maximized).unwrap_or(false) {
             ctx.send_viewport_cmd(ViewportCommand::Maximized(false));
@@ -1626,11 +3009,23 @@ impl eframe::App for MangoChatApp {
             }
         }
 
+        // The audio channel overflowed: say so instead of silently losing words.
+        let drops = self.state.audio_chunks_dropped.load(Ordering::Relaxed);
+        if drops != self.audio_drops_seen {
+            self.audio_drops_seen = drops;
+            if self.is_recording {
+                self.set_status("Network can't keep up, dropping audio", "live");
+            }
+        }
+
         // Close → quit app directly.
         if ctx.input(|i| i.viewport().close_requested()) {
             self.should_quit = true;
         }
         if self.should_quit {
+            crate::persist::flush();
+            updater::run_install_on_exit();
+            crate::diagnostics::end_run_marker();
             std::process::exit(0);
         }
 
@@ -1648,11 +3043,13 @@ impl eframe::App for MangoChatApp {
         // Snip overlay viewport
         if self.snip_overlay_active {
             let vp = if let Some(b) = &self.snip_bounds {
-                let scale = if b.scale_factor > 0.0 {
+                // Viewport geometry is in zoomed points (see `apply_appearance`).
+                let native = if b.scale_factor > 0.0 {
                     b.scale_factor
                 } else {
                     1.0
                 };
+                let scale = native * ctx.zoom_factor();
                 let logical_x = b.x as f32 / scale;
                 let logical_y = b.y as f32 / scale;
                 let logical_w = b.width as f32 / scale;
@@ -1680,11 +3077,42 @@ impl eframe::App for MangoChatApp {
             );
         }
 
-        // Repaint rate
-        if self.is_recording {
-            ctx.request_repaint();
-        } else {
-            ctx.request_repaint_after(Duration::from_millis(33));
+        if self.settings.perf_overlay && !perf::show_overlay(ctx, &self.perf, &self.state) {
+            self.set_perf_overlay(false);
         }
+
+        // Events and input wake the UI on their own; this only paces the
+        // visualizer and timers.
+        let visible = !self.hidden_to_tray && !self.hidden_for_fullscreen;
+        let visualizer = visible && self.settings.visualizer_enabled;
+        self.state
+            .visualizer_active
+            .store(visualizer, Ordering::Relaxed);
+        let repaint_ms = if self.is_recording && visualizer {
+            1000 / self.settings.visualizer_fps.max(1) as u64
+        } else if visible {
+            IDLE_REPAINT_MS
+        } else {
+            HIDDEN_REPAINT_MS
+        };
+        ctx.request_repaint_after(Duration::from_millis(repaint_ms));
     }
 }
+
+/// Forward app events to a new receiver, waking egui for each one so the
+/// UI reacts immediately without polling at a fixed frame rate.
+fn wake_on_events(
+    event_rx: EventReceiver<AppEvent>,
+    ctx: egui::Context,
+) -> EventReceiver<AppEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = event_rx.recv() {
+            if tx.send(event).is_err() {
+                return;
+            }
+            ctx.request_repaint();
+        }
+    });
+    rx
+}
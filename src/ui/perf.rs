@@ -0,0 +1,144 @@
+use eframe::egui;
+use egui::{vec2, Color32, ViewportBuilder, ViewportId};
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use crate::state::AppState;
+use crate::ui::theme::*;
+
+/// Frames kept for the frame-time graph (about two seconds at 60 fps).
+const HISTORY: usize = 120;
+/// Frames slower than this are drawn in red.
+const SLOW_FRAME_MS: f32 = 33.0;
+
+/// Numbers behind the debug performance overlay, sampled once per frame.
+#[derive(Default)]
+pub struct PerfStats {
+    last_frame: Option<Instant>,
+    /// Wall time between frames, ms.
+    frame_ms: VecDeque<f32>,
+    /// CPU time eframe spent in the previous `update`, ms.
+    cpu_ms: f32,
+    /// App events drained this frame and the most seen since the overlay opened.
+    events: usize,
+    events_peak: usize,
+}
+
+impl PerfStats {
+    pub fn begin_frame(&mut self, cpu_usage: Option<f32>) {
+        let now = Instant::now();
+        if let Some(last) = self.last_frame {
+            if self.frame_ms.len() == HISTORY {
+                self.frame_ms.pop_front();
+            }
+            self.frame_ms.push_back(now.duration_since(last).as_secs_f32() * 1000.0);
+        }
+        self.last_frame = Some(now);
+        self.cpu_ms = cpu_usage.unwrap_or(0.0) * 1000.0;
+    }
+
+    pub fn record_events(&mut self, drained: usize) {
+        self.events = drained;
+        self.events_peak = self.events_peak.max(drained);
+    }
+
+    fn frame_summary(&self) -> (f32, f32) {
+        if self.frame_ms.is_empty() {
+            return (0.0, 0.0);
+        }
+        let avg = self.frame_ms.iter().sum::<f32>() / self.frame_ms.len() as f32;
+        let max = self.frame_ms.iter().cloned().fold(0.0, f32::max);
+        (avg, max)
+    }
+}
+
+/// Audio chunks queued for the provider session (0 when not recording).
+fn audio_backlog(state: &AppState) -> usize {
+    state
+        .audio_tx
+        .lock()
+        .ok()
+        .and_then(|tx| tx.as_ref().map(|tx| tx.max_capacity() - tx.capacity()))
+        .unwrap_or(0)
+}
+
+/// Small always-on-top window with frame times, event queue depth, audio
+/// backlog and websocket send latency.
+pub fn show_overlay(ctx: &egui::Context, stats: &PerfStats, state: &AppState) -> bool {
+    let mut open = true;
+    let (avg, max) = stats.frame_summary();
+    let backlog = audio_backlog(state);
+    let ws_last = state.ws_send_latency_us.load(Ordering::Relaxed) as f32 / 1000.0;
+    let ws_peak = state.ws_send_latency_peak_us.load(Ordering::Relaxed) as f32 / 1000.0;
+    let frames: Vec<f32> = stats.frame_ms.iter().cloned().collect();
+    let (cpu_ms, events, events_peak) = (stats.cpu_ms, stats.events, stats.events_peak);
+
+    ctx.show_viewport_immediate(
+        ViewportId::from_hash_of("perf-overlay"),
+        ViewportBuilder::default()
+            .with_title("Mango Chat performance")
+            .with_inner_size(vec2(280.0, 190.0))
+            .with_always_on_top()
+            .with_resizable(false),
+        |ctx, _class| {
+            if ctx.input(|i| i.viewport().close_requested()) {
+                open = false;
+            }
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let row = |ui: &mut egui::Ui, label: &str, value: String, warn: bool| {
+                    ui.label(egui::RichText::new(label).size(11.0).color(text_muted()));
+                    ui.label(
+                        egui::RichText::new(value)
+                            .monospace()
+                            .size(11.0)
+                            .color(if warn { RED } else { text_color() }),
+                    );
+                    ui.end_row();
+                };
+                egui::Grid::new("perf_overlay_grid")
+                    .num_columns(2)
+                    .spacing(vec2(10.0, 2.0))
+                    .show(ui, |ui| {
+                        row(
+                            ui,
+                            "Frame time",
+                            format!("avg {:.1} ms  max {:.1} ms", avg, max),
+                            max > SLOW_FRAME_MS,
+                        );
+                        row(ui, "Update CPU", format!("{:.2} ms", cpu_ms), cpu_ms > 8.0);
+                        row(
+                            ui,
+                            "Event queue",
+                            format!("{} now  {} peak", events, events_peak),
+                            events > 32,
+                        );
+                        row(ui, "Audio backlog", format!("{} chunks", backlog), backlog > 16);
+                        row(
+                            ui,
+                            "WS send",
+                            format!("{:.1} ms  peak {:.1} ms", ws_last, ws_peak),
+                            ws_last > 50.0,
+                        );
+                    });
+                ui.add_space(6.0);
+                let (rect, _) =
+                    ui.allocate_exact_size(vec2(ui.available_width(), 48.0), egui::Sense::hover());
+                let painter = ui.painter();
+                painter.rect_filled(rect, 2.0, Color32::from_black_alpha(40));
+                let bar_w = rect.width() / HISTORY as f32;
+                for (i, ms) in frames.iter().enumerate() {
+                    let h = (ms / (SLOW_FRAME_MS * 2.0)).min(1.0) * rect.height();
+                    let x = rect.min.x + i as f32 * bar_w;
+                    let bar = egui::Rect::from_min_max(
+                        egui::pos2(x, rect.max.y - h),
+                        egui::pos2(x + bar_w.max(1.0), rect.max.y),
+                    );
+                    let color = if *ms > SLOW_FRAME_MS { RED } else { AMBER };
+                    painter.rect_filled(bar, 0.0, color);
+                }
+            });
+        },
+    );
+    open
+}
@@ -1,30 +1,33 @@
 use eframe::egui;
-use egui::{pos2, vec2, Color32, CursorIcon, FontId, Rect, Sense, Stroke, ViewportCommand};
+use egui::{
+    pos2, vec2, Color32, CursorIcon, FontId, Key, Rect, Sense, Stroke, Vec2, ViewportCommand,
+};
 use std::sync::atomic::Ordering;
 
-use super::theme::TEXT_COLOR;
+// The overlay sits on a dimmed screenshot, so it always uses the dark palette.
+use super::theme::DARK_PALETTE as OVERLAY;
 use super::MangoChatApp;
 
+/// Selection the keyboard starts from when nothing is selected yet, in points.
+const KEYBOARD_SELECTION: Vec2 = vec2(320.0, 180.0);
+/// Number keys and the width:height ratios they reshape the selection to.
+const ASPECT_PRESETS: [(Key, f32); 2] = [(Key::Num1, 16.0 / 9.0), (Key::Num2, 1.0)];
+
 impl MangoChatApp {
     pub fn trigger_snip(&mut self) {
+        if self.region_recorder.is_some() {
+            self.state.snip_active.store(false, Ordering::SeqCst);
+            self.stop_region_recording();
+            return;
+        }
         if !self.state.screenshot_enabled.load(Ordering::SeqCst) {
             return;
         }
-        let cursor = self.state.cursor_pos.lock().ok().and_then(|v| *v);
+        let cursor = self.state.cursor_pos();
         let state = self.state.clone();
 
         match crate::snip::capture_screen(cursor) {
-            Ok((img, bounds)) => {
-                if let Ok(mut guard) = state.snip_image.lock() {
-                    *guard = Some(img);
-                }
-                self.snip_bounds = Some(bounds);
-                self.snip_overlay_active = true;
-                self.snip_texture = None;
-                self.snip_drag_start = None;
-                self.snip_drag_current = None;
-                self.snip_focus_pending = true;
-            }
+            Ok((img, bounds)) => self.open_snip_overlay(img, bounds),
             Err(e) => {
                 app_err!("[ui] capture error: {}", e);
                 state.snip_active.store(false, Ordering::SeqCst);
@@ -32,56 +35,389 @@ impl MangoChatApp {
         }
     }
 
+    fn open_snip_overlay(&mut self, img: image::RgbaImage, bounds: crate::snip::MonitorBounds) {
+        let held = self
+            .last_snip_capture
+            .as_ref()
+            .map_or(0, |(last, _)| crate::snip::image_bytes(last));
+        if held > 0 && held + crate::snip::image_bytes(&img) > self.snip_memory_cap() {
+            app_log!("[snip] dropping previous capture to stay under the memory cap");
+            self.last_snip_capture = None;
+        }
+        self.snip_image_size = [img.width(), img.height()];
+        if let Ok(mut guard) = self.state.snip_image.lock() {
+            *guard = Some(img);
+        }
+        self.snip_window_rects = crate::snip::window_rects(&bounds)
+            .into_iter()
+            .map(|(x, y, w, h)| {
+                Rect::from_min_size(pos2(x as f32, y as f32), vec2(w as f32, h as f32))
+            })
+            .collect();
+        self.snip_bounds = Some(bounds);
+        self.snip_overlay_active = true;
+        self.snip_texture = None;
+        self.snip_drag_start = None;
+        self.snip_drag_current = None;
+        self.snip_focus_pending = true;
+        self.snip_mode = "region".into();
+    }
+
+    fn snip_memory_cap(&self) -> usize {
+        self.settings.snip_memory_cap_mb as usize * 1024 * 1024
+    }
+
+    pub fn snip_output(&self) -> crate::snip::SnipOutput {
+        crate::snip::SnipOutput {
+            format: self.settings.snip_format.clone(),
+            quality: self.settings.snip_quality,
+            max_dimension: self.settings.snip_max_dimension,
+        }
+    }
+
+    /// Capture without the overlay and deliver it using the active preset.
+    pub fn instant_snip(&mut self, all_monitors: bool) {
+        if !self.state.screenshot_enabled.load(Ordering::SeqCst) {
+            return;
+        }
+        let captured = if all_monitors {
+            crate::snip::capture_all_monitors()
+        } else {
+            let cursor = self.state.cursor_pos();
+            crate::snip::capture_screen(cursor).map(|(img, _)| img)
+        };
+        let img = match captured {
+            Ok(img) => img,
+            Err(e) => {
+                app_err!("[snip] instant capture error: {}", e);
+                return;
+            }
+        };
+        match crate::snip::crop_and_save(
+            &img,
+            0,
+            0,
+            img.width(),
+            img.height(),
+            self.settings.screenshot_retention_count as usize,
+            &self.snip_output(),
+        ) {
+            Ok((path, saved)) => self.deliver_snip(&path, &saved),
+            Err(e) => app_err!("[snip] save error: {}", e),
+        }
+    }
+
+    /// Capture the last snip's rectangle again, without the overlay.
+    pub fn repeat_last_snip(&mut self) {
+        if !self.state.screenshot_enabled.load(Ordering::SeqCst) {
+            return;
+        }
+        let Some(region) = self.settings.snip_last_region else {
+            self.set_status("No snip region to repeat yet", "idle");
+            return;
+        };
+        self.snip_saved_region(&region);
+    }
+
+    /// Capture a named region preset, without the overlay.
+    pub fn snip_region_preset(&mut self, name: &str) {
+        if !self.state.screenshot_enabled.load(Ordering::SeqCst) {
+            return;
+        }
+        let region = self
+            .settings
+            .snip_region_presets
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.region);
+        let Some(region) = region else {
+            self.set_status(&format!("No snip region named \"{}\"", name), "error");
+            return;
+        };
+        app_log!("[snip] region preset \"{}\"", name);
+        self.snip_saved_region(&region);
+    }
+
+    /// Capture a stored rectangle and deliver it using the active preset.
+    fn snip_saved_region(&mut self, region: &crate::settings::SnipRegion) {
+        let img = match crate::snip::capture_region(region) {
+            Ok(img) => img,
+            Err(e) => {
+                app_err!("[snip] repeat capture error: {}", e);
+                self.set_status(&e, "error");
+                return;
+            }
+        };
+        match crate::snip::crop_and_save(
+            &img,
+            0,
+            0,
+            img.width(),
+            img.height(),
+            self.settings.screenshot_retention_count as usize,
+            &self.snip_output(),
+        ) {
+            Ok((path, saved)) => self.deliver_snip(&path, &saved),
+            Err(e) => app_err!("[snip] save error: {}", e),
+        }
+    }
+
+    /// Remember a finished selection for `repeat_last_snip`.
+    fn remember_snip_region(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        let Some(bounds) = self.snip_bounds.as_ref() else {
+            return;
+        };
+        let region = crate::settings::SnipRegion {
+            monitor_x: bounds.x,
+            monitor_y: bounds.y,
+            x,
+            y,
+            width: w,
+            height: h,
+        };
+        if self.settings.snip_last_region != Some(region) {
+            self.settings.snip_last_region = Some(region);
+            crate::persist::save_settings(&self.settings);
+        }
+    }
+
+    /// Copy a saved snip per the P/I/E preset and open the editor if requested.
+    fn deliver_snip(&mut self, path: &std::path::Path, img: &image::RgbaImage) {
+        if self.snip_copy_image {
+            let _ = crate::snip::copy_image_to_clipboard(img);
+        } else {
+            let _ = crate::snip::copy_path_to_clipboard(path);
+        }
+        if self.snip_edit_after {
+            if let Err(e) = crate::snip::open_in_editor(
+                path,
+                Some(self.settings.snip_editor_path.as_str()),
+            ) {
+                app_err!("[snip] editor error: {}", e);
+            }
+            match self.settings.snip_edit_revert.as_str() {
+                "image" => {
+                    self.snip_copy_image = true;
+                    self.snip_edit_after = false;
+                }
+                "path" => {
+                    self.snip_copy_image = false;
+                    self.snip_edit_after = false;
+                }
+                _ => {} // "stay" — keep edit mode
+            }
+        }
+        app_log!("[snip] saved to {}", path.to_string_lossy());
+    }
+
+    fn start_region_recording(
+        &mut self,
+        format: crate::recorder::RecordFormat,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+    ) {
+        let Some(bounds) = self.snip_bounds.as_ref() else {
+            return;
+        };
+        let region = crate::recorder::RecordRegion {
+            monitor_x: bounds.x,
+            monitor_y: bounds.y,
+            x,
+            y,
+            width: w,
+            height: h,
+        };
+        let mic = if self.settings.mic_device.trim().is_empty() {
+            None
+        } else {
+            Some(self.settings.mic_device.clone())
+        };
+        match crate::recorder::RegionRecorder::start(
+            region,
+            format,
+            mic,
+            self.settings.snip_record_audio,
+            self.settings.screenshot_retention_count as usize,
+        ) {
+            Ok(rec) => {
+                self.region_recorder = Some(rec);
+                self.set_status("Recording screen - Right Alt to stop", "idle");
+            }
+            Err(e) => {
+                app_err!("[record] start error: {}", e);
+                self.set_status(&format!("Screen recording failed: {}", e), "error");
+            }
+        }
+    }
+
+    pub fn stop_region_recording(&mut self) {
+        let Some(rec) = self.region_recorder.take() else {
+            return;
+        };
+        match rec.stop() {
+            Ok(path) => {
+                let _ = crate::snip::copy_path_to_clipboard(&path);
+                app_log!("[record] saved to {}", path.to_string_lossy());
+                self.set_status("Recording saved, path copied", "idle");
+            }
+            Err(e) => {
+                app_err!("[record] {}", e);
+                self.set_status(&format!("Screen recording failed: {}", e), "error");
+            }
+        }
+    }
+
     pub fn finish_snip(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        self.remember_snip_region(x, y, w, h);
+        if self.snip_mode == "record" || self.snip_mode == "gif" {
+            if let Ok(mut guard) = self.state.snip_image.lock() {
+                *guard = None;
+            }
+            let format = if self.snip_mode == "gif" {
+                crate::recorder::RecordFormat::Gif
+            } else {
+                crate::recorder::RecordFormat::Mp4
+            };
+            self.start_region_recording(format, x, y, w, h);
+            self.close_snip();
+            return;
+        }
         let img = {
             let mut guard = self.state.snip_image.lock().unwrap();
             guard.take()
         };
         if let Some(img) = img {
-            match crate::snip::crop_and_save(
-                &img,
-                x,
-                y,
-                w,
-                h,
-                self.settings.screenshot_retention_count as usize,
-            ) {
-                Ok((path, cropped)) => {
-                    if self.snip_copy_image {
-                        let _ = crate::snip::copy_image_to_clipboard(&cropped);
-                    } else {
-                        let _ = crate::snip::copy_path_to_clipboard(&path);
-                    }
-                    if self.snip_edit_after {
-                        if let Err(e) = crate::snip::open_in_editor(
-                            &path,
-                            Some(self.settings.snip_editor_path.as_str()),
-                        ) {
-                            app_err!("[snip] editor error: {}", e);
+            match self.snip_mode.as_str() {
+                "attach" => match crate::snip::crop_region(&img, x, y, w, h) {
+                    Ok(cropped) => {
+                        if let Ok(mut guard) = self.state.attached_snip.lock() {
+                            *guard = Some(cropped);
                         }
-                        match self.settings.snip_edit_revert.as_str() {
-                            "image" => {
-                                self.snip_copy_image = true;
-                                self.snip_edit_after = false;
-                            }
-                            "path" => {
-                                self.snip_copy_image = false;
-                                self.snip_edit_after = false;
-                            }
-                            _ => {} // "stay" — keep edit mode
+                        if let Some(service) = &self.service {
+                            service.snip_attached();
                         }
+                        app_log!("[snip] attached to next dictation");
+                        self.set_status("Snip attached - dictate your question", "idle");
                     }
-                    app_log!("[snip] saved to {}", path.to_string_lossy());
-                }
-                Err(e) => app_err!("[snip] save error: {}", e),
+                    Err(e) => app_err!("[snip] crop error: {}", e),
+                },
+                "vision" => match crate::snip::crop_region(&img, x, y, w, h) {
+                    Ok(cropped) => self.ask_vision(cropped),
+                    Err(e) => app_err!("[snip] crop error: {}", e),
+                },
+                "ocr" => match crate::snip::crop_region(&img, x, y, w, h) {
+                    Ok(cropped) => {
+                        // OCR can take a moment; keep it off the UI thread.
+                        std::thread::spawn(move || match crate::snip::ocr_image(&cropped) {
+                            Ok(text) if text.trim().is_empty() => {
+                                app_log!("[snip] OCR found no text");
+                            }
+                            Ok(text) => match crate::snip::copy_text_to_clipboard(&text) {
+                                Ok(()) => app_log!("[snip] OCR copied {} chars", text.len()),
+                                Err(e) => app_err!("[snip] {}", e),
+                            },
+                            Err(e) => app_err!("[snip] OCR error: {}", e),
+                        });
+                    }
+                    Err(e) => app_err!("[snip] crop error: {}", e),
+                },
+                _ => match crate::snip::crop_and_save(
+                    &img,
+                    x,
+                    y,
+                    w,
+                    h,
+                    self.settings.screenshot_retention_count as usize,
+                    &self.snip_output(),
+                ) {
+                    Ok((path, cropped)) => self.deliver_snip(&path, &cropped),
+                    Err(e) => app_err!("[snip] save error: {}", e),
+                },
             }
+            self.keep_last_capture(img);
         }
         self.close_snip();
     }
 
-    pub fn cancel_snip(&mut self) {
+    /// Hold on to the full capture (one at a time) so it can be re-cropped,
+    /// unless it alone is over the memory cap; then it is released now.
+    fn keep_last_capture(&mut self, img: image::RgbaImage) {
+        if crate::snip::image_bytes(&img) > self.snip_memory_cap() {
+            app_log!("[snip] capture over the memory cap, not kept for re-crop");
+            self.last_snip_capture = None;
+            return;
+        }
+        if let Some(bounds) = self.snip_bounds.take() {
+            self.last_snip_capture = Some((img, bounds));
+        }
+    }
+
+    /// Re-open the overlay on the previous capture instead of taking a new one.
+    pub fn recrop_last_snip(&mut self) {
+        if self.region_recorder.is_some() {
+            self.trigger_snip();
+            return;
+        }
+        let Some((img, bounds)) = self.last_snip_capture.take() else {
+            app_log!("[snip] no previous capture, taking a new one");
+            self.trigger_snip();
+            return;
+        };
+        app_log!("[snip] re-cropping last capture");
+        self.open_snip_overlay(img, bounds);
+    }
+
+    /// Post a snip to the configured vision model and copy its reply.
+    fn ask_vision(&mut self, img: image::RgbaImage) {
+        let endpoint = self.settings.vision_endpoint.clone();
+        let model = self.settings.vision_model.clone();
+        let api_key = self.settings.api_key_for("openai").to_string();
+        let prompt = crate::vision::preset_prompt(&self.settings.vision_prompt);
+        let tx = self.event_tx.clone();
+        self.set_status("Asking vision model...", "idle");
+        std::thread::spawn(move || {
+            let (status, message) =
+                match crate::vision::ask_about_image(&endpoint, &model, &api_key, prompt, &img) {
+                    Ok(text) => match crate::snip::copy_text_to_clipboard(&text) {
+                        Ok(()) => {
+                            app_log!("[vision] copied {} chars", text.len());
+                            ("idle", "Vision response copied".to_string())
+                        }
+                        Err(e) => ("error", e),
+                    },
+                    Err(e) => {
+                        app_err!("[vision] {}", e);
+                        ("error", format!("Vision request failed: {}", e))
+                    }
+                };
+            let _ = tx.send(crate::state::AppEvent::StatusUpdate {
+                status: status.into(),
+                message,
+            });
+        });
+    }
+
+    /// Redact part of the in-memory capture so later crops/OCR only see the
+    /// redacted pixels. The overlay stays open for the actual selection.
+    fn redact_snip(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        let pixelate = self.snip_mode == "pixelate";
         if let Ok(mut guard) = self.state.snip_image.lock() {
-            *guard = None;
+            if let Some(img) = guard.as_mut() {
+                crate::snip::redact_region(img, x, y, w, h, pixelate);
+            }
+        }
+        // Reload the texture from the edited capture on next render.
+        self.snip_texture = None;
+        self.snip_drag_start = None;
+        self.snip_drag_current = None;
+    }
+
+    pub fn cancel_snip(&mut self) {
+        let img = self.state.snip_image.lock().ok().and_then(|mut g| g.take());
+        if let Some(img) = img {
+            self.keep_last_capture(img);
         }
         self.close_snip();
         app_log!("[snip] cancelled");
@@ -93,6 +429,7 @@ impl MangoChatApp {
         self.snip_drag_start = None;
         self.snip_drag_current = None;
         self.snip_bounds = None;
+        self.snip_window_rects.clear();
         self.state.snip_active.store(false, Ordering::SeqCst);
     }
 
@@ -101,13 +438,25 @@ impl MangoChatApp {
             ctx.send_viewport_cmd(ViewportCommand::Focus);
             self.snip_focus_pending = false;
         }
-        // Load texture on first render
+        // Load texture on first render, downscaled if the preview setting or
+        // the memory left under the cap calls for it.
         if self.snip_texture.is_none() {
             if let Ok(guard) = self.state.snip_image.lock() {
                 if let Some(ref img) = *guard {
-                    let size = [img.width() as usize, img.height() as usize];
+                    let held = crate::snip::image_bytes(img)
+                        + self
+                            .last_snip_capture
+                            .as_ref()
+                            .map_or(0, |(last, _)| crate::snip::image_bytes(last));
+                    let preview = crate::snip::preview_image(
+                        img,
+                        self.settings.snip_preview_max_dimension,
+                        self.snip_memory_cap().saturating_sub(held),
+                    );
+                    let shown = preview.as_ref().unwrap_or(img);
+                    let size = [shown.width() as usize, shown.height() as usize];
                     let color_image =
-                        egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+                        egui::ColorImage::from_rgba_unmultiplied(size, shown.as_raw());
                     self.snip_texture = Some(ctx.load_texture(
                         "snip-screenshot",
                         color_image,
@@ -128,9 +477,39 @@ impl MangoChatApp {
             .frame(egui::Frame::none().fill(Color32::BLACK))
             .show(ctx, |ui| {
                 let rect = ui.max_rect();
-                let response = ui.allocate_rect(rect, Sense::drag());
+                let response = ui.allocate_rect(rect, Sense::click_and_drag());
+                // Overlay points to capture pixels (not texture pixels, which
+                // may be a smaller preview).
+                let sx = match self.snip_image_size[0] {
+                    0 => 1.0,
+                    w => w as f32 / rect.width(),
+                };
+                let sy = match self.snip_image_size[1] {
+                    0 => 1.0,
+                    h => h as f32 / rect.height(),
+                };
+
+                // Window under the pointer (window mode only), in overlay coords.
+                let hovered_window = if self.snip_mode == "window"
+                    && self.snip_drag_start.is_none()
+                {
+                    ctx.pointer_hover_pos().and_then(|pos| {
+                        self.snip_window_rects
+                            .iter()
+                            .map(|r| {
+                                Rect::from_min_max(
+                                    pos2(r.min.x / sx, r.min.y / sy),
+                                    pos2(r.max.x / sx, r.max.y / sy),
+                                )
+                            })
+                            .find(|r| r.contains(pos))
+                    })
+                } else {
+                    None
+                };
 
-                if response.drag_started() {
+                let picking = self.snip_mode == "color";
+                if response.drag_started() && !picking {
                     if let Some(pos) = response.interact_pointer_pos() {
                         self.snip_drag_start = Some(pos);
                         self.snip_drag_current = Some(pos);
@@ -141,6 +520,10 @@ impl MangoChatApp {
                         self.snip_drag_current = Some(pos);
                     }
                 }
+                let confirm = !picking
+                    && self.snip_mode != "window"
+                    && !response.dragged()
+                    && self.snip_keyboard(ctx, rect, sx, sy);
 
                 let painter = ui.painter();
 
@@ -157,11 +540,12 @@ impl MangoChatApp {
                 // Dark tint
                 painter.rect_filled(rect, 0.0, Color32::from_black_alpha(100));
 
-                // Selection rectangle
-                if let (Some(start), Some(current)) =
-                    (self.snip_drag_start, self.snip_drag_current)
-                {
-                    let sel = Rect::from_two_pos(start, current);
+                // Selection rectangle (drag) or highlighted window
+                let selection = match (self.snip_drag_start, self.snip_drag_current) {
+                    (Some(start), Some(current)) => Some(Rect::from_two_pos(start, current)),
+                    _ => hovered_window,
+                };
+                if let Some(sel) = selection {
                     if sel.width() > 0.0 && sel.height() > 0.0 {
                         // Bright region (no tint)
                         if let Some(ref tex) = self.snip_texture {
@@ -183,15 +567,18 @@ impl MangoChatApp {
                             Stroke::new(1.0, Color32::from_white_alpha(230)),
                         );
 
-                        // Dimension label
-                        let label =
-                            format!("{}x{}", sel.width() as u32, sel.height() as u32);
+                        // Dimension label, in capture pixels
+                        let label = format!(
+                            "{}x{}",
+                            (sel.width() * sx) as u32,
+                            (sel.height() * sy) as u32
+                        );
                         let lpos =
                             pos2(sel.min.x + 8.0, (sel.min.y - 28.0).max(8.0));
                         let galley = painter.layout_no_wrap(
                             label,
                             FontId::proportional(13.0),
-                            TEXT_COLOR,
+                            OVERLAY.text,
                         );
                         let bg = Rect::from_min_size(
                             lpos,
@@ -202,36 +589,116 @@ impl MangoChatApp {
                             3.0,
                             Color32::from_black_alpha(150),
                         );
-                        painter.galley(lpos + vec2(6.0, 3.0), galley, TEXT_COLOR);
+                        painter.galley(lpos + vec2(6.0, 3.0), galley, OVERLAY.text);
                     }
                 }
 
                 // Hint
+                let hint = match self.snip_mode.as_str() {
+                    "window" => "Click a window to capture it. Escape to cancel.",
+                    "record" => "Drag to select the area to record. Right Alt stops. Escape to cancel.",
+                    "gif" => "Drag to select the area for a GIF (up to 15s). Right Alt stops. Escape to cancel.",
+                    "ocr" => "Drag to select text to copy. Escape to cancel.",
+                    "vision" => "Drag to select an area for the vision model. Escape to cancel.",
+                    "attach" => "Drag to select an area to ask about in your next dictation. Escape to cancel.",
+                    "color" => "Click to copy the color under the cursor. Escape to cancel.",
+                    "pixelate" | "blackout" => {
+                        "Drag over anything to hide, then pick a capture mode. Escape to cancel."
+                    }
+                    _ => "Drag to select. Escape to cancel.",
+                };
                 painter.text(
                     pos2(rect.center().x, 24.0),
                     egui::Align2::CENTER_CENTER,
-                    "Drag to select. Escape to cancel.",
+                    hint,
                     FontId::proportional(14.0),
                     Color32::from_white_alpha(200),
                 );
+                if !picking && self.snip_mode != "window" {
+                    painter.text(
+                        pos2(rect.center().x, rect.max.y - 24.0),
+                        egui::Align2::CENTER_CENTER,
+                        "Arrows move, Shift+arrows resize, 1 = 16:9, 2 = 1:1, Enter captures",
+                        FontId::proportional(12.0),
+                        Color32::from_white_alpha(160),
+                    );
+                }
+
+                // Crosshair guides and a coordinate loupe for pixel-exact edges
+                if self.settings.snip_magnifier && !picking && self.snip_mode != "window" {
+                    if let Some(pos) = ctx.pointer_hover_pos().filter(|p| rect.contains(*p)) {
+                        let guide = Stroke::new(1.0, Color32::from_white_alpha(90));
+                        painter.hline(rect.x_range(), pos.y, guide);
+                        painter.vline(pos.x, rect.y_range(), guide);
+                        let px = (pos.x * sx) as u32;
+                        let py = (pos.y * sy) as u32;
+                        self.paint_coordinate_loupe(painter, rect, pos, px, py);
+                    }
+                }
 
-                // Drag end → finish/cancel
-                if response.drag_stopped() {
+                // Eyedropper: loupe + value under the cursor, click copies hex
+                let mut picked = None;
+                if picking {
+                    if let Some(pos) = ctx.pointer_hover_pos().filter(|p| rect.contains(*p)) {
+                        let px = (pos.x * sx) as u32;
+                        let py = (pos.y * sy) as u32;
+                        picked = self.paint_color_loupe(painter, rect, pos, px, py);
+                    }
+                }
+
+                self.render_snip_toolbar(ctx);
+
+                if picking {
+                    if response.clicked() {
+                        if let Some(c) = picked {
+                            let hex = format!("#{:02X}{:02X}{:02X}", c.r(), c.g(), c.b());
+                            match crate::snip::copy_text_to_clipboard(&hex) {
+                                Ok(()) => {
+                                    app_log!("[snip] picked color {}", hex);
+                                    self.set_status(&format!("Copied {}", hex), "idle");
+                                }
+                                Err(e) => app_err!("[snip] {}", e),
+                            }
+                            self.cancel_snip();
+                        }
+                    }
+                    return;
+                }
+
+                // Window mode: click captures the highlighted window
+                if response.clicked() {
+                    if let Some(sel) = hovered_window {
+                        self.finish_snip(
+                            (sel.min.x * sx) as u32,
+                            (sel.min.y * sy) as u32,
+                            (sel.width() * sx) as u32,
+                            (sel.height() * sy) as u32,
+                        );
+                    }
+                    return;
+                }
+
+                // Drag end or Enter → finish/cancel
+                if response.drag_stopped() || confirm {
                     if let (Some(s), Some(c)) =
                         (self.snip_drag_start, self.snip_drag_current)
                     {
                         let sel = Rect::from_two_pos(s, c);
-                        if sel.width() >= 5.0 && sel.height() >= 5.0 {
-                            let sx = self
-                                .snip_texture
-                                .as_ref()
-                                .map(|t| t.size()[0] as f32 / rect.width())
-                                .unwrap_or(1.0);
-                            let sy = self
-                                .snip_texture
-                                .as_ref()
-                                .map(|t| t.size()[1] as f32 / rect.height())
-                                .unwrap_or(1.0);
+                        let redacting =
+                            self.snip_mode == "pixelate" || self.snip_mode == "blackout";
+                        if redacting {
+                            if sel.width() >= 2.0 && sel.height() >= 2.0 {
+                                self.redact_snip(
+                                    (sel.min.x * sx) as u32,
+                                    (sel.min.y * sy) as u32,
+                                    (sel.width() * sx) as u32,
+                                    (sel.height() * sy) as u32,
+                                );
+                            } else {
+                                self.snip_drag_start = None;
+                                self.snip_drag_current = None;
+                            }
+                        } else if sel.width() >= 5.0 && sel.height() >= 5.0 {
                             self.finish_snip(
                                 (sel.min.x * sx) as u32,
                                 (sel.min.y * sy) as u32,
@@ -247,5 +714,229 @@ impl MangoChatApp {
                 }
             });
     }
-}
 
+    /// Arrow keys nudge the selection by one capture pixel, Shift+arrows move
+    /// its bottom-right corner, and the number keys in `ASPECT_PRESETS`
+    /// reshape it around its center. Any of them starts a selection at the
+    /// cursor if there is none. Returns true when Enter confirms a selection.
+    fn snip_keyboard(&mut self, ctx: &egui::Context, screen: Rect, sx: f32, sy: f32) -> bool {
+        let (step, resize, ratio, enter) = ctx.input(|i| {
+            let mut step = Vec2::ZERO;
+            if i.key_pressed(Key::ArrowLeft) {
+                step.x -= 1.0;
+            }
+            if i.key_pressed(Key::ArrowRight) {
+                step.x += 1.0;
+            }
+            if i.key_pressed(Key::ArrowUp) {
+                step.y -= 1.0;
+            }
+            if i.key_pressed(Key::ArrowDown) {
+                step.y += 1.0;
+            }
+            let ratio = ASPECT_PRESETS
+                .iter()
+                .find(|(key, _)| i.key_pressed(*key))
+                .map(|(_, ratio)| *ratio);
+            (step, i.modifiers.shift, ratio, i.key_pressed(Key::Enter))
+        });
+        if step == Vec2::ZERO && ratio.is_none() {
+            return enter && self.snip_drag_start.is_some() && self.snip_drag_current.is_some();
+        }
+        let mut sel = match (self.snip_drag_start, self.snip_drag_current) {
+            (Some(start), Some(current)) => Rect::from_two_pos(start, current),
+            _ => {
+                let center = ctx
+                    .pointer_hover_pos()
+                    .filter(|p| screen.contains(*p))
+                    .unwrap_or_else(|| screen.center());
+                Rect::from_center_size(center, KEYBOARD_SELECTION)
+            }
+        };
+        if let Some(ratio) = ratio {
+            // The ratio is in capture pixels; sx and sy only differ if the
+            // capture was scaled unevenly.
+            let mut size = vec2(sel.width(), sel.width() * sx / (ratio * sy));
+            if size.y > screen.height() {
+                size = vec2(screen.height() * ratio * sy / sx, screen.height());
+            }
+            sel = Rect::from_center_size(sel.center(), size);
+        }
+        let pixel = vec2(step.x / sx, step.y / sy);
+        if resize {
+            sel.max += pixel;
+            sel.max.x = sel.max.x.max(sel.min.x + 1.0 / sx);
+            sel.max.y = sel.max.y.max(sel.min.y + 1.0 / sy);
+        } else {
+            sel = sel.translate(pixel);
+        }
+        // Slide back on screen rather than clipping, so a nudge never shrinks it.
+        let back = vec2(
+            (screen.min.x - sel.min.x).max(0.0) + (screen.max.x - sel.max.x).min(0.0),
+            (screen.min.y - sel.min.y).max(0.0) + (screen.max.y - sel.max.y).min(0.0),
+        );
+        let sel = sel.translate(back).intersect(screen);
+        self.snip_drag_start = Some(sel.min);
+        self.snip_drag_current = Some(sel.max);
+        enter
+    }
+
+    /// Draw a magnified pixel grid around (px, py) next to the cursor.
+    /// Returns the color under the cursor and where a caption goes.
+    fn paint_loupe(
+        &self,
+        painter: &egui::Painter,
+        screen: Rect,
+        pos: egui::Pos2,
+        px: u32,
+        py: u32,
+    ) -> Option<(Color32, egui::Pos2)> {
+        const RADIUS: i32 = 5;
+        const CELL: f32 = 10.0;
+        let guard = self.state.snip_image.lock().ok()?;
+        let img = guard.as_ref()?;
+        if px >= img.width() || py >= img.height() {
+            return None;
+        }
+        let side = (RADIUS * 2 + 1) as f32 * CELL;
+        // Keep the loupe on screen: flip to the other side of the cursor near edges.
+        let mut origin = pos + vec2(20.0, 20.0);
+        if origin.x + side > screen.max.x {
+            origin.x = pos.x - 20.0 - side;
+        }
+        if origin.y + side + 28.0 > screen.max.y {
+            origin.y = pos.y - 20.0 - side - 28.0;
+        }
+        for dy in -RADIUS..=RADIUS {
+            for dx in -RADIUS..=RADIUS {
+                let x = (px as i32 + dx).clamp(0, img.width() as i32 - 1) as u32;
+                let y = (py as i32 + dy).clamp(0, img.height() as i32 - 1) as u32;
+                let p = img.get_pixel(x, y);
+                let cell = Rect::from_min_size(
+                    origin + vec2((dx + RADIUS) as f32 * CELL, (dy + RADIUS) as f32 * CELL),
+                    vec2(CELL, CELL),
+                );
+                painter.rect_filled(cell, 0.0, Color32::from_rgb(p[0], p[1], p[2]));
+            }
+        }
+        let grid = Stroke::new(1.0, Color32::from_black_alpha(60));
+        for i in 1..RADIUS * 2 + 1 {
+            let offset = i as f32 * CELL;
+            painter.vline(origin.x + offset, origin.y..=origin.y + side, grid);
+            painter.hline(origin.x..=origin.x + side, origin.y + offset, grid);
+        }
+        let frame = Rect::from_min_size(origin, vec2(side, side));
+        painter.rect_stroke(frame, 0.0, Stroke::new(1.0, Color32::from_white_alpha(230)));
+        let center = Rect::from_min_size(
+            origin + vec2(RADIUS as f32 * CELL, RADIUS as f32 * CELL),
+            vec2(CELL, CELL),
+        );
+        painter.rect_stroke(center, 0.0, Stroke::new(1.5, Color32::WHITE));
+
+        let p = img.get_pixel(px, py);
+        Some((
+            Color32::from_rgb(p[0], p[1], p[2]),
+            origin + vec2(0.0, side + 4.0),
+        ))
+    }
+
+    /// Loupe plus the hex/RGB value under the cursor. Returns that color.
+    fn paint_color_loupe(
+        &self,
+        painter: &egui::Painter,
+        screen: Rect,
+        pos: egui::Pos2,
+        px: u32,
+        py: u32,
+    ) -> Option<Color32> {
+        let (color, lpos) = self.paint_loupe(painter, screen, pos, px, py)?;
+        let label = format!(
+            "#{:02X}{:02X}{:02X}  rgb({}, {}, {})",
+            color.r(),
+            color.g(),
+            color.b(),
+            color.r(),
+            color.g(),
+            color.b()
+        );
+        let galley = painter.layout_no_wrap(label, FontId::monospace(12.0), OVERLAY.text);
+        let bg = Rect::from_min_size(lpos, galley.size() + vec2(30.0, 6.0));
+        painter.rect_filled(bg, 3.0, Color32::from_black_alpha(190));
+        painter.rect_filled(
+            Rect::from_min_size(lpos + vec2(5.0, 4.0), vec2(14.0, bg.height() - 8.0)),
+            2.0,
+            color,
+        );
+        painter.galley(lpos + vec2(24.0, 3.0), galley, OVERLAY.text);
+        Some(color)
+    }
+
+    /// Loupe plus the capture-pixel coordinates under the cursor.
+    fn paint_coordinate_loupe(
+        &self,
+        painter: &egui::Painter,
+        screen: Rect,
+        pos: egui::Pos2,
+        px: u32,
+        py: u32,
+    ) {
+        let Some((_, lpos)) = self.paint_loupe(painter, screen, pos, px, py) else {
+            return;
+        };
+        let label = format!("x {}  y {}", px, py);
+        let galley = painter.layout_no_wrap(label, FontId::monospace(12.0), OVERLAY.text);
+        let bg = Rect::from_min_size(lpos, galley.size() + vec2(12.0, 6.0));
+        painter.rect_filled(bg, 3.0, Color32::from_black_alpha(190));
+        painter.galley(lpos + vec2(6.0, 3.0), galley, OVERLAY.text);
+    }
+
+    /// Mode buttons pinned above the selection surface.
+    fn render_snip_toolbar(&mut self, ctx: &egui::Context) {
+        let accent = self.current_accent();
+        let modes = [
+            ("region", "Region"),
+            ("window", "Window"),
+            ("ocr", "Copy text (OCR)"),
+            ("vision", "Ask AI"),
+            ("attach", "Attach to dictation"),
+            ("record", "Record video"),
+            ("gif", "GIF"),
+            ("pixelate", "Pixelate"),
+            ("blackout", "Blackout"),
+            ("color", "Color picker"),
+        ];
+        egui::Area::new(egui::Id::new("snip_toolbar"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_TOP, vec2(0.0, 44.0))
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(Color32::from_black_alpha(180))
+                    .stroke(Stroke::new(0.5, OVERLAY.btn_border))
+                    .rounding(6.0)
+                    .inner_margin(egui::Margin::same(4.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            for (id, label) in modes {
+                                let active = self.snip_mode == id;
+                                let resp = ui.add(
+                                    egui::Button::new(
+                                        egui::RichText::new(label).size(12.0).color(
+                                            if active { Color32::BLACK } else { OVERLAY.text },
+                                        ),
+                                    )
+                                    .fill(if active { accent.base } else { OVERLAY.btn_bg })
+                                    .stroke(Stroke::new(
+                                        1.0,
+                                        if active { accent.ring } else { OVERLAY.btn_border },
+                                    )),
+                                );
+                                if resp.clicked() {
+                                    self.snip_mode = id.to_string();
+                                }
+                            }
+                        });
+                    });
+            });
+    }
+}
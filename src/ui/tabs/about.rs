@@ -1,6 +1,7 @@
 use eframe::egui;
 use crate::ui::theme::*;
 use crate::ui::{MangoChatApp, UpdateUiState};
+use crate::updater;
 
 fn truncate_chars(input: &str, max_chars: usize) -> String {
     let count = input.chars().count();
@@ -12,7 +13,63 @@ fn truncate_chars(input: &str, max_chars: usize) -> String {
     out
 }
 
-pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+/// Strip inline markdown that plain labels can't show (bold, code, links).
+fn strip_inline_markdown(line: &str) -> String {
+    let mut out = line.replace("**", "").replace('`', "");
+    // [text](url) -> text
+    while let (Some(open), Some(mid)) = (out.find('['), out.find("](")) {
+        let Some(close) = out[mid..].find(')').map(|i| mid + i) else {
+            break;
+        };
+        if open > mid {
+            break;
+        }
+        let text = out[open + 1..mid].to_string();
+        out.replace_range(open..=close, &text);
+    }
+    out
+}
+
+/// Render release notes line by line: headings, bullets and paragraphs.
+fn render_release_notes(ui: &mut egui::Ui, notes: &str, accent: egui::Color32) {
+    for raw in notes.lines() {
+        let line = raw.trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            ui.add_space(4.0);
+            continue;
+        }
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            ui.label(
+                egui::RichText::new(strip_inline_markdown(heading.trim_start_matches('#').trim()))
+                    .size(12.5)
+                    .strong()
+                    .color(accent),
+            );
+        } else if let Some(item) = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+        {
+            let indent = (line.len() - trimmed.len()) as f32 * 4.0;
+            ui.horizontal_wrapped(|ui| {
+                ui.add_space(indent);
+                ui.label(
+                    egui::RichText::new(format!("\u{2022} {}", strip_inline_markdown(item)))
+                        .size(11.5)
+                        .color(text_color()),
+                );
+            });
+        } else {
+            ui.label(
+                egui::RichText::new(strip_inline_markdown(trimmed))
+                    .size(11.5)
+                    .color(text_color()),
+            );
+        }
+    }
+}
+
+pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
     egui::ScrollArea::vertical()
         .max_height(ui.available_height().max(260.0))
         .show(ui, |ui| {
@@ -37,7 +94,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     egui::RichText::new("Mango Chat \u{2014} Voice Dictation & Productivity App")
                         .size(13.0)
                         .strong()
-                        .color(TEXT_COLOR),
+                        .color(text_color()),
                 );
             });
 
@@ -58,12 +115,12 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                 ui.label(
                     egui::RichText::new("Made by Kalyan Sattaluri")
                         .size(sz)
-                        .color(TEXT_COLOR),
+                        .color(text_color()),
                 );
                 ui.label(
                     egui::RichText::new("Made with Claude & Codex")
                         .size(sz)
-                        .color(TEXT_MUTED),
+                        .color(text_muted()),
                 );
 
                 let fmt = |color| egui::text::TextFormat {
@@ -72,11 +129,11 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     ..Default::default()
                 };
                 let mut job = egui::text::LayoutJob::default();
-                job.append("Made for ", 0.0, fmt(TEXT_MUTED));
-                job.append("Shreya ", 0.0, fmt(TEXT_COLOR));
+                job.append("Made for ", 0.0, fmt(text_muted()));
+                job.append("Shreya ", 0.0, fmt(text_color()));
                 job.append("\u{2665}", 0.0, fmt(accent.base));
-                job.append(" & ", 0.0, fmt(TEXT_MUTED));
-                job.append("Avy ", 0.0, fmt(TEXT_COLOR));
+                job.append(" & ", 0.0, fmt(text_muted()));
+                job.append("Avy ", 0.0, fmt(text_color()));
                 job.append("\u{2665}", 0.0, fmt(accent.base));
                 ui.label(job);
 
@@ -99,7 +156,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                         egui::pos2(rect.min.x, rect.min.y),
                         egui::pos2(rect.max.x, rect.min.y),
                     ],
-                    egui::Stroke::new(0.5, BTN_BORDER),
+                    egui::Stroke::new(0.5, btn_border()),
                 );
             }
             ui.add_space(6.0);
@@ -107,7 +164,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                 egui::RichText::new("Updates")
                     .size(13.0)
                     .strong()
-                    .color(TEXT_MUTED),
+                    .color(text_muted()),
             );
 
             egui::Grid::new("updates_grid")
@@ -118,25 +175,25 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     ui.label(
                         egui::RichText::new("Version")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
+                    );
+                    let current = format!(
+                        "{} {}",
+                        env!("CARGO_PKG_VERSION"),
+                        updater::channel_label(&app.settings.update_channel)
                     );
                     let version_text = match &app.update_state {
-                        UpdateUiState::UpToDate => {
-                            format!("{} (up to date)", env!("CARGO_PKG_VERSION"))
-                        }
+                        UpdateUiState::UpToDate => format!("{} (up to date)", current),
                         UpdateUiState::Available { latest } => {
-                            format!("{} -> {} ({})", env!("CARGO_PKG_VERSION"), latest.version, latest.tag)
+                            format!("{} -> {} ({})", current, latest.version, latest.tag)
                         }
-                        UpdateUiState::Checking => {
-                            format!("{} (checking\u{2026})", env!("CARGO_PKG_VERSION"))
+                        UpdateUiState::Checking => format!("{} (checking\u{2026})", current),
+                        UpdateUiState::Installing => format!("{} (installing\u{2026})", current),
+                        UpdateUiState::ReadyOnExit { version } => {
+                            format!("{} -> {} (installs on quit)", current, version)
                         }
-                        UpdateUiState::Installing => {
-                            format!("{} (installing\u{2026})", env!("CARGO_PKG_VERSION"))
-                        }
-                        UpdateUiState::Error(e) => {
-                            format!("{} (error: {})", env!("CARGO_PKG_VERSION"), e)
-                        }
-                        _ => env!("CARGO_PKG_VERSION").to_string(),
+                        UpdateUiState::Error(e) => format!("{} (error: {})", current, e),
+                        _ => current,
                     };
                     let display_version = truncate_chars(&version_text, 72);
                     ui.allocate_ui_with_layout(
@@ -147,7 +204,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                                 egui::Label::new(
                                     egui::RichText::new(display_version)
                                         .size(12.0)
-                                        .color(TEXT_MUTED),
+                                        .color(text_muted()),
                                 )
                                 .wrap_mode(egui::TextWrapMode::Truncate),
                             );
@@ -155,7 +212,128 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     );
                     ui.end_row();
 
+                    ui.label(
+                        egui::RichText::new("Channel")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("update_channel_select")
+                            .selected_text(updater::channel_label(&app.form.update_channel))
+                            .width(110.0)
+                            .show_ui(ui, |ui| {
+                                for (id, label) in updater::UPDATE_CHANNELS {
+                                    ui.selectable_value(
+                                        &mut app.form.update_channel,
+                                        id.to_string(),
+                                        label,
+                                    );
+                                }
+                            });
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(match app.form.update_channel.as_str() {
+                                "nightly" => "(every build, may be unstable)",
+                                "beta" => "(pre-releases before they ship)",
+                                _ => "(full releases only)",
+                            })
+                            .size(12.0)
+                            .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Check signature")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.update_verify_signature;
+                        egui::ComboBox::from_id_salt("update_verify_signature_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.update_verify_signature = enabled;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(installer hash is always checked)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Install on quit")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.update_install_on_exit;
+                        egui::ComboBox::from_id_salt("update_install_on_exit_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.update_install_on_exit = enabled;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(download in the background, install silently when you quit)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
                 });
+
+            if let UpdateUiState::Available { latest } = &app.update_state {
+                if !latest.notes.trim().is_empty() {
+                    ui.add_space(6.0);
+                    ui.label(
+                        egui::RichText::new(format!("What's new in {}", latest.tag))
+                            .size(12.0)
+                            .color(text_muted()),
+                    );
+                    egui::Frame::none()
+                        .fill(input_bg())
+                        .stroke(egui::Stroke::new(1.0, btn_border()))
+                        .rounding(4.0)
+                        .inner_margin(8.0)
+                        .show(ui, |ui| {
+                            egui::ScrollArea::vertical()
+                                .id_salt("release_notes_scroll")
+                                .max_height(180.0)
+                                .auto_shrink([false, true])
+                                .show(ui, |ui| {
+                                    render_release_notes(ui, &latest.notes, accent.base);
+                                });
+                        });
+                }
+            }
+
+            if let Some((downloaded, total)) = app.update_download_progress {
+                ui.add_space(4.0);
+                let mb = |b: u64| b as f64 / (1024.0 * 1024.0);
+                let (fraction, text) = match total {
+                    Some(t) if t > 0 => (
+                        downloaded as f32 / t as f32,
+                        format!("{:.1} / {:.1} MB", mb(downloaded), mb(t)),
+                    ),
+                    _ => (0.0, format!("{:.1} MB", mb(downloaded))),
+                };
+                ui.add(
+                    egui::ProgressBar::new(fraction)
+                        .desired_width(360.0)
+                        .fill(accent.base)
+                        .text(egui::RichText::new(text).size(11.0)),
+                );
+            }
 
             ui.add_space(4.0);
             ui.horizontal(|ui| {
@@ -165,7 +343,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                         egui::Button::new(
                             egui::RichText::new("Check now")
                                 .size(11.0)
-                                .color(TEXT_COLOR),
+                                .color(text_color()),
                         ),
                     )
                     .clicked()
@@ -192,7 +370,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     egui::Button::new(
                         egui::RichText::new(install_text)
                             .size(11.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     )
                 };
                 if ui
@@ -202,6 +380,79 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     app.trigger_update_install();
                 }
             });
+
+            // --- Settings backup ---
+            ui.add_space(14.0);
+            {
+                let rect = ui.available_rect_before_wrap();
+                ui.painter().line_segment(
+                    [
+                        egui::pos2(rect.min.x, rect.min.y),
+                        egui::pos2(rect.max.x, rect.min.y),
+                    ],
+                    egui::Stroke::new(0.5, btn_border()),
+                );
+            }
+            ui.add_space(6.0);
+            ui.label(
+                egui::RichText::new("Settings backup")
+                    .size(13.0)
+                    .strong()
+                    .color(text_muted()),
+            );
+            ui.add_space(6.0);
+            ui.add(
+                egui::TextEdit::singleline(&mut app.settings_bundle_path)
+                    .hint_text("Path to settings .json")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                let path_set = !app.settings_bundle_path.trim().is_empty();
+                if ui
+                    .add_enabled(
+                        path_set,
+                        egui::Button::new(
+                            egui::RichText::new("Export settings")
+                                .size(11.0)
+                                .color(egui::Color32::BLACK),
+                        )
+                        .fill(accent.base)
+                        .stroke(egui::Stroke::new(1.0, accent.ring)),
+                    )
+                    .clicked()
+                {
+                    app.export_settings_bundle();
+                }
+                if ui
+                    .add_enabled(
+                        path_set,
+                        egui::Button::new(
+                            egui::RichText::new("Import settings")
+                                .size(11.0)
+                                .color(text_color()),
+                        )
+                        .stroke(egui::Stroke::new(1.0, btn_border())),
+                    )
+                    .clicked()
+                {
+                    app.import_settings_bundle(ctx);
+                }
+                ui.add_space(10.0);
+                ui.checkbox(
+                    &mut app.settings_bundle_include_keys,
+                    egui::RichText::new("Include API keys")
+                        .size(11.5)
+                        .color(text_color()),
+                );
+            });
+            if app.settings_bundle_include_keys {
+                ui.label(
+                    egui::RichText::new("Keys are written in plain text. Keep the file private.")
+                        .size(10.5)
+                        .color(text_muted()),
+                );
+            }
 
             // --- Diagnostics ---
             ui.add_space(14.0);
@@ -212,7 +463,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                         egui::pos2(rect.min.x, rect.min.y),
                         egui::pos2(rect.max.x, rect.min.y),
                     ],
-                    egui::Stroke::new(0.5, BTN_BORDER),
+                    egui::Stroke::new(0.5, btn_border()),
                 );
             }
             ui.add_space(6.0);
@@ -221,13 +472,13 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                     egui::RichText::new("Diagnostics")
                         .size(13.0)
                         .strong()
-                        .color(TEXT_MUTED),
+                        .color(text_muted()),
                 );
                 ui.add_space(8.0);
                 ui.label(
                     egui::RichText::new("(API keys excluded)")
                         .size(11.5)
-                        .color(TEXT_MUTED),
+                        .color(text_muted()),
                 );
             });
             ui.add_space(6.0);
@@ -237,9 +488,9 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                         egui::Button::new(
                             egui::RichText::new("Open logs folder")
                                 .size(11.0)
-                                .color(TEXT_COLOR),
+                                .color(text_color()),
                         )
-                        .stroke(egui::Stroke::new(1.0, BTN_BORDER)),
+                        .stroke(egui::Stroke::new(1.0, btn_border())),
                     )
                     .clicked()
                 {
@@ -263,7 +514,7 @@ pub fn render_about(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Cont
                 ui.add_space(10.0);
                 ui.label(
                     egui::RichText::new(format!(
-                        "Need help? Email the ZIP to {}",
+                        "API keys are removed. Need help? Email the ZIP to {}",
                         crate::diagnostics::support_email()
                     ))
                     .size(11.5)
@@ -306,7 +557,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
                     egui::Align2::LEFT_CENTER,
                     "Frequently Asked Questions",
                     egui::FontId::proportional(13.0),
-                    TEXT_COLOR,
+                    text_color(),
                 );
 
                 // Controls on the right: "Text size" [-] [+]
@@ -356,7 +607,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
                     egui::Align2::CENTER_CENTER,
                     "\u{2212}",
                     egui::FontId::proportional(14.0),
-                    TEXT_COLOR,
+                    text_color(),
                 );
                 if minus_resp.clicked() {
                     app.faq_text_size = (app.faq_text_size - 1.0).max(9.0);
@@ -384,7 +635,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
                     egui::Align2::CENTER_CENTER,
                     "+",
                     egui::FontId::proportional(14.0),
-                    TEXT_COLOR,
+                    text_color(),
                 );
                 if plus_resp.clicked() {
                     app.faq_text_size = (app.faq_text_size + 1.0).min(20.0);
@@ -415,7 +666,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
                 ),
                 (
                     "What are the hotkeys to start and stop Mango Chat?",
-                    "In addition to the start/stop buttons on the UI, you can use Right Ctrl to start and stop recording when that hotkey is enabled in settings.",
+                    "In addition to the start/stop buttons on the UI, you can use Right Ctrl to start and stop recording when that hotkey is enabled in settings. Press Pause to rate the last transcript as accurate, or Shift + Pause as inaccurate.",
                 ),
                 (
                     "Why do I sometimes experience delays or inaccurate transcription?",
@@ -423,7 +674,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
                 ),
                 (
                     "How do I take a screenshot?",
-                    "When screenshot capture is enabled, move your cursor to the monitor you want, press Right Alt, then select the region.",
+                    "When screenshot capture is enabled, move your cursor to the monitor you want, press Right Alt, then select the region. Print Screen captures the whole monitor under your cursor, and Shift + Print Screen captures all monitors. Shift + Right Alt re-opens your last capture so you can grab another region from the same moment.",
                 ),
                 (
                     "What happens after I capture a screenshot?",
@@ -451,7 +702,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
             let a_size = (app.faq_text_size - 0.5).max(9.0);
             let fmt_normal = |sz: f32| egui::text::TextFormat {
                 font_id: egui::FontId::proportional(sz),
-                color: TEXT_MUTED,
+                color: text_muted(),
                 ..Default::default()
             };
             let fmt_accent = |sz: f32| egui::text::TextFormat {
@@ -502,7 +753,7 @@ pub fn render_faq(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Contex
                             egui::Label::new(
                                 egui::RichText::new(*a)
                                     .size(a_size)
-                                    .color(TEXT_MUTED),
+                                    .color(text_muted()),
                             )
                             .wrap(),
                         );
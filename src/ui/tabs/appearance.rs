@@ -1,6 +1,7 @@
 use eframe::egui;
 
 use crate::ui::theme::*;
+use crate::ui::widgets;
 use crate::ui::window::*;
 use crate::ui::MangoChatApp;
 
@@ -31,19 +32,48 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                 .min_col_width(label_w)
                 .spacing([16.0, 10.0])
                 .show(ui, |ui| {
+                    // ── Theme ──
+                    ui.label(
+                        egui::RichText::new("Theme")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    egui::ComboBox::from_id_salt("theme_select")
+                        .selected_text(match app.form.theme.as_str() {
+                            "light" => "Light",
+                            "auto" => "Auto (follow Windows)",
+                            _ => "Dark",
+                        })
+                        .width(control_w)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut app.form.theme, "dark".to_string(), "Dark");
+                            ui.selectable_value(&mut app.form.theme, "light".to_string(), "Light");
+                            ui.selectable_value(
+                                &mut app.form.theme,
+                                "auto".to_string(),
+                                "Auto (follow Windows)",
+                            );
+                        });
+                    ui.end_row();
+
                     // ── Accent color ──
                     ui.label(
                         egui::RichText::new("Theme color")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     {
                         let options = accent_options();
-                        let selected_name = options
-                            .iter()
-                            .find(|o| o.id == app.form.accent_color)
-                            .map(|o| o.name)
-                            .unwrap_or("Green");
+                        let is_custom = app.form.accent_color.starts_with('#');
+                        let selected_name = if is_custom {
+                            "Custom"
+                        } else {
+                            options
+                                .iter()
+                                .find(|o| o.id == app.form.accent_color)
+                                .map(|o| o.name)
+                                .unwrap_or("Green")
+                        };
                         egui::ComboBox::from_id_salt("accent_color_select")
                             .selected_text(
                                 egui::RichText::new(selected_name)
@@ -60,16 +90,136 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                     );
                                     if resp.changed() {}
                                 }
+                                let custom = parse_hex_color(&app.form.custom_accent_hex)
+                                    .unwrap_or(accent.base);
+                                if ui
+                                    .selectable_label(
+                                        is_custom,
+                                        egui::RichText::new("Custom").color(custom),
+                                    )
+                                    .clicked()
+                                {
+                                    app.form.accent_color = hex_color(custom);
+                                }
                             });
                     }
                     ui.end_row();
 
+                    if app.form.accent_color.starts_with('#') {
+                        ui.label(
+                            egui::RichText::new("Custom color")
+                                .size(13.0)
+                                .color(text_color()),
+                        );
+                        ui.horizontal(|ui| {
+                            let mut color = accent.base;
+                            if egui::color_picker::color_edit_button_srgba(
+                                ui,
+                                &mut color,
+                                egui::color_picker::Alpha::Opaque,
+                            )
+                            .changed()
+                            {
+                                app.form.accent_color = hex_color(color);
+                                app.form.custom_accent_hex = app.form.accent_color.clone();
+                            }
+                            let resp = ui.add(
+                                egui::TextEdit::singleline(&mut app.form.custom_accent_hex)
+                                    .hint_text("#rrggbb")
+                                    .desired_width(90.0),
+                            );
+                            if resp.changed() {
+                                if let Some(c) = parse_hex_color(&app.form.custom_accent_hex) {
+                                    app.form.accent_color = hex_color(c);
+                                }
+                            }
+                            ui.add_space(8.0);
+                            ui.label(
+                                egui::RichText::new("(hover and ring shades are derived)")
+                                    .size(12.0)
+                                    .color(text_muted()),
+                            );
+                        });
+                        ui.end_row();
+                    }
+                    // ── Text size ──
+                    ui.label(
+                        egui::RichText::new("Text size")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let label = match app.form.text_size.as_str() {
+                            "small" => "Small",
+                            "large" => "Large",
+                            "custom" => "Custom",
+                            _ => "Medium",
+                        };
+                        egui::ComboBox::from_id_salt("text_size_select")
+                            .selected_text(label)
+                            .width(120.0)
+                            .show_ui(ui, |ui| {
+                                for (id, name) in [
+                                    ("small", "Small"),
+                                    ("medium", "Medium"),
+                                    ("large", "Large"),
+                                    ("custom", "Custom"),
+                                ] {
+                                    ui.selectable_value(
+                                        &mut app.form.text_size,
+                                        id.to_string(),
+                                        name,
+                                    );
+                                }
+                            });
+                        if app.form.text_size == "custom" {
+                            ui.add(
+                                egui::DragValue::new(&mut app.form.ui_scale)
+                                    .range(0.75..=2.0)
+                                    .speed(0.01)
+                                    .fixed_decimals(2)
+                                    .suffix("x"),
+                            );
+                        }
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(scales the whole window, applied on Save)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Pill mode ──
+                    ui.label(
+                        egui::RichText::new("Pill mode")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut pill = app.form.pill_mode;
+                        egui::ComboBox::from_id_salt("pill_mode_select")
+                            .selected_text(if pill { "Yes" } else { "No" })
+                            .width(120.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut pill, true, "Yes");
+                                ui.selectable_value(&mut pill, false, "No");
+                            });
+                        app.form.pill_mode = pill;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(right-click the pill for Settings)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
 
                     // ── Transparent background ──
                     ui.label(
                         egui::RichText::new("Transparent background")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     {
                         let mut transparent = !app.form.compact_background_enabled;
@@ -84,6 +234,84 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     }
                     ui.end_row();
 
+                    // ── Background opacity ──
+                    ui.label(
+                        egui::RichText::new("Background opacity")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.add_enabled(
+                        app.form.compact_background_enabled,
+                        egui::Slider::new(&mut app.form.compact_opacity, 20..=100).suffix("%"),
+                    );
+                    ui.end_row();
+
+                    // ── Visualizer ──
+                    ui.label(
+                        egui::RichText::new("Spectrum bars")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    {
+                        let mut enabled = app.form.visualizer_enabled;
+                        egui::ComboBox::from_id_salt("visualizer_enabled_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(control_w)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.visualizer_enabled = enabled;
+                    }
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Spectrum bands")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.add_enabled(
+                        app.form.visualizer_enabled,
+                        egui::Slider::new(&mut app.form.visualizer_bands, 8..=100),
+                    );
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Visualizer frame rate")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.add_enabled(
+                        app.form.visualizer_enabled,
+                        egui::Slider::new(&mut app.form.visualizer_fps, 5..=60).suffix(" fps"),
+                    );
+                    ui.end_row();
+
+                    // ── Click-through ──
+                    ui.label(
+                        egui::RichText::new("Click-through")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut click_through = app.form.click_through;
+                        egui::ComboBox::from_id_salt("click_through_select")
+                            .selected_text(if click_through { "Yes" } else { "No" })
+                            .width(120.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut click_through, true, "Yes");
+                                ui.selectable_value(&mut click_through, false, "No");
+                            });
+                        app.form.click_through = click_through;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(Scroll Lock toggles interaction)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
                     // ── Separator ──
                     ui.separator();
                     ui.separator();
@@ -93,7 +321,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Monitor")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     {
                         let choices = app.monitor_choices();
@@ -130,7 +358,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Anchor")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     egui::ComboBox::from_id_salt("window_anchor_select")
                         .selected_text(MangoChatApp::anchor_label(&app.form.window_anchor))
@@ -166,6 +394,11 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                 WINDOW_ANCHOR_BOTTOM_RIGHT.to_string(),
                                 "Bottom Right",
                             );
+                            ui.selectable_value(
+                                &mut app.form.window_anchor,
+                                WINDOW_ANCHOR_CUSTOM.to_string(),
+                                "Where I drag it",
+                            );
                         });
                     ui.end_row();
 
@@ -173,7 +406,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Auto-minimize on focus loss")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     {
                         let mut auto_min = app.form.auto_minimize;
@@ -188,6 +421,129 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     }
                     ui.end_row();
 
+                    // ── Edge dock ──
+                    ui.label(
+                        egui::RichText::new("Dock to screen edge")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut dock = app.form.edge_dock;
+                        egui::ComboBox::from_id_salt("edge_dock_select")
+                            .selected_text(if dock { "Yes" } else { "No" })
+                            .width(120.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut dock, true, "Yes");
+                                ui.selectable_value(&mut dock, false, "No");
+                            });
+                        app.form.edge_dock = dock;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(slides back on hover or when recording)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Fullscreen auto-hide ──
+                    ui.label(
+                        egui::RichText::new("Hide when an app is fullscreen")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    {
+                        let mut hide = app.form.hide_when_fullscreen;
+                        egui::ComboBox::from_id_salt("hide_when_fullscreen_select")
+                            .selected_text(if hide { "Yes" } else { "No" })
+                            .width(control_w)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut hide, true, "Yes");
+                                ui.selectable_value(&mut hide, false, "No");
+                            });
+                        app.form.hide_when_fullscreen = hide;
+                    }
+                    ui.end_row();
+
+                    // ── Notifications ──
+                    ui.label(
+                        egui::RichText::new("Desktop notifications")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    {
+                        let mut toasts = app.form.toast_notifications;
+                        egui::ComboBox::from_id_salt("toast_notifications_select")
+                            .selected_text(if toasts { "Yes" } else { "No" })
+                            .width(control_w)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut toasts, true, "Yes");
+                                ui.selectable_value(&mut toasts, false, "No");
+                            });
+                        app.form.toast_notifications = toasts;
+                    }
+                    ui.end_row();
+
+                    // ── Startup ──
+                    ui.label(
+                        egui::RichText::new("Start with Windows")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut autostart = app.form.start_with_windows;
+                        egui::ComboBox::from_id_salt("start_with_windows_select")
+                            .selected_text(if autostart { "Yes" } else { "No" })
+                            .width(control_w)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut autostart, true, "Yes");
+                                ui.selectable_value(&mut autostart, false, "No");
+                            });
+                        app.form.start_with_windows = autostart;
+                        ui.label(
+                            egui::RichText::new("(starts hidden in the tray)")
+                                .size(11.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Background service")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut service = app.form.background_service;
+                        egui::ComboBox::from_id_salt("background_service_select")
+                            .selected_text(if service { "Yes" } else { "No" })
+                            .width(control_w)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut service, true, "Yes");
+                                ui.selectable_value(&mut service, false, "No");
+                            });
+                        app.form.background_service = service;
+                        ui.label(
+                            egui::RichText::new(
+                                "(dictation keeps running when this window closes; after restart)",
+                            )
+                            .size(11.0)
+                            .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Reset defaults")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    if widgets::reset_defaults_button(ui, accent, "(this tab only)") {
+                        app.form.reset_appearance_defaults();
+                        app.set_status("Appearance defaults restored. Click Save to apply.", "idle");
+                    }
+                    ui.end_row();
+
                 });
         });
 }
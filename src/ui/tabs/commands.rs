@@ -14,6 +14,12 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
         ("aliases", "Custom text aliases"),
         ("system", "Mango Chat aliases"),
         ("apps", "App locations"),
+        ("scripts", "Scripts"),
+        ("regions", "Snip regions"),
+        ("plugins", "Plugins"),
+        ("hooks", "Hooks"),
+        ("overrides", "Per-app"),
+        ("test", "Test"),
     ];
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = 12.0;
@@ -35,9 +41,24 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                 "browser" => render_browser_commands(app, ui),
                 "aliases" => render_text_aliases(app, ui),
                 "apps" => render_app_paths(app, ui),
+                "scripts" => render_script_commands(app, ui),
+                "regions" => render_snip_regions(app, ui),
+                "plugins" => render_plugins(app, ui),
+                "hooks" => render_hooks(app, ui),
+                "overrides" => render_app_overrides(app, ui),
                 "system" => render_system_placeholder(ui),
+                "test" => render_command_test(app, ui),
                 _ => render_browser_commands(app, ui),
             }
+            ui.add_space(16.0);
+            if widgets::reset_defaults_button(
+                ui,
+                accent,
+                "(all command lists, app paths and per-app overrides)",
+            ) {
+                app.form.reset_commands_defaults();
+                app.set_status("Command defaults restored. Click Save to apply.", "idle");
+            }
         });
 }
 
@@ -60,7 +81,7 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             egui::RichText::new("Default Browser")
                 .size(12.0)
                 .strong()
-                .color(TEXT_COLOR),
+                .color(text_color()),
         );
 
         ui.add_space(4.0);
@@ -71,27 +92,27 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             ("firefox", "Firefox"),
         ] {
             let active = app.form.default_browser == id;
-            let text_color = if active {
+            let label_color = if active {
                 Color32::BLACK
             } else {
-                TEXT_COLOR
+                text_color()
             };
             let fill = if active {
                 accent.base
             } else {
-                BTN_BG
+                btn_bg()
             };
             let border = if active {
                 accent.ring
             } else {
-                BTN_BORDER
+                btn_border()
             };
             if ui
                 .add(
                     egui::Button::new(
                         egui::RichText::new(label)
                             .size(12.0)
-                            .color(text_color),
+                            .color(label_color),
                     )
                     .fill(fill)
                     .stroke(Stroke::new(1.0, border)),
@@ -118,14 +139,14 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             Align2::LEFT_CENTER,
             "Command",
             font.clone(),
-            TEXT_MUTED,
+            text_muted(),
         );
         ui.painter().text(
             pos2(rect.min.x + trigger_w + spacing, rect.center().y),
             Align2::LEFT_CENTER,
             "Target address",
             font,
-            TEXT_MUTED,
+            text_muted(),
         );
     }
     ui.add_space(4.0);
@@ -138,7 +159,7 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.set_width(row_w.max(0.0));
             ui.visuals_mut().extreme_bg_color =
-                Color32::from_rgb(0x1a, 0x1d, 0x24);
+                input_bg();
             let trigger_id = egui::Id::new(("url_cmd_trigger", i));
             ui.add_sized(
                 [trigger_w, 22.0],
@@ -146,15 +167,15 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
                     .id(trigger_id)
                     .interactive(!cmd.builtin)
                     .font(FontId::proportional(13.0))
-                    .text_color(TEXT_COLOR),
+                    .text_color(text_color()),
             );
             ui.visuals_mut().extreme_bg_color =
-                Color32::from_rgb(0x1a, 0x1d, 0x24);
+                input_bg();
             ui.add_sized(
                 [url_w, 22.0],
                 egui::TextEdit::singleline(&mut cmd.url)
                     .font(FontId::proportional(13.0))
-                    .text_color(TEXT_COLOR),
+                    .text_color(text_color()),
             );
             if !cmd.builtin {
                 if ui
@@ -165,8 +186,8 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
                                 .size(13.0)
                                 .color(RED),
                         )
-                        .fill(BTN_BG)
-                        .stroke(Stroke::new(0.5, BTN_BORDER)),
+                        .fill(btn_bg())
+                        .stroke(Stroke::new(0.5, btn_border())),
                     )
                     .clicked()
                 {
@@ -193,10 +214,10 @@ fn render_browser_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             egui::Button::new(
                 egui::RichText::new("+ Add Command")
                     .size(13.0)
-                    .color(TEXT_COLOR),
+                    .color(text_color()),
             )
-            .fill(BTN_BG)
-            .stroke(Stroke::new(0.5, BTN_BORDER)),
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
         )
         .clicked()
     {
@@ -224,14 +245,14 @@ fn render_text_aliases(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             Align2::LEFT_CENTER,
             "Command",
             font.clone(),
-            TEXT_MUTED,
+            text_muted(),
         );
         ui.painter().text(
             pos2(rect.min.x + trigger_w + spacing, rect.center().y),
             Align2::LEFT_CENTER,
             "Text alias",
             font,
-            TEXT_MUTED,
+            text_muted(),
         );
     }
     ui.add_space(4.0);
@@ -245,22 +266,22 @@ fn render_text_aliases(app: &mut MangoChatApp, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.set_width(row_w.max(0.0));
             ui.visuals_mut().extreme_bg_color =
-                Color32::from_rgb(0x1a, 0x1d, 0x24);
+                input_bg();
             let trigger_id = egui::Id::new(("alias_trigger", i));
             ui.add_sized(
                 [trigger_w, 22.0],
                 egui::TextEdit::singleline(&mut cmd.trigger)
                     .id(trigger_id)
                     .font(FontId::proportional(13.0))
-                    .text_color(TEXT_COLOR),
+                    .text_color(text_color()),
             );
             ui.visuals_mut().extreme_bg_color =
-                Color32::from_rgb(0x1a, 0x1d, 0x24);
+                input_bg();
             ui.add_sized(
                 [replacement_w, 22.0],
                 egui::TextEdit::singleline(&mut cmd.replacement)
                     .font(FontId::proportional(13.0))
-                    .text_color(TEXT_COLOR),
+                    .text_color(text_color()),
             );
             if ui
                 .add_sized(
@@ -270,8 +291,8 @@ fn render_text_aliases(app: &mut MangoChatApp, ui: &mut egui::Ui) {
                             .size(13.0)
                             .color(RED),
                     )
-                    .fill(BTN_BG)
-                    .stroke(Stroke::new(0.5, BTN_BORDER)),
+                    .fill(btn_bg())
+                    .stroke(Stroke::new(0.5, btn_border())),
                 )
                 .clicked()
             {
@@ -291,10 +312,10 @@ fn render_text_aliases(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             egui::Button::new(
                 egui::RichText::new("+ Add Alias")
                     .size(13.0)
-                    .color(TEXT_COLOR),
+                    .color(text_color()),
             )
-            .fill(BTN_BG)
-            .stroke(Stroke::new(0.5, BTN_BORDER)),
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
         )
         .clicked()
     {
@@ -314,7 +335,7 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
     ui.label(
         egui::RichText::new("Use valid .exe paths for this machine; mileage may vary.")
             .size(12.0)
-            .color(TEXT_MUTED),
+            .color(text_muted()),
     );
     ui.add_space(8.0);
 
@@ -330,14 +351,14 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             Align2::LEFT_CENTER,
             "App name",
             font.clone(),
-            TEXT_MUTED,
+            text_muted(),
         );
         ui.painter().text(
             pos2(rect.min.x + trigger_w + spacing, rect.center().y),
             Align2::LEFT_CENTER,
             "App path",
             font,
-            TEXT_MUTED,
+            text_muted(),
         );
     }
     ui.add_space(4.0);
@@ -349,7 +370,7 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
 
         ui.horizontal(|ui| {
             ui.set_width(row_w.max(0.0));
-            ui.visuals_mut().extreme_bg_color = Color32::from_rgb(0x1a, 0x1d, 0x24);
+            ui.visuals_mut().extreme_bg_color = input_bg();
             let trigger_id = egui::Id::new(("app_shortcut_trigger", i));
             ui.add_sized(
                 [trigger_w, 22.0],
@@ -357,14 +378,14 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
                     .id(trigger_id)
                     .interactive(!shortcut.builtin)
                     .font(FontId::proportional(13.0))
-                    .text_color(TEXT_COLOR),
+                    .text_color(text_color()),
             );
-            ui.visuals_mut().extreme_bg_color = Color32::from_rgb(0x1a, 0x1d, 0x24);
+            ui.visuals_mut().extreme_bg_color = input_bg();
             ui.add_sized(
                 [path_w, 22.0],
                 egui::TextEdit::singleline(&mut shortcut.path)
                     .font(FontId::proportional(13.0))
-                    .text_color(TEXT_COLOR),
+                    .text_color(text_color()),
             );
             if !shortcut.builtin {
                 if ui
@@ -375,8 +396,8 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
                                 .size(13.0)
                                 .color(RED),
                         )
-                        .fill(BTN_BG)
-                        .stroke(Stroke::new(0.5, BTN_BORDER)),
+                        .fill(btn_bg())
+                        .stroke(Stroke::new(0.5, btn_border())),
                     )
                     .clicked()
                 {
@@ -399,10 +420,10 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
             egui::Button::new(
                 egui::RichText::new("+ Add Shortcut")
                     .size(13.0)
-                    .color(TEXT_COLOR),
+                    .color(text_color()),
             )
-            .fill(BTN_BG)
-            .stroke(Stroke::new(0.5, BTN_BORDER)),
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
         )
         .clicked()
     {
@@ -417,12 +438,515 @@ fn render_app_paths(app: &mut MangoChatApp, ui: &mut egui::Ui) {
     }
 }
 
+fn render_script_commands(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new(
+            "Runs an .ahk or .ps1 script. Words spoken after the command are passed as the argument.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(8.0);
+
+    let trigger_w = 140.0;
+    let delete_w = 24.0;
+    let spacing = ui.spacing().item_spacing.x;
+    {
+        let row_w = ui.available_width();
+        let (rect, _) = ui.allocate_exact_size(vec2(row_w.max(0.0), 20.0), Sense::hover());
+        let font = FontId::proportional(12.0);
+        ui.painter().text(
+            pos2(rect.min.x, rect.center().y),
+            Align2::LEFT_CENTER,
+            "Command",
+            font.clone(),
+            text_muted(),
+        );
+        ui.painter().text(
+            pos2(rect.min.x + trigger_w + spacing, rect.center().y),
+            Align2::LEFT_CENTER,
+            "Script path",
+            font,
+            text_muted(),
+        );
+    }
+    ui.add_space(4.0);
+
+    let mut delete_idx: Option<usize> = None;
+    for (i, cmd) in app.form.script_commands.iter_mut().enumerate() {
+        let row_w = ui.available_width();
+        let path_w = (row_w - trigger_w - delete_w - spacing * 2.0).max(180.0);
+
+        ui.horizontal(|ui| {
+            ui.set_width(row_w.max(0.0));
+            ui.visuals_mut().extreme_bg_color = input_bg();
+            let trigger_id = egui::Id::new(("script_cmd_trigger", i));
+            ui.add_sized(
+                [trigger_w, 22.0],
+                egui::TextEdit::singleline(&mut cmd.trigger)
+                    .id(trigger_id)
+                    .font(FontId::proportional(13.0))
+                    .text_color(text_color()),
+            );
+            ui.visuals_mut().extreme_bg_color = input_bg();
+            ui.add_sized(
+                [path_w, 22.0],
+                egui::TextEdit::singleline(&mut cmd.path)
+                    .font(FontId::proportional(13.0))
+                    .text_color(text_color()),
+            );
+            if ui
+                .add_sized(
+                    [delete_w, 22.0],
+                    egui::Button::new(
+                        egui::RichText::new("x")
+                            .size(13.0)
+                            .color(RED),
+                    )
+                    .fill(btn_bg())
+                    .stroke(Stroke::new(0.5, btn_border())),
+                )
+                .clicked()
+            {
+                delete_idx = Some(i);
+            }
+        });
+        ui.add_space(2.0);
+    }
+    if let Some(idx) = delete_idx {
+        app.form.script_commands.remove(idx);
+    }
+
+    ui.add_space(6.0);
+    if ui
+        .add_sized(
+            [ui.available_width() - 16.0, 28.0],
+            egui::Button::new(
+                egui::RichText::new("+ Add Script")
+                    .size(13.0)
+                    .color(text_color()),
+            )
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
+        )
+        .clicked()
+    {
+        let new_idx = app.form.script_commands.len();
+        app.form
+            .script_commands
+            .push(crate::settings::ScriptCommand {
+                trigger: String::new(),
+                path: String::new(),
+            });
+        let focus_id = egui::Id::new(("script_cmd_trigger", new_idx));
+        ui.memory_mut(|m| m.request_focus(focus_id));
+    }
+}
+
+fn region_hotkey_label(digit: u8) -> String {
+    match digit {
+        0 => "None".to_string(),
+        d => format!("Ctrl+Shift+{}", d),
+    }
+}
+
+fn render_snip_regions(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new(
+            "Captures a saved area without the snip overlay. Say \"snip\" and the name, \
+             or press its hotkey. Take a snip first, then save its area here.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(8.0);
+
+    let name_w = 140.0;
+    let hotkey_w = 110.0;
+    let delete_w = 24.0;
+    let spacing = ui.spacing().item_spacing.x;
+    {
+        let row_w = ui.available_width();
+        let (rect, _) = ui.allocate_exact_size(vec2(row_w.max(0.0), 20.0), Sense::hover());
+        let font = FontId::proportional(12.0);
+        let mut x = rect.min.x;
+        for (label, w) in [("Name", name_w), ("Hotkey", hotkey_w), ("Area", 0.0)] {
+            ui.painter().text(
+                pos2(x, rect.center().y),
+                Align2::LEFT_CENTER,
+                label,
+                font.clone(),
+                text_muted(),
+            );
+            x += w + spacing;
+        }
+    }
+    ui.add_space(4.0);
+
+    let mut delete_idx: Option<usize> = None;
+    for (i, preset) in app.form.snip_region_presets.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.visuals_mut().extreme_bg_color = input_bg();
+            let name_id = egui::Id::new(("snip_region_name", i));
+            ui.add_sized(
+                [name_w, 22.0],
+                egui::TextEdit::singleline(&mut preset.name)
+                    .id(name_id)
+                    .hint_text("meeting window")
+                    .font(FontId::proportional(13.0))
+                    .text_color(text_color()),
+            );
+            egui::ComboBox::from_id_salt(("snip_region_hotkey", i))
+                .selected_text(region_hotkey_label(preset.hotkey))
+                .width(hotkey_w)
+                .show_ui(ui, |ui| {
+                    for digit in 0..=9 {
+                        ui.selectable_value(&mut preset.hotkey, digit, region_hotkey_label(digit));
+                    }
+                });
+            let r = preset.region;
+            ui.label(
+                egui::RichText::new(format!("{}x{} at {}, {}", r.width, r.height, r.x, r.y))
+                    .size(12.0)
+                    .color(text_muted()),
+            );
+            if ui
+                .add_sized(
+                    [delete_w, 22.0],
+                    egui::Button::new(egui::RichText::new("x").size(13.0).color(RED))
+                        .fill(btn_bg())
+                        .stroke(Stroke::new(0.5, btn_border())),
+                )
+                .clicked()
+            {
+                delete_idx = Some(i);
+            }
+        });
+        ui.add_space(2.0);
+    }
+    if let Some(idx) = delete_idx {
+        app.form.snip_region_presets.remove(idx);
+    }
+
+    ui.add_space(6.0);
+    let last = app.settings.snip_last_region;
+    let add = ui.add_enabled(
+        last.is_some(),
+        egui::Button::new(
+            egui::RichText::new("+ Save Last Snip Area")
+                .size(13.0)
+                .color(text_color()),
+        )
+        .fill(btn_bg())
+        .stroke(Stroke::new(0.5, btn_border()))
+        .min_size(vec2(ui.available_width() - 16.0, 28.0)),
+    );
+    if let Some(region) = last.filter(|_| add.clicked()) {
+        let new_idx = app.form.snip_region_presets.len();
+        app.form
+            .snip_region_presets
+            .push(crate::settings::RegionPreset {
+                name: String::new(),
+                region,
+                hotkey: 0,
+            });
+        let focus_id = egui::Id::new(("snip_region_name", new_idx));
+        ui.memory_mut(|m| m.request_focus(focus_id));
+    }
+}
+
+fn render_plugins(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new(
+            "WASM plugins rewrite each final transcript before it is typed. They run sandboxed, \
+             with no file, network or clipboard access. Changes apply on Save.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(8.0);
+
+    let plugins = crate::plugins::list(&app.form.enabled_plugins);
+    if plugins.is_empty() {
+        ui.label(
+            egui::RichText::new("No .wasm files in the plugins folder yet.")
+                .size(12.0)
+                .color(text_color()),
+        );
+    }
+    for plugin in plugins {
+        ui.horizontal(|ui| {
+            let mut enabled = plugin.enabled;
+            if ui
+                .checkbox(
+                    &mut enabled,
+                    egui::RichText::new(&plugin.file_name)
+                        .size(13.0)
+                        .color(text_color()),
+                )
+                .changed()
+            {
+                if enabled {
+                    app.form.enabled_plugins.push(plugin.file_name.clone());
+                } else {
+                    app.form.enabled_plugins.retain(|n| *n != plugin.file_name);
+                }
+            }
+            if let Some(err) = &plugin.error {
+                ui.label(egui::RichText::new(err).size(11.0).color(RED));
+            }
+        });
+        ui.add_space(2.0);
+    }
+
+    ui.add_space(6.0);
+    if ui
+        .add_sized(
+            [ui.available_width() - 16.0, 28.0],
+            egui::Button::new(
+                egui::RichText::new("Open plugins folder")
+                    .size(13.0)
+                    .color(text_color()),
+            )
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
+        )
+        .clicked()
+    {
+        match crate::plugins::plugins_dir() {
+            Ok(dir) => {
+                if let Err(e) = std::fs::create_dir_all(&dir) {
+                    app.set_status(&format!("Failed to create plugins folder: {}", e), "error");
+                } else {
+                    crate::typing::open_in_explorer(&dir.to_string_lossy());
+                }
+            }
+            Err(e) => app.set_status(&e, "error"),
+        }
+    }
+}
+
+fn render_hooks(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new(
+            "A Rhai script with on_final(text), which can rewrite each final transcript, and \
+             on_command(trigger, args), which handles \"mango <trigger> <args>\" before the \
+             built-in commands. Hooks can call open_url, press_enter, copy and type_text.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(6.0);
+    ui.checkbox(
+        &mut app.form.hooks_enabled,
+        egui::RichText::new("Run hooks (applies on Save)")
+            .size(13.0)
+            .color(text_color()),
+    );
+    ui.add_space(6.0);
+
+    let source = app.hooks_source.get_or_insert_with(crate::hooks::read_script);
+    ui.add(
+        egui::TextEdit::multiline(source)
+            .code_editor()
+            .desired_rows(16)
+            .desired_width(f32::INFINITY),
+    );
+    if let Some(err) = crate::hooks::last_error() {
+        ui.label(egui::RichText::new(err).size(11.0).color(RED));
+    }
+    ui.add_space(6.0);
+    if ui
+        .add_sized(
+            [ui.available_width() - 16.0, 28.0],
+            egui::Button::new(
+                egui::RichText::new("Save script")
+                    .size(13.0)
+                    .color(text_color()),
+            )
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
+        )
+        .clicked()
+    {
+        let source = app.hooks_source.clone().unwrap_or_default();
+        match crate::hooks::write_script(&source) {
+            Ok(()) => {
+                crate::hooks::reload(app.settings.hooks_enabled);
+                if crate::hooks::last_error().is_none() {
+                    app.set_status("Hooks script saved", "idle");
+                }
+            }
+            Err(e) => app.set_status(&e, "error"),
+        }
+    }
+}
+
+fn override_provider_label(id: &str) -> &str {
+    if id.is_empty() {
+        "Default"
+    } else {
+        MangoChatApp::provider_display_name(id)
+    }
+}
+
+fn override_vad_label(mode: &str) -> &'static str {
+    match mode {
+        "strict" => "High",
+        "lenient" => "Low",
+        _ => "Default",
+    }
+}
+
+fn override_output_label(output: &str) -> &'static str {
+    match output {
+        "type" => "Type",
+        "paste" => "Paste",
+        _ => "Default",
+    }
+}
+
+fn render_app_overrides(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new(
+            "Used when the app is in the foreground as a session starts. Blank or Default keeps the global setting.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(8.0);
+
+    let process_w = 140.0;
+    let provider_w = 110.0;
+    let language_w = 50.0;
+    let vad_w = 80.0;
+    let output_w = 80.0;
+    let delete_w = 24.0;
+    let spacing = ui.spacing().item_spacing.x;
+    {
+        let row_w = ui.available_width();
+        let (rect, _) = ui.allocate_exact_size(vec2(row_w.max(0.0), 20.0), Sense::hover());
+        let font = FontId::proportional(12.0);
+        let mut x = rect.min.x;
+        for (label, w) in [
+            ("App (process)", process_w),
+            ("Provider", provider_w),
+            ("Language", language_w),
+            ("Noise", vad_w),
+            ("Output", output_w),
+        ] {
+            ui.painter().text(
+                pos2(x, rect.center().y),
+                Align2::LEFT_CENTER,
+                label,
+                font.clone(),
+                text_muted(),
+            );
+            x += w + spacing;
+        }
+    }
+    ui.add_space(4.0);
+
+    let mut delete_idx: Option<usize> = None;
+    for (i, o) in app.form.app_overrides.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.visuals_mut().extreme_bg_color = input_bg();
+            let process_id = egui::Id::new(("app_override_process", i));
+            ui.add_sized(
+                [process_w, 22.0],
+                egui::TextEdit::singleline(&mut o.process)
+                    .id(process_id)
+                    .hint_text("slack.exe")
+                    .font(FontId::proportional(13.0))
+                    .text_color(text_color()),
+            );
+            egui::ComboBox::from_id_salt(("app_override_provider", i))
+                .selected_text(override_provider_label(&o.provider))
+                .width(provider_w)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut o.provider, String::new(), "Default");
+                    for (id, name) in PROVIDER_ROWS {
+                        ui.selectable_value(&mut o.provider, id.to_string(), *name);
+                    }
+                });
+            ui.add_sized(
+                [language_w, 22.0],
+                egui::TextEdit::singleline(&mut o.language)
+                    .hint_text("en")
+                    .font(FontId::proportional(13.0))
+                    .text_color(text_color()),
+            );
+            egui::ComboBox::from_id_salt(("app_override_vad", i))
+                .selected_text(override_vad_label(&o.vad_mode))
+                .width(vad_w)
+                .show_ui(ui, |ui| {
+                    for mode in ["", "strict", "lenient"] {
+                        ui.selectable_value(
+                            &mut o.vad_mode,
+                            mode.to_string(),
+                            override_vad_label(mode),
+                        );
+                    }
+                });
+            egui::ComboBox::from_id_salt(("app_override_output", i))
+                .selected_text(override_output_label(&o.output))
+                .width(output_w)
+                .show_ui(ui, |ui| {
+                    for output in ["", "type", "paste"] {
+                        ui.selectable_value(
+                            &mut o.output,
+                            output.to_string(),
+                            override_output_label(output),
+                        );
+                    }
+                });
+            if ui
+                .add_sized(
+                    [delete_w, 22.0],
+                    egui::Button::new(egui::RichText::new("x").size(13.0).color(RED))
+                        .fill(btn_bg())
+                        .stroke(Stroke::new(0.5, btn_border())),
+                )
+                .clicked()
+            {
+                delete_idx = Some(i);
+            }
+        });
+        ui.add_space(2.0);
+    }
+    if let Some(idx) = delete_idx {
+        app.form.app_overrides.remove(idx);
+    }
+
+    ui.add_space(6.0);
+    if ui
+        .add_sized(
+            [ui.available_width() - 16.0, 28.0],
+            egui::Button::new(
+                egui::RichText::new("+ Add App Override")
+                    .size(13.0)
+                    .color(text_color()),
+            )
+            .fill(btn_bg())
+            .stroke(Stroke::new(0.5, btn_border())),
+        )
+        .clicked()
+    {
+        let new_idx = app.form.app_overrides.len();
+        app.form
+            .app_overrides
+            .push(crate::settings::AppOverride::default());
+        let focus_id = egui::Id::new(("app_override_process", new_idx));
+        ui.memory_mut(|m| m.request_focus(focus_id));
+    }
+}
+
 fn render_system_placeholder(ui: &mut egui::Ui) {
     let p = theme_palette(ui.visuals().dark_mode);
     ui.label(
         egui::RichText::new("Say these commands by themselves; mileage may vary.")
             .size(12.0)
-            .color(TEXT_MUTED),
+            .color(text_muted()),
     );
     ui.add_space(8.0);
 
@@ -436,13 +960,15 @@ fn render_system_placeholder(ui: &mut egui::Ui) {
         ("undo", "Undo the previous action (Ctrl+Z)."),
         ("copy", "Copy selected text (Ctrl+C)."),
         ("paste", "Paste from clipboard (Ctrl+V)."),
+        ("paste previous", "Paste the dictation pasted before the latest one."),
+        ("paste item three", "Paste the third most recent pasted dictation (one to ten)."),
         ("cut", "Cut selected text (Ctrl+X)."),
         ("select all", "Select all text (Ctrl+A)."),
     ];
 
     egui::Frame::none()
         .fill(p.settings_bg)
-        .stroke(Stroke::new(0.5, BTN_BORDER))
+        .stroke(Stroke::new(0.5, btn_border()))
         .inner_margin(egui::Margin::same(10.0))
         .rounding(egui::Rounding::same(8.0))
         .show(ui, |ui| {
@@ -492,7 +1018,7 @@ fn render_system_placeholder(ui: &mut egui::Ui) {
                                     egui::RichText::new(command)
                                         .size(13.0)
                                         .strong()
-                                        .color(TEXT_COLOR),
+                                        .color(text_color()),
                                 );
                             },
                         );
@@ -503,7 +1029,7 @@ fn render_system_placeholder(ui: &mut egui::Ui) {
                                 ui.label(
                                     egui::RichText::new(behavior)
                                         .size(12.5)
-                                        .color(TEXT_COLOR),
+                                        .color(text_color()),
                                 );
                             },
                         );
@@ -520,6 +1046,130 @@ fn render_system_placeholder(ui: &mut egui::Ui) {
     );
 }
 
+/// Dry-run console: shows what a phrase would trigger using the unsaved form
+/// values, without executing anything.
+fn render_command_test(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    let p = theme_palette(ui.visuals().dark_mode);
+    ui.label(
+        egui::RichText::new(
+            "Type or dictate a phrase to see which command would match. Nothing is executed.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(8.0);
+
+    ui.visuals_mut().extreme_bg_color = input_bg();
+    ui.add_sized(
+        [ui.available_width() - 16.0, 22.0],
+        egui::TextEdit::singleline(&mut app.command_test_input)
+            .id(egui::Id::new("command_test_input"))
+            .hint_text("e.g. mango new line hello")
+            .font(FontId::proportional(13.0))
+            .text_color(text_color()),
+    );
+    ui.add_space(10.0);
+
+    if app.command_test_input.trim().is_empty() {
+        return;
+    }
+
+    let mut preview_settings = app.settings.clone();
+    app.form.apply_to_settings(&mut preview_settings);
+    let url_commands: Vec<(String, String)> = app
+        .form
+        .url_commands
+        .iter()
+        .map(|c| (c.trigger.clone(), c.url.clone()))
+        .collect();
+    let alias_commands: Vec<(String, String)> = app
+        .form
+        .alias_commands
+        .iter()
+        .map(|c| (c.trigger.clone(), c.replacement.clone()))
+        .collect();
+    let app_shortcuts: Vec<(String, String)> = app
+        .form
+        .app_shortcuts
+        .iter()
+        .map(|c| (c.trigger.clone(), c.path.clone()))
+        .collect();
+    let script_commands: Vec<(String, String)> = app
+        .form
+        .script_commands
+        .iter()
+        .map(|c| (c.trigger.clone(), c.path.clone()))
+        .collect();
+    let action = crate::typing::resolve_transcript(
+        // Strip the trailing space left behind when dictating into this field.
+        app.command_test_input.trim(),
+        &preview_settings.resolved_browser_path(),
+        &app.form.paint_path,
+        &url_commands,
+        &alias_commands,
+        &app_shortcuts,
+        &script_commands,
+    );
+    let matched = !matches!(
+        action,
+        crate::typing::TranscriptAction::Type {
+            unknown_command: false,
+            ..
+        }
+    );
+
+    egui::Frame::none()
+        .fill(p.settings_bg)
+        .stroke(Stroke::new(0.5, btn_border()))
+        .inner_margin(egui::Margin::same(10.0))
+        .rounding(egui::Rounding::same(8.0))
+        .show(ui, |ui| {
+            ui.set_min_width(ui.available_width());
+            egui::Grid::new("command_test_grid")
+                .num_columns(2)
+                .spacing([12.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new("Match")
+                            .size(12.0)
+                            .strong()
+                            .color(p.text_muted),
+                    );
+                    ui.label(
+                        egui::RichText::new(action.kind_label())
+                            .size(13.0)
+                            .strong()
+                            .color(if matched { accent.base } else { text_color() }),
+                    );
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Result")
+                            .size(12.0)
+                            .strong()
+                            .color(p.text_muted),
+                    );
+                    ui.add(
+                        egui::Label::new(
+                            egui::RichText::new(action.describe())
+                                .size(12.5)
+                                .color(text_color()),
+                        )
+                        .wrap(),
+                    );
+                    ui.end_row();
+                });
+        });
+
+    ui.add_space(8.0);
+    ui.label(
+        egui::RichText::new("Uses the values on these tabs, including unsaved edits.")
+            .size(12.0)
+            .color(p.text_muted),
+    );
+}
+
 /// Draws a simple globe icon (circle + meridian + equator) at the given center.
 fn draw_globe_icon(painter: &egui::Painter, c: egui::Pos2, s: f32, color: Color32) {
     let r = s * 0.44;
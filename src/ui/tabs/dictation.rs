@@ -1,7 +1,9 @@
 use eframe::egui;
+use std::collections::HashMap;
 use crate::audio;
 use crate::snip;
 use crate::ui::theme::*;
+use crate::ui::widgets;
 use crate::ui::MangoChatApp;
 
 fn truncate_chars(input: &str, max_chars: usize) -> String {
@@ -14,6 +16,43 @@ fn truncate_chars(input: &str, max_chars: usize) -> String {
     out
 }
 
+/// Drag value for one provider's endpointing override. `overrides` only keeps
+/// values that differ from the provider's default.
+fn commit_timing_control(
+    ui: &mut egui::Ui,
+    overrides: &mut HashMap<String, u32>,
+    provider_id: &str,
+    default: u32,
+    range: std::ops::RangeInclusive<u32>,
+) {
+    let mut value = overrides.get(provider_id).copied().unwrap_or(default);
+    let resp = ui.add(egui::DragValue::new(&mut value).range(range).speed(10));
+    if resp.hovered() || resp.has_focus() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
+    }
+    if resp.changed() {
+        if value == default {
+            overrides.remove(provider_id);
+        } else {
+            overrides.insert(provider_id.to_string(), value);
+        }
+    }
+    ui.label(egui::RichText::new("ms").size(12.0).color(text_muted()));
+    ui.add_space(6.0);
+    if overrides.contains_key(provider_id) {
+        if ui.small_button("Default").clicked() {
+            overrides.remove(provider_id);
+        }
+    } else {
+        let name = crate::provider::display_name(provider_id);
+        ui.label(
+            egui::RichText::new(format!("({} default)", name))
+                .size(12.0)
+                .color(text_muted()),
+        );
+    }
+}
+
 pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
     let accent = app.current_accent();
     let frame_overhead = 34.0;
@@ -33,7 +72,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Microphone")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     ui.allocate_ui_with_layout(
                         egui::vec2(ui.available_width(), 26.0),
@@ -70,7 +109,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                             [72.0, 22.0],
                                             egui::Button::new(
                                                 egui::RichText::new("Refresh")
-                                                    .color(TEXT_COLOR),
+                                                    .color(text_color()),
                                             )
                                             .fill(accent.base.gamma_multiply(0.22))
                                             .stroke(egui::Stroke::new(
@@ -97,7 +136,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Session hotkey")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     ui.horizontal(|ui| {
                         let mut enabled = app.form.session_hotkey_enabled;
@@ -120,7 +159,34 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                         ui.label(
                             egui::RichText::new("(outside this window: start/stop recording)")
                                 .size(12.0)
-                                .color(TEXT_MUTED),
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // Win+H takeover
+                    ui.label(
+                        egui::RichText::new("Use Win+H")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.win_h_dictation;
+                        egui::ComboBox::from_id_salt("win_h_dictation_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.win_h_dictation = enabled;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(
+                                "(instead of Windows voice typing; applies after signing out)",
+                            )
+                            .size(12.0)
+                            .color(text_muted()),
                         );
                     });
                     ui.end_row();
@@ -129,7 +195,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Noise suppression")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     egui::ComboBox::from_id_salt("vad_mode")
                         .selected_text(match app.form.vad_mode.as_str() {
@@ -155,7 +221,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Max session length")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     ui.horizontal(|ui| {
                         let resp = ui.add(
@@ -170,7 +236,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                         ui.label(
                             egui::RichText::new("min")
                                 .size(12.0)
-                                .color(TEXT_MUTED),
+                                .color(text_muted()),
                         );
                     });
                     ui.end_row();
@@ -179,7 +245,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Inactivity timeout")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     ui.horizontal(|ui| {
                         let resp = ui.add(
@@ -194,11 +260,308 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                         ui.label(
                             egui::RichText::new("sec")
                                 .size(12.0)
-                                .color(TEXT_MUTED),
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Endpointing (advanced, per provider) ──
+                    let provider_id = app.form.provider.clone();
+                    let (default_silence, default_flush) =
+                        crate::provider::commit_timing_defaults(&provider_id);
+                    ui.label(
+                        egui::RichText::new("Trailing silence")
+                            .size(13.0)
+                            .color(text_color()),
+                    )
+                    .on_hover_text(
+                        "Silence sent before each commit. Raise it if the last word of a \
+                         sentence goes missing.",
+                    );
+                    ui.horizontal(|ui| {
+                        commit_timing_control(
+                            ui,
+                            &mut app.form.pre_commit_silence_ms,
+                            &provider_id,
+                            default_silence,
+                            0..=crate::provider::PRE_COMMIT_SILENCE_MAX_MS,
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Final wait")
+                            .size(13.0)
+                            .color(text_color()),
+                    )
+                    .on_hover_text(
+                        "How long to wait for the provider's final transcript after a \
+                         commit before typing what has arrived.",
+                    );
+                    ui.horizontal(|ui| {
+                        commit_timing_control(
+                            ui,
+                            &mut app.form.commit_flush_timeout_ms,
+                            &provider_id,
+                            default_flush,
+                            crate::provider::COMMIT_FLUSH_TIMEOUT_MIN_MS
+                                ..=crate::provider::COMMIT_FLUSH_TIMEOUT_MAX_MS,
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Low-latency socket")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.ws_tcp_nodelay;
+                        egui::ComboBox::from_id_salt("ws_tcp_nodelay_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.ws_tcp_nodelay = enabled;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(send audio without OS batching; applies next session)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Separator ──
+                    ui.separator();
+                    ui.separator();
+                    ui.end_row();
+
+                    // ── Wake word ──
+                    // Only builds with the `wake-word` feature can load Porcupine.
+                    if cfg!(feature = "wake-word") {
+                        ui.label(
+                            egui::RichText::new("Wake word")
+                                .size(13.0)
+                                .color(text_color()),
+                        );
+                        ui.horizontal(|ui| {
+                            let mut enabled = app.form.wake_word_enabled;
+                            egui::ComboBox::from_id_salt("wake_word_enabled_select")
+                                .selected_text(if enabled { "Yes" } else { "No" })
+                                .width(72.0)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut enabled, true, "Yes");
+                                    ui.selectable_value(&mut enabled, false, "No");
+                                });
+                            app.form.wake_word_enabled = enabled;
+                            let phrase =
+                                crate::wake::keyword_phrase(app.form.wake_word_keyword_path.trim());
+                            if !phrase.is_empty() {
+                                ui.add_space(8.0);
+                                ui.label(
+                                    egui::RichText::new(format!("\"{}\"", phrase))
+                                        .size(13.0)
+                                        .strong()
+                                        .color(accent.base),
+                                );
+                            }
+                            ui.add_space(6.0);
+                            ui.label(
+                                egui::RichText::new("(while idle: start recording hands-free)")
+                                    .size(12.0)
+                                    .color(text_muted()),
+                            );
+                        });
+                        ui.end_row();
+
+                        if app.form.wake_word_enabled {
+                            ui.label(
+                                egui::RichText::new("Picovoice key")
+                                    .size(13.0)
+                                    .color(text_color()),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(
+                                    app.form
+                                        .api_keys
+                                        .entry(crate::wake::ACCESS_KEY_ID.to_string())
+                                        .or_default(),
+                                )
+                                .password(true)
+                                .hint_text("AccessKey from console.picovoice.ai")
+                                .desired_width((content_w - 170.0).max(160.0)),
+                            );
+                            ui.end_row();
+
+                            for (label, path, hint) in [
+                                (
+                                    "Porcupine library",
+                                    &mut app.form.wake_word_library_path,
+                                    "lib/windows/amd64/libpv_porcupine.dll",
+                                ),
+                                (
+                                    "Model file",
+                                    &mut app.form.wake_word_model_path,
+                                    "lib/common/porcupine_params.pv",
+                                ),
+                            ] {
+                                ui.label(egui::RichText::new(label).size(13.0).color(text_color()));
+                                ui.add(
+                                    egui::TextEdit::singleline(path)
+                                        .hint_text(hint)
+                                        .desired_width((content_w - 170.0).max(160.0)),
+                                );
+                                ui.end_row();
+                            }
+
+                            ui.label(
+                                egui::RichText::new("Keyword file")
+                                    .size(13.0)
+                                    .color(text_color()),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(&mut app.form.wake_word_keyword_path)
+                                    .hint_text("hey-mango_en_windows_v3_0_0.ppn")
+                                    .desired_width((content_w - 170.0).max(160.0)),
+                            );
+                            ui.end_row();
+
+                            ui.label(
+                                egui::RichText::new("Sensitivity")
+                                    .size(13.0)
+                                    .color(text_color()),
+                            );
+                            ui.add(
+                                egui::Slider::new(&mut app.form.wake_word_sensitivity, 0.0..=1.0)
+                                    .step_by(0.05),
+                            )
+                            .on_hover_text("Higher misses less but triggers falsely more often");
+                            ui.end_row();
+                        }
+
+                        // ── Separator ──
+                        ui.separator();
+                        ui.separator();
+                        ui.end_row();
+                    }
+
+                    // ── Meeting transcription ──
+                    ui.label(
+                        egui::RichText::new("Meeting folder")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let default_dir = crate::meeting::default_dir()
+                            .map(|d| d.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        ui.add(
+                            egui::TextEdit::singleline(&mut app.form.meeting_folder)
+                                .hint_text(default_dir.as_str())
+                                .desired_width((content_w - 250.0).max(140.0)),
+                        );
+                        if ui.button("Open").clicked() {
+                            match crate::meeting::meeting_dir(&app.settings) {
+                                Ok(dir) => {
+                                    if let Err(e) = std::fs::create_dir_all(&dir) {
+                                        app_err!("[meeting] failed to create folder: {}", e);
+                                    }
+                                    crate::typing::open_in_explorer(&dir.to_string_lossy());
+                                }
+                                Err(e) => app_err!("[meeting] {}", e),
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Notes folder")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    {
+                        let default_dir = crate::notes::default_dir()
+                            .map(|d| d.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        ui.add(
+                            egui::TextEdit::singleline(&mut app.form.voice_notes_folder)
+                                .hint_text(default_dir.as_str())
+                                .desired_width((content_w - 250.0).max(140.0)),
+                        );
+                    }
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Meeting audio")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("meeting_system_audio_select")
+                            .selected_text(if app.form.meeting_system_audio {
+                                "Mic + system"
+                            } else {
+                                "Mic only"
+                            })
+                            .width(120.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut app.form.meeting_system_audio,
+                                    true,
+                                    "Mic + system",
+                                );
+                                ui.selectable_value(
+                                    &mut app.form.meeting_system_audio,
+                                    false,
+                                    "Mic only",
+                                );
+                            });
+                        ui.add_space(6.0);
+                        ui.label(
+                            egui::RichText::new("(you as \"Me\", the call as \"Others\")")
+                                .size(12.0)
+                                .color(text_muted()),
                         );
                     });
                     ui.end_row();
 
+                    ui.label(
+                        egui::RichText::new("Meeting")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let running = app.meeting.is_some();
+                        let label = if running { "Stop" } else { "Start" };
+                        if ui
+                            .add_sized(
+                                [72.0, 22.0],
+                                egui::Button::new(
+                                    egui::RichText::new(label).color(text_color()),
+                                )
+                                .fill(accent.base.gamma_multiply(0.22))
+                                .stroke(egui::Stroke::new(
+                                    1.0,
+                                    accent.base.gamma_multiply(0.85),
+                                )),
+                            )
+                            .clicked()
+                        {
+                            app.toggle_meeting();
+                        }
+                        ui.add_space(6.0);
+                        let note = match app.meeting.as_ref() {
+                            Some(meeting) => format!("Writing {}", meeting.path().display()),
+                            None => "(continuous transcript to Markdown; also in the tray)"
+                                .to_string(),
+                        };
+                        ui.label(egui::RichText::new(note).size(12.0).color(text_muted()));
+                    });
+                    ui.end_row();
+
                     // ── Separator ──
                     ui.separator();
                     ui.separator();
@@ -208,7 +571,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Screenshot capture")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     {
                         let control_w = (content_w - 216.0).max(160.0);
@@ -228,7 +591,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Screenshot hotkey")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     ui.horizontal(|ui| {
                         let mut enabled = app.form.screenshot_hotkey_enabled;
@@ -251,7 +614,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                         ui.label(
                             egui::RichText::new("(outside this window: screenshot on current monitor)")
                                 .size(12.0)
-                                .color(TEXT_MUTED),
+                                .color(text_muted()),
                         );
                     });
                     ui.end_row();
@@ -260,7 +623,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     ui.label(
                         egui::RichText::new("Retention count")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     ui.horizontal(|ui| {
                         let control_w = (content_w - 216.0).max(160.0);
@@ -278,7 +641,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                 ui.label(
                                     egui::RichText::new("images")
                                         .size(12.0)
-                                        .color(TEXT_MUTED),
+                                        .color(text_muted()),
                                 );
                                 ui.with_layout(
                                     egui::Layout::right_to_left(egui::Align::Center),
@@ -288,7 +651,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                                 [148.0, 22.0],
                                                 egui::Button::new(
                                                     egui::RichText::new("Open images folder")
-                                                        .color(TEXT_COLOR),
+                                                        .color(text_color()),
                                                 )
                                                 .fill(accent.base.gamma_multiply(0.22))
                                                 .stroke(egui::Stroke::new(
@@ -312,11 +675,258 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     });
                     ui.end_row();
 
+                    // ── Output format ──
+                    ui.label(
+                        egui::RichText::new("Image format")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("snip_format_select")
+                            .selected_text(match app.form.snip_format.as_str() {
+                                "png" => "PNG",
+                                "webp" => "WebP (lossless)",
+                                _ => "JPEG",
+                            })
+                            .width(140.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut app.form.snip_format,
+                                    "jpeg".to_string(),
+                                    "JPEG",
+                                );
+                                ui.selectable_value(
+                                    &mut app.form.snip_format,
+                                    "png".to_string(),
+                                    "PNG",
+                                );
+                                ui.selectable_value(
+                                    &mut app.form.snip_format,
+                                    "webp".to_string(),
+                                    "WebP (lossless)",
+                                );
+                            });
+                        if app.form.snip_format == "jpeg" {
+                            ui.add_space(8.0);
+                            ui.label(
+                                egui::RichText::new("Quality")
+                                    .size(12.0)
+                                    .color(text_muted()),
+                            );
+                            let resp = ui.add(
+                                egui::DragValue::new(&mut app.form.snip_quality).range(50..=100),
+                            );
+                            if resp.hovered() || resp.has_focus() {
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    // ── Max dimension ──
+                    ui.label(
+                        egui::RichText::new("Max image size")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut limit = app.form.snip_max_dimension > 0;
+                        egui::ComboBox::from_id_salt("snip_max_dimension_select")
+                            .selected_text(if limit { "Downscale" } else { "Original" })
+                            .width(140.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut limit, false, "Original");
+                                ui.selectable_value(&mut limit, true, "Downscale");
+                            });
+                        if !limit {
+                            app.form.snip_max_dimension = 0;
+                        } else {
+                            if app.form.snip_max_dimension == 0 {
+                                app.form.snip_max_dimension = 1920;
+                            }
+                            ui.add_space(8.0);
+                            let resp = ui.add(
+                                egui::DragValue::new(&mut app.form.snip_max_dimension)
+                                    .range(256..=8192),
+                            );
+                            if resp.hovered() || resp.has_focus() {
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
+                            }
+                            ui.label(
+                                egui::RichText::new("px longest edge")
+                                    .size(12.0)
+                                    .color(text_muted()),
+                            );
+                        }
+                    });
+                    ui.end_row();
+
+                    // ── Overlay preview size ──
+                    ui.label(
+                        egui::RichText::new("Overlay preview")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut limit = app.form.snip_preview_max_dimension > 0;
+                        egui::ComboBox::from_id_salt("snip_preview_max_dimension_select")
+                            .selected_text(if limit {
+                                "Downscale"
+                            } else {
+                                "Full resolution"
+                            })
+                            .width(140.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut limit, false, "Full resolution");
+                                ui.selectable_value(&mut limit, true, "Downscale");
+                            });
+                        if !limit {
+                            app.form.snip_preview_max_dimension = 0;
+                        } else {
+                            if app.form.snip_preview_max_dimension == 0 {
+                                app.form.snip_preview_max_dimension = 2560;
+                            }
+                            ui.add_space(8.0);
+                            let resp = ui.add(
+                                egui::DragValue::new(&mut app.form.snip_preview_max_dimension)
+                                    .range(1024..=8192),
+                            );
+                            if resp.hovered() || resp.has_focus() {
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
+                            }
+                            ui.label(
+                                egui::RichText::new("px longest edge")
+                                    .size(12.0)
+                                    .color(text_muted()),
+                            );
+                        }
+                    });
+                    ui.end_row();
+
+                    // ── Capture memory cap ──
+                    ui.label(
+                        egui::RichText::new("Capture memory")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let resp = ui.add(
+                            egui::DragValue::new(&mut app.form.snip_memory_cap_mb)
+                                .range(128..=4096)
+                                .speed(16),
+                        );
+                        if resp.hovered() || resp.has_focus() {
+                            ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
+                        }
+                        ui.label(
+                            egui::RichText::new("MB max for captures and preview")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Screen recording audio ──
+                    ui.label(
+                        egui::RichText::new("Record mic audio")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.snip_record_audio;
+                        egui::ComboBox::from_id_salt("snip_record_audio_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.snip_record_audio = enabled;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(video recordings from the snip overlay)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Overlay magnifier ──
+                    ui.label(
+                        egui::RichText::new("Magnifier")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.snip_magnifier;
+                        egui::ComboBox::from_id_salt("snip_magnifier_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.snip_magnifier = enabled;
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(crosshair and zoomed pixels while selecting)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    // ── Vision model (snip "Ask AI" mode) ──
+                    ui.label(
+                        egui::RichText::new("Ask AI prompt")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("vision_prompt_select")
+                            .selected_text(crate::vision::preset_label(&app.form.vision_prompt))
+                            .width(170.0)
+                            .show_ui(ui, |ui| {
+                                for preset in ["describe", "table", "text"] {
+                                    ui.selectable_value(
+                                        &mut app.form.vision_prompt,
+                                        preset.to_string(),
+                                        crate::vision::preset_label(preset),
+                                    );
+                                }
+                            });
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("(uses your OpenAI key)")
+                                .size(12.0)
+                                .color(text_muted()),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(
+                        egui::RichText::new("Vision model")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut app.form.vision_model)
+                                .desired_width(140.0),
+                        );
+                        ui.add(
+                            egui::TextEdit::singleline(&mut app.form.vision_endpoint)
+                                .hint_text("Chat completions URL")
+                                .desired_width((content_w - 376.0).max(160.0)),
+                        );
+                    });
+                    ui.end_row();
+
                     // ── After edit capture ──
                     ui.label(
                         egui::RichText::new("After edit capture")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
                     {
                         let control_w = (content_w - 216.0).max(160.0);
@@ -348,43 +958,106 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                     }
                     ui.end_row();
 
+                    // ── Transcript archive (opt-in) ──
                     ui.label(
-                        egui::RichText::new("Reset defaults")
+                        egui::RichText::new("Keep transcripts")
                             .size(13.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                     );
-                    ui.horizontal_wrapped(|ui| {
-                        let btn = egui::Button::new(
-                            egui::RichText::new("Reset")
-                                .size(12.0)
-                                .strong()
-                                .color(egui::Color32::BLACK),
-                        )
-                        .fill(accent.base)
-                        .stroke(egui::Stroke::new(1.0, accent.ring));
-                        if ui.add(btn).clicked() {
-                            app.form.reset_non_provider_defaults();
-                            app.mic_devices = audio::list_input_devices();
-                            if !app.form.mic.is_empty()
-                                && !app.mic_devices.contains(&app.form.mic)
-                            {
-                                app.form.mic.clear();
-                            }
-                            app.set_status(
-                                "Defaults restored. Click Save to apply.",
-                                "idle",
-                            );
-                        }
+                    ui.horizontal(|ui| {
+                        let mut enabled = app.form.transcript_archive_enabled;
+                        egui::ComboBox::from_id_salt("transcript_archive_select")
+                            .selected_text(if enabled { "Yes" } else { "No" })
+                            .width(72.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut enabled, true, "Yes");
+                                ui.selectable_value(&mut enabled, false, "No");
+                            });
+                        app.form.transcript_archive_enabled = enabled;
                         ui.add_space(8.0);
                         ui.label(
-                            egui::RichText::new(
-                                "(does not reset provider API keys, usage logs, or custom commands)",
-                            )
-                            .size(12.0)
-                            .color(TEXT_MUTED),
+                            egui::RichText::new("(saved on this PC only, with time and session)")
+                                .size(12.0)
+                                .color(text_muted()),
                         );
                     });
                     ui.end_row();
+
+                    if !app.archive_days.is_empty() {
+                        ui.label(
+                            egui::RichText::new("Export transcripts")
+                                .size(13.0)
+                                .color(text_color()),
+                        );
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_salt("archive_day_select")
+                                .selected_text(app.archive_export_day.clone())
+                                .width(110.0)
+                                .show_ui(ui, |ui| {
+                                    for day in &app.archive_days {
+                                        ui.selectable_value(
+                                            &mut app.archive_export_day,
+                                            day.clone(),
+                                            day.as_str(),
+                                        );
+                                    }
+                                });
+                            egui::ComboBox::from_id_salt("archive_format_select")
+                                .selected_text(if app.archive_export_format == "md" {
+                                    "Markdown"
+                                } else {
+                                    "Text"
+                                })
+                                .width(90.0)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(
+                                        &mut app.archive_export_format,
+                                        "md".to_string(),
+                                        "Markdown",
+                                    );
+                                    ui.selectable_value(
+                                        &mut app.archive_export_format,
+                                        "txt".to_string(),
+                                        "Text",
+                                    );
+                                });
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        egui::RichText::new("Export").color(text_color()),
+                                    )
+                                    .fill(accent.base.gamma_multiply(0.22))
+                                    .stroke(egui::Stroke::new(
+                                        1.0,
+                                        accent.base.gamma_multiply(0.85),
+                                    )),
+                                )
+                                .clicked()
+                            {
+                                app.export_transcript_archive();
+                            }
+                        });
+                        ui.end_row();
+                    }
+
+                    ui.label(
+                        egui::RichText::new("Reset defaults")
+                            .size(13.0)
+                            .color(text_color()),
+                    );
+                    if widgets::reset_defaults_button(
+                        ui,
+                        accent,
+                        "(this tab only; provider keys, appearance and commands stay)",
+                    ) {
+                        app.form.reset_dictation_defaults();
+                        app.mic_devices = audio::list_input_devices();
+                        if !app.form.mic.is_empty() && !app.mic_devices.contains(&app.form.mic) {
+                            app.form.mic.clear();
+                        }
+                        app.set_status("Dictation defaults restored. Click Save to apply.", "idle");
+                    }
+                    ui.end_row();
                 });
         });
 }
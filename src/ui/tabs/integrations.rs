@@ -0,0 +1,254 @@
+use eframe::egui;
+
+use crate::ui::theme::*;
+use crate::ui::widgets::section_header;
+use crate::ui::MangoChatApp;
+
+pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    egui::ScrollArea::vertical()
+        .max_height(ui.available_height().max(260.0))
+        .show(ui, |ui| {
+            ui.add_space(6.0);
+            render_obs(app, ui);
+            ui.add_space(16.0);
+            render_streamdeck(app, ui);
+            ui.add_space(16.0);
+            render_grpc(app, ui);
+            ui.add_space(16.0);
+            render_remote_mic(app, ui, ctx);
+            ui.add_space(16.0);
+            render_mcp(app, ui, ctx);
+        });
+}
+
+/// Live captions in OBS through obs-websocket (OBS 28+, Tools > WebSocket Server Settings).
+fn render_obs(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    section_header(ui, "OBS Captions");
+    ui.checkbox(
+        &mut app.form.obs_enabled,
+        egui::RichText::new("Send transcripts to OBS as live captions")
+            .size(12.0)
+            .color(text_color()),
+    );
+    ui.add_space(4.0);
+    ui.add_enabled_ui(app.form.obs_enabled, |ui| {
+        egui::Grid::new("obs_grid")
+            .num_columns(2)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                let label = |ui: &mut egui::Ui, text: &str| {
+                    ui.label(egui::RichText::new(text).size(12.0).color(text_color()));
+                };
+                label(ui, "Host");
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut app.form.obs_host).desired_width(140.0));
+                    ui.add(egui::DragValue::new(&mut app.form.obs_port).range(1..=65535));
+                });
+                ui.end_row();
+                label(ui, "Password");
+                ui.add(
+                    egui::TextEdit::singleline(
+                        app.form
+                            .api_keys
+                            .entry(crate::settings::OBS_PASSWORD_KEY.to_string())
+                            .or_default(),
+                    )
+                    .password(true)
+                    .desired_width(200.0),
+                );
+                ui.end_row();
+                label(ui, "Text source");
+                ui.add(
+                    egui::TextEdit::singleline(&mut app.form.obs_text_source)
+                        .hint_text("e.g. Captions (optional)")
+                        .desired_width(200.0),
+                );
+                ui.end_row();
+            });
+        ui.checkbox(
+            &mut app.form.obs_stream_captions,
+            egui::RichText::new("Also send final lines as stream captions (CEA-608)")
+                .size(12.0)
+                .color(text_color()),
+        );
+    });
+    if app.settings.obs_enabled {
+        let status = app
+            .state
+            .obs_status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default();
+        if !status.is_empty() {
+            ui.label(
+                egui::RichText::new(format!("OBS: {}", status))
+                    .size(11.0)
+                    .color(accent.base),
+            );
+        }
+    }
+}
+
+/// Endpoint for the Stream Deck plugin; the listener starts at launch.
+fn render_streamdeck(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    section_header(ui, "Stream Deck");
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut app.form.streamdeck_enabled,
+            egui::RichText::new("Accept the Stream Deck plugin on localhost port")
+                .size(12.0)
+                .color(text_color()),
+        );
+        ui.add_enabled(
+            app.form.streamdeck_enabled,
+            egui::DragValue::new(&mut app.form.streamdeck_port).range(1024..=65535),
+        );
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "ws://127.0.0.1:{} (takes effect after restarting Mango Chat). Install the plugin \
+             from integrations/streamdeck and set the same port in its button settings.",
+            app.form.streamdeck_port
+        ))
+        .size(11.0)
+        .color(accent.base),
+    );
+}
+
+/// Typed transcript stream for integrators; the listener starts at launch.
+fn render_grpc(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    section_header(ui, "gRPC API");
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut app.form.grpc_enabled,
+            egui::RichText::new("Stream transcripts over gRPC on localhost port")
+                .size(12.0)
+                .color(text_color()),
+        );
+        ui.add_enabled(
+            app.form.grpc_enabled,
+            egui::DragValue::new(&mut app.form.grpc_port).range(1024..=65535),
+        );
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "127.0.0.1:{} (takes effect after restarting Mango Chat). Service \
+             mangochat.v1.Transcripts, defined in proto/mangochat.proto.",
+            app.form.grpc_port
+        ))
+        .size(11.0)
+        .color(accent.base),
+    );
+}
+
+/// Phone as a microphone over the LAN; the listener starts at launch.
+fn render_remote_mic(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    let accent = app.current_accent();
+    section_header(ui, "Phone Microphone");
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut app.form.remote_mic_enabled,
+            egui::RichText::new("Use my phone as a microphone on port")
+                .size(12.0)
+                .color(text_color()),
+        );
+        ui.add_enabled(
+            app.form.remote_mic_enabled,
+            egui::DragValue::new(&mut app.form.remote_mic_port).range(1024..=65535),
+        );
+    });
+    let Some(url) = app.remote_mic.as_ref().map(|r| r.url()) else {
+        ui.label(
+            egui::RichText::new(
+                "Reachable from your local network (takes effect after restarting Mango Chat).",
+            )
+            .size(11.0)
+            .color(text_muted()),
+        );
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(format!("Open on your phone: {}", url))
+                .size(11.0)
+                .color(accent.base),
+        );
+        if ui.small_button("Copy").clicked() {
+            ctx.copy_text(url.clone());
+            app.set_status("Phone microphone address copied", "idle");
+        }
+    });
+    ui.label(
+        egui::RichText::new(
+            "The address changes each launch. The phone warns that the certificate \
+             isn't trusted: Mango Chat makes its own each launch, so continue to the page.",
+        )
+        .size(11.0)
+        .color(text_muted()),
+    );
+}
+
+/// MCP server for AI assistants; the listener starts at launch.
+fn render_mcp(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    let accent = app.current_accent();
+    section_header(ui, "MCP Server");
+    ui.label(
+        egui::RichText::new(
+            "Lets AI assistants read your last transcript, start or stop dictation \
+             and capture the screen.",
+        )
+        .size(12.0)
+        .color(text_muted()),
+    );
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut app.form.mcp_enabled,
+            egui::RichText::new("Serve MCP tools on localhost port")
+                .size(12.0)
+                .color(text_color()),
+        );
+        ui.add_enabled(
+            app.form.mcp_enabled,
+            egui::DragValue::new(&mut app.form.mcp_port).range(1024..=65535),
+        );
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "http://127.0.0.1:{}/mcp (takes effect after restarting Mango Chat)",
+            app.form.mcp_port
+        ))
+        .size(11.0)
+        .color(accent.base),
+    );
+    ui.add_space(8.0);
+
+    // Clients that only launch commands use the stdio bridge.
+    let exe = std::env::current_exe()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "mangochat.exe".into());
+    let config = serde_json::to_string_pretty(&serde_json::json!({
+        "mcpServers": {
+            "mangochat": {
+                "command": exe,
+                "args": ["--mcp-stdio", "--port", app.form.mcp_port.to_string()]
+            }
+        }
+    }))
+    .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Client config (stdio)")
+                .size(12.0)
+                .color(text_color()),
+        );
+        if ui.small_button("Copy").clicked() {
+            ctx.copy_text(config.clone());
+            app.set_status("MCP client config copied", "idle");
+        }
+    });
+    ui.label(egui::RichText::new(config).monospace().size(11.0).color(text_muted()));
+}
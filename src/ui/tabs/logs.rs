@@ -0,0 +1,315 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::ui::formatting::fmt_bytes;
+use crate::ui::theme::*;
+use crate::ui::MangoChatApp;
+
+/// Lines of the session log kept in view.
+const LOG_TAIL_LINES: usize = 1000;
+/// How often the tail is re-read while the tab is open.
+const LOG_REFRESH: Duration = Duration::from_secs(1);
+
+/// Matches both the plain `[LEVEL]` format and JSON lines.
+fn has_level(line: &str, level: &str) -> bool {
+    line.contains(&format!("[{}]", level)) || line.contains(&format!("\"level\":\"{}\"", level))
+}
+
+fn line_matches(line: &str, filter: &str) -> bool {
+    match filter {
+        "error" => has_level(line, "ERROR"),
+        "warn" => has_level(line, "WARN"),
+        "info" => has_level(line, "INFO"),
+        "debug" => has_level(line, "DEBUG") || has_level(line, "TRACE"),
+        _ => true,
+    }
+}
+
+/// On-demand checks of the mic, provider, clipboard and screen capture.
+fn render_health(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    ui.horizontal(|ui| {
+        let label = if app.health_check_running {
+            "Checking\u{2026}"
+        } else {
+            "Run health check"
+        };
+        if ui
+            .add_enabled(
+                !app.health_check_running,
+                egui::Button::new(
+                    egui::RichText::new(label)
+                        .size(11.0)
+                        .color(egui::Color32::BLACK),
+                )
+                .fill(accent.base)
+                .stroke(egui::Stroke::new(1.0, accent.ring)),
+            )
+            .clicked()
+        {
+            app.run_health_checks();
+        }
+        if !app.health_checks.is_empty()
+            && ui
+                .add(
+                    egui::Button::new(egui::RichText::new("Hide").size(11.0).color(text_color()))
+                        .stroke(egui::Stroke::new(1.0, btn_border())),
+                )
+                .clicked()
+        {
+            app.health_checks.clear();
+        }
+    });
+    if app.health_checks.is_empty() {
+        ui.add_space(6.0);
+        return;
+    }
+    ui.add_space(4.0);
+    egui::Grid::new("health_check_grid")
+        .num_columns(3)
+        .spacing(egui::vec2(10.0, 4.0))
+        .show(ui, |ui| {
+            for check in &app.health_checks {
+                let (mark, color) = if check.passed {
+                    ("\u{2714}", accent.base)
+                } else {
+                    ("\u{2716}", RED)
+                };
+                ui.label(egui::RichText::new(mark).size(12.0).color(color));
+                ui.label(egui::RichText::new(check.name).size(12.0).color(text_color()));
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new(&check.detail).size(11.0).color(text_muted()));
+                    if !check.passed {
+                        ui.label(egui::RichText::new(check.hint).size(11.0).color(accent.base));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    ui.add_space(6.0);
+}
+
+pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    let accent = app.current_accent();
+
+    if app
+        .log_last_read
+        .map(|t| t.elapsed() >= LOG_REFRESH)
+        .unwrap_or(true)
+    {
+        match crate::diagnostics::read_session_log_tail(LOG_TAIL_LINES) {
+            Ok(lines) => app.log_lines = lines,
+            Err(e) => app.log_lines = vec![e],
+        }
+        app.logs_size_bytes = crate::diagnostics::logs_size_bytes();
+        app.log_last_read = Some(Instant::now());
+    }
+    ctx.request_repaint_after(LOG_REFRESH);
+
+    let visible: Vec<String> = app
+        .log_lines
+        .iter()
+        .filter(|l| line_matches(l, &app.log_filter))
+        .cloned()
+        .collect();
+
+    if app.previous_crash {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Mango Chat closed unexpectedly last time.")
+                    .size(12.0)
+                    .color(RED),
+            );
+            if ui
+                .add(
+                    egui::Button::new(
+                        egui::RichText::new("Open crash folder")
+                            .size(11.0)
+                            .color(egui::Color32::BLACK),
+                    )
+                    .fill(accent.base)
+                    .stroke(egui::Stroke::new(1.0, accent.ring)),
+                )
+                .clicked()
+            {
+                app.open_logs_folder();
+                app.previous_crash = false;
+            }
+            if ui
+                .add(
+                    egui::Button::new(egui::RichText::new("Dismiss").size(11.0).color(text_color()))
+                        .stroke(egui::Stroke::new(1.0, btn_border())),
+                )
+                .clicked()
+            {
+                app.previous_crash = false;
+            }
+        });
+        ui.add_space(6.0);
+    }
+
+    render_health(app, ui);
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Show").size(13.0).color(text_color()));
+        egui::ComboBox::from_id_salt("log_filter_select")
+            .selected_text(match app.log_filter.as_str() {
+                "error" => "Errors",
+                "warn" => "Warnings",
+                "info" => "Info",
+                "debug" => "Debug",
+                _ => "All",
+            })
+            .width(100.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut app.log_filter, "all".to_string(), "All");
+                ui.selectable_value(&mut app.log_filter, "info".to_string(), "Info");
+                ui.selectable_value(&mut app.log_filter, "warn".to_string(), "Warnings");
+                ui.selectable_value(&mut app.log_filter, "error".to_string(), "Errors");
+                ui.selectable_value(&mut app.log_filter, "debug".to_string(), "Debug");
+            });
+        ui.add_space(8.0);
+        if ui
+            .add(
+                egui::Button::new(
+                    egui::RichText::new("Copy")
+                        .size(11.0)
+                        .color(egui::Color32::BLACK),
+                )
+                .fill(accent.base)
+                .stroke(egui::Stroke::new(1.0, accent.ring)),
+            )
+            .clicked()
+        {
+            let text = visible.join("\n");
+            ctx.copy_text(text);
+            app.set_status("Log copied", "idle");
+        }
+        if ui
+            .add(
+                egui::Button::new(
+                    egui::RichText::new("Open logs folder")
+                        .size(11.0)
+                        .color(text_color()),
+                )
+                .stroke(egui::Stroke::new(1.0, btn_border())),
+            )
+            .clicked()
+        {
+            app.open_logs_folder();
+        }
+        if ui
+            .add(
+                egui::Button::new(
+                    egui::RichText::new("Export diagnostics")
+                        .size(11.0)
+                        .color(text_color()),
+                )
+                .stroke(egui::Stroke::new(1.0, btn_border())),
+            )
+            .on_hover_text("Zip recent logs, settings, usage and system info. API keys are removed.")
+            .clicked()
+        {
+            app.export_diagnostics_zip();
+        }
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(format!(
+                "{} lines \u{00b7} logs using {}",
+                visible.len(),
+                fmt_bytes(app.logs_size_bytes)
+            ))
+            .size(11.0)
+            .color(text_muted()),
+        );
+    });
+    ui.add_space(4.0);
+    ui.horizontal_wrapped(|ui| {
+        let limit = |v: f64, unit: &str| {
+            if v <= 0.0 {
+                "no limit".to_string()
+            } else {
+                format!("{} {}", v as u32, unit)
+            }
+        };
+        ui.label(egui::RichText::new("Level").size(12.0).color(text_color()));
+        let mut level = app.settings.log_level.clone();
+        egui::ComboBox::from_id_salt("log_level_select")
+            .selected_text(level.as_str())
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for l in crate::diagnostics::LOG_LEVELS {
+                    ui.selectable_value(&mut level, l.to_string(), l);
+                }
+            });
+        if level != app.settings.log_level {
+            app.set_log_level(&level);
+        }
+        let mut json = app.settings.log_json;
+        if ui
+            .checkbox(&mut json, egui::RichText::new("JSON").size(12.0).color(text_color()))
+            .on_hover_text("Write the log as JSON lines for log collectors")
+            .clicked()
+        {
+            app.set_json_logging(json);
+        }
+        let mut perf = app.settings.perf_overlay;
+        if ui
+            .checkbox(&mut perf, egui::RichText::new("Perf overlay").size(12.0).color(text_color()))
+            .on_hover_text("Frame times, event queue and audio backlog (Left Ctrl + Shift + F12)")
+            .clicked()
+        {
+            app.set_perf_overlay(perf);
+        }
+        ui.add_space(8.0);
+        ui.label(egui::RichText::new("Keep").size(12.0).color(text_color()));
+        ui.add(
+            egui::DragValue::new(&mut app.form.log_max_files)
+                .range(0..=1000)
+                .custom_formatter(|v, _| limit(v, "files")),
+        );
+        ui.add(
+            egui::DragValue::new(&mut app.form.log_max_total_mb)
+                .range(0..=10_000)
+                .custom_formatter(|v, _| limit(v, "MB")),
+        );
+        ui.add(
+            egui::DragValue::new(&mut app.form.log_max_age_days)
+                .range(0..=3650)
+                .custom_formatter(|v, _| limit(v, "days")),
+        );
+        ui.label(
+            egui::RichText::new("(older logs are pruned at startup)")
+                .size(11.0)
+                .color(text_muted()),
+        );
+    });
+    ui.add_space(6.0);
+
+    egui::Frame::none()
+        .fill(input_bg())
+        .stroke(egui::Stroke::new(1.0, btn_border()))
+        .rounding(4.0)
+        .inner_margin(6.0)
+        .show(ui, |ui| {
+            egui::ScrollArea::both()
+                .max_height(ui.available_height().max(200.0))
+                .stick_to_bottom(true)
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for line in &visible {
+                        let color = if line.contains("[ERROR]") {
+                            RED
+                        } else {
+                            text_color()
+                        };
+                        ui.label(
+                            egui::RichText::new(line.as_str())
+                                .monospace()
+                                .size(11.0)
+                                .color(color),
+                        );
+                    }
+                });
+        });
+}
@@ -3,5 +3,9 @@ pub mod dictation;
 pub mod commands;
 pub mod appearance;
 pub mod usage;
+pub mod notes;
+pub mod integrations;
 pub mod about;
+pub mod logs;
+pub mod onboarding;
 
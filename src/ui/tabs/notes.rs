@@ -0,0 +1,113 @@
+use eframe::egui;
+
+use crate::ui::formatting::fmt_relative_time;
+use crate::ui::theme::*;
+use crate::ui::widgets::section_header;
+use crate::ui::MangoChatApp;
+
+fn small_button(ui: &mut egui::Ui, text: &str) -> egui::Response {
+    ui.add(
+        egui::Button::new(egui::RichText::new(text).size(11.0).color(text_color()))
+            .stroke(egui::Stroke::new(1.0, btn_border())),
+    )
+}
+
+pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    ui.add_space(4.0);
+    let mut enabled = app.settings.voice_notes_mode;
+    if ui
+        .checkbox(
+            &mut enabled,
+            egui::RichText::new("Voice notes mode: save each utterance instead of typing it")
+                .size(12.0)
+                .color(text_color()),
+        )
+        .changed()
+    {
+        app.set_voice_notes_mode(enabled);
+    }
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut app.voice_notes_filter)
+                .hint_text("Search notes")
+                .desired_width(200.0),
+        );
+        if small_button(ui, "Refresh").clicked() {
+            app.reload_voice_notes();
+        }
+        if small_button(ui, "Open folder").clicked() {
+            match crate::notes::notes_dir(&app.settings) {
+                Ok(dir) => {
+                    if let Err(e) = std::fs::create_dir_all(&dir) {
+                        app_err!("[notes] failed to create folder: {}", e);
+                    }
+                    crate::typing::open_in_explorer(&dir.to_string_lossy());
+                }
+                Err(e) => app_err!("[notes] {}", e),
+            }
+        }
+    });
+    ui.add_space(6.0);
+    section_header(ui, &format!("Notes ({})", app.voice_notes.len()));
+
+    if app.voice_notes.is_empty() {
+        ui.label(
+            egui::RichText::new("No voice notes yet. Turn on voice notes mode and start a session.")
+                .size(12.0)
+                .color(text_muted()),
+        );
+        return;
+    }
+
+    let filter = app.voice_notes_filter.trim().to_lowercase();
+    let mut deleted = None;
+    egui::ScrollArea::vertical()
+        .max_height(ui.available_height().max(200.0))
+        .show(ui, |ui| {
+            for (i, note) in app.voice_notes.iter().enumerate() {
+                if !filter.is_empty() && !note.meta.text.to_lowercase().contains(&filter) {
+                    continue;
+                }
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} · {:.1}s · {}",
+                            fmt_relative_time(note.meta.created_ms),
+                            note.meta.duration_ms as f64 / 1000.0,
+                            crate::provider::display_name(&note.meta.provider)
+                        ))
+                        .size(11.0)
+                        .color(text_muted()),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if small_button(ui, "Delete").clicked() {
+                            deleted = Some(i);
+                        }
+                        if small_button(ui, "Copy").clicked() {
+                            ctx.copy_text(note.meta.text.clone());
+                        }
+                        if small_button(ui, "Play").clicked() {
+                            crate::typing::open_in_explorer(&note.audio_path.to_string_lossy());
+                        }
+                    });
+                });
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(&note.meta.text)
+                            .size(12.0)
+                            .color(text_color()),
+                    )
+                    .wrap(),
+                );
+                ui.separator();
+            }
+        });
+
+    if let Some(i) = deleted {
+        let note = app.voice_notes.remove(i);
+        if let Err(e) = crate::notes::delete(&note) {
+            app_err!("[notes] {}", e);
+            app.reload_voice_notes();
+        }
+    }
+}
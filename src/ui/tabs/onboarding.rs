@@ -0,0 +1,241 @@
+use eframe::egui;
+use egui::{Color32, Stroke};
+
+use crate::ui::theme::*;
+use crate::ui::window::*;
+use crate::ui::MangoChatApp;
+
+const STEPS: [&str; 5] = ["Provider", "API key", "Microphone", "Try it", "Placement"];
+
+/// First-run setup: provider, key, mic, a test dictation and window placement.
+/// Shown as the "Setup" tab until finished or skipped.
+pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    let accent = app.current_accent();
+    let step = app.onboarding_step.unwrap_or(0).min(STEPS.len() - 1);
+
+    ui.horizontal(|ui| {
+        for (i, name) in STEPS.iter().enumerate() {
+            let color = if i == step {
+                accent.base
+            } else if i < step {
+                text_color()
+            } else {
+                text_muted()
+            };
+            ui.label(
+                egui::RichText::new(format!("{}. {}", i + 1, name))
+                    .size(12.0)
+                    .strong()
+                    .color(color),
+            );
+            if i + 1 < STEPS.len() {
+                ui.label(egui::RichText::new("›").size(12.0).color(text_muted()));
+            }
+        }
+    });
+    ui.add_space(12.0);
+
+    let can_continue = match step {
+        0 => render_provider(app, ui),
+        1 => render_api_key(app, ui),
+        2 => render_mic(app, ui),
+        3 => render_try_it(app, ui),
+        _ => render_placement(app, ui),
+    };
+
+    ui.add_space(16.0);
+    ui.horizontal(|ui| {
+        if step > 0 && ui.button("Back").clicked() {
+            app.onboarding_step = Some(step - 1);
+        }
+        let last = step + 1 == STEPS.len();
+        let next = ui.add_enabled(
+            can_continue,
+            egui::Button::new(
+                egui::RichText::new(if last { "Finish" } else { "Next" })
+                    .strong()
+                    .color(Color32::BLACK),
+            )
+            .fill(accent.base)
+            .stroke(Stroke::new(1.0, accent.ring)),
+        );
+        if next.clicked() {
+            if step == 2 {
+                // The test step records with the real settings, so persist now.
+                app.apply_onboarding_form();
+            }
+            if last {
+                app.finish_onboarding(ctx);
+            } else {
+                app.onboarding_step = Some(step + 1);
+            }
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .link(egui::RichText::new("Skip setup").size(12.0).color(text_muted()))
+                .clicked()
+            {
+                app.finish_onboarding(ctx);
+            }
+        });
+    });
+}
+
+fn heading(ui: &mut egui::Ui, title: &str, hint: &str) {
+    ui.label(egui::RichText::new(title).size(15.0).strong().color(text_color()));
+    ui.label(egui::RichText::new(hint).size(12.0).color(text_muted()));
+    ui.add_space(8.0);
+}
+
+fn render_provider(app: &mut MangoChatApp, ui: &mut egui::Ui) -> bool {
+    heading(
+        ui,
+        "Choose a speech provider",
+        "Dictation is transcribed by one of these services. You can change it later.",
+    );
+    let p = theme_palette(is_dark_theme());
+    for (id, name) in PROVIDER_ROWS {
+        let selected = app.form.provider == *id;
+        let label = egui::RichText::new(*name)
+            .size(14.0)
+            .color(MangoChatApp::provider_color(id, p));
+        if ui.selectable_label(selected, label).clicked() {
+            app.form.provider = id.to_string();
+            app.provider_default_explicitly_selected = true;
+        }
+    }
+    !app.form.provider.is_empty()
+}
+
+fn render_api_key(app: &mut MangoChatApp, ui: &mut egui::Ui) -> bool {
+    let provider = app.form.provider.clone();
+    heading(
+        ui,
+        &format!(
+            "Paste your {} API key",
+            MangoChatApp::provider_display_name(&provider)
+        ),
+        "Keys are encrypted for your Windows account, not stored in the settings file.",
+    );
+    let key = app.form.api_keys.entry(provider.clone()).or_default();
+    let resp = ui.add(
+        egui::TextEdit::singleline(key)
+            .password(true)
+            .hint_text("API key")
+            .desired_width(320.0),
+    );
+    let key = key.trim().to_string();
+    if resp.changed() {
+        app.key_check_result.remove(&provider);
+    }
+
+    let inflight = app.key_check_inflight.contains(&provider);
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(!key.is_empty() && !inflight, egui::Button::new("Validate"))
+            .clicked()
+        {
+            app.validate_api_key(&provider, key.clone());
+        }
+        if inflight {
+            ui.spinner();
+        } else if let Some((ok, msg)) = app.key_check_result.get(&provider) {
+            let color = if *ok {
+                app.current_accent().base
+            } else {
+                Color32::from_rgb(0xE5, 0x39, 0x35)
+            };
+            ui.label(egui::RichText::new(msg).size(12.0).color(color));
+        }
+    });
+    app.key_check_result
+        .get(&provider)
+        .map(|(ok, _)| *ok)
+        .unwrap_or(false)
+}
+
+fn render_mic(app: &mut MangoChatApp, ui: &mut egui::Ui) -> bool {
+    heading(
+        ui,
+        "Pick a microphone",
+        "Default follows the Windows default input device.",
+    );
+    ui.horizontal(|ui| {
+        let selected = if app.form.mic.is_empty() {
+            "Default".to_string()
+        } else {
+            app.form.mic.clone()
+        };
+        egui::ComboBox::from_id_salt("onboarding_mic_select")
+            .selected_text(selected)
+            .width(280.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut app.form.mic, String::new(), "Default");
+                for dev in &app.mic_devices {
+                    ui.selectable_value(&mut app.form.mic, dev.clone(), dev);
+                }
+            });
+        if ui.button("Refresh").clicked() {
+            app.mic_devices = crate::audio::list_input_devices();
+            if !app.form.mic.is_empty() && !app.mic_devices.contains(&app.form.mic) {
+                app.form.mic.clear();
+            }
+        }
+    });
+    true
+}
+
+fn render_try_it(app: &mut MangoChatApp, ui: &mut egui::Ui) -> bool {
+    heading(
+        ui,
+        "Try a dictation",
+        "Click Start (or hold Right Ctrl), say a sentence, then stop. The text lands below.",
+    );
+    let resp = ui.add(
+        egui::TextEdit::multiline(&mut app.onboarding_sample)
+            .hint_text("Your dictation appears here")
+            .desired_rows(4)
+            .desired_width(f32::INFINITY),
+    );
+    ui.add_space(6.0);
+    let label = if app.is_recording { "Stop" } else { "Start" };
+    if ui.button(label).clicked() {
+        // Dictation is typed into the focused control, so keep focus on the box.
+        resp.request_focus();
+        if app.is_recording {
+            app.stop_recording();
+        } else {
+            app.start_recording();
+        }
+    }
+    true
+}
+
+fn render_placement(app: &mut MangoChatApp, ui: &mut egui::Ui) -> bool {
+    heading(
+        ui,
+        "Where should Mango Chat sit?",
+        "The compact window stays on top. You can also drag it anywhere later.",
+    );
+    egui::ComboBox::from_id_salt("onboarding_anchor_select")
+        .selected_text(MangoChatApp::anchor_label(&app.form.window_anchor))
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            for anchor in [
+                WINDOW_ANCHOR_TOP_LEFT,
+                WINDOW_ANCHOR_TOP_CENTER,
+                WINDOW_ANCHOR_TOP_RIGHT,
+                WINDOW_ANCHOR_BOTTOM_LEFT,
+                WINDOW_ANCHOR_BOTTOM_CENTER,
+                WINDOW_ANCHOR_BOTTOM_RIGHT,
+            ] {
+                ui.selectable_value(
+                    &mut app.form.window_anchor,
+                    anchor.to_string(),
+                    MangoChatApp::anchor_label(anchor),
+                );
+            }
+        });
+    true
+}
@@ -1,5 +1,5 @@
 use eframe::egui;
-use egui::{Color32, FontId, Stroke, vec2};
+use egui::{FontId, Stroke, vec2};
 
 use crate::ui::theme::*;
 use crate::ui::widgets::*;
@@ -26,7 +26,7 @@ fn provider_dashboard_url(provider_id: &str) -> &'static str {
 }
 
 pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
-    let p = theme_palette(true);
+    let p = theme_palette(is_dark_theme());
     let accent = app.current_accent();
 
     let current_provider_name = PROVIDER_ROWS
@@ -128,6 +128,8 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                 ui.horizontal(|ui| {
                     ui.add_space(row_pad_x);
                     let model_label = provider_model_label(app, &provider_id);
+                    let external_source =
+                        app.settings.external_key_sources.get(&provider_id).cloned();
                     let key_value = app
                         .form
                         .api_keys
@@ -189,7 +191,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                         egui::Label::new(
                                             egui::RichText::new(model_label)
                                                 .size(11.5)
-                                                .color(TEXT_MUTED),
+                                                .color(text_muted()),
                                         )
                                         .wrap_mode(egui::TextWrapMode::Truncate),
                                     );
@@ -207,17 +209,9 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                             ),
                             |ui| {
                                 ui.scope(|ui| {
-                                    let dark = ui.visuals().dark_mode;
-                                    let input_bg = if dark {
-                                        Color32::from_rgb(0x1a, 0x1d, 0x24)
-                                    } else {
-                                        Color32::from_rgb(0xff, 0xff, 0xff)
-                                    };
-                                    let input_stroke = if dark {
-                                        Color32::from_rgb(0x2c, 0x2f, 0x36)
-                                    } else {
-                                        Color32::from_rgb(0xd1, 0xd5, 0xdb)
-                                    };
+                                    let p = theme_palette(ui.visuals().dark_mode);
+                                    let input_bg = p.input_bg;
+                                    let input_stroke = p.btn_border;
                                     let visuals = ui.visuals_mut();
                                     visuals.extreme_bg_color = input_bg;
                                     visuals.widgets.inactive.bg_fill = input_bg;
@@ -229,12 +223,19 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                                         Stroke::new(1.0, input_stroke);
                                     visuals.widgets.active.bg_stroke =
                                         Stroke::new(1.0, input_stroke);
-                                    ui.add_sized(
+                                    let resp = ui.add_sized(
                                         [api_w, 22.0],
                                         egui::TextEdit::singleline(key_value)
                                             .password(true)
+                                            .interactive(external_source.is_none())
                                             .font(FontId::proportional(13.0)),
-                                    )
+                                    );
+                                    match &external_source {
+                                        Some(source) => {
+                                            resp.on_hover_text(format!("Set by {}", source))
+                                        }
+                                        None => resp,
+                                    }
                                 })
                                 .inner
                             },
@@ -275,58 +276,8 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
                         )
                         .inner;
                     if validate_resp.clicked() && key_present && !inflight {
-                        app.key_check_inflight.insert(provider_id.clone());
-                        app.key_check_result.remove(&provider_id);
-                        app.last_validated_provider = Some(provider_id.clone());
-                        let provider_name = PROVIDER_ROWS
-                            .iter()
-                            .find(|(id, _)| *id == provider_id.as_str())
-                            .map(|(_, name)| (*name).to_string())
-                            .unwrap_or_else(|| provider_id.clone());
-                        let provider =
-                            crate::provider::create_provider(&provider_id);
-                        let provider_settings = crate::provider::ProviderSettings {
-                            api_key: key_value.clone(),
-                            model: app.form.model.clone(),
-                            transcription_model: app
-                                .settings
-                                .transcription_model
-                                .clone(),
-                            language: app.form.language.clone(),
-                        };
-                        let event_tx = app.event_tx.clone();
-                        let validated_provider_id = provider_id.clone();
-                        app.runtime.spawn(async move {
-                            let result =
-                                crate::provider::session::validate_key(
-                                    provider,
-                                    provider_settings,
-                                )
-                                .await;
-                            let (ok, message) = match result {
-                                Ok(()) => (
-                                    true,
-                                    format!(
-                                        "{} API key is valid",
-                                        provider_name
-                                    ),
-                                ),
-                                Err(e) => (
-                                    false,
-                                    format!(
-                                        "{} validation failed: {}",
-                                        provider_name, e
-                                    ),
-                                ),
-                            };
-                            let _ = event_tx.send(
-                                crate::state::AppEvent::ApiKeyValidated {
-                                    provider: validated_provider_id,
-                                    ok,
-                                    message,
-                                },
-                            );
-                        });
+                        let key = key_value.clone();
+                        app.validate_api_key(&provider_id, key);
                     }
                     validate_resp.on_hover_text(if inflight {
                         "Validating..."
@@ -373,9 +324,30 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
         ui.label(
             egui::RichText::new("Default provider must have an API key.")
                 .size(11.0)
-                .color(TEXT_MUTED),
+                .color(text_muted()),
         );
     }
+
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Key file")
+                .size(13.0)
+                .color(text_color()),
+        );
+        ui.visuals_mut().extreme_bg_color = input_bg();
+        ui.add(
+            egui::TextEdit::singleline(&mut app.form.api_key_file)
+                .hint_text("Optional path to provider=key lines")
+                .desired_width(280.0)
+                .font(FontId::proportional(13.0)),
+        );
+        ui.label(
+            egui::RichText::new("(read at startup; MANGOCHAT_<PROVIDER>_KEY env vars also work)")
+                .size(11.0)
+                .color(text_muted()),
+        );
+    });
 }
 
 
@@ -4,7 +4,7 @@ use egui::{vec2, Stroke};
 use crate::state::ProviderUsage;
 use crate::ui::formatting::*;
 use crate::ui::theme::*;
-use crate::ui::widgets::section_header;
+use crate::ui::widgets::{section_header, sub_tab_button};
 use crate::ui::MangoChatApp;
 
 /// A column in the metrics table.
@@ -52,18 +52,17 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
 
             // Live session (first column if recording)
             if app.is_recording {
-                if let Ok(s) = app.state.session_usage.lock() {
-                    if s.started_ms != 0 {
-                        columns.push(MetricsCol {
-                            label: "Live".into(),
-                            color: accent.base,
-                            ms_sent: s.ms_sent,
-                            ms_suppressed: s.ms_suppressed,
-                            bytes_sent: s.bytes_sent,
-                            finals: s.finals,
-                            is_live: true,
-                        });
-                    }
+                let s = app.state.session_usage.load();
+                if s.started_ms != 0 {
+                    columns.push(MetricsCol {
+                        label: "Live".into(),
+                        color: accent.base,
+                        ms_sent: s.ms_sent,
+                        ms_suppressed: s.ms_suppressed,
+                        bytes_sent: s.bytes_sent,
+                        finals: s.finals,
+                        is_live: true,
+                    });
                 }
             }
 
@@ -89,7 +88,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
             if let Ok(u) = app.state.usage.lock() {
                 columns.push(MetricsCol {
                     label: "Total".into(),
-                    color: TEXT_MUTED,
+                    color: text_muted(),
                     ms_sent: u.ms_sent,
                     ms_suppressed: u.ms_suppressed,
                     bytes_sent: u.bytes_sent,
@@ -114,7 +113,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                         ui.label(
                             egui::RichText::new(*label)
                                 .size(13.0)
-                                .color(TEXT_MUTED),
+                                .color(text_muted()),
                         );
                     }
                     ui.end_row();
@@ -130,7 +129,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                             let full_w = ui.min_rect().max.x;
                             ui.painter().line_segment(
                                 [egui::pos2(rect.min.x, y), egui::pos2(full_w, y)],
-                                Stroke::new(0.5, BTN_BORDER),
+                                Stroke::new(0.5, btn_border()),
                             );
                         }
 
@@ -169,7 +168,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                                     egui::RichText::new(&col.value(ri))
                                         .size(13.0)
                                         .strong()
-                                        .color(TEXT_COLOR),
+                                        .color(text_color()),
                                 );
                             }
                         }
@@ -185,10 +184,10 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                         egui::Button::new(
                             egui::RichText::new("Reset Totals")
                                 .size(11.0)
-                                .color(TEXT_COLOR),
+                                .color(text_color()),
                         )
-                        .fill(BTN_BG)
-                        .stroke(Stroke::new(1.0, BTN_BORDER))
+                        .fill(btn_bg())
+                        .stroke(Stroke::new(1.0, btn_border()))
                         .rounding(4.0),
                     )
                     .clicked()
@@ -201,10 +200,10 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                         egui::Button::new(
                             egui::RichText::new("Open Log Folder")
                                 .size(11.0)
-                                .color(TEXT_COLOR),
+                                .color(text_color()),
                         )
-                        .fill(BTN_BG)
-                        .stroke(Stroke::new(1.0, BTN_BORDER))
+                        .fill(btn_bg())
+                        .stroke(Stroke::new(1.0, btn_border()))
                         .rounding(4.0),
                     )
                     .clicked()
@@ -230,16 +229,16 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                                 "This deletes usage totals files and clears current totals. Continue?",
                             )
                             .size(11.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                         );
                         ui.add_space(4.0);
                         ui.checkbox(
                             &mut app.confirm_reset_include_sessions,
                             egui::RichText::new(
-                                "Also clear recent sessions (usage-session.jsonl)",
+                                "Also clear recent sessions (session history)",
                             )
                             .size(11.0)
-                            .color(TEXT_COLOR),
+                            .color(text_color()),
                         );
                         ui.add_space(8.0);
                         ui.horizontal(|ui| {
@@ -260,11 +259,12 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                                 if let Ok(mut pt) = app.state.provider_totals.lock() {
                                     pt.clear();
                                 }
-                                let _ = crate::usage::reset_totals_file();
-                                let _ = crate::usage::reset_provider_totals_file();
+                                let _ = crate::usage::reset_totals();
+                                let _ = crate::usage::reset_provider_totals();
                                 if app.confirm_reset_include_sessions {
-                                    let _ = crate::usage::reset_session_file();
+                                    let _ = crate::usage::reset_sessions();
                                     app.session_history.clear();
+                                    app.usage_sessions.clear();
                                 }
                                 app.set_status("Totals reset", "idle");
                                 close_dialog = true;
@@ -277,6 +277,29 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                 }
             }
 
+            ui.add_space(16.0);
+            render_period_summary(app, ui);
+
+            if !app.feedback_counts.is_empty() {
+                ui.add_space(16.0);
+                render_feedback(app, ui);
+            }
+
+            ui.add_space(16.0);
+            render_budgets(app, ui);
+
+            ui.add_space(16.0);
+            render_retention(app, ui);
+
+            ui.add_space(16.0);
+            render_metrics_endpoint(app, ui);
+
+            // ── Charts ──
+            if !app.usage_sessions.is_empty() {
+                ui.add_space(16.0);
+                render_charts(app, ui);
+            }
+
             // ── Recent Sessions ──
             if !app.session_history.is_empty() {
                 ui.add_space(16.0);
@@ -298,7 +321,7 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                                 egui::RichText::new(h)
                                     .size(10.0)
                                     .strong()
-                                    .color(TEXT_MUTED),
+                                    .color(text_muted()),
                             );
                         }
                         ui.end_row();
@@ -307,32 +330,38 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                             ui.label(
                                 egui::RichText::new(fmt_relative_time(s.started_ms))
                                     .size(10.0)
-                                    .color(TEXT_MUTED),
+                                    .color(text_muted()),
                             );
                             ui.label(
                                 egui::RichText::new(&s.provider)
                                     .size(10.0)
-                                    .color(TEXT_COLOR),
+                                    .color(text_color()),
                             );
                             ui.label(
                                 egui::RichText::new(fmt_duration_ms(dur))
                                     .size(10.0)
-                                    .color(TEXT_COLOR),
+                                    .color(text_color()),
                             );
-                            ui.label(
+                            let audio = ui.label(
                                 egui::RichText::new(fmt_duration_ms(s.ms_sent))
                                     .size(10.0)
-                                    .color(TEXT_COLOR),
+                                    .color(if s.chunks_dropped > 0 { RED } else { text_color() }),
                             );
+                            if s.chunks_dropped > 0 {
+                                audio.on_hover_text(format!(
+                                    "{} audio chunks dropped: the connection couldn't keep up",
+                                    s.chunks_dropped
+                                ));
+                            }
                             ui.label(
                                 egui::RichText::new(fmt_bytes(s.bytes_sent))
                                     .size(10.0)
-                                    .color(TEXT_COLOR),
+                                    .color(text_color()),
                             );
                             ui.label(
                                 egui::RichText::new(s.finals.to_string())
                                     .size(10.0)
-                                    .color(TEXT_COLOR),
+                                    .color(text_color()),
                             );
                             ui.end_row();
                         }
@@ -342,8 +371,378 @@ pub fn render(app: &mut MangoChatApp, ui: &mut egui::Ui, ctx: &egui::Context) {
                 ui.label(
                     egui::RichText::new("No session history yet")
                         .size(11.0)
-                        .color(TEXT_MUTED),
+                        .color(text_muted()),
+                );
+            }
+        });
+}
+
+/// Longest session bucket in the duration histogram, in minutes.
+const HISTOGRAM_MAX_MINUTES: usize = 15;
+
+fn day_label(day: f64) -> String {
+    chrono::NaiveDate::from_num_days_from_ce_opt(day.round() as i32)
+        .map(|d| d.format("%b %d").to_string())
+        .unwrap_or_default()
+}
+
+fn change_label(current: f64, previous: f64) -> String {
+    if previous <= 0.0 {
+        if current > 0.0 {
+            "new".into()
+        } else {
+            "-".into()
+        }
+    } else {
+        format!("{:+.0}%", (current - previous) / previous * 100.0)
+    }
+}
+
+/// This day/week/month so far against the whole previous one.
+fn render_period_summary(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    section_header(ui, "Summary");
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 12.0;
+        for (id, label) in [("day", "Day"), ("week", "Week"), ("month", "Month")] {
+            let active = app.usage_period == id;
+            if sub_tab_button(ui, label, active, accent).clicked() {
+                app.usage_period = id.to_string();
+            }
+        }
+    });
+    ui.add_space(4.0);
+
+    let (current, previous) =
+        crate::usage::current_and_previous_period(&app.usage_sessions, &app.usage_period);
+    let (this_label, last_label) = match app.usage_period.as_str() {
+        "day" => ("Today", "Yesterday"),
+        "month" => ("This month", "Last month"),
+        _ => ("This week", "Last week"),
+    };
+    let rows: [(&str, f64, f64, String, String); 4] = [
+        (
+            "Sessions",
+            current.sessions as f64,
+            previous.sessions as f64,
+            current.sessions.to_string(),
+            previous.sessions.to_string(),
+        ),
+        (
+            "Minutes sent",
+            current.minutes_sent,
+            previous.minutes_sent,
+            format!("{:.1}", current.minutes_sent),
+            format!("{:.1}", previous.minutes_sent),
+        ),
+        (
+            "Transcripts",
+            current.finals as f64,
+            previous.finals as f64,
+            current.finals.to_string(),
+            previous.finals.to_string(),
+        ),
+        (
+            "Est. cost",
+            current.cost,
+            previous.cost,
+            format!("${:.2}", current.cost),
+            format!("${:.2}", previous.cost),
+        ),
+    ];
+    egui::Grid::new("usage_period_grid")
+        .num_columns(4)
+        .spacing([16.0, 4.0])
+        .show(ui, |ui| {
+            for h in ["", this_label, last_label, "Change"] {
+                ui.label(egui::RichText::new(h).size(12.0).color(text_muted()));
+            }
+            ui.end_row();
+            for (label, cur, prev, cur_text, prev_text) in rows {
+                ui.label(egui::RichText::new(label).size(12.0).color(text_muted()));
+                ui.label(egui::RichText::new(cur_text).size(12.0).color(text_color()));
+                ui.label(egui::RichText::new(prev_text).size(12.0).color(text_color()));
+                ui.label(
+                    egui::RichText::new(change_label(cur, prev))
+                        .size(12.0)
+                        .color(accent.base),
                 );
+                ui.end_row();
+            }
+        });
+}
+
+/// Thumbs up/down ratings per provider and model (Pause / Shift + Pause or the tray).
+fn render_feedback(app: &MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    let p = theme_palette(ui.visuals().dark_mode);
+    section_header(ui, "Transcript Feedback");
+    egui::Grid::new("usage_feedback_grid")
+        .num_columns(4)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for label in ["Model", "Accurate", "Inaccurate", "Score"] {
+                ui.label(egui::RichText::new(label).size(12.0).color(text_muted()));
+            }
+            ui.end_row();
+            for f in &app.feedback_counts {
+                let name = if f.model.is_empty() {
+                    MangoChatApp::provider_display_name(&f.provider).to_string()
+                } else {
+                    format!(
+                        "{} · {}",
+                        short_provider_name(MangoChatApp::provider_display_name(&f.provider)),
+                        f.model
+                    )
+                };
+                ui.label(
+                    egui::RichText::new(name)
+                        .size(12.0)
+                        .color(MangoChatApp::provider_color(&f.provider, p)),
+                );
+                ui.label(egui::RichText::new(f.accurate.to_string()).size(12.0).color(text_color()));
+                ui.label(
+                    egui::RichText::new(f.inaccurate.to_string())
+                        .size(12.0)
+                        .color(text_color()),
+                );
+                let score = f
+                    .score()
+                    .map(|s| format!("{:.0}%", s * 100.0))
+                    .unwrap_or_else(|| "-".into());
+                ui.label(egui::RichText::new(score).size(12.0).color(text_color()));
+                ui.end_row();
+            }
+        });
+    ui.label(
+        egui::RichText::new("Press Pause after a transcript if it was accurate, Shift + Pause if not.")
+            .size(11.0)
+            .color(accent.base),
+    );
+}
+
+/// Per-provider monthly spend limits against this month's estimated cost.
+/// Limits are form fields, applied on Save like the other tabs.
+fn render_budgets(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    let p = theme_palette(ui.visuals().dark_mode);
+    section_header(ui, "Monthly Budget");
+    egui::Grid::new("usage_budget_grid")
+        .num_columns(3)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for (provider_id, name) in PROVIDER_ROWS {
+                ui.label(
+                    egui::RichText::new(*name)
+                        .size(12.0)
+                        .color(MangoChatApp::provider_color(provider_id, p)),
+                );
+                let limit = app
+                    .form
+                    .monthly_budgets
+                    .entry(provider_id.to_string())
+                    .or_insert(0.0);
+                ui.add(
+                    egui::DragValue::new(limit)
+                        .speed(1.0)
+                        .range(0.0..=10_000.0)
+                        .custom_formatter(|v, _| {
+                            if v <= 0.0 {
+                                "No limit".into()
+                            } else {
+                                format!("${:.2}", v)
+                            }
+                        }),
+                );
+                let limit = *limit;
+                let spent = crate::usage::month_to_date_cost(&app.usage_sessions, provider_id);
+                let (text, color) = if limit > 0.0 {
+                    let pct = spent / limit * 100.0;
+                    let color = if pct >= 100.0 {
+                        RED
+                    } else if pct >= 80.0 {
+                        AMBER
+                    } else {
+                        text_muted()
+                    };
+                    (format!("${:.2} this month ({:.0}%)", spent, pct), color)
+                } else {
+                    (format!("${:.2} this month", spent), text_muted())
+                };
+                ui.label(egui::RichText::new(text).size(12.0).color(color));
+                ui.end_row();
+            }
+        });
+    ui.add_space(4.0);
+    ui.checkbox(
+        &mut app.form.budget_block_at_limit,
+        egui::RichText::new("Refuse new sessions once a provider's budget is spent")
+            .size(12.0)
+            .color(text_color()),
+    );
+    ui.label(
+        egui::RichText::new("Warnings at 80% and 100%. Costs are estimates from list prices.")
+            .size(11.0)
+            .color(accent.base),
+    );
+}
+
+/// Session history limits; pruning runs at startup.
+fn render_retention(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    section_header(ui, "History Retention");
+    egui::Grid::new("usage_retention_grid")
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            ui.label(egui::RichText::new("Keep sessions for").size(12.0).color(text_color()));
+            ui.add(
+                egui::DragValue::new(&mut app.form.session_retention_days)
+                    .speed(1.0)
+                    .range(0..=3650)
+                    .custom_formatter(|v, _| {
+                        if v <= 0.0 {
+                            "Forever".into()
+                        } else {
+                            format!("{} days", v as u32)
+                        }
+                    }),
+            );
+            ui.end_row();
+            ui.label(egui::RichText::new("Max sessions").size(12.0).color(text_color()));
+            ui.add(
+                egui::DragValue::new(&mut app.form.session_retention_max)
+                    .speed(10.0)
+                    .range(0..=1_000_000)
+                    .custom_formatter(|v, _| {
+                        if v <= 0.0 {
+                            "No limit".into()
+                        } else {
+                            format!("{}", v as u32)
+                        }
+                    }),
+            );
+            ui.end_row();
+        });
+    ui.label(
+        egui::RichText::new("Older sessions are pruned the next time Mango Chat starts.")
+            .size(11.0)
+            .color(accent.base),
+    );
+}
+
+/// Optional Prometheus endpoint; the listener starts at launch.
+fn render_metrics_endpoint(app: &mut MangoChatApp, ui: &mut egui::Ui) {
+    let accent = app.current_accent();
+    section_header(ui, "Metrics Endpoint");
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut app.form.metrics_enabled,
+            egui::RichText::new("Serve Prometheus metrics on localhost port")
+                .size(12.0)
+                .color(text_color()),
+        );
+        ui.add_enabled(
+            app.form.metrics_enabled,
+            egui::DragValue::new(&mut app.form.metrics_port).range(1024..=65535),
+        );
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "http://127.0.0.1:{}/metrics (takes effect after restarting Mango Chat)",
+            app.form.metrics_port
+        ))
+        .size(11.0)
+        .color(accent.base),
+    );
+}
+
+fn render_charts(app: &MangoChatApp, ui: &mut egui::Ui) {
+    use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+
+    let accent = app.current_accent();
+    let p = theme_palette(ui.visuals().dark_mode);
+    let days = crate::usage::daily_usage(&app.usage_sessions);
+    let chart_h = 130.0;
+
+    section_header(ui, "Minutes Sent per Day");
+    let bars: Vec<Bar> = days
+        .iter()
+        .map(|(day, u)| Bar::new(*day as f64, u.minutes_sent).width(0.7))
+        .collect();
+    Plot::new("usage_minutes_per_day")
+        .height(chart_h)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .x_axis_formatter(|mark, _| day_label(mark.value))
+        .y_axis_label("min")
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).color(accent.base));
+        });
+
+    ui.add_space(12.0);
+    section_header(ui, "Estimated Cost per Provider");
+    let mut providers: Vec<&String> = days
+        .iter()
+        .flat_map(|(_, u)| u.cost_by_provider.keys())
+        .collect();
+    providers.sort();
+    providers.dedup();
+    Plot::new("usage_cost_per_provider")
+        .height(chart_h)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .legend(Legend::default())
+        .x_axis_formatter(|mark, _| day_label(mark.value))
+        .y_axis_formatter(|mark, _| format!("${:.2}", mark.value))
+        .show(ui, |plot_ui| {
+            for provider in providers {
+                // Cumulative so the lines read as spend to date.
+                let mut total = 0.0;
+                let points: PlotPoints = days
+                    .iter()
+                    .map(|(day, u)| {
+                        total += u.cost_by_provider.get(provider).copied().unwrap_or(0.0);
+                        [*day as f64, total]
+                    })
+                    .collect();
+                plot_ui.line(
+                    Line::new(points)
+                        .name(short_provider_name(MangoChatApp::provider_display_name(
+                            provider,
+                        )))
+                        .color(MangoChatApp::provider_color(provider, p)),
+                );
+            }
+        });
+
+    ui.add_space(12.0);
+    section_header(ui, "Session Length");
+    let buckets = crate::usage::session_duration_histogram(
+        &app.usage_sessions,
+        HISTOGRAM_MAX_MINUTES,
+    );
+    let bars: Vec<Bar> = buckets
+        .iter()
+        .enumerate()
+        .map(|(minutes, count)| Bar::new(minutes as f64, *count as f64).width(0.8))
+        .collect();
+    Plot::new("usage_session_histogram")
+        .height(chart_h)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .x_axis_formatter(|mark, _| {
+            if mark.value >= HISTOGRAM_MAX_MINUTES as f64 {
+                format!("{}m+", HISTOGRAM_MAX_MINUTES)
+            } else {
+                format!("{}m", mark.value as usize)
             }
+        })
+        .y_axis_label("sessions")
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).color(text_muted()));
         });
 }
@@ -1,19 +1,10 @@
 use eframe::egui::Color32;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-// Colors matching the original CSS theme
-pub const TEXT_COLOR: Color32 = Color32::from_rgb(0xe6, 0xe6, 0xe6);
-pub const TEXT_MUTED: Color32 = Color32::from_rgb(0x9c, 0xa3, 0xaf);
-pub const BTN_BG: Color32 = Color32::from_rgb(0x25, 0x28, 0x30);
-pub const BTN_BORDER: Color32 = Color32::from_rgb(0x2c, 0x2f, 0x36);
-pub const SETTINGS_BG: Color32 = Color32::from_rgb(0x1c, 0x1f, 0x2a);
 pub const RED: Color32 = Color32::from_rgb(0xef, 0x44, 0x44);
+pub const AMBER: Color32 = Color32::from_rgb(0xf5, 0x9e, 0x0b);
 
-pub const PROVIDER_ROWS: &[(&str, &str)] = &[
-    ("deepgram", "Deepgram"),
-    ("assemblyai", "AssemblyAI"),
-    ("openai", "OpenAI Realtime"),
-    ("elevenlabs", "ElevenLabs Realtime"),
-];
+pub use crate::provider::PROVIDER_ROWS;
 
 #[derive(Clone, Copy)]
 pub struct ThemePalette {
@@ -22,6 +13,166 @@ pub struct ThemePalette {
     pub btn_bg: Color32,
     pub btn_border: Color32,
     pub settings_bg: Color32,
+    pub input_bg: Color32,
+    pub hover_bg: Color32,
+    pub hover_border: Color32,
+}
+
+// Colors matching the original CSS theme
+pub const DARK_PALETTE: ThemePalette = ThemePalette {
+    text: Color32::from_rgb(0xe6, 0xe6, 0xe6),
+    text_muted: Color32::from_rgb(0x9c, 0xa3, 0xaf),
+    btn_bg: Color32::from_rgb(0x25, 0x28, 0x30),
+    btn_border: Color32::from_rgb(0x2c, 0x2f, 0x36),
+    settings_bg: Color32::from_rgb(0x1c, 0x1f, 0x2a),
+    input_bg: Color32::from_rgb(0x1a, 0x1d, 0x24),
+    hover_bg: Color32::from_rgb(0x2d, 0x31, 0x3c),
+    hover_border: Color32::from_rgb(0x44, 0x48, 0x54),
+};
+
+pub const LIGHT_PALETTE: ThemePalette = ThemePalette {
+    text: Color32::from_rgb(0x1f, 0x23, 0x28),
+    text_muted: Color32::from_rgb(0x6b, 0x72, 0x80),
+    btn_bg: Color32::from_rgb(0xe9, 0xec, 0xf1),
+    btn_border: Color32::from_rgb(0xd1, 0xd5, 0xdb),
+    settings_bg: Color32::from_rgb(0xf6, 0xf7, 0xf9),
+    input_bg: Color32::from_rgb(0xff, 0xff, 0xff),
+    hover_bg: Color32::from_rgb(0xdd, 0xe1, 0xe8),
+    hover_border: Color32::from_rgb(0xb8, 0xbe, 0xc9),
+};
+
+/// Active theme, set from settings each frame by `apply_appearance`.
+static DARK_THEME: AtomicBool = AtomicBool::new(true);
+
+pub fn set_dark_theme(dark: bool) {
+    DARK_THEME.store(dark, Ordering::Relaxed);
+}
+
+pub fn is_dark_theme() -> bool {
+    DARK_THEME.load(Ordering::Relaxed)
+}
+
+/// Last known Windows app theme, kept current by `start_system_theme_watcher`.
+static SYSTEM_DARK: AtomicBool = AtomicBool::new(true);
+
+/// Resolve a theme setting ("dark" | "light" | "auto") to dark or not.
+pub fn theme_is_dark(theme: &str) -> bool {
+    match theme {
+        "light" => false,
+        "auto" => SYSTEM_DARK.load(Ordering::Relaxed),
+        _ => true,
+    }
+}
+
+#[cfg(windows)]
+const PERSONALIZE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize";
+
+/// Read `AppsUseLightTheme`. `None` when the value is missing (older Windows
+/// builds), which leaves the dark default in place.
+#[cfg(windows)]
+fn read_system_dark() -> Option<bool> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let mut value: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(PERSONALIZE_KEY),
+            &HSTRING::from("AppsUseLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut u32 as *mut _),
+            Some(&mut size),
+        )
+    };
+    status.is_ok().then_some(value == 0)
+}
+
+/// Track the Windows light/dark app theme and send `SystemThemeChanged`
+/// whenever it flips, so "auto" can switch palettes live.
+pub fn start_system_theme_watcher(event_tx: std::sync::mpsc::Sender<crate::state::AppEvent>) {
+    #[cfg(not(windows))]
+    {
+        let _ = event_tx;
+    }
+
+    #[cfg(windows)]
+    {
+        use windows::core::HSTRING;
+        use windows::Win32::Foundation::HANDLE;
+        use windows::Win32::System::Registry::{
+            RegCloseKey, RegNotifyChangeKeyValue, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER,
+            KEY_NOTIFY, KEY_READ, REG_NOTIFY_CHANGE_LAST_SET,
+        };
+
+        if let Some(dark) = read_system_dark() {
+            SYSTEM_DARK.store(dark, Ordering::Relaxed);
+        }
+        std::thread::spawn(move || unsafe {
+            let mut key = HKEY::default();
+            let status = RegOpenKeyExW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(PERSONALIZE_KEY),
+                0,
+                KEY_READ | KEY_NOTIFY,
+                &mut key,
+            );
+            if status.is_err() {
+                app_err!("[theme] failed to open Personalize key: {:?}", status);
+                return;
+            }
+            loop {
+                // Blocks until a value under the key changes.
+                let status = RegNotifyChangeKeyValue(
+                    key,
+                    false,
+                    REG_NOTIFY_CHANGE_LAST_SET,
+                    HANDLE::default(),
+                    false,
+                );
+                if status.is_err() {
+                    app_err!("[theme] registry watch failed: {:?}", status);
+                    break;
+                }
+                let Some(dark) = read_system_dark() else {
+                    continue;
+                };
+                if SYSTEM_DARK.swap(dark, Ordering::Relaxed) != dark {
+                    app_log!("[theme] system theme changed: dark={}", dark);
+                    if event_tx.send(crate::state::AppEvent::SystemThemeChanged).is_err() {
+                        break;
+                    }
+                }
+            }
+            let _ = RegCloseKey(key);
+        });
+    }
+}
+
+pub fn text_color() -> Color32 {
+    theme_palette(is_dark_theme()).text
+}
+
+pub fn text_muted() -> Color32 {
+    theme_palette(is_dark_theme()).text_muted
+}
+
+pub fn btn_bg() -> Color32 {
+    theme_palette(is_dark_theme()).btn_bg
+}
+
+pub fn btn_border() -> Color32 {
+    theme_palette(is_dark_theme()).btn_border
+}
+
+pub fn settings_bg() -> Color32 {
+    theme_palette(is_dark_theme()).settings_bg
+}
+
+pub fn input_bg() -> Color32 {
+    theme_palette(is_dark_theme()).input_bg
 }
 
 #[derive(Clone, Copy)]
@@ -34,17 +185,76 @@ pub struct AccentPalette {
     pub tint_bg: Color32,
 }
 
-pub fn theme_palette(_dark: bool) -> ThemePalette {
-    ThemePalette {
-        text: TEXT_COLOR,
-        text_muted: TEXT_MUTED,
-        btn_bg: BTN_BG,
-        btn_border: BTN_BORDER,
-        settings_bg: SETTINGS_BG,
+pub fn theme_palette(dark: bool) -> ThemePalette {
+    if dark {
+        DARK_PALETTE
+    } else {
+        LIGHT_PALETTE
+    }
+}
+
+/// Whole-UI zoom factor for the text size setting. Applied through egui's
+/// zoom so fixed font sizes, widget metrics and window sizes all scale.
+pub fn ui_scale(text_size: &str, custom: f32) -> f32 {
+    match text_size {
+        "small" => 0.9,
+        "large" => 1.15,
+        "custom" => custom.clamp(0.75, 2.0),
+        _ => 1.0,
     }
 }
 
+/// egui visuals for the theme, with window/panel fills taken from the palette.
+pub fn theme_visuals(dark: bool) -> eframe::egui::Visuals {
+    let p = theme_palette(dark);
+    let mut visuals = if dark {
+        eframe::egui::Visuals::dark()
+    } else {
+        eframe::egui::Visuals::light()
+    };
+    visuals.panel_fill = p.settings_bg;
+    visuals.window_fill = p.settings_bg;
+    visuals.extreme_bg_color = p.input_bg;
+    visuals
+}
+
+/// Parse "#RRGGBB" (leading '#' optional).
+pub fn parse_hex_color(hex: &str) -> Option<Color32> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let v = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color32::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8))
+}
+
+pub fn hex_color(c: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b())
+}
+
+fn mix(a: Color32, b: Color32, t: f32) -> Color32 {
+    let ch = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgb(ch(a.r(), b.r()), ch(a.g(), b.g()), ch(a.b(), b.b()))
+}
+
+/// Custom accent: hover/ring are progressively darker, tint is a pale wash,
+/// roughly matching the steps between the preset colors.
+fn custom_accent(base: Color32) -> AccentPalette {
+    AccentPalette {
+        id: "custom",
+        name: "Custom",
+        base,
+        hover: mix(base, Color32::BLACK, 0.10),
+        ring: mix(base, Color32::BLACK, 0.25),
+        tint_bg: mix(base, Color32::WHITE, 0.55),
+    }
+}
+
+/// Preset id ("green", "purple", ...) or a custom "#RRGGBB" value.
 pub fn accent_palette(id: &str) -> AccentPalette {
+    if let Some(base) = id.starts_with('#').then(|| parse_hex_color(id)).flatten() {
+        return custom_accent(base);
+    }
     match id {
         "purple" => AccentPalette {
             id: "purple",
@@ -1,26 +1,176 @@
-use super::theme::AccentPalette;
+use super::theme::{AccentPalette, PROVIDER_ROWS};
 
 /// Mango icon PNG embedded at compile time.
 const MANGO_PNG: &[u8] = include_bytes!("../../icons/mango.png");
 
-pub fn setup_tray(_accent: AccentPalette) -> Option<tray_icon::TrayIcon> {
-    use tray_icon::menu::{Menu, MenuItem, PredefinedMenuItem};
-    use tray_icon::TrayIconBuilder;
+/// What the tray icon overlay shows on top of the mango.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TrayIconStatus {
+    Idle,
+    /// Recording, with the current pulse frame (0..TRAY_PULSE_FRAMES).
+    Recording(u8),
+    Error,
+}
+
+/// Frames in one recording pulse cycle.
+pub const TRAY_PULSE_FRAMES: u8 = 6;
+/// Duration of one recording pulse cycle.
+pub const TRAY_PULSE_PERIOD_MS: u128 = 1200;
+
+/// Live values reflected in the tray menu; rebuilt whenever they change.
+pub struct TrayMenuState {
+    pub recording: bool,
+    pub meeting: bool,
+    pub voice_notes: bool,
+    pub provider: String,
+    /// (provider id, has API key)
+    pub providers_with_keys: Vec<(String, bool)>,
+    pub screenshots: bool,
+    pub pill: bool,
+    pub window_hidden: bool,
+    pub recent_transcripts: Vec<String>,
+    /// A final transcript is waiting for a rating.
+    pub can_rate: bool,
+}
+
+/// Longest transcript label shown in the "Recent transcripts" submenu.
+const TRANSCRIPT_LABEL_CHARS: usize = 48;
+
+/// A transcript shortened to fit on one menu line.
+pub fn transcript_label(text: &str) -> String {
+    let mut label: String = text.chars().take(TRANSCRIPT_LABEL_CHARS).collect();
+    if text.chars().count() > TRANSCRIPT_LABEL_CHARS {
+        label.push_str("...");
+    }
+    label
+}
+
+pub fn build_tray_menu(state: &TrayMenuState) -> tray_icon::menu::Menu {
+    use tray_icon::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 
     let menu = Menu::new();
-    let quit = MenuItem::with_id("quit", "Quit", true, None);
+    let window = MenuItem::with_id(
+        "toggle_window",
+        if state.window_hidden {
+            "Show Mango Chat"
+        } else {
+            "Hide to tray"
+        },
+        true,
+        None,
+    );
+    let _ = menu.append(&window);
+
+    let record = MenuItem::with_id(
+        "toggle_recording",
+        if state.recording {
+            "Stop recording"
+        } else {
+            "Start recording"
+        },
+        true,
+        None,
+    );
+    let _ = menu.append(&record);
 
+    let meeting = MenuItem::with_id(
+        "toggle_meeting",
+        if state.meeting {
+            "Stop meeting transcription"
+        } else {
+            "Start meeting transcription"
+        },
+        true,
+        None,
+    );
+    let _ = menu.append(&meeting);
+
+    let providers = Submenu::new("Provider", true);
+    for (id, label) in PROVIDER_ROWS {
+        let has_key = state
+            .providers_with_keys
+            .iter()
+            .any(|(p, ok)| p == id && *ok);
+        let item = CheckMenuItem::with_id(
+            format!("provider:{}", id),
+            *label,
+            has_key,
+            state.provider == *id,
+            None,
+        );
+        let _ = providers.append(&item);
+    }
+    let _ = menu.append(&providers);
+
+    let screenshots = CheckMenuItem::with_id(
+        "toggle_screenshots",
+        "Screenshot mode",
+        true,
+        state.screenshots,
+        None,
+    );
+    let _ = menu.append(&screenshots);
+
+    let voice_notes = CheckMenuItem::with_id(
+        "toggle_voice_notes",
+        "Voice notes mode",
+        true,
+        state.voice_notes,
+        None,
+    );
+    let _ = menu.append(&voice_notes);
+
+    let pill = CheckMenuItem::with_id("toggle_pill", "Pill mode", true, state.pill, None);
+    let _ = menu.append(&pill);
+
+    let copy_last = MenuItem::with_id(
+        "copy_last",
+        "Copy last transcript",
+        !state.recent_transcripts.is_empty(),
+        None,
+    );
+    let _ = menu.append(&copy_last);
+
+    let recent = Submenu::new("Recent transcripts", !state.recent_transcripts.is_empty());
+    for (i, text) in state.recent_transcripts.iter().enumerate() {
+        let item = MenuItem::with_id(
+            format!("transcript:{}", i),
+            transcript_label(text),
+            true,
+            None,
+        );
+        let _ = recent.append(&item);
+    }
+    let _ = menu.append(&recent);
+
+    let rate = Submenu::new("Rate last transcript", state.can_rate);
+    let _ = rate.append(&MenuItem::with_id("rate:up", "Accurate", true, None));
+    let _ = rate.append(&MenuItem::with_id("rate:down", "Inaccurate", true, None));
+    let _ = menu.append(&rate);
+
+    let quit = MenuItem::with_id("quit", "Quit", true, None);
     let _ = menu.append(&PredefinedMenuItem::separator());
     let _ = menu.append(&quit);
+    menu
+}
 
-    let icon = match make_tray_icon() {
+pub fn setup_tray(
+    accent: AccentPalette,
+    dark: bool,
+    menu_state: &TrayMenuState,
+) -> Option<tray_icon::TrayIcon> {
+    use tray_icon::TrayIconBuilder;
+
+    let menu = build_tray_menu(menu_state);
+
+    let icon = match make_tray_icon(accent, dark, TrayIconStatus::Idle) {
         Some(i) => i,
         None => return None,
     };
 
     let tray = match TrayIconBuilder::new()
         .with_menu(Box::new(menu))
-        .with_tooltip("Mango Chat")
+        .with_tooltip(crate::profile::window_title())
         .with_icon(icon)
         .build()
     {
@@ -37,18 +187,73 @@ pub fn setup_tray(_accent: AccentPalette) -> Option<tray_icon::TrayIcon> {
     tray
 }
 
-fn make_tray_icon() -> Option<tray_icon::Icon> {
-    let img = match image::load_from_memory(MANGO_PNG) {
-        Ok(i) => i,
+/// Swap the tray icon for one reflecting `status` (recording pulse or error badge).
+pub fn update_tray_icon(
+    tray: &tray_icon::TrayIcon,
+    accent: AccentPalette,
+    dark: bool,
+    status: TrayIconStatus,
+) {
+    if let Some(icon) = make_tray_icon(accent, dark, status) {
+        if let Err(e) = tray.set_icon(Some(icon)) {
+            app_err!("[tray] set_icon error: {}", e);
+        }
+    }
+    let tooltip = match status {
+        TrayIconStatus::Idle => "Mango Chat",
+        TrayIconStatus::Recording(_) => "Mango Chat — recording",
+        TrayIconStatus::Error => "Mango Chat — error",
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// Decoded 32x32 mango, cached so pulse frames don't re-decode the PNG.
+fn base_icon() -> Option<&'static image::RgbaImage> {
+    static BASE: std::sync::OnceLock<Option<image::RgbaImage>> = std::sync::OnceLock::new();
+    BASE.get_or_init(|| match image::load_from_memory(MANGO_PNG) {
+        // Resize to 32x32 (crisp on standard and high-DPI displays)
+        Ok(img) => Some(
+            img.resize(32, 32, image::imageops::FilterType::Lanczos3)
+                .to_rgba8(),
+        ),
         Err(e) => {
             app_err!("[tray] failed to decode mango.png: {}", e);
-            return None;
+            None
         }
-    };
+    })
+    .as_ref()
+}
 
-    // Resize to 32x32 (crisp on standard and high-DPI displays)
-    let resized = img.resize(32, 32, image::imageops::FilterType::Lanczos3);
-    let rgba = resized.to_rgba8();
+fn make_tray_icon(
+    accent: AccentPalette,
+    dark: bool,
+    status: TrayIconStatus,
+) -> Option<tray_icon::Icon> {
+    let mut rgba = base_icon()?.clone();
+    if !dark {
+        outline_icon(&mut rgba);
+    }
+    match status {
+        TrayIconStatus::Idle => {}
+        TrayIconStatus::Recording(frame) => {
+            // Triangle wave over the cycle: the dot swells and fades back.
+            let half = (TRAY_PULSE_FRAMES / 2).max(1) as f32;
+            let t = 1.0 - ((frame % TRAY_PULSE_FRAMES) as f32 - half).abs() / half;
+            let radius = 6.0 + 2.0 * t;
+            let ring = [accent.base.r(), accent.base.g(), accent.base.b(), 255];
+            paint_dot(&mut rgba, 24.0, 24.0, radius + 1.5, ring);
+            paint_dot(&mut rgba, 24.0, 24.0, radius, [0xE5, 0x39, 0x35, 170 + (85.0 * t) as u8]);
+        }
+        TrayIconStatus::Error => {
+            paint_dot(&mut rgba, 24.0, 8.0, 8.0, [0xFF, 0xFF, 0xFF, 255]);
+            paint_dot(&mut rgba, 24.0, 8.0, 7.0, [0xF5, 0x9E, 0x0B, 255]);
+            // "!" mark
+            for y in 4..10 {
+                paint_dot(&mut rgba, 24.0, y as f32, 0.9, [0x20, 0x20, 0x20, 255]);
+            }
+            paint_dot(&mut rgba, 24.0, 12.0, 1.0, [0x20, 0x20, 0x20, 255]);
+        }
+    }
     let (w, h) = rgba.dimensions();
 
     match tray_icon::Icon::from_rgba(rgba.into_raw(), w, h) {
@@ -59,3 +264,52 @@ fn make_tray_icon() -> Option<tray_icon::Icon> {
         }
     }
 }
+
+/// Alpha-blend an anti-aliased filled circle onto the icon.
+fn paint_dot(img: &mut image::RgbaImage, cx: f32, cy: f32, radius: f32, color: [u8; 4]) {
+    let (w, h) = img.dimensions();
+    for y in 0..h {
+        for x in 0..w {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let coverage = (radius + 0.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+            if coverage <= 0.0 {
+                continue;
+            }
+            let a = coverage * color[3] as f32 / 255.0;
+            let px = img.get_pixel_mut(x, y);
+            for c in 0..3 {
+                px[c] = (color[c] as f32 * a + px[c] as f32 * (1.0 - a)).round() as u8;
+            }
+            px[3] = (255.0 * a + px[3] as f32 * (1.0 - a)).round() as u8;
+        }
+    }
+}
+
+/// Light-theme variant: add a thin dark outline so the mango stays legible
+/// on light taskbars.
+fn outline_icon(img: &mut image::RgbaImage) {
+    let src = img.clone();
+    let (w, h) = src.dimensions();
+    for y in 0..h {
+        for x in 0..w {
+            if src.get_pixel(x, y)[3] > 0 {
+                continue;
+            }
+            let near_opaque = (-1i32..=1).any(|dy| {
+                (-1i32..=1).any(|dx| {
+                    let nx = x as i32 + dx;
+                    let ny = y as i32 + dy;
+                    nx >= 0
+                        && ny >= 0
+                        && (nx as u32) < w
+                        && (ny as u32) < h
+                        && src.get_pixel(nx as u32, ny as u32)[3] > 128
+                })
+            });
+            if near_opaque {
+                img.put_pixel(x, y, image::Rgba([0x33, 0x33, 0x33, 200]));
+            }
+        }
+    }
+}
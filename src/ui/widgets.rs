@@ -10,6 +10,32 @@ pub struct ControlTooltipState {
     pub until: f64,
 }
 
+/// Screen-reader info and a visible keyboard focus ring for custom-painted
+/// controls. Focused widgets already activate on Space/Enter in egui.
+fn accessible(
+    ui: &egui::Ui,
+    response: egui::Response,
+    label: &str,
+    selected: Option<bool>,
+    rounding: f32,
+) -> egui::Response {
+    let enabled = ui.is_enabled();
+    response.widget_info(|| match selected {
+        Some(sel) => {
+            egui::WidgetInfo::selected(egui::WidgetType::SelectableLabel, enabled, sel, label)
+        }
+        None => egui::WidgetInfo::labeled(egui::WidgetType::Button, enabled, label),
+    });
+    if response.has_focus() {
+        ui.painter().rect_stroke(
+            response.rect.expand(2.0),
+            rounding,
+            ui.visuals().selection.stroke,
+        );
+    }
+    response
+}
+
 pub fn settings_toggle(
     ui: &mut egui::Ui,
     is_recording: bool,
@@ -43,7 +69,8 @@ pub fn settings_toggle(
                 .circle_stroke(center, r_ring, Stroke::new(1.2, Color32::WHITE));
             ui.painter().circle_filled(center, r_inner, Color32::WHITE);
         }
-        response.on_hover_cursor(CursorIcon::PointingHand)
+        accessible(ui, response, "Settings", None, radius)
+            .on_hover_cursor(CursorIcon::PointingHand)
     } else {
         // Idle: subtle outlined ring + gear in warm muted string color
         let size = 24.0;
@@ -71,7 +98,8 @@ pub fn settings_toggle(
                 .circle_stroke(center, r_ring, Stroke::new(1.0, color));
             ui.painter().circle_filled(center, r_inner, color);
         }
-        response.on_hover_cursor(CursorIcon::PointingHand)
+        accessible(ui, response, "Settings", None, size / 2.0)
+            .on_hover_cursor(CursorIcon::PointingHand)
     }
 }
 
@@ -104,21 +132,62 @@ pub fn mic_unavailable_badge(ui: &mut egui::Ui, rect: Rect) -> egui::Response {
     response
 }
 
+/// "Offline" strip painted over the visualizer while the network is down.
+pub fn paint_offline_banner(painter: &egui::Painter, viz_rect: Rect) {
+    let rect = Rect::from_center_size(viz_rect.center(), vec2(48.0, viz_rect.height().min(14.0)));
+    painter.rect_filled(rect, 3.0, RED);
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        "OFFLINE",
+        FontId::proportional(9.0),
+        Color32::WHITE,
+    );
+}
+
+/// Badge over the visualizer while meeting transcription runs.
+pub fn paint_meeting_banner(painter: &egui::Painter, viz_rect: Rect, color: Color32, text: &str) {
+    let width = (text.len() as f32 * 5.5 + 12.0).min(viz_rect.width());
+    let rect = Rect::from_center_size(viz_rect.center(), vec2(width, viz_rect.height().min(14.0)));
+    painter.rect_filled(rect, 3.0, color);
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        text,
+        FontId::proportional(9.0),
+        Color32::WHITE,
+    );
+}
+
+/// Pulsing ear-dot at the left of the visualizer while the wake-word
+/// listener is active. Returns the painted rect for a hover tooltip.
+pub fn paint_wake_indicator(painter: &egui::Painter, viz_rect: Rect, color: Color32, t: f32) -> Rect {
+    let center = pos2(viz_rect.min.x + 8.0, viz_rect.center().y);
+    let pulse = 0.5 + 0.5 * (t * 2.0).sin();
+    painter.circle_stroke(
+        center,
+        4.0 + pulse * 1.5,
+        Stroke::new(1.0, color.gamma_multiply(0.35 + 0.4 * (1.0 - pulse))),
+    );
+    painter.circle_filled(center, 2.5, color);
+    Rect::from_center_size(center, vec2(14.0, 14.0))
+}
+
 pub fn collapse_toggle(ui: &mut egui::Ui, accent: AccentPalette) -> egui::Response {
     let size = vec2(30.0, 30.0);
     let (rect, response) = ui.allocate_exact_size(size, Sense::click());
     if ui.is_rect_visible(rect) {
         let hovered = response.hovered();
         let fill = if hovered {
-            Color32::from_rgb(0x2d, 0x31, 0x3c)
+            theme_palette(is_dark_theme()).hover_bg
         } else {
-            BTN_BG
+            btn_bg()
         };
         ui.painter().rect(
             rect,
             6.0,
             fill,
-            Stroke::new(1.0, BTN_BORDER),
+            Stroke::new(1.0, btn_border()),
         );
 
         // Draw a larger inverted triangle (font-independent).
@@ -136,7 +205,74 @@ pub fn collapse_toggle(ui: &mut egui::Ui, accent: AccentPalette) -> egui::Respon
             Stroke::NONE,
         ));
     }
-    response.on_hover_cursor(CursorIcon::PointingHand)
+    accessible(ui, response, "Close settings", None, 6.0)
+        .on_hover_cursor(CursorIcon::PointingHand)
+}
+
+/// Compact-mode badge showing the active provider initial and language code.
+pub fn provider_badge(ui: &mut egui::Ui, text: &str, color: Color32) -> egui::Response {
+    let size = vec2(32.0, 14.0);
+    let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+    if ui.is_rect_visible(rect) {
+        let fill = if response.hovered() {
+            color.gamma_multiply(0.35)
+        } else {
+            color.gamma_multiply(0.2)
+        };
+        ui.painter().rect(rect, 7.0, fill, Stroke::new(1.0, color));
+        ui.painter().text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            text,
+            egui::FontId::proportional(9.0),
+            color,
+        );
+    }
+    accessible(ui, response, "Switch provider", None, 7.0)
+        .on_hover_cursor(CursorIcon::PointingHand)
+}
+
+/// Pill-mode record control: a small dot that pulses while recording.
+pub fn pill_mic_dot(
+    ui: &mut egui::Ui,
+    is_recording: bool,
+    accent: AccentPalette,
+    t: f32,
+) -> egui::Response {
+    let size = 18.0;
+    let (rect, response) = ui.allocate_exact_size(vec2(size, size), Sense::click());
+    if ui.is_rect_visible(rect) {
+        let center = rect.center();
+        if is_recording {
+            let pulse = (t * 3.0).sin() * 0.5 + 0.5;
+            ui.painter().circle_filled(
+                center,
+                5.5 + pulse * 2.0,
+                Color32::from_rgba_unmultiplied(
+                    accent.base.r(),
+                    accent.base.g(),
+                    accent.base.b(),
+                    60,
+                ),
+            );
+            ui.painter().circle_filled(center, 5.0, accent.base);
+        } else {
+            let color = if response.hovered() {
+                text_color()
+            } else {
+                text_muted()
+            };
+            ui.painter()
+                .circle_stroke(center, 6.0, Stroke::new(1.2, color));
+            ui.painter().circle_filled(center, 3.0, color);
+        }
+    }
+    let label = if is_recording {
+        "Stop dictation"
+    } else {
+        "Start dictation"
+    };
+    accessible(ui, response, label, None, size / 2.0).on_hover_cursor(CursorIcon::PointingHand)
 }
 
 pub fn record_toggle(
@@ -164,7 +300,8 @@ pub fn record_toggle(
             let sq_rect = egui::Rect::from_center_size(center, vec2(sq, sq));
             ui.painter().rect_filled(sq_rect, 1.5, Color32::WHITE);
         }
-        response.on_hover_cursor(CursorIcon::PointingHand)
+        accessible(ui, response, "Stop dictation", None, radius)
+            .on_hover_cursor(CursorIcon::PointingHand)
     } else {
         // Idle: subtle outlined ring + dot in warm muted string color
         let size = 24.0;
@@ -179,7 +316,8 @@ pub fn record_toggle(
                 .circle_stroke(center, size * 0.44, Stroke::new(1.0, color));
             ui.painter().circle_filled(center, 4.0, color);
         }
-        response.on_hover_cursor(CursorIcon::PointingHand)
+        accessible(ui, response, "Start dictation", None, size / 2.0)
+            .on_hover_cursor(CursorIcon::PointingHand)
     }
 }
 
@@ -233,7 +371,7 @@ pub fn provider_default_button(
     ui.painter()
         .circle_filled(rect.center(), rect.width() * 0.16, dot);
 
-    response
+    accessible(ui, response, "Default provider", Some(is_default), rect.width() / 2.0)
 }
 
 pub fn provider_validate_button(
@@ -252,7 +390,7 @@ pub fn provider_validate_button(
             Color32::from_rgb(0x4f, 0x55, 0x63),
             Color32::from_rgb(0x3a, 0x3f, 0x4a),
             "",
-            TEXT_COLOR,
+            text_color(),
         )
     } else if inflight {
         (
@@ -343,14 +481,16 @@ pub fn provider_validate_button(
         );
     }
 
-    response.on_hover_cursor(CursorIcon::PointingHand)
+    accessible(ui, response, "Validate API key", None, 4.0)
+
+        .on_hover_cursor(CursorIcon::PointingHand)
 }
 
 pub fn draw_dancing_strings(
     painter: &egui::Painter,
     rect: Rect,
     t: f32,
-    live_fft: Option<&[f32; 50]>,
+    live_fft: Option<&[f32]>,
     accent: AccentPalette,
 ) {
     let is_live = live_fft.is_some();
@@ -410,7 +550,7 @@ pub fn draw_dancing_strings(
     if let Some(fft) = live_fft {
         // Only show bars when there's actual speech energy.
         let total_energy: f32 = fft.iter().sum();
-        if total_energy > 0.15 {
+        if fft.len() > 1 && total_energy > 0.15 {
             let bar_count = 28usize;
             let gap = 2.0;
             let overlay_w = w * 0.94;
@@ -418,7 +558,7 @@ pub fn draw_dancing_strings(
             let bar_w =
                 ((overlay_w - gap * (bar_count as f32 - 1.0)) / bar_count as f32).max(2.0);
             for i in 0..bar_count {
-                let idx = ((i as f32 / (bar_count - 1) as f32) * 49.0) as usize;
+                let idx = ((i as f32 / (bar_count - 1) as f32) * (fft.len() - 1) as f32) as usize;
                 let boosted = (fft[idx] * 70.0).min(1.0);
                 if boosted < 0.01 { continue; }
                 let value = boosted.sqrt();
@@ -463,6 +603,30 @@ pub fn section_header(ui: &mut egui::Ui, text: &str) {
     );
 }
 
+/// Accent "Reset" button with a note on what it leaves alone. Only the open
+/// tab's fields change, in the form; nothing is written until Save.
+pub fn reset_defaults_button(ui: &mut egui::Ui, accent: AccentPalette, note: &str) -> bool {
+    let mut clicked = false;
+    ui.horizontal_wrapped(|ui| {
+        let btn = egui::Button::new(
+            egui::RichText::new("Reset")
+                .size(12.0)
+                .strong()
+                .color(Color32::BLACK),
+        )
+        .fill(accent.base)
+        .stroke(Stroke::new(1.0, accent.ring));
+        clicked = ui.add(btn).clicked();
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(note)
+                .size(12.0)
+                .color(text_muted()),
+        );
+    });
+    clicked
+}
+
 pub fn draw_tab_icon(
     painter: &egui::Painter,
     tab_id: &str,
@@ -591,6 +755,19 @@ pub fn draw_tab_icon(
             );
         }
 
+        // ── Page with text lines (notes) ──
+        "notes" => {
+            let page = Rect::from_center_size(c, vec2(s * 0.56, s * 0.72));
+            painter.rect_stroke(page, 1.5, stroke);
+            for i in 0..3 {
+                let y = page.min.y + page.height() * (0.3 + 0.2 * i as f32);
+                painter.line_segment(
+                    [pos2(page.min.x + s * 0.1, y), pos2(page.max.x - s * 0.1, y)],
+                    thin,
+                );
+            }
+        }
+
         // ── Question mark in circle (FAQ) ──
         "faq" => {
             painter.circle_stroke(c, s * 0.36, stroke);
@@ -640,14 +817,14 @@ pub fn tab_button(
         let fill = if active {
             accent.base
         } else if hovered {
-            Color32::from_rgb(0x1e, 0x21, 0x2a)
+            p.hover_bg
         } else {
             Color32::TRANSPARENT
         };
         let border = if active {
             accent.ring
         } else if hovered {
-            Color32::from_rgb(0x36, 0x3a, 0x44)
+            p.hover_border
         } else {
             p.btn_border
         };
@@ -659,7 +836,7 @@ pub fn tab_button(
         let icon_color = if active {
             Color32::from_rgba_unmultiplied(0, 0, 0, 210)
         } else if hovered {
-            TEXT_COLOR
+            text_color()
         } else {
             accent.base
         };
@@ -670,7 +847,7 @@ pub fn tab_button(
         let text_x = rect.min.x + 34.0;
 
         let animate = hovered && !active;
-        let lit_color = TEXT_COLOR;
+        let lit_color = text_color();
         let dim_color = p.text_muted;
 
         if active {
@@ -770,7 +947,9 @@ pub fn tab_button(
         }
     }
 
-    response.on_hover_cursor(CursorIcon::PointingHand)
+    accessible(ui, response, label, Some(active), 6.0)
+
+        .on_hover_cursor(CursorIcon::PointingHand)
 }
 
 /// Draws a preset-mode icon (P/I/E) at center `c` within a logical `s`-sized box.
@@ -905,14 +1084,14 @@ pub fn preset_icon_button(
         let fill = if active {
             accent.base
         } else if hovered {
-            Color32::from_rgb(0x2d, 0x31, 0x3c)
+            p.hover_bg
         } else {
             p.btn_bg
         };
         let border = if active {
             accent.ring
         } else if hovered {
-            Color32::from_rgb(0x44, 0x48, 0x54)
+            p.hover_border
         } else {
             p.btn_border
         };
@@ -922,14 +1101,20 @@ pub fn preset_icon_button(
         let icon_color = if active {
             Color32::WHITE
         } else if hovered {
-            TEXT_COLOR
+            text_color()
         } else {
             p.text
         };
         draw_preset_icon(ui.painter(), preset, rect.center(), 18.0, icon_color);
     }
 
-    response.on_hover_cursor(CursorIcon::PointingHand)
+    let name = match preset {
+        "image" => "Snip copies image",
+        "edit" => "Snip copies image and opens editor",
+        _ => "Snip copies image path",
+    };
+    accessible(ui, response, name, Some(active), 4.0)
+        .on_hover_cursor(CursorIcon::PointingHand)
 }
 
 /// Renders a horizontal sub-tab button with an underline indicator.
@@ -940,13 +1125,13 @@ pub fn sub_tab_button(
     accent: AccentPalette,
 ) -> egui::Response {
     let p = theme_palette(ui.visuals().dark_mode);
-    let text_color = if active { TEXT_COLOR } else { p.text_muted };
+    let label_color = if active { text_color() } else { p.text_muted };
     let font_size = if active { 12.5 } else { 12.0 };
 
     let galley = ui.painter().layout_no_wrap(
         label.to_string(),
         FontId::proportional(font_size),
-        text_color,
+        label_color,
     );
     let text_w = galley.size().x;
     let pad_h = 8.0;
@@ -960,9 +1145,9 @@ pub fn sub_tab_button(
 
         // Draw label text
         let draw_color = if active {
-            TEXT_COLOR
+            text_color()
         } else if hovered {
-            TEXT_COLOR
+            text_color()
         } else {
             p.text_muted
         };
@@ -987,12 +1172,14 @@ pub fn sub_tab_button(
         } else if hovered {
             ui.painter().line_segment(
                 [pos2(rect.min.x + 2.0, line_y), pos2(rect.max.x - 2.0, line_y)],
-                Stroke::new(1.0, BTN_BORDER),
+                Stroke::new(1.0, btn_border()),
             );
         }
     }
 
-    response.on_hover_cursor(CursorIcon::PointingHand)
+    accessible(ui, response, label, Some(active), 2.0)
+
+        .on_hover_cursor(CursorIcon::PointingHand)
 }
 
 /// Sample a cubic bezier into `n` points (excluding the start point).
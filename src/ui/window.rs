@@ -1,5 +1,6 @@
 use eframe::egui;
 use egui::{pos2, Pos2, Rect};
+use std::collections::HashMap;
 
 pub const COMPACT_WINDOW_W_WITH_SNIP: f32 = 198.0;
 pub const COMPACT_WINDOW_W_NO_SNIP: f32 = 176.0;
@@ -7,6 +8,8 @@ pub const COMPACT_WINDOW_H: f32 = 74.0;
 pub const COMPACT_WINDOW_H_WITH_SNIP: f32 = 102.0;
 pub const COMPACT_BG_EXTRA_W: f32 = 36.0;
 pub const COMPACT_BG_EXTRA_H: f32 = 12.0;
+pub const PILL_WINDOW_W: f32 = 90.0;
+pub const PILL_WINDOW_H: f32 = 30.0;
 
 pub const WINDOW_MONITOR_MODE_FIXED: &str = "fixed";
 pub const WINDOW_ANCHOR_TOP_LEFT: &str = "top_left";
@@ -15,6 +18,8 @@ pub const WINDOW_ANCHOR_TOP_RIGHT: &str = "top_right";
 pub const WINDOW_ANCHOR_BOTTOM_LEFT: &str = "bottom_left";
 pub const WINDOW_ANCHOR_BOTTOM_CENTER: &str = "bottom_center";
 pub const WINDOW_ANCHOR_BOTTOM_RIGHT: &str = "bottom_right";
+/// Position dropped by dragging the compact window, stored per monitor.
+pub const WINDOW_ANCHOR_CUSTOM: &str = "custom";
 
 #[derive(Clone)]
 pub struct MonitorChoice {
@@ -22,10 +27,22 @@ pub struct MonitorChoice {
     pub label: String,
 }
 
+/// Work area in physical pixels.
+#[cfg(windows)]
+pub type PxRect = windows::Win32::Foundation::RECT;
+#[cfg(not(windows))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PxRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
 #[derive(Clone)]
 pub struct MonitorWorkArea {
     pub id: String,
-    pub work_px: windows::Win32::Foundation::RECT,
+    pub work_px: PxRect,
     pub is_primary: bool,
     pub scale_factor: f32,
 }
@@ -87,9 +104,52 @@ pub fn enumerate_monitor_work_areas() -> Vec<MonitorWorkArea> {
     out
 }
 
+/// Full display bounds from xcap; unlike Windows this doesn't exclude the
+/// macOS menu bar and Dock, so anchors sit slightly closer to the edges.
 #[cfg(not(windows))]
 pub fn enumerate_monitor_work_areas() -> Vec<MonitorWorkArea> {
-    Vec::new()
+    let Ok(monitors) = xcap::Monitor::all() else {
+        return Vec::new();
+    };
+    monitors
+        .iter()
+        .filter_map(|m| {
+            let scale_factor = m.scale_factor().unwrap_or(1.0);
+            let px = |v: i32| (v as f32 * scale_factor).round() as i32;
+            let (x, y) = (m.x().ok()?, m.y().ok()?);
+            let (w, h) = (m.width().ok()? as i32, m.height().ok()? as i32);
+            Some(MonitorWorkArea {
+                id: m.name().unwrap_or_default(),
+                work_px: PxRect {
+                    left: px(x),
+                    top: px(y),
+                    right: px(x + w),
+                    bottom: px(y + h),
+                },
+                is_primary: m.is_primary().unwrap_or(false),
+                scale_factor,
+            })
+        })
+        .collect()
+}
+
+/// One line per monitor for the diagnostics bundle's system info.
+pub fn describe_monitors() -> Vec<String> {
+    enumerate_monitor_work_areas()
+        .iter()
+        .map(|m| {
+            format!(
+                "{} work=({},{})-({},{}) scale={:.2}{}",
+                m.id,
+                m.work_px.left,
+                m.work_px.top,
+                m.work_px.right,
+                m.work_px.bottom,
+                m.scale_factor,
+                if m.is_primary { " primary" } else { "" }
+            )
+        })
+        .collect()
 }
 
 pub fn available_monitor_choices() -> Vec<MonitorChoice> {
@@ -111,27 +171,35 @@ pub fn available_monitor_choices() -> Vec<MonitorChoice> {
         .collect()
 }
 
-pub fn resolve_target_monitor(monitor_id: &str) -> Option<MonitorWorkArea> {
-    use windows::Win32::Foundation::RECT;
+#[cfg(windows)]
+fn primary_work_area() -> Option<PxRect> {
     use windows::Win32::UI::WindowsAndMessaging::{
         SystemParametersInfoW, SPI_GETWORKAREA, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
     };
 
-    let monitors = enumerate_monitor_work_areas();
-    if monitors.is_empty() {
-        return None;
-    }
-
-    let mut primary_work = RECT::default();
-    let have_primary_work = unsafe {
+    let mut work = PxRect::default();
+    unsafe {
         SystemParametersInfoW(
             SPI_GETWORKAREA,
             0,
-            Some((&mut primary_work as *mut RECT).cast()),
+            Some((&mut work as *mut PxRect).cast()),
             SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
         )
     }
-    .is_ok();
+    .ok()
+    .map(|()| work)
+}
+
+#[cfg(not(windows))]
+fn primary_work_area() -> Option<PxRect> {
+    None
+}
+
+pub fn resolve_target_monitor(monitor_id: &str) -> Option<MonitorWorkArea> {
+    let monitors = enumerate_monitor_work_areas();
+    if monitors.is_empty() {
+        return None;
+    }
 
     if !monitor_id.trim().is_empty() {
         if let Some(m) = monitors.iter().find(|m| m.id == monitor_id) {
@@ -139,7 +207,7 @@ pub fn resolve_target_monitor(monitor_id: &str) -> Option<MonitorWorkArea> {
         }
     }
 
-    if have_primary_work {
+    if let Some(primary_work) = primary_work_area() {
         if let Some(m) = monitors.iter().find(|m| {
             m.work_px.left == primary_work.left
                 && m.work_px.top == primary_work.top
@@ -175,8 +243,82 @@ pub fn move_window_physical(x: i32, y: i32) {
 #[cfg(not(windows))]
 pub fn move_window_physical(_x: i32, _y: i32) {}
 
+#[cfg(windows)]
+pub fn window_pos_physical() -> Option<(i32, i32)> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::UI::WindowsAndMessaging::{FindWindowW, GetWindowRect};
+
+    let title: Vec<u16> = "Mango Chat\0".encode_utf16().collect();
+    let hwnd = unsafe { FindWindowW(PCWSTR::null(), PCWSTR(title.as_ptr())) }.ok()?;
+    if hwnd.is_invalid() {
+        return None;
+    }
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect) }.ok()?;
+    Some((rect.left, rect.top))
+}
+
+#[cfg(not(windows))]
+pub fn window_pos_physical() -> Option<(i32, i32)> {
+    None
+}
+
+/// True when another process's foreground window covers its whole monitor
+/// (games, slideshows, fullscreen video). The desktop and shell don't count.
+#[cfg(windows)]
+pub fn foreground_is_fullscreen() -> bool {
+    use std::mem::size_of;
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetWindowRect,
+        GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() || hwnd == GetDesktopWindow() || hwnd == GetShellWindow() {
+            return false;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == std::process::id() {
+            return false;
+        }
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return false;
+        }
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+        let m = info.rcMonitor;
+        rect.left <= m.left && rect.top <= m.top && rect.right >= m.right && rect.bottom >= m.bottom
+    }
+}
+
+#[cfg(not(windows))]
+pub fn foreground_is_fullscreen() -> bool {
+    false
+}
+
+/// Monitor whose work area contains the given physical point.
+pub fn monitor_at_physical(x: i32, y: i32) -> Option<MonitorWorkArea> {
+    enumerate_monitor_work_areas().into_iter().find(|m| {
+        x >= m.work_px.left && x < m.work_px.right && y >= m.work_px.top && y < m.work_px.bottom
+    })
+}
+
 pub fn anchored_pos_physical(
-    work: windows::Win32::Foundation::RECT,
+    work: PxRect,
     size_px: (i32, i32),
     anchor: &str,
 ) -> (i32, i32) {
@@ -197,10 +339,13 @@ pub fn anchored_pos_physical(
     }
 }
 
+/// `size_logical` is in unzoomed logical pixels (callers multiply egui
+/// points by the zoom factor).
 pub fn place_compact_fixed_native(
     size_logical: egui::Vec2,
     monitor_id: &str,
     anchor: &str,
+    custom_positions: &HashMap<String, [i32; 2]>,
 ) -> bool {
     let Some(m) = resolve_target_monitor(monitor_id) else {
         return false;
@@ -210,7 +355,19 @@ pub fn place_compact_fixed_native(
         (size_logical.x * sf).round() as i32,
         (size_logical.y * sf).round() as i32,
     );
-    let (x, y) = anchored_pos_physical(m.work_px, size_px, anchor);
+    let (x, y) = match custom_positions.get(&m.id) {
+        Some(offset) if anchor == WINDOW_ANCHOR_CUSTOM => {
+            // Offsets are relative to the work area; keep the window fully on it.
+            let work = m.work_px;
+            let max_x = (work.right - size_px.0).max(work.left);
+            let max_y = (work.bottom - size_px.1).max(work.top);
+            (
+                (work.left + offset[0]).clamp(work.left, max_x),
+                (work.top + offset[1]).clamp(work.top, max_y),
+            )
+        }
+        _ => anchored_pos_physical(m.work_px, size_px, anchor),
+    };
     move_window_physical(x, y);
     true
 }
@@ -247,7 +404,7 @@ pub fn anchored_position_in_work_area(
 }
 
 pub fn work_area_rect_logical(
-    _ctx: &egui::Context,
+    ctx: &egui::Context,
     monitor_mode: &str,
     monitor_id: &str,
 ) -> Option<Rect> {
@@ -255,7 +412,8 @@ pub fn work_area_rect_logical(
     let chosen = resolve_target_monitor(monitor_id);
 
     if let Some(m) = chosen {
-        let sf = m.scale_factor.max(0.5);
+        // Logical points here are egui points, which include the UI zoom.
+        let sf = m.scale_factor.max(0.5) * ctx.zoom_factor();
         return Some(Rect::from_min_max(
             pos2(m.work_px.left as f32 / sf, m.work_px.top as f32 / sf),
             pos2(
@@ -268,6 +426,40 @@ pub fn work_area_rect_logical(
     None
 }
 
+/// Visible strip left on screen when the compact window is edge-docked.
+pub const DOCK_STRIP: f32 = 4.0;
+
+/// Docked position for a window at `home`: pushed past the work-area edge
+/// it is anchored to (nearest edge for custom positions), leaving `DOCK_STRIP`.
+pub fn docked_window_pos(home: Rect, work: Rect, anchor: &str) -> Pos2 {
+    let to_top = home.min.y - work.min.y;
+    let to_bottom = work.max.y - home.max.y;
+    let to_left = home.min.x - work.min.x;
+    let to_right = work.max.x - home.max.x;
+    let edge = if anchor.starts_with("top") {
+        "top"
+    } else if anchor.starts_with("bottom") {
+        "bottom"
+    } else {
+        [
+            ("top", to_top),
+            ("bottom", to_bottom),
+            ("left", to_left),
+            ("right", to_right),
+        ]
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(edge, _)| edge)
+        .unwrap_or("bottom")
+    };
+    match edge {
+        "top" => pos2(home.min.x, work.min.y - home.height() + DOCK_STRIP),
+        "left" => pos2(work.min.x - home.width() + DOCK_STRIP, home.min.y),
+        "right" => pos2(work.max.x - DOCK_STRIP, home.min.y),
+        _ => pos2(home.min.x, work.max.y - DOCK_STRIP),
+    }
+}
+
 pub fn clamp_window_pos(
     ctx: &egui::Context,
     pos: Pos2,
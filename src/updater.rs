@@ -1,12 +1,12 @@
 use semver::Version;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::fs::{self, File};
-use std::io::Write;
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::mpsc::Sender;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -22,6 +22,34 @@ const APP_USER_AGENT: &str = "mangochat-updater";
 const CREATE_NO_WINDOW: u32 = 0x0800_0000;
 #[cfg(windows)]
 const UPDATE_HELPER_WAIT_TIMEOUT_MS: u32 = 120_000;
+/// Attempts per download; each retry resumes from the partial file.
+const DOWNLOAD_ATTEMPTS: u64 = 4;
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Release channels selectable in About: (id, label).
+pub const UPDATE_CHANNELS: [(&str, &str); 3] =
+    [("stable", "Stable"), ("beta", "Beta"), ("nightly", "Nightly")];
+
+pub fn channel_label(channel: &str) -> &'static str {
+    UPDATE_CHANNELS
+        .iter()
+        .find(|(id, _)| *id == channel)
+        .map(|(_, label)| *label)
+        .unwrap_or("Stable")
+}
+
+/// Whether a release belongs on `channel`. Stable takes full releases only,
+/// beta adds pre-releases (beta/rc), nightly takes everything.
+fn channel_accepts(channel: &str, prerelease: bool, version: &Version) -> bool {
+    if !prerelease && version.pre.is_empty() {
+        return true;
+    }
+    match channel {
+        "nightly" => true,
+        "beta" => !version.pre.as_str().to_ascii_lowercase().starts_with("nightly"),
+        _ => false,
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ReleaseAsset {
@@ -34,12 +62,25 @@ pub struct ReleaseInfo {
     pub tag: String,
     pub version: Version,
     pub assets: Vec<ReleaseAsset>,
+    /// Release notes (markdown) from the release body.
+    pub notes: String,
 }
 
 #[derive(Debug, Clone)]
 pub enum WorkerMessage {
     CheckFinished(Result<CheckOutcome, String>),
-    InstallFinished(Result<String, String>),
+    /// Bytes received so far for the current download; total when known.
+    DownloadProgress { downloaded: u64, total: Option<u64> },
+    InstallFinished(Result<DownloadedInstaller, String>),
+}
+
+/// A downloaded installer and the SHA-256 the release manifest lists for it.
+#[derive(Debug, Clone)]
+pub struct DownloadedInstaller {
+    pub version: Version,
+    pub path: String,
+    pub sha256: String,
+    pub verify_signature: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +95,8 @@ struct GitHubRelease {
     prerelease: bool,
     draft: bool,
     #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
     assets: Vec<GitHubAsset>,
 }
 
@@ -72,9 +115,13 @@ fn parse_tag_version(tag: &str) -> Option<Version> {
     Version::parse(raw).ok()
 }
 
-pub fn spawn_check_with_override(tx: Sender<WorkerMessage>, feed_url_override: Option<String>) {
+pub fn spawn_check_with_override(
+    tx: Sender<WorkerMessage>,
+    feed_url_override: Option<String>,
+    channel: String,
+) {
     std::thread::spawn(move || {
-        let result = check_for_updates(feed_url_override.as_deref());
+        let result = check_for_updates(feed_url_override.as_deref(), &channel);
         let _ = tx.send(WorkerMessage::CheckFinished(result));
     });
 }
@@ -117,7 +164,10 @@ fn release_feed_url(feed_url_override: Option<&str>) -> String {
     )
 }
 
-fn check_for_updates(feed_url_override: Option<&str>) -> Result<CheckOutcome, String> {
+fn check_for_updates(
+    feed_url_override: Option<&str>,
+    channel: &str,
+) -> Result<CheckOutcome, String> {
     let current = current_version()?;
     let url = release_feed_url(feed_url_override);
 
@@ -141,12 +191,12 @@ fn check_for_updates(feed_url_override: Option<&str>) -> Result<CheckOutcome, St
         if rel.draft {
             continue;
         }
-        if rel.prerelease {
-            continue;
-        }
         let Some(version) = parse_tag_version(&rel.tag_name) else {
             continue;
         };
+        if !channel_accepts(channel, rel.prerelease, &version) {
+            continue;
+        }
         let info = ReleaseInfo {
             tag: rel.tag_name,
             version,
@@ -158,6 +208,7 @@ fn check_for_updates(feed_url_override: Option<&str>) -> Result<CheckOutcome, St
                     download_url: a.browser_download_url,
                 })
                 .collect(),
+            notes: rel.body.unwrap_or_default(),
         };
         let replace = best
             .as_ref()
@@ -179,15 +230,15 @@ fn check_for_updates(feed_url_override: Option<&str>) -> Result<CheckOutcome, St
     }
 }
 
-pub fn spawn_install(tx: Sender<WorkerMessage>, release: ReleaseInfo) {
+pub fn spawn_install(tx: Sender<WorkerMessage>, release: ReleaseInfo, verify_signature: bool) {
     std::thread::spawn(move || {
-        let result = download_installer_for_update(&release);
+        let result = download_installer_for_update(&release, verify_signature, &tx);
         let _ = tx.send(WorkerMessage::InstallFinished(result));
     });
 }
 
-fn download_installer_for_update(release: &ReleaseInfo) -> Result<String, String> {
-    let asset = release
+fn find_installer_asset(release: &ReleaseInfo) -> Result<&ReleaseAsset, String> {
+    release
         .assets
         .iter()
         .find(|a| {
@@ -200,54 +251,388 @@ fn download_installer_for_update(release: &ReleaseInfo) -> Result<String, String
                 .iter()
                 .find(|a| a.name.to_ascii_lowercase().ends_with(".exe"))
         })
-        .ok_or("no .exe installer asset found on release")?;
-
-    let client = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(90))
-        .build()
-        .map_err(|e| format!("http client error: {e}"))?;
+        .ok_or_else(|| "no .exe installer asset found on release".to_string())
+}
 
-    let installer_bytes = client
-        .get(&asset.download_url)
+fn fetch_checksums(
+    client: &reqwest::blocking::Client,
+    release: &ReleaseInfo,
+) -> Result<Option<String>, String> {
+    let Some(checksums_asset) = release
+        .assets
+        .iter()
+        .find(|a| a.name.eq_ignore_ascii_case("SHA256SUMS.txt"))
+    else {
+        return Ok(None);
+    };
+    let text = client
+        .get(&checksums_asset.download_url)
         .header("User-Agent", APP_USER_AGENT)
         .send()
-        .map_err(|e| format!("download request failed: {e}"))?
+        .map_err(|e| format!("checksums request failed: {e}"))?
         .error_for_status()
-        .map_err(|e| format!("download failed: {e}"))?
-        .bytes()
-        .map_err(|e| format!("failed reading installer bytes: {e}"))?;
+        .map_err(|e| format!("checksums download failed: {e}"))?
+        .text()
+        .map_err(|e| format!("failed reading SHA256SUMS.txt: {e}"))?;
+    Ok(Some(text))
+}
 
-    if let Some(checksums_asset) = release
+/// Download `url` into `part_path`, retrying with a Range request so a
+/// network blip resumes instead of starting over. Returns the full contents.
+fn download_resumable(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part_path: &Path,
+    tx: &Sender<WorkerMessage>,
+) -> Result<Vec<u8>, String> {
+    let mut last_err = String::new();
+    for attempt in 1..=DOWNLOAD_ATTEMPTS {
+        match download_attempt(client, url, part_path, tx) {
+            Ok(()) => {
+                let bytes =
+                    fs::read(part_path).map_err(|e| format!("cannot read download: {e}"))?;
+                let _ = fs::remove_file(part_path);
+                return Ok(bytes);
+            }
+            Err(e) => {
+                app_err!(
+                    "[updater] download attempt {}/{} failed: {}",
+                    attempt,
+                    DOWNLOAD_ATTEMPTS,
+                    e
+                );
+                last_err = e;
+                if attempt < DOWNLOAD_ATTEMPTS {
+                    std::thread::sleep(Duration::from_secs(2 * attempt));
+                }
+            }
+        }
+    }
+    Err(format!(
+        "download failed after {} attempts: {}",
+        DOWNLOAD_ATTEMPTS, last_err
+    ))
+}
+
+fn download_attempt(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part_path: &Path,
+    tx: &Sender<WorkerMessage>,
+) -> Result<(), String> {
+    let existing = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    let mut request = client.get(url).header("User-Agent", APP_USER_AGENT);
+    if existing > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+    }
+    let response = request
+        .send()
+        .map_err(|e| format!("download request failed: {e}"))?;
+    if existing > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        let _ = fs::remove_file(part_path);
+        return Err("stale partial download discarded".into());
+    }
+    let mut response = response
+        .error_for_status()
+        .map_err(|e| format!("download failed: {e}"))?;
+
+    let resumed = existing > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { existing } else { 0 };
+    if resumed {
+        app_log!("[updater] resuming download at {} KB", existing / 1024);
+    }
+    let total = response.content_length().map(|len| len + downloaded);
+    let mut file = if resumed {
+        OpenOptions::new().append(true).open(part_path)
+    } else {
+        File::create(part_path)
+    }
+    .map_err(|e| format!("cannot open download file: {e}"))?;
+
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut last_sent: Option<Instant> = None;
+    loop {
+        let n = response
+            .read(&mut buf)
+            .map_err(|e| format!("download interrupted: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n])
+            .map_err(|e| format!("cannot write download file: {e}"))?;
+        downloaded += n as u64;
+        if last_sent.map_or(true, |t| t.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL) {
+            let _ = tx.send(WorkerMessage::DownloadProgress { downloaded, total });
+            last_sent = Some(Instant::now());
+        }
+    }
+    let _ = tx.send(WorkerMessage::DownloadProgress { downloaded, total });
+    if total.is_some_and(|t| downloaded < t) {
+        return Err("connection closed before the download finished".into());
+    }
+    Ok(())
+}
+
+/// Installers from past updates are kept here as the base for delta patches.
+fn installer_cache_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("MangoChat").join("updates"))
+}
+
+fn cached_installer_path(version: &Version) -> Option<PathBuf> {
+    installer_cache_dir().map(|d| d.join(format!("MangoChat-Setup-{}.exe", version)))
+}
+
+/// Keep `bytes` as the base for the next delta and drop older cached installers.
+fn cache_installer(version: &Version, bytes: &[u8]) {
+    let (Some(dir), Some(path)) = (installer_cache_dir(), cached_installer_path(version)) else {
+        return;
+    };
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, bytes)) {
+        app_err!("[updater] failed to cache installer: {}", e);
+        return;
+    }
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            if entry.path() != path {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// Build the new installer from a bsdiff patch against the cached installer
+/// of the running version, checked against SHA256SUMS. Returns Ok(None) when
+/// no usable delta exists.
+fn try_delta_update(
+    client: &reqwest::blocking::Client,
+    release: &ReleaseInfo,
+    checksums: &str,
+    installer_name: &str,
+    tx: &Sender<WorkerMessage>,
+) -> Result<Option<Vec<u8>>, String> {
+    let current = current_version()?;
+    let patch_name = format!("MangoChat-{}-to-{}.patch", current, release.version);
+    let Some(patch_asset) = release
         .assets
         .iter()
-        .find(|a| a.name.eq_ignore_ascii_case("SHA256SUMS.txt"))
-    {
-        let checksums_text = client
-            .get(&checksums_asset.download_url)
-            .header("User-Agent", APP_USER_AGENT)
-            .send()
-            .map_err(|e| format!("checksums request failed: {e}"))?
-            .error_for_status()
-            .map_err(|e| format!("checksums download failed: {e}"))?
-            .text()
-            .map_err(|e| format!("failed reading SHA256SUMS.txt: {e}"))?;
-        verify_sha256_from_release(&checksums_text, &asset.name, installer_bytes.as_ref())?;
-    } else {
-        app_log!(
-            "[updater] SHA256SUMS.txt not present for release {}; skipping checksum verification",
-            release.tag
-        );
+        .find(|a| a.name.eq_ignore_ascii_case(&patch_name))
+    else {
+        return Ok(None);
+    };
+    let Some(base) = cached_installer_path(&current).and_then(|p| fs::read(p).ok()) else {
+        app_log!("[updater] no cached {} installer; skipping delta", current);
+        return Ok(None);
+    };
+
+    let part = std::env::temp_dir().join(format!("{}.part", patch_name));
+    let patch = download_resumable(client, &patch_asset.download_url, &part, tx)?;
+    let target = apply_patch(&base, &patch, checksums, installer_name)?;
+    app_log!(
+        "[updater] delta update applied: {} KB patch instead of {} KB installer",
+        patch.len() / 1024,
+        target.len() / 1024
+    );
+    Ok(Some(target))
+}
+
+/// Apply a bsdiff patch to `base` and check the result against SHA256SUMS.
+fn apply_patch(
+    base: &[u8],
+    patch: &[u8],
+    checksums: &str,
+    installer_name: &str,
+) -> Result<Vec<u8>, String> {
+    let patcher = qbsdiff::Bspatch::new(patch).map_err(|e| format!("invalid patch: {e}"))?;
+    let mut target = Vec::with_capacity(patcher.hint_target_size() as usize);
+    patcher
+        .apply(base, std::io::Cursor::new(&mut target))
+        .map_err(|e| format!("failed to apply patch: {e}"))?;
+    verify_sha256_from_release(checksums, installer_name, &target)?;
+    Ok(target)
+}
+
+/// The delta-built installer when there is one, otherwise the full download.
+/// A failed delta (bad patch, checksum mismatch) falls back to `full`.
+fn delta_or_full(
+    delta: Result<Option<Vec<u8>>, String>,
+    full: impl FnOnce() -> Result<Vec<u8>, String>,
+) -> Result<Vec<u8>, String> {
+    match delta {
+        Ok(Some(bytes)) => Ok(bytes),
+        Ok(None) => full(),
+        Err(e) => {
+            app_err!("[updater] delta update failed, downloading full installer: {}", e);
+            full()
+        }
     }
+}
+
+/// Release tooling: `--make-update-patch <old installer> <new installer> <out>`
+/// writes the bsdiff patch that `try_delta_update` applies.
+pub fn make_patch_from_args(args: &[String]) -> Result<(), String> {
+    let [old, new, out] = args else {
+        return Err("usage: --make-update-patch <old> <new> <out>".into());
+    };
+    let old = fs::read(old).map_err(|e| format!("cannot read {old}: {e}"))?;
+    let new = fs::read(new).map_err(|e| format!("cannot read {new}: {e}"))?;
+    let mut patch = Vec::new();
+    qbsdiff::Bsdiff::new(&old, &new)
+        .compare(std::io::Cursor::new(&mut patch))
+        .map_err(|e| format!("failed to build patch: {e}"))?;
+    fs::write(out, &patch).map_err(|e| format!("cannot write {out}: {e}"))?;
+    Ok(())
+}
+
+fn download_installer_for_update(
+    release: &ReleaseInfo,
+    verify_signature: bool,
+    tx: &Sender<WorkerMessage>,
+) -> Result<DownloadedInstaller, String> {
+    let asset = find_installer_asset(release)?;
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(90))
+        .build()
+        .map_err(|e| format!("http client error: {e}"))?;
+
+    let checksums = fetch_checksums(&client, release)?.ok_or_else(|| {
+        format!(
+            "release {} has no SHA256SUMS.txt; refusing to install an unverified installer",
+            release.tag
+        )
+    })?;
+    let expected = expected_sha256(&checksums, &asset.name)?;
+    let delta = try_delta_update(&client, release, &checksums, &asset.name, tx);
+    let installer_bytes = delta_or_full(delta, || {
+        let part =
+            std::env::temp_dir().join(format!("MangoChat-Setup-{}.exe.part", release.version));
+        let bytes = download_resumable(&client, &asset.download_url, &part, tx)?;
+        verify_sha256_from_release(&checksums, &asset.name, &bytes)?;
+        Ok(bytes)
+    })?;
+    cache_installer(&release.version, &installer_bytes);
 
     let mut path: PathBuf = std::env::temp_dir();
     path.push(format!("MangoChat-Setup-{}.exe", release.version));
     let mut file = File::create(&path).map_err(|e| format!("cannot create installer file: {e}"))?;
     file.write_all(&installer_bytes)
         .map_err(|e| format!("cannot write installer file: {e}"))?;
-    Ok(path.display().to_string())
+    drop(file);
+
+    let installer = DownloadedInstaller {
+        version: release.version.clone(),
+        path: path.display().to_string(),
+        sha256: expected,
+        verify_signature,
+    };
+    verify_installer_file(&installer.path, &installer.sha256, verify_signature)?;
+    Ok(installer)
 }
 
-pub fn schedule_silent_install_and_relaunch(installer_path: &str) -> Result<(), String> {
+/// Re-check the installer on disk right before it runs: the SHA-256 must
+/// match the release manifest and, if asked, the Authenticode signature
+/// must be valid.
+pub fn verify_installer_file(
+    path: &str,
+    expected_sha256: &str,
+    verify_signature: bool,
+) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| format!("cannot read installer: {e}"))?;
+    let actual = sha256_hex(&bytes);
+    if !actual.eq_ignore_ascii_case(expected_sha256) {
+        return Err(format!(
+            "installer checksum mismatch: expected {}, got {}",
+            expected_sha256, actual
+        ));
+    }
+    if verify_signature {
+        verify_authenticode(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn verify_authenticode(path: &str) -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Security::WinTrust::{
+        WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0,
+        WINTRUST_FILE_INFO, WTD_CHOICE_FILE, WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE,
+        WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+    };
+
+    let wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut file_info = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: PCWSTR(wide.as_ptr()),
+        ..Default::default()
+    };
+    let mut data = WINTRUST_DATA {
+        cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+        dwUIChoice: WTD_UI_NONE,
+        fdwRevocationChecks: WTD_REVOKE_NONE,
+        dwUnionChoice: WTD_CHOICE_FILE,
+        Anonymous: WINTRUST_DATA_0 {
+            pFile: &mut file_info,
+        },
+        dwStateAction: WTD_STATEACTION_VERIFY,
+        ..Default::default()
+    };
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+    let status = unsafe {
+        let status = WinVerifyTrust(
+            HWND::default(),
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut std::ffi::c_void,
+        );
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        let _ = WinVerifyTrust(
+            HWND::default(),
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut std::ffi::c_void,
+        );
+        status
+    };
+    if status != 0 {
+        return Err(format!(
+            "installer signature is missing or invalid (0x{:08x})",
+            status as u32
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn verify_authenticode(_path: &str) -> Result<(), String> {
+    Err("signature verification is only supported on Windows".into())
+}
+
+/// Installer downloaded this session, to run silently when the app quits.
+static INSTALL_ON_EXIT: std::sync::Mutex<Option<DownloadedInstaller>> =
+    std::sync::Mutex::new(None);
+
+pub fn set_install_on_exit(installer: DownloadedInstaller) {
+    if let Ok(mut pending) = INSTALL_ON_EXIT.lock() {
+        *pending = Some(installer);
+    }
+}
+
+/// Called on every quit path: hands a pending installer to the helper, which
+/// installs silently after this process exits without relaunching the app.
+pub fn run_install_on_exit() {
+    let Some(installer) = INSTALL_ON_EXIT.lock().ok().and_then(|mut p| p.take()) else {
+        return;
+    };
+    app_log!("[updater] installing {} on exit", installer.path);
+    if let Err(e) = schedule_silent_install(&installer, false) {
+        app_err!("[updater] install on exit failed: {}", e);
+    }
+}
+
+pub fn schedule_silent_install(
+    installer: &DownloadedInstaller,
+    relaunch: bool,
+) -> Result<(), String> {
     let current_pid = std::process::id();
     let app_exe =
         std::env::current_exe().map_err(|e| format!("failed to resolve current exe: {e}"))?;
@@ -272,9 +657,16 @@ pub fn schedule_silent_install_and_relaunch(installer_path: &str) -> Result<(),
         .arg("--wait-pid")
         .arg(current_pid.to_string())
         .arg("--installer")
-        .arg(installer_path)
-        .arg("--relaunch")
-        .arg(app_exe.to_string_lossy().to_string());
+        .arg(&installer.path)
+        .arg("--sha256")
+        .arg(&installer.sha256);
+    if relaunch {
+        cmd.arg("--relaunch")
+            .arg(app_exe.to_string_lossy().to_string());
+    }
+    if installer.verify_signature {
+        cmd.arg("--verify-signature");
+    }
     #[cfg(windows)]
     {
         cmd.creation_flags(CREATE_NO_WINDOW);
@@ -289,6 +681,8 @@ pub fn run_update_helper_from_args(args: &[String]) -> Result<(), String> {
     let mut wait_pid: Option<u32> = None;
     let mut installer: Option<String> = None;
     let mut relaunch: Option<String> = None;
+    let mut sha256: Option<String> = None;
+    let mut verify_signature = false;
     let mut i = 0usize;
     while i < args.len() {
         match args[i].as_str() {
@@ -305,14 +699,20 @@ pub fn run_update_helper_from_args(args: &[String]) -> Result<(), String> {
                 i += 1;
                 relaunch = args.get(i).cloned();
             }
+            "--sha256" => {
+                i += 1;
+                sha256 = args.get(i).cloned();
+            }
+            "--verify-signature" => verify_signature = true,
             _ => {}
         }
         i += 1;
     }
     let installer_path = installer.ok_or("missing --installer")?;
-    let relaunch_path = relaunch.ok_or("missing --relaunch")?;
     helper_log(&format!("[helper] installer={}", installer_path));
-    helper_log(&format!("[helper] relaunch={}", relaunch_path));
+    if let Some(path) = &relaunch {
+        helper_log(&format!("[helper] relaunch={}", path));
+    }
 
     if let Some(pid) = wait_pid {
         helper_log(&format!("[helper] waiting for pid={}", pid));
@@ -320,6 +720,16 @@ pub fn run_update_helper_from_args(args: &[String]) -> Result<(), String> {
         std::thread::sleep(std::time::Duration::from_millis(500));
     }
 
+    let sha256 = sha256.ok_or("missing --sha256")?;
+    if let Err(e) = verify_installer_file(&installer_path, &sha256, verify_signature) {
+        helper_log(&format!("[helper] refusing to run installer: {}", e));
+        if let Some(path) = &relaunch {
+            let _ = Command::new(path).spawn();
+        }
+        return Err(e);
+    }
+    helper_log("[helper] installer verified");
+
     let silent_status = Command::new(&installer_path)
         .args(["/VERYSILENT", "/SUPPRESSMSGBOXES", "/NORESTART"])
         .status()
@@ -344,10 +754,12 @@ pub fn run_update_helper_from_args(args: &[String]) -> Result<(), String> {
         }
     }
 
-    helper_log("[helper] install succeeded, relaunching app");
-    Command::new(&relaunch_path)
-        .spawn()
-        .map_err(|e| format!("failed to relaunch app: {e}"))?;
+    if let Some(path) = &relaunch {
+        helper_log("[helper] install succeeded, relaunching app");
+        Command::new(path)
+            .spawn()
+            .map_err(|e| format!("failed to relaunch app: {e}"))?;
+    }
     helper_log("[helper] done");
     Ok(())
 }
@@ -393,20 +805,25 @@ fn sha256_hex(bytes: &[u8]) -> String {
     digest.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
+fn expected_sha256(checksums_text: &str, installer_name: &str) -> Result<String, String> {
+    parse_sha256sums(checksums_text)
+        .remove(installer_name)
+        .ok_or_else(|| {
+            format!(
+                "SHA256SUMS.txt missing entry for installer '{}'",
+                installer_name
+            )
+        })
+}
+
 fn verify_sha256_from_release(
     checksums_text: &str,
     installer_name: &str,
     installer_bytes: &[u8],
 ) -> Result<(), String> {
-    let checksums = parse_sha256sums(checksums_text);
-    let expected = checksums.get(installer_name).ok_or_else(|| {
-        format!(
-            "SHA256SUMS.txt missing entry for installer '{}'",
-            installer_name
-        )
-    })?;
+    let expected = expected_sha256(checksums_text, installer_name)?;
     let actual = sha256_hex(installer_bytes);
-    if actual != *expected {
+    if actual != expected {
         return Err(format!(
             "installer checksum mismatch: expected {}, got {}",
             expected, actual
@@ -467,3 +884,43 @@ pub fn cleanup_stale_temp_installers(max_age_days: u64) -> Result<usize, String>
     }
     Ok(removed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTALLER: &str = "MangoChat-Setup-1.2.0.exe";
+
+    fn patch(old: &[u8], new: &[u8]) -> Vec<u8> {
+        let mut patch = Vec::new();
+        qbsdiff::Bsdiff::new(old, new)
+            .compare(std::io::Cursor::new(&mut patch))
+            .unwrap();
+        patch
+    }
+
+    #[test]
+    fn verified_delta_is_used() {
+        let (old, new) = (
+            b"installer v1.1.0".repeat(64),
+            b"installer v1.2.0".repeat(64),
+        );
+        let checksums = format!("{}  {}\n", sha256_hex(&new), INSTALLER);
+        let delta = apply_patch(&old, &patch(&old, &new), &checksums, INSTALLER).map(Some);
+        let bytes = delta_or_full(delta, || panic!("full installer downloaded")).unwrap();
+        assert_eq!(bytes, new);
+    }
+
+    #[test]
+    fn delta_with_wrong_hash_falls_back_to_full_installer() {
+        let (old, new) = (
+            b"installer v1.1.0".repeat(64),
+            b"installer v1.2.0".repeat(64),
+        );
+        let checksums = format!("{}  {}\n", sha256_hex(b"something else"), INSTALLER);
+        let delta = apply_patch(&old, &patch(&old, &new), &checksums, INSTALLER);
+        assert!(matches!(&delta, Err(e) if e.contains("checksum mismatch")));
+        let bytes = delta_or_full(delta.map(Some), || Ok(b"full".to_vec())).unwrap();
+        assert_eq!(bytes, b"full");
+    }
+}
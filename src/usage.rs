@@ -1,185 +1,691 @@
 use crate::state::{ProviderUsage, SessionUsage, UsageTotals};
-use serde::Serialize;
+use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 pub const USAGE_SAVE_INTERVAL_SECS: u64 = 60;
 
-/// Max lines to keep in usage-session.jsonl (one line per session).
-const MAX_SESSION_LOG_LINES: usize = 500;
-/// Max lines to keep in usage.jsonl (periodic all-time snapshots).
-const MAX_TOTALS_LOG_LINES: usize = 100;
+/// Bumped when the database layout changes; stored in `PRAGMA user_version`.
+const DB_VERSION: i32 = 2;
 
-pub fn usage_path() -> Result<PathBuf, String> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Ok(dir.join("MangoChat").join("usage.jsonl"));
+/// Shared connection, opened on first use. Usage is written from the UI
+/// thread and the periodic saver, so access is serialized here.
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Return the Mango Chat data directory path.
+pub fn data_dir() -> Option<PathBuf> {
+    crate::profile::data_dir()
+}
+
+pub fn db_path() -> Result<PathBuf, String> {
+    data_dir()
+        .map(|d| d.join("usage.db"))
+        .ok_or_else(|| "Failed to resolve data directory for usage database".into())
+}
+
+fn open_db() -> Result<Connection, String> {
+    let path = db_path()?;
+    let dir = path
+        .parent()
+        .ok_or_else(|| "Usage database path has no parent directory".to_string())?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create usage dir: {}", e))?;
+    let conn =
+        Connection::open(&path).map_err(|e| format!("Failed to open usage database: {}", e))?;
+    conn.busy_timeout(std::time::Duration::from_secs(2))
+        .map_err(|e| format!("Failed to configure usage database: {}", e))?;
+    init_db(&conn, dir)?;
+    Ok(conn)
+}
+
+/// Create the tables and bring the schema up to `DB_VERSION`, importing the
+/// legacy JSON files from `legacy_dir` on a database that predates them.
+fn init_db(conn: &Connection, legacy_dir: &Path) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sessions (
+             session_id    INTEGER PRIMARY KEY,
+             provider      TEXT NOT NULL,
+             model         TEXT NOT NULL,
+             bytes_sent    INTEGER NOT NULL,
+             ms_sent       INTEGER NOT NULL,
+             ms_suppressed INTEGER NOT NULL,
+             commits       INTEGER NOT NULL,
+             finals        INTEGER NOT NULL,
+             started_ms    INTEGER NOT NULL,
+             updated_ms    INTEGER NOT NULL,
+             chunks_dropped INTEGER NOT NULL DEFAULT 0
+         );
+         CREATE INDEX IF NOT EXISTS sessions_started ON sessions (started_ms);
+         CREATE INDEX IF NOT EXISTS sessions_provider ON sessions (provider, started_ms);
+         CREATE TABLE IF NOT EXISTS totals (
+             id             INTEGER PRIMARY KEY CHECK (id = 0),
+             provider       TEXT NOT NULL,
+             model          TEXT NOT NULL,
+             bytes_sent     INTEGER NOT NULL,
+             ms_sent        INTEGER NOT NULL,
+             ms_suppressed  INTEGER NOT NULL,
+             commits        INTEGER NOT NULL,
+             finals         INTEGER NOT NULL,
+             last_update_ms INTEGER NOT NULL,
+             chunks_dropped INTEGER NOT NULL DEFAULT 0
+         );
+         CREATE TABLE IF NOT EXISTS transcripts (
+             id         INTEGER PRIMARY KEY AUTOINCREMENT,
+             session_id INTEGER NOT NULL,
+             provider   TEXT NOT NULL,
+             created_ms INTEGER NOT NULL,
+             text       TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS transcripts_created ON transcripts (created_ms);
+         CREATE TABLE IF NOT EXISTS feedback (
+             provider   TEXT NOT NULL,
+             model      TEXT NOT NULL,
+             accurate   INTEGER NOT NULL,
+             inaccurate INTEGER NOT NULL,
+             PRIMARY KEY (provider, model)
+         );
+         CREATE TABLE IF NOT EXISTS provider_totals (
+             provider      TEXT PRIMARY KEY,
+             ms_sent       INTEGER NOT NULL,
+             ms_suppressed INTEGER NOT NULL,
+             bytes_sent    INTEGER NOT NULL,
+             finals        INTEGER NOT NULL
+         );",
+    )
+    .map_err(|e| format!("Failed to create usage tables: {}", e))?;
+
+    let version: i32 = conn
+        .query_row("PRAGMA user_version", [], |r| r.get(0))
+        .map_err(|e| format!("Failed to read usage database version: {}", e))?;
+    if version < 1 {
+        migrate_legacy_files(conn, legacy_dir)?;
     }
-    if let Some(home) = dirs::home_dir() {
-        return Ok(home.join(".mangochat").join("usage.jsonl"));
+    if version < 2 && !has_column(conn, "sessions", "chunks_dropped") {
+        conn.execute_batch(
+            "ALTER TABLE sessions ADD COLUMN chunks_dropped INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE totals ADD COLUMN chunks_dropped INTEGER NOT NULL DEFAULT 0;",
+        )
+        .map_err(|e| format!("Failed to upgrade usage database: {}", e))?;
     }
-    Err("Failed to resolve data directory for usage logs".into())
+    if version < DB_VERSION {
+        conn.execute_batch(&format!("PRAGMA user_version = {}", DB_VERSION))
+            .map_err(|e| format!("Failed to set usage database version: {}", e))?;
+    }
+    Ok(())
 }
 
-pub fn session_usage_path() -> Result<PathBuf, String> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Ok(dir.join("MangoChat").join("usage-session.jsonl"));
-    }
-    if let Some(home) = dirs::home_dir() {
-        return Ok(home.join(".mangochat").join("usage-session.jsonl"));
+fn has_column(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.prepare(&format!("SELECT {} FROM {} LIMIT 0", column, table)).is_ok()
+}
+
+/// Run `f` against the shared connection, opening it on first use.
+pub fn with_db<T>(f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = DB
+        .lock()
+        .map_err(|_| "Usage database lock poisoned".to_string())?;
+    if guard.is_none() {
+        *guard = Some(open_db()?);
     }
-    Err("Failed to resolve data directory for usage logs".into())
+    let conn = guard.as_mut().expect("usage database opened above");
+    f(conn).map_err(|e| format!("Usage database error: {}", e))
 }
 
-pub fn load_usage(path: &PathBuf) -> UsageTotals {
-    if let Ok(text) = fs::read_to_string(path) {
-        if let Some(line) = text.lines().rev().find(|l| !l.trim().is_empty()) {
-            if let Ok(v) = serde_json::from_str::<UsageTotals>(line) {
-                return v;
+// --- Legacy JSON/JSONL files (imported once, then renamed to *.migrated) ---
+
+/// Import the legacy files in one transaction that also bumps
+/// `user_version` to 1, so the import is recorded even if the files can't be
+/// renamed afterwards and is never applied a second time.
+fn migrate_legacy_files(conn: &Connection, dir: &Path) -> Result<(), String> {
+    let sessions_path = dir.join("usage-session.jsonl");
+    let totals_path = dir.join("usage.jsonl");
+    let provider_path = dir.join("usage-provider.json");
+
+    let sessions: Vec<SessionUsage> = fs::read_to_string(&sessions_path)
+        .ok()
+        .map(|text| {
+            text.lines()
+                .filter(|l| !l.trim().is_empty())
+                .filter_map(|l| serde_json::from_str(l).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let totals: Option<UsageTotals> = fs::read_to_string(&totals_path).ok().and_then(|text| {
+        text.lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .and_then(|l| serde_json::from_str(l).ok())
+    });
+    let provider_totals: HashMap<String, ProviderUsage> = fs::read_to_string(&provider_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    if sessions.is_empty() && totals.is_none() && provider_totals.is_empty() {
+        return Ok(());
+    }
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start usage migration: {}", e))?;
+    let result = (|| -> rusqlite::Result<()> {
+        for s in &sessions {
+            insert_session(&tx, s)?;
+        }
+        if let Some(t) = &totals {
+            write_totals(&tx, t)?;
+        }
+        write_provider_totals(&tx, &provider_totals)?;
+        tx.execute_batch("PRAGMA user_version = 1")
+    })();
+    result.map_err(|e| format!("Failed to migrate usage files: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit usage migration: {}", e))?;
+
+    for path in [sessions_path, totals_path, provider_path] {
+        if path.exists() {
+            let mut migrated = path.clone().into_os_string();
+            migrated.push(".migrated");
+            if let Err(e) = fs::rename(&path, migrated) {
+                app_err!("[usage] failed to rename {}: {}", path.display(), e);
             }
         }
     }
-    UsageTotals::default()
+    app_log!(
+        "[usage] migrated {} sessions from JSON files to usage.db",
+        sessions.len()
+    );
+    Ok(())
 }
 
-pub fn save_usage(path: &PathBuf, usage: &UsageTotals) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create usage dir: {}", e))?;
-    }
-    let line = serde_json::to_string(usage)
-        .map_err(|e| format!("Failed to serialize usage: {}", e))?;
-    let mut text = line;
-    text.push('\n');
-    fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-        .and_then(|mut f| std::io::Write::write_all(&mut f, text.as_bytes()))
-        .map_err(|e| format!("Failed to append usage log: {}", e))?;
-    truncate_log(path, MAX_TOTALS_LOG_LINES);
+// --- Row helpers ---
+
+fn insert_session(conn: &Connection, s: &SessionUsage) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sessions (session_id, provider, model, bytes_sent, ms_sent,
+             ms_suppressed, commits, finals, started_ms, updated_ms, chunks_dropped)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            s.session_id as i64,
+            s.provider,
+            s.model,
+            s.bytes_sent as i64,
+            s.ms_sent as i64,
+            s.ms_suppressed as i64,
+            s.commits as i64,
+            s.finals as i64,
+            s.started_ms as i64,
+            s.updated_ms as i64,
+            s.chunks_dropped as i64,
+        ],
+    )?;
     Ok(())
 }
 
-pub fn append_usage_line<T: Serialize>(path: &PathBuf, usage: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create usage dir: {}", e))?;
-    }
-    let line = serde_json::to_string(usage).map_err(|e| format!("Failed to serialize usage: {}", e))?;
-    let mut text = line;
-    text.push('\n');
-    fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-        .and_then(|mut f| std::io::Write::write_all(&mut f, text.as_bytes()))
-        .map_err(|e| format!("Failed to append usage log: {}", e))?;
-    truncate_log(path, MAX_SESSION_LOG_LINES);
+fn write_totals(conn: &Connection, t: &UsageTotals) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO totals (id, provider, model, bytes_sent, ms_sent,
+             ms_suppressed, commits, finals, last_update_ms, chunks_dropped)
+         VALUES (0, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            t.provider,
+            t.model,
+            t.bytes_sent as i64,
+            t.ms_sent as i64,
+            t.ms_suppressed as i64,
+            t.commits as i64,
+            t.finals as i64,
+            t.last_update_ms as i64,
+            t.chunks_dropped as i64,
+        ],
+    )?;
     Ok(())
 }
 
-/// If `path` has more than `max_lines` lines, rewrite it keeping only the last `max_lines`.
-fn truncate_log(path: &PathBuf, max_lines: usize) {
-    let text = match fs::read_to_string(path) {
-        Ok(t) => t,
-        Err(_) => return,
-    };
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.len() <= max_lines {
-        return;
+fn write_provider_totals(
+    conn: &Connection,
+    totals: &HashMap<String, ProviderUsage>,
+) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM provider_totals", [])?;
+    for (provider, pu) in totals {
+        conn.execute(
+            "INSERT INTO provider_totals (provider, ms_sent, ms_suppressed, bytes_sent, finals)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                provider,
+                pu.ms_sent as i64,
+                pu.ms_suppressed as i64,
+                pu.bytes_sent as i64,
+                pu.finals as i64,
+            ],
+        )?;
     }
-    let keep = &lines[lines.len() - max_lines..];
-    let mut out = keep.join("\n");
-    out.push('\n');
-    let _ = fs::write(path, out.as_bytes());
+    Ok(())
 }
 
-/// Load the most recent `max` session entries from usage-session.jsonl (newest first).
-pub fn load_recent_sessions(max: usize) -> Vec<SessionUsage> {
-    let path = match session_usage_path() {
-        Ok(p) => p,
-        Err(_) => return vec![],
-    };
-    let text = match fs::read_to_string(&path) {
-        Ok(t) => t,
-        Err(_) => return vec![],
-    };
-    text.lines()
-        .rev()
-        .filter(|l| !l.trim().is_empty())
-        .filter_map(|l| serde_json::from_str(l).ok())
-        .take(max)
-        .collect()
+fn session_from_row(r: &rusqlite::Row) -> rusqlite::Result<SessionUsage> {
+    Ok(SessionUsage {
+        session_id: r.get::<_, i64>(0)? as u64,
+        provider: r.get(1)?,
+        model: r.get(2)?,
+        bytes_sent: r.get::<_, i64>(3)? as u64,
+        ms_sent: r.get::<_, i64>(4)? as u64,
+        ms_suppressed: r.get::<_, i64>(5)? as u64,
+        commits: r.get::<_, i64>(6)? as u64,
+        finals: r.get::<_, i64>(7)? as u64,
+        started_ms: r.get::<_, i64>(8)? as u64,
+        updated_ms: r.get::<_, i64>(9)? as u64,
+        chunks_dropped: r.get::<_, i64>(10)? as u64,
+    })
 }
 
-/// Return the Mango Chat data directory path.
-pub fn data_dir() -> Option<PathBuf> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Some(dir.join("MangoChat"));
+const SESSION_COLUMNS: &str = "session_id, provider, model, bytes_sent, ms_sent, ms_suppressed, \
+     commits, finals, started_ms, updated_ms, chunks_dropped";
+
+fn query_sessions(sql: &str, args: impl rusqlite::Params) -> Vec<SessionUsage> {
+    let result = with_db(|conn| {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(args, session_from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    });
+    result.unwrap_or_else(|e| {
+        app_err!("[usage] {}", e);
+        vec![]
+    })
+}
+
+// --- Public API ---
+
+pub fn load_usage() -> UsageTotals {
+    let result = with_db(|conn| {
+        conn.query_row(
+            "SELECT provider, model, bytes_sent, ms_sent, ms_suppressed, commits, finals,
+                 last_update_ms, chunks_dropped FROM totals WHERE id = 0",
+            [],
+            |r| {
+                Ok(UsageTotals {
+                    provider: r.get(0)?,
+                    model: r.get(1)?,
+                    bytes_sent: r.get::<_, i64>(2)? as u64,
+                    ms_sent: r.get::<_, i64>(3)? as u64,
+                    ms_suppressed: r.get::<_, i64>(4)? as u64,
+                    commits: r.get::<_, i64>(5)? as u64,
+                    finals: r.get::<_, i64>(6)? as u64,
+                    last_update_ms: r.get::<_, i64>(7)? as u64,
+                    chunks_dropped: r.get::<_, i64>(8)? as u64,
+                })
+            },
+        )
+        .optional()
+    });
+    match result {
+        Ok(v) => v.unwrap_or_default(),
+        Err(e) => {
+            app_err!("[usage] {}", e);
+            UsageTotals::default()
+        }
     }
-    if let Some(home) = dirs::home_dir() {
-        return Some(home.join(".mangochat"));
+}
+
+pub fn save_usage(usage: &UsageTotals) -> Result<(), String> {
+    with_db(|conn| write_totals(conn, usage))
+}
+
+/// Record a finished session.
+pub fn append_session(session: &SessionUsage) -> Result<(), String> {
+    with_db(|conn| insert_session(conn, session))
+}
+
+/// Load the most recent `max` sessions (newest first).
+pub fn load_recent_sessions(max: usize) -> Vec<SessionUsage> {
+    query_sessions(
+        &format!(
+            "SELECT {} FROM sessions ORDER BY started_ms DESC LIMIT ?1",
+            SESSION_COLUMNS
+        ),
+        params![max as i64],
+    )
+}
+
+/// Sessions started at or after `start_ms` (oldest first).
+pub fn sessions_since(start_ms: u64) -> Vec<SessionUsage> {
+    query_sessions(
+        &format!(
+            "SELECT {} FROM sessions WHERE started_ms >= ?1 ORDER BY started_ms",
+            SESSION_COLUMNS
+        ),
+        params![start_ms as i64],
+    )
+}
+
+pub fn load_provider_totals() -> HashMap<String, ProviderUsage> {
+    let result = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT provider, ms_sent, ms_suppressed, bytes_sent, finals FROM provider_totals",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                ProviderUsage {
+                    ms_sent: r.get::<_, i64>(1)? as u64,
+                    ms_suppressed: r.get::<_, i64>(2)? as u64,
+                    bytes_sent: r.get::<_, i64>(3)? as u64,
+                    finals: r.get::<_, i64>(4)? as u64,
+                },
+            ))
+        })?;
+        rows.collect::<rusqlite::Result<HashMap<_, _>>>()
+    });
+    result.unwrap_or_else(|e| {
+        app_err!("[usage] {}", e);
+        HashMap::new()
+    })
+}
+
+pub fn save_provider_totals(totals: &HashMap<String, ProviderUsage>) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        write_provider_totals(&tx, totals)?;
+        tx.commit()
+    })
+}
+
+/// Clear the all-time totals.
+pub fn reset_totals() -> Result<(), String> {
+    with_db(|conn| conn.execute("DELETE FROM totals", []).map(|_| ()))
+}
+
+pub fn reset_provider_totals() -> Result<(), String> {
+    with_db(|conn| conn.execute("DELETE FROM provider_totals", []).map(|_| ()))
+}
+
+pub fn reset_sessions() -> Result<(), String> {
+    with_db(|conn| conn.execute("DELETE FROM sessions", []).map(|_| ()))
+}
+
+/// Thumbs up/down counts for one provider and model.
+#[derive(Debug, Clone, Default)]
+pub struct FeedbackCounts {
+    pub provider: String,
+    pub model: String,
+    pub accurate: u64,
+    pub inaccurate: u64,
+}
+
+impl FeedbackCounts {
+    /// Share of ratings that were thumbs up, or None before the first rating.
+    pub fn score(&self) -> Option<f64> {
+        let total = self.accurate + self.inaccurate;
+        (total > 0).then(|| self.accurate as f64 / total as f64)
     }
-    None
 }
 
-/// Delete the all-time totals log file.
-pub fn reset_totals_file() -> Result<(), String> {
-    let path = usage_path()?;
-    if path.exists() {
-        fs::remove_file(&path).map_err(|e| format!("Failed to reset totals: {}", e))?;
+pub fn record_feedback(provider: &str, model: &str, accurate: bool) -> Result<(), String> {
+    let (up, down) = if accurate { (1, 0) } else { (0, 1) };
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO feedback (provider, model, accurate, inaccurate) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (provider, model) DO UPDATE SET
+                 accurate = accurate + excluded.accurate,
+                 inaccurate = inaccurate + excluded.inaccurate",
+            params![provider, model, up, down],
+        )
+        .map(|_| ())
+    })
+}
+
+pub fn load_feedback() -> Vec<FeedbackCounts> {
+    let result = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT provider, model, accurate, inaccurate FROM feedback ORDER BY provider, model",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok(FeedbackCounts {
+                provider: r.get(0)?,
+                model: r.get(1)?,
+                accurate: r.get::<_, i64>(2)? as u64,
+                inaccurate: r.get::<_, i64>(3)? as u64,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    });
+    result.unwrap_or_else(|e| {
+        app_err!("[usage] {}", e);
+        vec![]
+    })
+}
+
+/// Drop sessions older than `max_age_days` and all but the newest `max_count`.
+/// A zero limit disables that rule. Returns the number of sessions removed.
+pub fn prune_sessions(max_age_days: u32, max_count: u32) -> Result<usize, String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        if max_age_days > 0 {
+            let cutoff = now.saturating_sub(max_age_days as u64 * 24 * 60 * 60 * 1000);
+            removed += tx.execute(
+                "DELETE FROM sessions WHERE started_ms < ?1",
+                params![cutoff as i64],
+            )?;
+        }
+        if max_count > 0 {
+            removed += tx.execute(
+                "DELETE FROM sessions WHERE session_id NOT IN (
+                     SELECT session_id FROM sessions ORDER BY started_ms DESC LIMIT ?1
+                 )",
+                params![max_count as i64],
+            )?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    })
+}
+
+/// Approximate list price in USD per minute of audio sent, used for
+/// cost estimates only.
+pub fn provider_rate_per_minute(provider: &str) -> f64 {
+    match provider {
+        "openai" => 0.003,
+        "deepgram" => 0.0077,
+        "elevenlabs" => 0.0067,
+        "assemblyai" => 0.0025,
+        _ => 0.0,
     }
-    Ok(())
 }
 
-pub fn provider_totals_path() -> Result<PathBuf, String> {
-    if let Some(dir) = dirs::data_local_dir() {
-        return Ok(dir.join("MangoChat").join("usage-provider.json"));
+pub fn estimated_cost(provider: &str, ms_sent: u64) -> f64 {
+    ms_sent as f64 / 60_000.0 * provider_rate_per_minute(provider)
+}
+
+/// Load every recorded session (oldest first).
+pub fn load_all_sessions() -> Vec<SessionUsage> {
+    sessions_since(0)
+}
+
+/// Local calendar month of a timestamp, as "YYYY-MM".
+pub fn month_key(ms: u64) -> String {
+    use chrono::TimeZone;
+    chrono::Local
+        .timestamp_millis_opt(ms as i64)
+        .single()
+        .map(|t| t.format("%Y-%m").to_string())
+        .unwrap_or_default()
+}
+
+/// Estimated spend on `provider` in the current local calendar month.
+pub fn month_to_date_cost(sessions: &[SessionUsage], provider: &str) -> f64 {
+    let month = month_key(chrono::Local::now().timestamp_millis() as u64);
+    sessions
+        .iter()
+        .filter(|s| s.provider == provider && month_key(s.started_ms) == month)
+        .map(|s| estimated_cost(&s.provider, s.ms_sent))
+        .sum()
+}
+
+/// The share of `limit` (80 or 100 percent) that this month's spend on
+/// `provider` has reached as of `now_ms`, with the spend. None below 80%.
+pub fn budget_mark(provider: &str, limit: f64, now_ms: u64) -> Option<(u8, f64)> {
+    // 32 days always covers the current calendar month.
+    let since = now_ms.saturating_sub(32 * 24 * 60 * 60 * 1000);
+    let spent = month_to_date_cost(&sessions_since(since), provider);
+    if spent >= limit {
+        Some((100, spent))
+    } else if spent >= limit * 0.8 {
+        Some((80, spent))
+    } else {
+        None
     }
-    if let Some(home) = dirs::home_dir() {
-        return Ok(home.join(".mangochat").join("usage-provider.json"));
+}
+
+/// Per-day totals aggregated from the session log.
+#[derive(Debug, Default, Clone)]
+pub struct DailyUsage {
+    pub minutes_sent: f64,
+    pub cost_by_provider: HashMap<String, f64>,
+}
+
+/// Group sessions by local calendar day (days since CE), oldest first.
+pub fn daily_usage(sessions: &[SessionUsage]) -> Vec<(i32, DailyUsage)> {
+    use chrono::{Datelike, TimeZone};
+
+    let mut days: std::collections::BTreeMap<i32, DailyUsage> = std::collections::BTreeMap::new();
+    for s in sessions {
+        let Some(when) = chrono::Local.timestamp_millis_opt(s.started_ms as i64).single() else {
+            continue;
+        };
+        let day = days.entry(when.num_days_from_ce()).or_default();
+        day.minutes_sent += s.ms_sent as f64 / 60_000.0;
+        *day.cost_by_provider.entry(s.provider.clone()).or_default() +=
+            estimated_cost(&s.provider, s.ms_sent);
     }
-    Err("Failed to resolve data directory for provider totals".into())
+    days.into_iter().collect()
 }
 
-pub fn load_provider_totals() -> HashMap<String, ProviderUsage> {
-    let path = match provider_totals_path() {
-        Ok(p) => p,
-        Err(_) => return HashMap::new(),
-    };
-    let text = match fs::read_to_string(&path) {
-        Ok(t) => t,
-        Err(_) => return HashMap::new(),
-    };
-    serde_json::from_str(&text).unwrap_or_default()
+/// Totals for one calendar period (day, week or month).
+#[derive(Debug, Default, Clone)]
+pub struct PeriodUsage {
+    pub sessions: u32,
+    pub minutes_sent: f64,
+    pub finals: u64,
+    pub cost: f64,
 }
 
-pub fn save_provider_totals(totals: &HashMap<String, ProviderUsage>) -> Result<(), String> {
-    let path = provider_totals_path()?;
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create provider totals dir: {}", e))?;
+impl PeriodUsage {
+    fn add(&mut self, s: &SessionUsage) {
+        self.sessions += 1;
+        self.minutes_sent += s.ms_sent as f64 / 60_000.0;
+        self.finals += s.finals;
+        self.cost += estimated_cost(&s.provider, s.ms_sent);
     }
-    let json = serde_json::to_string(totals)
-        .map_err(|e| format!("Failed to serialize provider totals: {}", e))?;
-    fs::write(&path, json.as_bytes())
-        .map_err(|e| format!("Failed to write provider totals: {}", e))
 }
 
-pub fn reset_provider_totals_file() -> Result<(), String> {
-    let path = provider_totals_path()?;
-    if path.exists() {
-        fs::remove_file(&path)
-            .map_err(|e| format!("Failed to reset provider totals: {}", e))?;
+/// First local day of the period containing `today` and of the one before it.
+/// `period` is "day", "week" (Monday start) or "month".
+fn period_starts(period: &str, today: chrono::NaiveDate) -> (chrono::NaiveDate, chrono::NaiveDate) {
+    use chrono::{Datelike, Days, Months};
+
+    match period {
+        "day" => (today, today - Days::new(1)),
+        "month" => {
+            let start = today.with_day(1).unwrap_or(today);
+            (start, start - Months::new(1))
+        }
+        _ => {
+            let start = today - Days::new(today.weekday().num_days_from_monday() as u64);
+            (start, start - Days::new(7))
+        }
     }
-    Ok(())
 }
 
-pub fn reset_session_file() -> Result<(), String> {
-    let path = session_usage_path()?;
-    if path.exists() {
-        fs::remove_file(&path)
-            .map_err(|e| format!("Failed to reset session usage: {}", e))?;
+/// Usage in the current period so far and in the whole previous period.
+pub fn current_and_previous_period(
+    sessions: &[SessionUsage],
+    period: &str,
+) -> (PeriodUsage, PeriodUsage) {
+    use chrono::TimeZone;
+
+    let (current_start, previous_start) = period_starts(period, chrono::Local::now().date_naive());
+    let mut current = PeriodUsage::default();
+    let mut previous = PeriodUsage::default();
+    for s in sessions {
+        let Some(when) = chrono::Local.timestamp_millis_opt(s.started_ms as i64).single() else {
+            continue;
+        };
+        let day = when.date_naive();
+        if day >= current_start {
+            current.add(s);
+        } else if day >= previous_start {
+            previous.add(s);
+        }
     }
-    Ok(())
+    (current, previous)
+}
+
+/// Count sessions by length in whole minutes; the last bucket collects
+/// everything at or above `max_minutes`.
+pub fn session_duration_histogram(sessions: &[SessionUsage], max_minutes: usize) -> Vec<u32> {
+    let mut buckets = vec![0u32; max_minutes + 1];
+    for s in sessions {
+        let minutes = (s.updated_ms.saturating_sub(s.started_ms) / 60_000) as usize;
+        buckets[minutes.min(max_minutes)] += 1;
+    }
+    buckets
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals_bytes(conn: &Connection) -> i64 {
+        conn.query_row("SELECT bytes_sent FROM totals WHERE id = 0", [], |r| {
+            r.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn legacy_import_runs_once() {
+        let dir = std::env::temp_dir().join(format!("mangochat-usage-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("usage-session.jsonl"),
+            "{\"session_id\":1,\"provider\":\"openai\",\"ms_sent\":1000}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("usage.jsonl"),
+            "{\"provider\":\"openai\",\"bytes_sent\":10}\n",
+        )
+        .unwrap();
+        let db = dir.join("usage.db");
+
+        let conn = Connection::open(&db).unwrap();
+        init_db(&conn, &dir).unwrap();
+        assert_eq!(totals_bytes(&conn), 10);
+        assert!(dir.join("usage.jsonl.migrated").exists());
+        assert!(!dir.join("usage.jsonl").exists());
+        conn.execute("UPDATE totals SET bytes_sent = 25 WHERE id = 0", [])
+            .unwrap();
+        drop(conn);
+
+        // A file left behind (e.g. the rename failed) must not be imported again.
+        fs::write(
+            dir.join("usage.jsonl"),
+            "{\"provider\":\"openai\",\"bytes_sent\":10}\n",
+        )
+        .unwrap();
+        let conn = Connection::open(&db).unwrap();
+        init_db(&conn, &dir).unwrap();
+        assert_eq!(totals_bytes(&conn), 25);
+        let sessions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sessions", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(sessions, 1);
+        drop(conn);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
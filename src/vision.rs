@@ -0,0 +1,130 @@
+use base64::Engine;
+use image::RgbaImage;
+use serde_json::json;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::state::AppEvent;
+
+/// Longest edge sent to the model; larger snips are downscaled first.
+const VISION_MAX_DIMENSION: u32 = 1568;
+
+/// Endpoint, model and key for vision requests, mirrored into `AppState`
+/// so the session thread can answer attached-snip questions.
+#[derive(Debug, Clone, Default)]
+pub struct VisionConfig {
+    pub endpoint: String,
+    pub model: String,
+    pub api_key: String,
+}
+
+impl VisionConfig {
+    pub fn from_settings(settings: &crate::settings::Settings) -> Self {
+        Self {
+            endpoint: settings.vision_endpoint.clone(),
+            model: settings.vision_model.clone(),
+            api_key: settings.api_key_for("openai").to_string(),
+        }
+    }
+}
+
+/// Prompt text for a snip vision preset ("describe", "table" or "text").
+pub fn preset_prompt(preset: &str) -> &'static str {
+    match preset {
+        "table" => "Extract the table in this image as CSV. Reply with the CSV only.",
+        "text" => "Transcribe all text in this image exactly as written. Reply with the text only.",
+        _ => "Describe this image concisely.",
+    }
+}
+
+pub fn preset_label(preset: &str) -> &'static str {
+    match preset {
+        "table" => "Extract table as CSV",
+        "text" => "Transcribe text",
+        _ => "Describe",
+    }
+}
+
+/// Send an image plus prompt to an OpenAI-compatible chat completions
+/// endpoint and return the reply text. Blocking; call off the UI thread.
+pub fn ask_about_image(
+    endpoint: &str,
+    model: &str,
+    api_key: &str,
+    prompt: &str,
+    img: &RgbaImage,
+) -> Result<String, String> {
+    if api_key.trim().is_empty() {
+        return Err("No OpenAI API key configured".into());
+    }
+    let output = crate::snip::SnipOutput {
+        format: "jpeg".into(),
+        quality: 85,
+        max_dimension: VISION_MAX_DIMENSION,
+    };
+    let scaled = crate::snip::limit_dimension(img.clone(), output.max_dimension);
+    let bytes = crate::snip::encode_image(&scaled, &output)?;
+    let data_url = format!(
+        "data:image/jpeg;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    );
+
+    let body = json!({
+        "model": model,
+        "messages": [{
+            "role": "user",
+            "content": [
+                { "type": "text", "text": prompt },
+                { "type": "image_url", "image_url": { "url": data_url } }
+            ]
+        }]
+    });
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("http client error: {e}"))?;
+    let resp: serde_json::Value = client
+        .post(endpoint)
+        .bearer_auth(api_key.trim())
+        .json(&body)
+        .send()
+        .map_err(|e| format!("request failed: {e}"))?
+        .error_for_status()
+        .map_err(|e| format!("vision api error: {e}"))?
+        .json()
+        .map_err(|e| format!("invalid response json: {e}"))?;
+
+    resp["choices"][0]["message"]["content"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Vision model returned no text".to_string())
+}
+
+/// Answer a dictated question about an attached snip and type the reply.
+pub fn answer_with_snip(
+    config: &VisionConfig,
+    question: &str,
+    img: &RgbaImage,
+    tx: &Sender<AppEvent>,
+) {
+    let status = |status: &str, message: String| {
+        let _ = tx.send(AppEvent::StatusUpdate {
+            status: status.into(),
+            message,
+        });
+    };
+    status("idle", "Asking about attached snip...".into());
+    match ask_about_image(&config.endpoint, &config.model, &config.api_key, question, img) {
+        Ok(answer) => {
+            app_log!("[vision] typing {} char answer", answer.len());
+            crate::typing::type_text(&answer);
+            status("idle", "Answered from attached snip".into());
+        }
+        Err(e) => {
+            app_err!("[vision] {}", e);
+            status("error", format!("Vision request failed: {}", e));
+        }
+    }
+}
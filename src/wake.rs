@@ -0,0 +1,141 @@
+// Hands-free activation: a Porcupine keyword spotter on its own 16kHz mic
+// stream. It only runs while no dictation session is active (the UI drops it
+// on start and recreates it on stop), so it never shares audio with a
+// provider and costs a few percent of one core while idle.
+//
+// Porcupine itself is not bundled. Builds with the `wake-word` feature load
+// the library, model and keyword file the user downloaded from Picovoice.
+
+use crate::settings::Settings;
+use crate::state::AppEvent;
+use std::sync::mpsc::Sender as EventSender;
+
+/// `api_keys` entry holding the Picovoice access key.
+pub const ACCESS_KEY_ID: &str = "picovoice";
+/// Ignore repeat detections of the same utterance.
+#[cfg(feature = "wake-word")]
+const DETECTION_COOLDOWN: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WakeConfig {
+    pub access_key: String,
+    pub library_path: String,
+    pub model_path: String,
+    pub keyword_path: String,
+    pub sensitivity: f32,
+    pub mic: String,
+}
+
+impl WakeConfig {
+    /// None when the wake word is off, no access key is set, or this build
+    /// has no wake-word support.
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        let access_key = settings.api_key_for(ACCESS_KEY_ID).trim();
+        if !cfg!(feature = "wake-word") || !settings.wake_word_enabled || access_key.is_empty() {
+            return None;
+        }
+        Some(Self {
+            access_key: access_key.to_string(),
+            library_path: settings.wake_word_library_path.clone(),
+            model_path: settings.wake_word_model_path.clone(),
+            keyword_path: settings.wake_word_keyword_path.clone(),
+            sensitivity: settings.wake_word_sensitivity,
+            mic: settings.mic_device.clone(),
+        })
+    }
+
+    /// What to tell the user to say.
+    pub fn phrase(&self) -> String {
+        keyword_phrase(&self.keyword_path)
+    }
+}
+
+/// "hey mango" for hey-mango_en_windows_v3_0_0.ppn; empty without a file.
+pub fn keyword_phrase(keyword_path: &str) -> String {
+    let stem = std::path::Path::new(keyword_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    stem.split('_').next().unwrap_or(stem).replace('-', " ")
+}
+
+/// Sends `AppEvent::WakeWordDetected` when the keyword is heard. Dropping it
+/// closes the mic stream, which ends the detector thread.
+#[cfg_attr(not(feature = "wake-word"), allow(dead_code))]
+pub struct WakeListener {
+    config: WakeConfig,
+    _capture: crate::audio::ResampledCapture,
+}
+
+impl WakeListener {
+    #[cfg(feature = "wake-word")]
+    pub fn start(config: WakeConfig, event_tx: EventSender<AppEvent>) -> Result<Self, String> {
+        use std::time::Instant;
+
+        for (path, what) in [
+            (&config.library_path, "Porcupine library"),
+            (&config.model_path, "model file (.pv)"),
+            (&config.keyword_path, "keyword file (.ppn)"),
+        ] {
+            if path.is_empty() {
+                return Err(format!("Set the {} in Settings > Dictation", what));
+            }
+        }
+        let porcupine = crate::porcupine::Porcupine::load(
+            &config.library_path,
+            &config.model_path,
+            &config.keyword_path,
+            &config.access_key,
+            config.sensitivity,
+        )
+        .map_err(|e| format!("Failed to start wake word engine: {}", e))?;
+
+        let frame_len = porcupine.frame_length();
+        let (pcm_tx, pcm_rx) = std::sync::mpsc::sync_channel::<Vec<i16>>(64);
+        let mic = (!config.mic.is_empty()).then_some(config.mic.as_str());
+        let capture = crate::audio::ResampledCapture::start(mic, porcupine.sample_rate(), pcm_tx)?;
+
+        let phrase = config.phrase();
+        std::thread::spawn(move || {
+            let mut frame: Vec<i16> = Vec::with_capacity(frame_len * 2);
+            let mut last_detection: Option<Instant> = None;
+            while let Ok(pcm) = pcm_rx.recv() {
+                frame.extend_from_slice(&pcm);
+                while frame.len() >= frame_len {
+                    let detected = match porcupine.process(&frame[..frame_len]) {
+                        Ok(detected) => detected,
+                        Err(e) => {
+                            app_err!("[wake] process error: {}", e);
+                            false
+                        }
+                    };
+                    frame.drain(..frame_len);
+                    if !detected
+                        || last_detection.is_some_and(|t| t.elapsed() < DETECTION_COOLDOWN)
+                    {
+                        continue;
+                    }
+                    last_detection = Some(Instant::now());
+                    app_log!("[wake] heard \"{}\"", phrase);
+                    let _ = event_tx.send(AppEvent::WakeWordDetected);
+                }
+            }
+            app_log!("[wake] listener stopped");
+        });
+
+        app_log!("[wake] listening for \"{}\"", config.phrase());
+        Ok(Self {
+            config,
+            _capture: capture,
+        })
+    }
+
+    #[cfg(not(feature = "wake-word"))]
+    pub fn start(_config: WakeConfig, _event_tx: EventSender<AppEvent>) -> Result<Self, String> {
+        Err("This build has no wake-word support".to_string())
+    }
+
+    pub fn config(&self) -> &WakeConfig {
+        &self.config
+    }
+}
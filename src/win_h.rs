@@ -0,0 +1,104 @@
+// Win+H takeover: stops Windows opening its voice typing panel so the hotkey
+// listener can use Win+H for dictation. Explorer skips the Win+<letter>
+// shortcuts listed in the DisabledHotkeys value, but only reads it when it
+// starts, so a change applies after signing out or restarting Explorer.
+
+#[cfg(windows)]
+const ADVANCED_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Advanced";
+#[cfg(windows)]
+const DISABLED_HOTKEYS: &str = "DisabledHotkeys";
+
+#[cfg(windows)]
+fn read_disabled() -> String {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let mut buf = [0u16; 256];
+    let mut size = (buf.len() * 2) as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(ADVANCED_KEY),
+            &HSTRING::from(DISABLED_HOTKEYS),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buf.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        )
+    };
+    if status.is_err() {
+        return String::new();
+    }
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+/// True when Windows is told to leave Win+H alone.
+#[cfg(windows)]
+pub fn is_taken_over() -> bool {
+    read_disabled().to_ascii_uppercase().contains('H')
+}
+
+/// Add or remove H in DisabledHotkeys, keeping any other letters there.
+#[cfg(windows)]
+pub fn set_taken_over(enabled: bool) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegDeleteValueW, RegSetValueExW, HKEY, HKEY_CURRENT_USER,
+        KEY_SET_VALUE, REG_OPTION_NON_VOLATILE, REG_SZ,
+    };
+
+    let others: String = read_disabled()
+        .chars()
+        .filter(|c| !c.eq_ignore_ascii_case(&'h'))
+        .collect();
+    let value = if enabled {
+        format!("{}H", others)
+    } else {
+        others
+    };
+    let mut key = HKEY::default();
+    unsafe {
+        RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(ADVANCED_KEY),
+            0,
+            None,
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE,
+            None,
+            &mut key,
+            None,
+        )
+        .ok()
+        .map_err(|e| format!("Failed to open Explorer settings: {}", e))?;
+
+        let result = if value.is_empty() {
+            let _ = RegDeleteValueW(key, &HSTRING::from(DISABLED_HOTKEYS));
+            Ok(())
+        } else {
+            let wide: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+            let bytes = std::slice::from_raw_parts(wide.as_ptr() as *const u8, wide.len() * 2);
+            RegSetValueExW(key, &HSTRING::from(DISABLED_HOTKEYS), 0, REG_SZ, Some(bytes))
+                .ok()
+                .map_err(|e| format!("Failed to update DisabledHotkeys: {}", e))
+        };
+        let _ = RegCloseKey(key);
+        result?;
+    }
+    app_log!(
+        "[win-h] Windows voice typing shortcut {}",
+        if enabled { "disabled" } else { "restored" }
+    );
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn is_taken_over() -> bool {
+    false
+}
+
+#[cfg(not(windows))]
+pub fn set_taken_over(_enabled: bool) -> Result<(), String> {
+    Err("Win+H takeover is only supported on Windows".into())
+}